@@ -35,6 +35,7 @@ impl PanelState2D {
         }
     }
 
+    #[allow(clippy::needless_return)]
     pub fn viewing_plane(&self) -> PanelViewingPlane {
         return self.viewing_plane;
     }
@@ -4,9 +4,25 @@
 
 use crate::editor_panel_state::*;
 use crate::insert_preview_state::*;
-use mesh_editor::mesh::{LineIndex, PolyIndex, VertIndex};
+use macroquad::prelude::*;
+use mesh_editor::mesh::{
+    Axis, LineIndex, MaterialIndex, Plane, PlyExtras, PolyIndex, Primitive, SubdivisionMode,
+    VertIndex,
+};
 use strum::Display;
 
+// colors the paint tool cycles through
+const PAINT_PALETTE: [(&str, Color); 8] = [
+    ("Red", RED),
+    ("Orange", ORANGE),
+    ("Yellow", YELLOW),
+    ("Green", GREEN),
+    ("Sky blue", SKYBLUE),
+    ("Blue", BLUE),
+    ("Purple", PURPLE),
+    ("Pink", PINK),
+];
+
 pub enum Selection {
     None,
     Verticies(Vec<VertIndex>),
@@ -14,6 +30,22 @@ pub enum Selection {
     Polys(Vec<PolyIndex>),
 }
 
+//
+// Line dragged in a 2D pane, the bisect plane contains it and the pane's view axis
+//
+pub struct BisectLine {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub view_axis: Vec3,
+    pub is_dragging: bool,
+}
+
+impl BisectLine {
+    pub fn plane(&self) -> Option<Plane> {
+        Plane::containing_line(self.start, self.end, self.view_axis)
+    }
+}
+
 #[derive(Display)]
 pub enum InputMode {
     SelectVerts,
@@ -22,6 +54,14 @@ pub enum InputMode {
     EditModel,
 }
 
+// part of the active object's transform the arrow keys change
+#[derive(Copy, Clone, Display, PartialEq)]
+pub enum TransformPart {
+    Location,
+    Rotation,
+    Scale,
+}
+
 #[derive(PartialEq)]
 pub enum ViewerMode {
     EditorPanels,
@@ -37,6 +77,18 @@ pub struct EditorState {
     panel_state_rotate_cam: PanelStateFreeCam,
     insert_preview: InsertPreview,
     viewer_mode: ViewerMode,
+    subdivision_mode: SubdivisionMode,
+    subdivision_preview_level: usize,
+    status_message: Option<String>,
+    show_validation_overlay: bool,
+    show_uv_checker: bool,
+    paint_color_index: usize,
+    active_material: MaterialIndex,
+    primitive_insert: Option<Primitive>,
+    lathe_axis: Axis,
+    transform_part: TransformPart,
+    bisect_line: Option<BisectLine>,
+    ply_extras: PlyExtras,
 }
 
 //
@@ -54,6 +106,18 @@ impl EditorState {
             panel_state_rotate_cam: PanelStateFreeCam::new(),
             viewer_mode: ViewerMode::EditorPanels,
             insert_preview: InsertPreview::new(),
+            subdivision_mode: SubdivisionMode::CatmullClark,
+            subdivision_preview_level: 0,
+            status_message: None,
+            show_validation_overlay: false,
+            show_uv_checker: false,
+            paint_color_index: 0,
+            active_material: 0,
+            primitive_insert: None,
+            lathe_axis: Axis::Y,
+            transform_part: TransformPart::Location,
+            bisect_line: None,
+            ply_extras: PlyExtras::default(),
         }
     }
 
@@ -123,4 +187,122 @@ impl EditorState {
             ViewerMode::FreeCam => self.viewer_mode = ViewerMode::EditorPanels,
         }
     }
+
+    pub fn subdivision_mode(&self) -> SubdivisionMode {
+        self.subdivision_mode
+    }
+
+    pub fn toggle_subdivision_mode(&mut self) {
+        match self.subdivision_mode {
+            SubdivisionMode::Simple => self.subdivision_mode = SubdivisionMode::CatmullClark,
+            SubdivisionMode::CatmullClark => self.subdivision_mode = SubdivisionMode::Simple,
+        }
+    }
+
+    pub fn subdivision_preview_level(&self) -> usize {
+        self.subdivision_preview_level
+    }
+
+    pub fn subdivision_preview_level_mut(&mut self) -> &mut usize {
+        &mut self.subdivision_preview_level
+    }
+
+    pub fn status_message(&self) -> Option<&String> {
+        self.status_message.as_ref()
+    }
+
+    pub fn set_status_message(&mut self, message: String) {
+        self.status_message = Some(message);
+    }
+
+    pub fn show_validation_overlay(&self) -> bool {
+        self.show_validation_overlay
+    }
+
+    pub fn toggle_validation_overlay(&mut self) {
+        self.show_validation_overlay = !self.show_validation_overlay;
+    }
+
+    pub fn show_uv_checker(&self) -> bool {
+        self.show_uv_checker
+    }
+
+    pub fn toggle_uv_checker(&mut self) {
+        self.show_uv_checker = !self.show_uv_checker;
+    }
+
+    pub fn paint_color(&self) -> Color {
+        PAINT_PALETTE[self.paint_color_index].1
+    }
+
+    pub fn paint_color_name(&self) -> &'static str {
+        PAINT_PALETTE[self.paint_color_index].0
+    }
+
+    pub fn cycle_paint_color(&mut self) {
+        self.paint_color_index = (self.paint_color_index + 1) % PAINT_PALETTE.len();
+    }
+
+    // material slot the selection is assigned to (may not exist yet)
+    pub fn active_material(&self) -> MaterialIndex {
+        self.active_material
+    }
+
+    pub fn set_active_material(&mut self, material: MaterialIndex) {
+        self.active_material = material;
+    }
+
+    // primitive being placed at the insert cursor, None when not inserting
+    pub fn primitive_insert(&self) -> Option<&Primitive> {
+        self.primitive_insert.as_ref()
+    }
+
+    pub fn set_primitive_insert(&mut self, primitive: Option<Primitive>) {
+        self.primitive_insert = primitive;
+    }
+
+    pub fn lathe_axis(&self) -> Axis {
+        self.lathe_axis
+    }
+
+    pub fn cycle_lathe_axis(&mut self) {
+        match self.lathe_axis {
+            Axis::X => self.lathe_axis = Axis::Y,
+            Axis::Y => self.lathe_axis = Axis::Z,
+            Axis::Z => self.lathe_axis = Axis::X,
+        }
+    }
+
+    pub fn transform_part(&self) -> TransformPart {
+        self.transform_part
+    }
+
+    pub fn cycle_transform_part(&mut self) {
+        match self.transform_part {
+            TransformPart::Location => self.transform_part = TransformPart::Rotation,
+            TransformPart::Rotation => self.transform_part = TransformPart::Scale,
+            TransformPart::Scale => self.transform_part = TransformPart::Location,
+        }
+    }
+
+    pub fn bisect_line(&self) -> Option<&BisectLine> {
+        self.bisect_line.as_ref()
+    }
+
+    pub fn bisect_line_mut(&mut self) -> Option<&mut BisectLine> {
+        self.bisect_line.as_mut()
+    }
+
+    pub fn set_bisect_line(&mut self, line: Option<BisectLine>) {
+        self.bisect_line = line;
+    }
+
+    // properties of the last imported PLY that the mesh doesn't hold, written back on export
+    pub fn ply_extras(&self) -> &PlyExtras {
+        &self.ply_extras
+    }
+
+    pub fn set_ply_extras(&mut self, extras: PlyExtras) {
+        self.ply_extras = extras;
+    }
 }
@@ -0,0 +1,230 @@
+//
+// file_commands -> keypresses that import into the active mesh, export the scene (transforms
+// baked in, or as a glTF scene graph) or save / load the whole scene (files next to the
+// working dir)
+//
+
+use crate::editor_panel_state::*;
+use crate::editor_state::*;
+use crate::global_commands::*;
+use macroquad::prelude::*;
+use mesh_editor::mesh::{Mesh as MeshData, *};
+use mesh_editor::scene::*;
+use std::path::Path;
+
+const STL_PATH: &str = "mesh.stl";
+const STL_WELD_TOLERANCE: f32 = 0.0001;
+const PLY_PATH: &str = "mesh.ply";
+const GLTF_PATH: &str = "mesh.gltf";
+const GLB_PATH: &str = "mesh.glb";
+const OBJ_PATH: &str = "mesh.obj";
+const SCENE_PATH: &str = "scene.json";
+// millimetres per world unit in the blueprints
+const SVG_SCALE: f32 = 10.0;
+
+pub fn handle_file_commands(editor_state: &mut EditorState, scene: &mut Scene) {
+    let modifiers = check_modifier_keys();
+    if !modifiers.meta_key {
+        return;
+    }
+
+    if is_key_pressed(KeyCode::S) && modifiers.shift_key {
+        load_scene(editor_state, scene);
+    } else if is_key_pressed(KeyCode::S) {
+        save_scene(editor_state, scene);
+    }
+
+    if is_key_pressed(KeyCode::E) && modifiers.alt_key {
+        export_ply(editor_state, &scene.baked_mesh(), modifiers.shift_key);
+    } else if is_key_pressed(KeyCode::E) {
+        export_stl(editor_state, &scene.baked_mesh(), modifiers.shift_key);
+    }
+    if is_key_pressed(KeyCode::G) && modifiers.alt_key {
+        export_gltf_scene(editor_state, scene, modifiers.shift_key);
+    } else if is_key_pressed(KeyCode::G) {
+        export_gltf(editor_state, &scene.baked_mesh(), modifiers.shift_key);
+    }
+    if is_key_pressed(KeyCode::O) && modifiers.shift_key {
+        import_obj(editor_state, scene.active_mesh_mut());
+    } else if is_key_pressed(KeyCode::O) {
+        export_obj(editor_state, &scene.baked_mesh());
+    }
+    if is_key_pressed(KeyCode::V) {
+        export_svg(editor_state, &scene.baked_mesh(), modifiers.shift_key);
+    }
+    if is_key_pressed(KeyCode::I) && modifiers.alt_key {
+        import_ply(editor_state, scene.active_mesh_mut());
+    } else if is_key_pressed(KeyCode::I) {
+        import_stl(editor_state, scene.active_mesh_mut());
+    }
+}
+
+//
+// STL: meta + E exports binary, meta + shift + E ascii, meta + I imports (quads rebuilt)
+//
+fn export_stl(editor_state: &mut EditorState, mesh: &MeshData, is_ascii: bool) {
+    let format = if is_ascii {
+        StlFormat::Ascii
+    } else {
+        StlFormat::Binary
+    };
+    match mesh.save_stl(Path::new(STL_PATH), format) {
+        Ok(()) => editor_state.set_status_message(format!("Exported {}", STL_PATH)),
+        Err(error) => editor_state.set_status_message(format!("STL export: {}", error)),
+    }
+}
+
+fn import_stl(editor_state: &mut EditorState, mesh: &mut MeshData) {
+    match MeshData::load_stl(Path::new(STL_PATH), STL_WELD_TOLERANCE, true) {
+        Ok(imported) => {
+            *mesh = imported;
+            editor_state.set_ply_extras(PlyExtras::default());
+            *editor_state.selection_mut() = Selection::None;
+            editor_state.set_status_message(format!("Imported {}", STL_PATH));
+        }
+        Err(error) => editor_state.set_status_message(format!("STL import: {}", error)),
+    }
+}
+
+//
+// PLY: meta + alt + E exports binary, meta + alt + shift + E ascii, meta + alt + I imports
+// (extra properties of the imported file are written back while the counts still match)
+//
+fn export_ply(editor_state: &mut EditorState, mesh: &MeshData, is_ascii: bool) {
+    let format = if is_ascii {
+        PlyFormat::Ascii
+    } else {
+        PlyFormat::BinaryLittleEndian
+    };
+    match mesh.save_ply(Path::new(PLY_PATH), format, editor_state.ply_extras()) {
+        Ok(()) => editor_state.set_status_message(format!("Exported {}", PLY_PATH)),
+        Err(error) => editor_state.set_status_message(format!("PLY export: {}", error)),
+    }
+}
+
+fn import_ply(editor_state: &mut EditorState, mesh: &mut MeshData) {
+    match MeshData::load_ply(Path::new(PLY_PATH)) {
+        Ok((imported, extras)) => {
+            *mesh = imported;
+            editor_state.set_ply_extras(extras);
+            *editor_state.selection_mut() = Selection::None;
+            editor_state.set_status_message(format!("Imported {}", PLY_PATH));
+        }
+        Err(error) => editor_state.set_status_message(format!("PLY import: {}", error)),
+    }
+}
+
+//
+// glTF: meta + G exports .glb, meta + shift + G .gltf + .bin (flat shaded, with the lines);
+// with alt each object is a node carrying its transform instead of being baked in
+//
+const GLTF_OPTIONS: GltfExportOptions = GltfExportOptions {
+    flat_normals: true,
+    include_lines: true,
+};
+
+fn export_gltf(editor_state: &mut EditorState, mesh: &MeshData, is_separate: bool) {
+    let options = GLTF_OPTIONS;
+    let (path, result) = if is_separate {
+        (GLTF_PATH, mesh.save_gltf(Path::new(GLTF_PATH), options))
+    } else {
+        (GLB_PATH, mesh.save_glb(Path::new(GLB_PATH), options))
+    };
+    match result {
+        Ok(()) => editor_state.set_status_message(format!("Exported {}", path)),
+        Err(error) => editor_state.set_status_message(format!("glTF export: {}", error)),
+    }
+}
+
+fn export_gltf_scene(editor_state: &mut EditorState, scene: &Scene, is_separate: bool) {
+    let options = GLTF_OPTIONS;
+    let (path, result) = if is_separate {
+        (GLTF_PATH, scene.save_gltf(Path::new(GLTF_PATH), options))
+    } else {
+        (GLB_PATH, scene.save_glb(Path::new(GLB_PATH), options))
+    };
+    match result {
+        Ok(()) => editor_state.set_status_message(format!("Exported {} (scene graph)", path)),
+        Err(error) => editor_state.set_status_message(format!("glTF export: {}", error)),
+    }
+}
+
+//
+// OBJ: meta + O exports (with the UVs, vert colors and a .mtl for the materials),
+// meta + shift + O imports
+//
+fn export_obj(editor_state: &mut EditorState, mesh: &MeshData) {
+    match mesh.save_obj(Path::new(OBJ_PATH)) {
+        Ok(()) => editor_state.set_status_message(format!("Exported {}", OBJ_PATH)),
+        Err(error) => editor_state.set_status_message(format!("OBJ export: {}", error)),
+    }
+}
+
+fn import_obj(editor_state: &mut EditorState, mesh: &mut MeshData) {
+    match MeshData::load_obj(Path::new(OBJ_PATH)) {
+        Ok(imported) => {
+            *mesh = imported;
+            editor_state.set_ply_extras(PlyExtras::default());
+            *editor_state.selection_mut() = Selection::None;
+            editor_state.set_status_message(format!("Imported {}", OBJ_PATH));
+        }
+        Err(error) => editor_state.set_status_message(format!("OBJ import: {}", error)),
+    }
+}
+
+//
+// Native (every object and everything its mesh holds): meta + S saves the scene,
+// meta + shift + S loads it
+//
+fn save_scene(editor_state: &mut EditorState, scene: &Scene) {
+    match scene.save_native(Path::new(SCENE_PATH)) {
+        Ok(()) => editor_state.set_status_message(format!("Saved {}", SCENE_PATH)),
+        Err(error) => editor_state.set_status_message(format!("Save: {}", error)),
+    }
+}
+
+fn load_scene(editor_state: &mut EditorState, scene: &mut Scene) {
+    match Scene::load_native(Path::new(SCENE_PATH)) {
+        Ok(loaded) => {
+            *scene = loaded;
+            editor_state.set_ply_extras(PlyExtras::default());
+            *editor_state.selection_mut() = Selection::None;
+            editor_state.set_status_message(format!("Loaded {}", SCENE_PATH));
+        }
+        Err(error) => editor_state.set_status_message(format!("Load: {}", error)),
+    }
+}
+
+//
+// SVG: meta + V writes a blueprint of each 2D pane as seen in it (mesh_xz.svg, mesh_yz.svg,
+// mesh_xy.svg), hidden lines removed and dimensioned; meta + shift + V fills the polys
+//
+fn export_svg(editor_state: &mut EditorState, mesh: &MeshData, is_filled: bool) {
+    let panels = [
+        *editor_state.panel_state_xz(),
+        *editor_state.panel_state_yz(),
+        *editor_state.panel_state_xy(),
+    ];
+    for panel in panels {
+        let (plane, path) = match panel.viewing_plane() {
+            PanelViewingPlane::XZ => (ProjectionPlane::XZ, "mesh_xz.svg"),
+            PanelViewingPlane::YZ => (ProjectionPlane::YZ, "mesh_yz.svg"),
+            PanelViewingPlane::XY => (ProjectionPlane::XY, "mesh_xy.svg"),
+        };
+        let options = SvgExportOptions {
+            plane,
+            is_flipped: panel.is_flipped(),
+            fill_polys: is_filled,
+            hidden_line_removal: true,
+            dimension_frame: true,
+            scale: SVG_SCALE,
+        };
+        if let Err(error) = mesh.save_svg(Path::new(path), &options) {
+            editor_state.set_status_message(format!("SVG export: {}", error));
+            return;
+        }
+    }
+    editor_state.set_status_message(String::from(
+        "Exported mesh_xz.svg, mesh_yz.svg, mesh_xy.svg",
+    ));
+}
@@ -6,9 +6,9 @@ use crate::editor_state::*;
 use macroquad::prelude::*;
 
 pub struct ModifierKeys {
-    shift_key: bool,
-    meta_key: bool, // both command or ctrl
-    alt_key: bool,
+    pub shift_key: bool,
+    pub meta_key: bool, // both command or ctrl
+    pub alt_key: bool,
 }
 
 pub fn handle_global_keyboard_commands(editor_state: &mut EditorState) {
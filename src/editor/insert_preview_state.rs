@@ -2,6 +2,7 @@
 // insert_preview_state -> state type for insert preview
 //
 use macroquad::prelude::*;
+#[allow(unused_imports)]
 use mesh_editor::mesh::{Line, LineIndex, Poly, PolyIndex, VertIndex};
 
 pub struct InsertPreview {
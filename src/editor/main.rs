@@ -3,15 +3,19 @@
 //
 
 use macroquad::prelude::*;
+#[allow(unused_imports)]
 use mesh_editor::mesh::{Mesh as MeshData, *};
+use mesh_editor::scene::*;
 
 mod editor_state;
 use editor_state::*;
 
 mod editor_panel_state;
+#[allow(unused_imports)]
 use editor_panel_state::*;
 
 mod insert_preview_state;
+#[allow(unused_imports)]
 use insert_preview_state::*;
 
 mod status_text;
@@ -30,44 +34,72 @@ mod viewer_commands;
 use viewer_commands::*;
 
 mod viewer_selection;
+#[allow(unused_imports)]
 use viewer_selection::*;
 
 mod screen_to_world;
+#[allow(unused_imports)]
 use screen_to_world::*;
 
+mod mesh_commands;
+use mesh_commands::*;
+
+mod file_commands;
+use file_commands::*;
+
+mod scene_commands;
+use scene_commands::*;
+
 #[macroquad::main("Mesh Editor")]
 async fn main() {
-    let mut current_mesh = MeshData::new_tapered_box();
+    let mut scene = Scene::with_object("Object", MeshData::new_tapered_box());
     let mut editor_state = EditorState::new();
+    let uv_checker = uv_checker_texture();
 
     loop {
         let panes = Panes::calc_from_screen_dims();
 
         handle_global_keyboard_commands(&mut editor_state);
-        handle_viewer_commands(&mut editor_state, &current_mesh, &panes);
+        handle_viewer_commands(&mut editor_state, scene.active_object(), &panes);
+        handle_mesh_commands(&mut editor_state, scene.active_mesh_mut());
+        handle_scene_commands(&mut editor_state, &mut scene);
+        handle_file_commands(&mut editor_state, &mut scene);
+        let current_mesh = scene.active_mesh();
+
+        let overlay = ViewportOverlay {
+            preview_mesh: subdivision_preview(&editor_state, current_mesh),
+            issues: validation_issues(&editor_state, current_mesh),
+            insert_mesh: primitive_preview(&editor_state),
+            guide_lines: bisect_guide_lines(&editor_state),
+            uv_checker: editor_state.show_uv_checker().then(|| uv_checker.clone()),
+        };
 
         clear_background(BLACK);
 
         if *editor_state.viewer_mode() == ViewerMode::EditorPanels {
             render_editor_pane_viewport(
                 editor_state.panel_state_xz(),
-                &current_mesh,
+                &scene,
+                &overlay,
                 panes.left_viewport(),
             );
             render_editor_pane_viewport(
                 editor_state.panel_state_yz(),
-                &current_mesh,
+                &scene,
+                &overlay,
                 panes.top_right_viewport(),
             );
             render_editor_pane_viewport(
                 editor_state.panel_state_xy(),
-                &current_mesh,
+                &scene,
+                &overlay,
                 panes.bottom_right_viewport(),
             );
         } else {
             render_editor_pane_viewport(
                 editor_state.panel_state_rotate_cam(),
-                &current_mesh,
+                &scene,
+                &overlay,
                 panes.full_content_viewport(),
             );
         }
@@ -77,7 +109,7 @@ async fn main() {
         } else {
             panes.draw_bottom_border();
         }
-        draw_status_text(&editor_state, &current_mesh);
+        draw_status_text(&editor_state, &scene, &overlay);
 
         next_frame().await
     }
@@ -0,0 +1,804 @@
+//
+// mesh_commands -> keypresses that edit the mesh or its display preview
+//
+
+use crate::editor_state::*;
+use crate::global_commands::*;
+use itertools::Itertools;
+use macroquad::prelude::*;
+use mesh_editor::mesh::{Mesh as MeshData, *};
+
+const MAX_SUBDIVISION_PREVIEW_LEVEL: usize = 4;
+const LOOP_CUT_POSITION: f32 = 0.5;
+const MERGE_DISTANCE: f32 = 0.001;
+const PRIMITIVE_RESIZE_FACTOR: f32 = 1.25;
+const LATHE_STEPS: usize = 16;
+const BISECT_GUIDE_EXTENT: f32 = 10.0;
+const DECIMATE_RATIO: f32 = 0.5;
+const SMOOTHING_STRENGTH: f32 = 0.5;
+const SMOOTHING_ITERATIONS: usize = 5;
+const SELECTION_SET_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+pub fn handle_mesh_commands(editor_state: &mut EditorState, mesh: &mut MeshData) {
+    let modifiers = check_modifier_keys();
+
+    handle_subdivision_commands(editor_state, mesh, &modifiers);
+    handle_loop_commands(editor_state, mesh, &modifiers);
+
+    if is_key_pressed(KeyCode::M) && modifiers.meta_key {
+        merge_by_distance(editor_state, mesh);
+    }
+
+    handle_validation_commands(editor_state, mesh, &modifiers);
+    handle_winding_commands(editor_state, mesh, &modifiers);
+    handle_primitive_commands(editor_state, mesh, &modifiers);
+    handle_lathe_commands(editor_state, mesh, &modifiers);
+    handle_fill_commands(editor_state, mesh, &modifiers);
+    handle_bisect_commands(editor_state, mesh, &modifiers);
+
+    handle_boolean_commands(editor_state, mesh, &modifiers);
+
+    if is_key_pressed(KeyCode::H) && !modifiers.meta_key {
+        add_convex_hull(editor_state, mesh);
+    }
+
+    if is_key_pressed(KeyCode::D) && modifiers.meta_key {
+        decimate(editor_state, mesh);
+    }
+
+    if is_key_pressed(KeyCode::S) && !modifiers.meta_key {
+        smooth_selection(editor_state, mesh, modifiers.shift_key);
+    }
+
+    handle_uv_commands(editor_state, mesh, &modifiers);
+    handle_paint_commands(editor_state, mesh, &modifiers);
+    handle_material_commands(editor_state, mesh, &modifiers);
+    handle_selection_set_commands(editor_state, mesh, &modifiers);
+}
+
+//
+// Subdivision: [ / ] change the preview level, \ toggles the mode,
+// meta + ] applies one level to the mesh itself
+//
+fn handle_subdivision_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    if is_key_pressed(KeyCode::Backslash) {
+        editor_state.toggle_subdivision_mode();
+    }
+
+    if is_key_pressed(KeyCode::RightBracket) {
+        if modifiers.meta_key {
+            apply_subdivision(editor_state, mesh);
+        } else {
+            let level = editor_state.subdivision_preview_level_mut();
+            *level = (*level + 1).min(MAX_SUBDIVISION_PREVIEW_LEVEL);
+        }
+    }
+
+    if is_key_pressed(KeyCode::LeftBracket) {
+        let level = editor_state.subdivision_preview_level_mut();
+        *level = level.saturating_sub(1);
+    }
+}
+
+fn apply_subdivision(editor_state: &mut EditorState, mesh: &mut MeshData) {
+    let mode = editor_state.subdivision_mode();
+    let creases = selected_lines(editor_state.selection());
+    let new_creases = mesh.subdivide(mode, &creases);
+
+    *editor_state.selection_mut() = if new_creases.is_empty() {
+        Selection::None
+    } else {
+        Selection::Lines(new_creases)
+    };
+}
+
+//
+// Preview mesh for the viewport, None when no preview level is set
+//
+pub fn subdivision_preview(editor_state: &EditorState, mesh: &MeshData) -> Option<MeshData> {
+    let level = editor_state.subdivision_preview_level();
+    if level == 0 {
+        return None;
+    }
+    let creases = selected_lines(editor_state.selection());
+    Some(mesh.subdivided(editor_state.subdivision_mode(), level, &creases))
+}
+
+//
+// Loops: L selects the edge loop, shift + L the edge ring, meta + R inserts a loop cut
+// (all starting from the first selected line, or the first two selected verts)
+//
+fn handle_loop_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    let Some(start) = selected_start_edge(editor_state.selection(), mesh) else {
+        return;
+    };
+
+    let new_selection = if is_key_pressed(KeyCode::L) {
+        if modifiers.shift_key {
+            mesh.select_edge_ring(start)
+        } else {
+            mesh.select_edge_loop(start)
+        }
+    } else if is_key_pressed(KeyCode::R) && modifiers.meta_key {
+        mesh.loop_cut(start, LOOP_CUT_POSITION)
+    } else {
+        return;
+    };
+
+    if !new_selection.is_empty() {
+        *editor_state.selection_mut() = Selection::Lines(new_selection);
+    }
+}
+
+//
+// Merge: meta + M welds verts closer than MERGE_DISTANCE
+//
+fn merge_by_distance(editor_state: &mut EditorState, mesh: &mut MeshData) {
+    let removed_count = mesh.merge_by_distance(MERGE_DISTANCE);
+    if removed_count > 0 {
+        *editor_state.selection_mut() = Selection::None;
+    }
+    editor_state.set_status_message(format!("Merged {} verts", removed_count));
+}
+
+//
+// Validation: F5 toggles the issue overlay, meta + F5 repairs what can be fixed safely
+//
+fn handle_validation_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    if !is_key_pressed(KeyCode::F5) {
+        return;
+    }
+
+    if modifiers.meta_key {
+        let report = mesh.repair();
+        *editor_state.selection_mut() = Selection::None;
+        editor_state.set_status_message(format!(
+            "Repaired: -{} verts -{} lines -{} polys, {} issues left",
+            report.removed_verts,
+            report.removed_lines,
+            report.removed_polys,
+            report.remaining.len()
+        ));
+    } else {
+        editor_state.toggle_validation_overlay();
+    }
+}
+
+//
+// Issues for the overlay, empty when the overlay is hidden
+//
+pub fn validation_issues(editor_state: &EditorState, mesh: &MeshData) -> Vec<MeshIssue> {
+    if !editor_state.show_validation_overlay() {
+        return Vec::new();
+    }
+    mesh.validate()
+}
+
+//
+// Winding: N flips the selected polys, shift + N recalculates winding outward for the mesh
+//
+fn handle_winding_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    if !is_key_pressed(KeyCode::N) {
+        return;
+    }
+
+    if modifiers.shift_key {
+        let flipped_count = mesh.orient_outward();
+        editor_state.set_status_message(format!("Reoriented {} polys", flipped_count));
+    } else {
+        let polys = selected_polys(editor_state.selection(), mesh);
+        mesh.flip_polys(&polys);
+        editor_state.set_status_message(format!("Flipped {} polys", polys.len()));
+    }
+}
+
+//
+// Primitives: P starts inserting a primitive at the insert cursor (or cycles the shape),
+// = / - add / remove segments, shift + = / - grow / shrink, Enter adds it, Escape cancels
+//
+fn handle_primitive_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    if is_key_pressed(KeyCode::P) && !modifiers.meta_key {
+        let primitive = next_primitive(editor_state.primitive_insert());
+        editor_state.set_status_message(format!("Add {}", primitive.name()));
+        editor_state.set_primitive_insert(Some(primitive));
+    }
+
+    let Some(&primitive) = editor_state.primitive_insert() else {
+        return;
+    };
+
+    let adjusted = if is_key_pressed(KeyCode::Equal) {
+        Some(if modifiers.shift_key {
+            primitive.resized(PRIMITIVE_RESIZE_FACTOR)
+        } else {
+            primitive.refined(1)
+        })
+    } else if is_key_pressed(KeyCode::Minus) {
+        Some(if modifiers.shift_key {
+            primitive.resized(1.0 / PRIMITIVE_RESIZE_FACTOR)
+        } else {
+            primitive.refined(-1)
+        })
+    } else {
+        None
+    };
+    if let Some(adjusted) = adjusted {
+        editor_state.set_primitive_insert(Some(adjusted));
+    }
+
+    if is_key_pressed(KeyCode::Enter) {
+        let new_verts = mesh.append(&primitive.build(), insert_cursor(editor_state));
+        editor_state.set_status_message(format!("Added {}", primitive.name()));
+        *editor_state.selection_mut() = Selection::Verticies(new_verts);
+        editor_state.set_primitive_insert(None);
+    } else if is_key_pressed(KeyCode::Escape) {
+        editor_state.set_primitive_insert(None);
+    }
+}
+
+// default of the shape after the current one, the first shape when not inserting yet
+fn next_primitive(current: Option<&Primitive>) -> Primitive {
+    let defaults = Primitive::defaults();
+    let next_index = current
+        .and_then(|current| {
+            defaults
+                .iter()
+                .position(|p| std::mem::discriminant(p) == std::mem::discriminant(current))
+        })
+        .map_or(0, |i| (i + 1) % defaults.len());
+    defaults[next_index]
+}
+
+fn insert_cursor(editor_state: &EditorState) -> Vec3 {
+    editor_state
+        .insert_preview()
+        .vert()
+        .copied()
+        .unwrap_or(Vec3::ZERO)
+}
+
+//
+// Primitive being inserted, placed at the insert cursor
+//
+pub fn primitive_preview(editor_state: &EditorState) -> Option<MeshData> {
+    let primitive = editor_state.primitive_insert()?;
+    let mut preview = MeshData::new();
+    preview.append(&primitive.build(), insert_cursor(editor_state));
+    Some(preview)
+}
+
+//
+// Lathe: T revolves the selected vert chain around the lathe axis, meta + T also caps
+// the open ends, shift + T cycles the axis
+//
+fn handle_lathe_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    if !is_key_pressed(KeyCode::T) {
+        return;
+    }
+
+    if modifiers.shift_key {
+        editor_state.cycle_lathe_axis();
+        editor_state.set_status_message(format!("Lathe axis {}", editor_state.lathe_axis()));
+        return;
+    }
+
+    let Selection::Verticies(verts) = editor_state.selection() else {
+        editor_state.set_status_message(String::from("Lathe needs a vert chain"));
+        return;
+    };
+    let axis = editor_state.lathe_axis();
+    let lathed = mesh
+        .vert_chain(verts)
+        .and_then(|chain| mesh.lathe(&chain, axis, LATHE_STEPS, modifiers.meta_key));
+
+    match lathed {
+        Ok(lathed) => {
+            let new_verts = mesh.append(&lathed, Vec3::ZERO);
+            editor_state.set_status_message(format!("Lathed around {}", axis));
+            *editor_state.selection_mut() = Selection::Verticies(new_verts);
+        }
+        Err(error) => editor_state.set_status_message(format!("Lathe: {}", error)),
+    }
+}
+
+//
+// Fill: F fills the boundary loop under the selection with an n-gon, shift + F with
+// triangles, B bridges the two boundary loops under the selection
+//
+fn handle_fill_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    let fill_pressed = is_key_pressed(KeyCode::F);
+    let bridge_pressed = is_key_pressed(KeyCode::B);
+    if !fill_pressed && !bridge_pressed {
+        return;
+    }
+
+    let loops = mesh.boundary_loops_touching(&selected_verts(editor_state.selection(), mesh));
+    let result = match (fill_pressed, loops.as_slice()) {
+        (true, [hole]) => {
+            let mode = if modifiers.shift_key {
+                FillMode::Triangles
+            } else {
+                FillMode::NGon
+            };
+            mesh.fill_loop(hole, mode)
+        }
+        (false, [first, second]) => mesh.bridge_loops(first, second),
+        _ => {
+            let needed = if fill_pressed { 1 } else { 2 };
+            editor_state.set_status_message(format!(
+                "Select {} boundary loop(s), found {}",
+                needed,
+                loops.len()
+            ));
+            return;
+        }
+    };
+
+    match result {
+        Ok(new_polys) => {
+            editor_state.set_status_message(format!("Added {} polys", new_polys.len()));
+            *editor_state.selection_mut() = Selection::Polys(new_polys);
+        }
+        Err(error) => editor_state.set_status_message(format!("Fill: {}", error)),
+    }
+}
+
+//
+// Bisect (plane from alt + dragging a line in a 2D pane): K cuts keeping both sides,
+// shift + K keeps the front side and fills the cut, meta + K fills and separates both
+// halves, Escape drops the line
+//
+fn handle_bisect_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    if is_key_pressed(KeyCode::Escape) {
+        editor_state.set_bisect_line(None);
+    }
+    if !is_key_pressed(KeyCode::K) {
+        return;
+    }
+
+    let Some(plane) = editor_state.bisect_line().and_then(|line| line.plane()) else {
+        editor_state.set_status_message(String::from("Bisect: alt + drag a line first"));
+        return;
+    };
+    let (keep, fill_cut) = if modifiers.shift_key {
+        (BisectKeep::Front, true)
+    } else {
+        (BisectKeep::Both, modifiers.meta_key)
+    };
+
+    let cut_lines = mesh.bisect(plane, keep, fill_cut);
+    editor_state.set_status_message(format!("Cut {} lines", cut_lines.len()));
+    *editor_state.selection_mut() = Selection::Lines(cut_lines);
+    editor_state.set_bisect_line(None);
+}
+
+//
+// Outline of the bisect plane around the dragged line (seen edge-on in its own pane)
+//
+pub fn bisect_guide_lines(editor_state: &EditorState) -> Vec<(Vec3, Vec3)> {
+    let Some(line) = editor_state.bisect_line() else {
+        return Vec::new();
+    };
+    let depth = line.view_axis * BISECT_GUIDE_EXTENT;
+    let corners = [
+        line.start - depth,
+        line.end - depth,
+        line.end + depth,
+        line.start + depth,
+    ];
+    (0..4).map(|i| (corners[i], corners[(i + 1) % 4])).collect()
+}
+
+//
+// Booleans: U unions the two parts (connected components) the selection touches,
+// shift + U subtracts the second from the first, alt + U keeps their intersection
+//
+fn handle_boolean_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    if !is_key_pressed(KeyCode::U) || modifiers.meta_key {
+        return;
+    }
+
+    let op = if modifiers.shift_key {
+        BooleanOp::Difference
+    } else if modifiers.alt_key {
+        BooleanOp::Intersection
+    } else {
+        BooleanOp::Union
+    };
+
+    // parts in the order their first vert was selected
+    let components = mesh.connected_components();
+    let touched: Vec<&Vec<PolyIndex>> = selected_verts(editor_state.selection(), mesh)
+        .into_iter()
+        .filter_map(|v| {
+            components
+                .iter()
+                .find(|component| component.iter().any(|&p| mesh.polys()[p].contains(&v)))
+        })
+        .unique()
+        .collect();
+    let [first, second] = touched[..] else {
+        editor_state.set_status_message(String::from("Boolean: select verts on two parts"));
+        return;
+    };
+
+    match mesh.boolean_polys(first, second, op) {
+        Ok(new_polys) => {
+            editor_state.set_status_message(format!("{:?}: {} polys", op, new_polys.len()));
+            *editor_state.selection_mut() = Selection::Polys(new_polys);
+        }
+        Err(error) => editor_state.set_status_message(format!("Boolean: {}", error)),
+    }
+}
+
+//
+// Convex hull: H adds the hull around the selected verts (every vert with nothing selected)
+//
+fn add_convex_hull(editor_state: &mut EditorState, mesh: &mut MeshData) {
+    let mut verts = selected_verts(editor_state.selection(), mesh);
+    if verts.is_empty() {
+        verts = (0..mesh.verts().len()).collect();
+    }
+
+    match mesh.convex_hull(&verts) {
+        Ok(hull) => {
+            let first_new_poly = mesh.polys().len();
+            mesh.append(&hull, Vec3::ZERO);
+            let new_polys: Vec<PolyIndex> = (first_new_poly..mesh.polys().len()).collect();
+            editor_state.set_status_message(format!("Hull: {} polys", new_polys.len()));
+            *editor_state.selection_mut() = Selection::Polys(new_polys);
+        }
+        Err(error) => editor_state.set_status_message(format!("Hull: {}", error)),
+    }
+}
+
+//
+// Decimate: meta + D halves the poly count, keeping the mesh's mirror symmetry
+//
+fn decimate(editor_state: &mut EditorState, mesh: &mut MeshData) {
+    let target_count = (mesh.polys().len() as f32 * DECIMATE_RATIO) as usize;
+    let report = mesh.decimate(DecimateTarget::FaceCount(target_count), true);
+    editor_state.set_status_message(format!("Decimated: {}", report));
+    *editor_state.selection_mut() = Selection::None;
+}
+
+//
+// Smoothing: S smooths the selected verts (laplacian), shift + S without shrinking (taubin)
+//
+fn smooth_selection(editor_state: &mut EditorState, mesh: &mut MeshData, keep_volume: bool) {
+    let mode = if keep_volume {
+        SmoothingMode::Taubin
+    } else {
+        SmoothingMode::Laplacian
+    };
+    let verts = selected_verts(editor_state.selection(), mesh);
+    let moved_count =
+        mesh.smooth_verts(&verts, &[], mode, SMOOTHING_STRENGTH, SMOOTHING_ITERATIONS);
+    editor_state.set_status_message(format!("Smoothed {} verts ({:?})", moved_count, mode));
+}
+
+//
+// UVs: J projects the selected polys (all polys without a selection) flat as the XY pane
+// shows them, shift + J as the XZ pane, alt + J as the YZ pane; O box projects,
+// shift + O wraps them around the lathe axis; F6 toggles the checker preview
+//
+fn handle_uv_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    if is_key_pressed(KeyCode::F6) {
+        editor_state.toggle_uv_checker();
+        return;
+    }
+    let project_planar = is_key_pressed(KeyCode::J);
+    let project_box = is_key_pressed(KeyCode::O) && !modifiers.meta_key;
+    if !project_planar && !project_box {
+        return;
+    }
+
+    let mut polys = selected_polys(editor_state.selection(), mesh);
+    if polys.is_empty() {
+        polys = (0..mesh.polys().len()).collect();
+    }
+    let projection = if project_planar {
+        let (plane, panel) = if modifiers.shift_key {
+            (ProjectionPlane::XZ, editor_state.panel_state_xz())
+        } else if modifiers.alt_key {
+            (ProjectionPlane::YZ, editor_state.panel_state_yz())
+        } else {
+            (ProjectionPlane::XY, editor_state.panel_state_xy())
+        };
+        mesh.project_uvs_planar(&polys, plane, panel.is_flipped());
+        format!("planar {:?}", plane)
+    } else if modifiers.shift_key {
+        let axis = editor_state.lathe_axis();
+        mesh.project_uvs_cylindrical(&polys, axis);
+        format!("cylindrical around {}", axis)
+    } else {
+        mesh.project_uvs_box(&polys);
+        String::from("box")
+    };
+    editor_state.set_status_message(format!("UVs of {} polys ({})", polys.len(), projection));
+}
+
+//
+// Paint: C paints the selected polys (or verts, when verts / lines are selected) with the
+// paint color, alt + C erases their color, shift + C cycles the paint color
+//
+fn handle_paint_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    if !is_key_pressed(KeyCode::C) || modifiers.meta_key {
+        return;
+    }
+    if modifiers.shift_key {
+        editor_state.cycle_paint_color();
+        let name = editor_state.paint_color_name();
+        editor_state.set_status_message(format!("Paint color: {}", name));
+        return;
+    }
+
+    let (color, action) = match modifiers.alt_key {
+        true => (None, String::from("Erased")),
+        false => (
+            Some(editor_state.paint_color()),
+            format!("Painted ({})", editor_state.paint_color_name()),
+        ),
+    };
+    let message = match editor_state.selection() {
+        Selection::Polys(polys) => {
+            let painted_count = mesh.paint_polys(polys, color);
+            format!("{} {} polys", action, painted_count)
+        }
+        selection => {
+            let verts = selected_verts(selection, mesh);
+            let painted_count = mesh.paint_verts(&verts, color);
+            format!("{} {} verts", action, painted_count)
+        }
+    };
+    editor_state.set_status_message(message);
+}
+
+//
+// Materials: Q assigns the selected polys to the active material (adding one when the mesh
+// has none), shift + Q makes the next slot active (a new one after the last, unless that is
+// still unused), alt + Q selects the polys of the active material
+//
+fn handle_material_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    if !is_key_pressed(KeyCode::Q) || modifiers.meta_key {
+        return;
+    }
+    let material_count = mesh.materials().len();
+    let active = editor_state
+        .active_material()
+        .min(material_count.saturating_sub(1));
+
+    if modifiers.shift_key {
+        let last_is_used = material_count > 0
+            && !mesh
+                .polys_with_material(Some(material_count - 1))
+                .is_empty();
+        let next = if active + 1 < material_count || last_is_used {
+            active + 1
+        } else {
+            0
+        };
+        let next = match next < material_count {
+            true => next,
+            false => mesh.add_material(&format!("Material {}", next + 1)),
+        };
+        editor_state.set_active_material(next);
+        let name = &mesh.materials()[next].name;
+        editor_state.set_status_message(format!("Material: {}", name));
+        return;
+    }
+
+    if modifiers.alt_key {
+        if active >= material_count {
+            editor_state.set_status_message(String::from("Material: the mesh has none"));
+            return;
+        }
+        let polys = mesh.polys_with_material(Some(active));
+        let name = &mesh.materials()[active].name;
+        editor_state.set_status_message(format!("Selected {} polys of {}", polys.len(), name));
+        *editor_state.selection_mut() = Selection::Polys(polys);
+        return;
+    }
+
+    let polys = selected_polys(editor_state.selection(), mesh);
+    if polys.is_empty() {
+        editor_state.set_status_message(String::from("Material: select polys to assign"));
+        return;
+    }
+    let material = match active < material_count {
+        true => active,
+        false => mesh.add_material(&format!("Material {}", material_count + 1)),
+    };
+    editor_state.set_active_material(material);
+    let assigned_count = mesh.assign_material(&polys, Some(material));
+    let name = &mesh.materials()[material].name;
+    editor_state.set_status_message(format!("Assigned {} polys to {}", assigned_count, name));
+}
+
+//
+// Selection sets: meta + 1..9 saves the selection as "Set N" (an empty selection removes
+// it), 1..9 recalls the set, shift + 1..9 adds it to the selection, alt + 1..9 subtracts it
+//
+fn handle_selection_set_commands(
+    editor_state: &mut EditorState,
+    mesh: &mut MeshData,
+    modifiers: &ModifierKeys,
+) {
+    let Some(number) = SELECTION_SET_KEYS
+        .iter()
+        .position(|&key| is_key_pressed(key))
+        .map(|position| position + 1)
+    else {
+        return;
+    };
+    let name = format!("Set {}", number);
+
+    if modifiers.meta_key {
+        let message = match selection_parts(editor_state.selection()) {
+            Some((kind, indicies)) => {
+                let saved_count = mesh.save_selection_set(&name, kind, &indicies);
+                format!("Saved {} {} as {}", saved_count, kind, name)
+            }
+            None => match mesh.remove_selection_set(&name) {
+                Some(_) => format!("Removed {}", name),
+                None => format!("{}: select something to save", name),
+            },
+        };
+        editor_state.set_status_message(message);
+        return;
+    }
+
+    let Some(set) = mesh.selection_set(&name) else {
+        editor_state.set_status_message(format!("{} is empty", name));
+        return;
+    };
+    let current = selection_parts(editor_state.selection());
+    let same_kind = current.as_ref().is_some_and(|(kind, _)| *kind == set.kind);
+    let (indicies, action): (Vec<usize>, &str) = match (current, same_kind) {
+        (Some((_, current)), true) if modifiers.shift_key => (
+            current
+                .into_iter()
+                .chain(set.indicies.clone())
+                .unique()
+                .collect(),
+            "Added",
+        ),
+        (Some((_, current)), true) if modifiers.alt_key => (
+            current
+                .into_iter()
+                .filter(|i| !set.indicies.contains(i))
+                .collect(),
+            "Subtracted",
+        ),
+        (_, false) if modifiers.alt_key => {
+            editor_state
+                .set_status_message(format!("{}: select {} to subtract from", name, set.kind));
+            return;
+        }
+        _ => (set.indicies.clone(), "Recalled"),
+    };
+    let message = format!("{} {} ({} {})", action, name, set.indicies.len(), set.kind);
+    *editor_state.selection_mut() = selection_from_parts(set.kind, indicies);
+    editor_state.set_status_message(message);
+}
+
+// kind and indicies of the selection, None when nothing is selected
+fn selection_parts(selection: &Selection) -> Option<(SetKind, Vec<usize>)> {
+    let (kind, indicies) = match selection {
+        Selection::None => return None,
+        Selection::Verticies(verts) => (SetKind::Verts, verts),
+        Selection::Lines(lines) => (SetKind::Lines, lines),
+        Selection::Polys(polys) => (SetKind::Polys, polys),
+    };
+    (!indicies.is_empty()).then(|| (kind, indicies.clone()))
+}
+
+fn selection_from_parts(kind: SetKind, indicies: Vec<usize>) -> Selection {
+    if indicies.is_empty() {
+        return Selection::None;
+    }
+    match kind {
+        SetKind::Verts => Selection::Verticies(indicies),
+        SetKind::Lines => Selection::Lines(indicies),
+        SetKind::Polys => Selection::Polys(indicies),
+    }
+}
+
+fn selected_verts(selection: &Selection, mesh: &MeshData) -> Vec<VertIndex> {
+    match selection {
+        Selection::Verticies(verts) => verts.clone(),
+        Selection::Lines(lines) => lines
+            .iter()
+            .flat_map(|&line_index| {
+                let (a, b) = mesh.lines()[line_index];
+                [a, b]
+            })
+            .collect(),
+        Selection::Polys(polys) => polys
+            .iter()
+            .flat_map(|&poly_index| mesh.polys()[poly_index].clone())
+            .collect(),
+        Selection::None => Vec::new(),
+    }
+}
+
+pub fn selected_polys(selection: &Selection, mesh: &MeshData) -> Vec<PolyIndex> {
+    match selection {
+        Selection::Polys(polys) => polys.clone(),
+        Selection::Verticies(verts) => mesh.polys_in_vertex_indicies(verts.clone()),
+        _ => Vec::new(),
+    }
+}
+
+fn selected_start_edge(selection: &Selection, mesh: &MeshData) -> Option<Line> {
+    match selection {
+        Selection::Lines(lines) => mesh.lines().get(*lines.first()?).copied(),
+        Selection::Verticies(verts) if verts.len() >= 2 => Some((verts[0], verts[1])),
+        _ => None,
+    }
+}
+
+fn selected_lines(selection: &Selection) -> Vec<LineIndex> {
+    match selection {
+        Selection::Lines(lines) => lines.clone(),
+        _ => Vec::new(),
+    }
+}
@@ -211,6 +211,7 @@ impl Panes {
         rect_to_viewport(self.full_content)
     }
 
+    #[allow(private_interfaces)]
     pub fn bottom_border(&self) -> &BorderLine {
         &self.bottom_border
     }
@@ -5,7 +5,8 @@
 use crate::editor_panel_state::*;
 use crate::panes::Viewport;
 use macroquad::prelude::{Mesh as MacroMesh, *};
-use mesh_editor::mesh::Mesh as MeshData;
+use mesh_editor::mesh::{Mesh as MeshData, *};
+use mesh_editor::scene::*;
 
 pub trait PanelCameraVectors {
     fn to_target_vec(&self) -> Vec3;
@@ -15,9 +16,46 @@ pub trait PanelCameraVectors {
     fn distance(&self) -> f32;
 }
 
+//
+// Extra per-frame display data drawn on top of / instead of parts of the mesh
+//
+pub struct ViewportOverlay {
+    pub preview_mesh: Option<MeshData>,
+    pub issues: Vec<MeshIssue>,
+    pub insert_mesh: Option<MeshData>,
+    pub guide_lines: Vec<(Vec3, Vec3)>,
+    // textures the polys by their UVs when set
+    pub uv_checker: Option<Texture2D>,
+}
+
+//
+// Checker texture for previewing UVs: 8 x 8 cells over 0..1
+//
+pub fn uv_checker_texture() -> Texture2D {
+    let cells = 8;
+    let cell_size = 32;
+    let size = (cells * cell_size) as u16;
+    let mut image = Image::gen_image_color(size, size, WHITE);
+    for y in 0..size as u32 {
+        for x in 0..size as u32 {
+            if (x / cell_size + y / cell_size) % 2 == 1 {
+                image.set_pixel(x, y, DARKGRAY);
+            }
+        }
+    }
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Nearest);
+    texture
+}
+
+//
+// Renders the scene: the active object with its overlay, the others dimmed behind it, each
+// placed by its own model matrix (the overlay is in the active object's space)
+//
 pub fn render_editor_pane_viewport(
     panel_state: &impl PanelCameraVectors,
-    mesh: &MeshData,
+    scene: &Scene,
+    overlay: &ViewportOverlay,
     viewport: Viewport,
 ) {
     let aspect = (viewport.2 as f32) / (viewport.3 as f32);
@@ -27,7 +65,7 @@ pub fn render_editor_pane_viewport(
         position: panel_state.to_camera_pos_vec(),
         target: panel_state.to_target_vec(),
         up: panel_state.to_up_vec(),
-        fovy: fovy,
+        fovy,
         projection: Projection::Orthographics,
         viewport: Some(viewport),
         render_target: None,
@@ -41,13 +79,54 @@ pub fn render_editor_pane_viewport(
     let rotation = panel_state.to_model_rotation();
     let rotation_matrix = Mat4::from_euler(EulerRot::XYZ, rotation.x, rotation.y, rotation.z);
 
-    push_model_matrix(rotation_matrix);
-    render_mesh(mesh);
+    let view_dir = panel_state.to_target_vec() - panel_state.to_camera_pos_vec();
+
+    for (index, object) in scene.objects().iter().enumerate() {
+        if index == scene.active_index() {
+            continue;
+        }
+        let model_matrix = rotation_matrix * object.transform.matrix();
+        push_model_matrix(model_matrix);
+        render_inactive_mesh(&object.mesh, model_view_dir(model_matrix, view_dir));
+        pop_model_matrix();
+    }
+
+    let active = scene.active_object();
+    let mesh = &active.mesh;
+    let model_matrix = rotation_matrix * active.transform.matrix();
+    let model_view_dir = model_view_dir(model_matrix, view_dir);
+    push_model_matrix(model_matrix);
+    // surface comes from the preview (e.g. subdivided) mesh, cage from the mesh itself
+    render_mesh(
+        overlay.preview_mesh.as_ref().unwrap_or(mesh),
+        model_view_dir,
+        overlay.uv_checker.as_ref(),
+        false,
+    );
     render_lines(mesh);
     render_points(mesh);
+    render_issues(mesh, &overlay.issues);
+    if let Some(insert_mesh) = &overlay.insert_mesh {
+        render_insert_mesh(insert_mesh);
+    }
+    for (v1, v2) in overlay.guide_lines.iter() {
+        draw_line_3d(*v1, *v2, YELLOW);
+    }
     pop_model_matrix()
 }
 
+//
+// view direction in model space, used to tell front from back faces (a mirroring model
+// matrix shows the polys from their other side)
+//
+fn model_view_dir(model_matrix: Mat4, view_dir: Vec3) -> Vec3 {
+    let model_view_dir = model_matrix.inverse().transform_vector3(view_dir);
+    match model_matrix.determinant() < 0.0 {
+        true => -model_view_dir,
+        false => model_view_dir,
+    }
+}
+
 fn render_points(mesh: &MeshData) {
     let sphere_color = RED;
     let sphere_radius = 0.05;
@@ -65,46 +144,147 @@ fn render_lines(mesh: &MeshData) {
     }
 }
 
+//
+// Objects other than the active one: dimmed surface and lines, no points
+//
+fn render_inactive_mesh(mesh: &MeshData, view_dir: Vec3) {
+    render_mesh(mesh, view_dir, None, true);
+    for (v1, v2) in mesh.lines_to_vert_pairs() {
+        draw_line_3d(v1, v2, DARKGREEN);
+    }
+}
+
+//
+// Mesh about to be inserted, drawn as a wireframe
+//
+fn render_insert_mesh(mesh: &MeshData) {
+    for (v1, v2) in mesh.lines_to_vert_pairs() {
+        draw_line_3d(v1, v2, SKYBLUE);
+    }
+}
+
+//
+// Validation overlay: problem verts / edges / polys in warning colors
+//
+fn render_issues(mesh: &MeshData, issues: &[MeshIssue]) {
+    let issue_color = ORANGE;
+    let vert_radius = 0.08;
+    let verts = mesh.verts();
+    let draw_edge = |a: VertIndex, b: VertIndex| draw_line_3d(verts[a], verts[b], issue_color);
+    let draw_poly = |poly_index: PolyIndex| {
+        for (a, b) in poly_edges(&mesh.polys()[poly_index]) {
+            draw_line_3d(verts[a], verts[b], MAGENTA);
+        }
+    };
+
+    for issue in issues {
+        match issue {
+            MeshIssue::DegenerateLine(line) | MeshIssue::DuplicateLine { line, .. } => {
+                let (a, b) = mesh.lines()[*line];
+                draw_edge(a, b);
+                draw_sphere(verts[a], vert_radius, None, issue_color);
+            }
+            MeshIssue::NonManifoldEdge { edge, .. }
+            | MeshIssue::InconsistentWinding { edge, .. } => draw_edge(edge.0, edge.1),
+            MeshIssue::DuplicatePoly { poly, .. } | MeshIssue::NonPlanarPoly { poly, .. } => {
+                draw_poly(*poly)
+            }
+            MeshIssue::IsolatedVert(vert) => {
+                draw_sphere(verts[*vert], vert_radius, None, issue_color)
+            }
+            // can't be drawn at a non-finite position
+            MeshIssue::NonFiniteVert(_) => {}
+        }
+    }
+}
+
 //
 // Render mesh in one go zoom zoom
 //
-fn render_mesh(mesh: &MeshData) {
-    let color = GRAY;
-    let mesh = mesh_data_to_macro_mesh(mesh, GRAY);
-    draw_mesh(&mesh);
+// (each poly gets its own verts so it can be colored by facing)
+fn render_mesh(mesh: &MeshData, view_dir: Vec3, texture: Option<&Texture2D>, is_dimmed: bool) {
+    let max_verts_per_mesh = u16::MAX as usize;
+    let mut chunk: Vec<PolyIndex> = Vec::new();
+    let mut chunk_vert_count = 0;
+
+    for (poly_index, poly) in mesh.polys().iter().enumerate() {
+        if chunk_vert_count + poly.len() > max_verts_per_mesh {
+            draw_mesh(&mesh_data_to_macro_mesh(
+                mesh, &chunk, view_dir, texture, is_dimmed,
+            ));
+            chunk.clear();
+            chunk_vert_count = 0;
+        }
+        chunk.push(poly_index);
+        chunk_vert_count += poly.len();
+    }
+    draw_mesh(&mesh_data_to_macro_mesh(
+        mesh, &chunk, view_dir, texture, is_dimmed,
+    ));
 }
 
-fn mesh_data_to_macro_mesh(mesh_data: &MeshData, color: Color) -> MacroMesh {
-    let vertices = mesh_data
-        .verts()
-        .iter()
-        .map(|v| Vertex {
-            position: *v,
-            uv: Vec2::ZERO,
-            color: color.into(),
-            normal: Vec4::ZERO,
-        })
-        .collect();
+fn mesh_data_to_macro_mesh(
+    mesh_data: &MeshData,
+    poly_indicies: &[PolyIndex],
+    view_dir: Vec3,
+    texture: Option<&Texture2D>,
+    is_dimmed: bool,
+) -> MacroMesh {
+    // the texture is tinted by the vertex color, so unpainted textured fronts are white
+    let front_color = if texture.is_some() { WHITE } else { GRAY };
+    let back_color = Color::new(0.45, 0.15, 0.2, 1.0);
+
+    let mut vertices: Vec<Vertex> = Vec::new();
+    let mut indices: Vec<u16> = Vec::new();
 
-    let indices = mesh_data
-        .polys_to_triangle_indicies()
-        .iter()
-        .map(|index| *index as u16)
-        .collect();
+    for &poly_index in poly_indicies {
+        let poly = &mesh_data.polys()[poly_index];
+        let is_back_face = mesh_data.poly_normal(poly_index).dot(view_dir) > 0.0;
+        let corner_color = |corner: usize| {
+            let color = match is_back_face {
+                true => back_color,
+                false => mesh_data
+                    .corner_color(poly_index, corner)
+                    .unwrap_or(front_color),
+            };
+            match is_dimmed {
+                true => dimmed_color(color),
+                false => color,
+            }
+        };
 
-    pub struct Mesh {
-        pub vertices: Vec<Vertex>,
-        pub indices: Vec<u16>,
-        pub texture: Option<Texture2D>,
+        let first_vert = vertices.len() as u16;
+        let uvs = mesh_data.poly_uvs(poly_index);
+        vertices.extend(poly.iter().enumerate().map(|(corner, &vert_index)| Vertex {
+            position: mesh_data.verts()[vert_index],
+            // textures have v pointing down
+            uv: uvs.map_or(Vec2::ZERO, |uvs| vec2(uvs[corner].x, 1.0 - uvs[corner].y)),
+            color: corner_color(corner).into(),
+            normal: Vec4::ZERO,
+        }));
+        for i in 1..(poly.len() as u16).saturating_sub(1) {
+            indices.extend([first_vert, first_vert + i, first_vert + i + 1]);
+        }
     }
 
     MacroMesh {
         vertices,
         indices,
-        texture: None,
+        texture: texture.cloned(),
     }
 }
 
+// inactive objects fade towards the background
+fn dimmed_color(color: Color) -> Color {
+    let factor = 0.35;
+    Color::new(
+        color.r * factor,
+        color.g * factor,
+        color.b * factor,
+        color.a,
+    )
+}
+
 //
 // Per-panel camera setup
 //
@@ -0,0 +1,160 @@
+//
+// scene_commands -> keypresses that switch the active object, move geometry between objects
+// or change / apply the active object's transform
+//
+
+use crate::editor_state::*;
+use crate::global_commands::*;
+use crate::mesh_commands::*;
+use macroquad::prelude::*;
+use mesh_editor::scene::*;
+
+const LOCATION_STEP: f32 = 0.25;
+const ROTATION_STEP_DEGREES: f32 = 15.0;
+const SCALE_FACTOR: f32 = 1.25;
+
+pub fn handle_scene_commands(editor_state: &mut EditorState, scene: &mut Scene) {
+    let modifiers = check_modifier_keys();
+    if modifiers.meta_key {
+        if is_key_pressed(KeyCode::A) {
+            apply_transform(editor_state, scene);
+        }
+        return;
+    }
+
+    if is_key_pressed(KeyCode::A) {
+        cycle_active_object(editor_state, scene, modifiers.shift_key);
+    }
+    if is_key_pressed(KeyCode::X) && modifiers.shift_key {
+        join_objects(editor_state, scene);
+    } else if is_key_pressed(KeyCode::X) {
+        separate_selection(editor_state, scene);
+    }
+    handle_transform_commands(editor_state, scene, modifiers.shift_key);
+}
+
+//
+// A makes the next object active, shift + A the previous one (the selection is dropped,
+// its indicies belong to the old mesh)
+//
+fn cycle_active_object(editor_state: &mut EditorState, scene: &mut Scene, is_reverse: bool) {
+    if scene.objects().len() < 2 {
+        editor_state.set_status_message(String::from("Only one object"));
+        return;
+    }
+    scene.cycle_active(is_reverse);
+    *editor_state.selection_mut() = Selection::None;
+    editor_state.set_status_message(format!("Active {}", scene.active_object().name));
+}
+
+//
+// X moves the selected polys into a new object, shift + X joins every other object into
+// the active one
+//
+fn separate_selection(editor_state: &mut EditorState, scene: &mut Scene) {
+    let polys = selected_polys(editor_state.selection(), scene.active_mesh());
+    match scene.separate_polys(&polys) {
+        Some(index) => {
+            *editor_state.selection_mut() = Selection::None;
+            editor_state.set_status_message(format!(
+                "Separated {} polys into {}",
+                polys.len(),
+                scene.objects()[index].name
+            ));
+        }
+        None => editor_state.set_status_message(String::from("Separate needs polys")),
+    }
+}
+
+fn join_objects(editor_state: &mut EditorState, scene: &mut Scene) {
+    let others: Vec<ObjectIndex> = (0..scene.objects().len()).collect();
+    let joined_count = scene.join_objects(&others);
+    *editor_state.selection_mut() = Selection::None;
+    editor_state.set_status_message(format!(
+        "Joined {} objects into {}",
+        joined_count,
+        scene.active_object().name
+    ));
+}
+
+//
+// W picks what the arrow keys change (location, rotation, scale), shift + W resets it;
+// left / right step along x, up / down along y, shift + up / down along z
+//
+fn handle_transform_commands(editor_state: &mut EditorState, scene: &mut Scene, is_shift: bool) {
+    if is_key_pressed(KeyCode::W) && is_shift {
+        reset_transform_part(editor_state, scene);
+    } else if is_key_pressed(KeyCode::W) {
+        editor_state.cycle_transform_part();
+        editor_state.set_status_message(format!("Transform {}", editor_state.transform_part()));
+    }
+
+    let direction = if is_key_pressed(KeyCode::Left) {
+        -Vec3::X
+    } else if is_key_pressed(KeyCode::Right) {
+        Vec3::X
+    } else if is_key_pressed(KeyCode::Up) {
+        if is_shift { Vec3::Z } else { Vec3::Y }
+    } else if is_key_pressed(KeyCode::Down) {
+        if is_shift { -Vec3::Z } else { -Vec3::Y }
+    } else {
+        return;
+    };
+
+    let part = editor_state.transform_part();
+    let transform = &mut scene.active_object_mut().transform;
+    match part {
+        TransformPart::Location => transform.location += direction * LOCATION_STEP,
+        TransformPart::Rotation => {
+            transform.rotation += direction * ROTATION_STEP_DEGREES.to_radians()
+        }
+        TransformPart::Scale => {
+            // the axis stepped along grows, stepping back shrinks it
+            let axis = direction.abs();
+            let factor = match direction.max_element() > 0.0 {
+                true => SCALE_FACTOR,
+                false => SCALE_FACTOR.recip(),
+            };
+            transform.scale *= Vec3::ONE + axis * (factor - 1.0);
+        }
+    }
+    let message = format_transform_part(part, transform);
+    editor_state.set_status_message(message);
+}
+
+fn reset_transform_part(editor_state: &mut EditorState, scene: &mut Scene) {
+    let part = editor_state.transform_part();
+    let transform = &mut scene.active_object_mut().transform;
+    match part {
+        TransformPart::Location => transform.location = Transform::IDENTITY.location,
+        TransformPart::Rotation => transform.rotation = Transform::IDENTITY.rotation,
+        TransformPart::Scale => transform.scale = Transform::IDENTITY.scale,
+    }
+    let message = format_transform_part(part, transform);
+    editor_state.set_status_message(message);
+}
+
+//
+// meta + A bakes the active object's transform into its verts
+//
+fn apply_transform(editor_state: &mut EditorState, scene: &mut Scene) {
+    let object = scene.active_object_mut();
+    if object.transform.is_identity() {
+        editor_state.set_status_message(String::from("Transform already applied"));
+        return;
+    }
+    object.apply_transform();
+    let message = format!("Applied transform of {}", object.name);
+    editor_state.set_status_message(message);
+}
+
+fn format_transform_part(part: TransformPart, transform: &Transform) -> String {
+    match part {
+        TransformPart::Location => format!("Location {}", transform.location),
+        TransformPart::Rotation => {
+            let degrees = transform.rotation * (180.0 / std::f32::consts::PI);
+            format!("Rotation {}", degrees)
+        }
+        TransformPart::Scale => format!("Scale {}", transform.scale),
+    }
+}
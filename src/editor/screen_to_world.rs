@@ -8,6 +8,7 @@ use macroquad::prelude::*;
 //
 // Used by pan function
 //
+#[allow(clippy::needless_return, clippy::unnecessary_cast)]
 pub fn mouse_delta_to_world_scale_vec2(
     mouse_delta: Vec2,
     panel: &PanelState2D,
@@ -44,3 +45,29 @@ pub fn mouse_coord_to_world_coord_vec2(
 
     vec2(world_x, world_y)
 }
+
+//
+// Point in a panel's viewing plane as a world coord, the depth axis comes from base
+//
+pub fn panel_coord_to_world_coord_vec3(
+    panel_coord: Vec2,
+    viewing_plane: PanelViewingPlane,
+    base: Vec3,
+) -> Vec3 {
+    match viewing_plane {
+        PanelViewingPlane::XY => vec3(panel_coord.x, panel_coord.y, base.z),
+        PanelViewingPlane::XZ => vec3(panel_coord.x, base.y, panel_coord.y),
+        PanelViewingPlane::YZ => vec3(base.x, panel_coord.y, panel_coord.x),
+    }
+}
+
+//
+// Direction a panel looks along (the axis its viewing plane leaves out)
+//
+pub fn panel_view_axis(viewing_plane: PanelViewingPlane) -> Vec3 {
+    match viewing_plane {
+        PanelViewingPlane::XY => Vec3::Z,
+        PanelViewingPlane::XZ => Vec3::Y,
+        PanelViewingPlane::YZ => Vec3::X,
+    }
+}
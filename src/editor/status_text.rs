@@ -4,15 +4,19 @@
 
 use crate::editor_state::*;
 use crate::insert_preview_state::*;
+use crate::render_pane::*;
 use macroquad::prelude::*;
 use mesh_editor::mesh::{Mesh as MeshData, *};
+use mesh_editor::scene::*;
 
-pub fn draw_status_text(editor_state: &EditorState, mesh: &MeshData) {
+#[allow(clippy::redundant_pattern_matching)]
+pub fn draw_status_text(editor_state: &EditorState, scene: &Scene, overlay: &ViewportOverlay) {
     // editor state to display
     // selection (single point / other)
     // input mode (debug)
     // input preview mode
     // mirroring mode
+    // active object
     const TEXT_HEIGHT: f32 = 16.0;
     const Y_PADDING: f32 = 6.0;
     const X_PADDING: f32 = 20.0;
@@ -21,11 +25,22 @@ pub fn draw_status_text(editor_state: &EditorState, mesh: &MeshData) {
     let x_offset_1 = offset_incr;
     let x_offset_2 = offset_incr * 2.0;
     let y_offset = screen_height() - Y_PADDING;
+    let mesh = scene.active_mesh();
 
-    let input_mode_desc = format_input_mode(editor_state.input_mode());
+    let input_mode_desc = match editor_state.status_message() {
+        Some(message) => format!(
+            "{}  {}",
+            format_input_mode(editor_state.input_mode()),
+            message
+        ),
+        None => format_input_mode(editor_state.input_mode()),
+    };
     draw_text(&input_mode_desc, x_offset_0, y_offset, TEXT_HEIGHT, WHITE);
 
-    if let Some(_) = editor_state.insert_preview().vert() {
+    if let Some(primitive) = editor_state.primitive_insert() {
+        let primitive_desc = format_primitive_insert(primitive);
+        draw_text(&primitive_desc, x_offset_1, y_offset, TEXT_HEIGHT, WHITE);
+    } else if let Some(_) = editor_state.insert_preview().vert() {
         let insert_preview_desc = format_insert(editor_state.insert_preview());
         draw_text(
             &insert_preview_desc,
@@ -39,7 +54,13 @@ pub fn draw_status_text(editor_state: &EditorState, mesh: &MeshData) {
         draw_text(&selection_desc, x_offset_1, y_offset, TEXT_HEIGHT, WHITE);
     }
 
-    let mirror_desc = format_mirror(mesh);
+    let mirror_desc = format!(
+        "{}  {}  {}{}",
+        format_object(scene),
+        format_mirror(mesh),
+        format_subdivision(editor_state),
+        format_issues(editor_state, overlay)
+    );
     draw_text(&mirror_desc, x_offset_2, y_offset, TEXT_HEIGHT, WHITE);
 }
 
@@ -47,11 +68,12 @@ fn format_input_mode(input_mode: &InputMode) -> String {
     format!("Mode: {}", input_mode)
 }
 
+#[allow(clippy::useless_format)]
 fn format_selection(selection: &Selection, mesh: &MeshData) -> String {
     match selection {
         Selection::None => format!("Sel: None"),
         Selection::Verticies(verts) if verts.len() == 1 => {
-            let vert = mesh.verts()[0];
+            let vert = mesh.verts()[verts[0]];
             format!("Sel: {}", vert)
         }
         Selection::Verticies(verts) => format!("Sel: Verts ({})", verts.len()),
@@ -67,6 +89,33 @@ fn format_insert(insert_preview: &InsertPreview) -> String {
     format!("Ins: {}", vert)
 }
 
+fn format_primitive_insert(primitive: &Primitive) -> String {
+    format!("Add: {} (Enter / Esc)", primitive.name())
+}
+
+fn format_object(scene: &Scene) -> String {
+    format!(
+        "Obj: {} ({}/{})",
+        scene.active_object().name,
+        scene.active_index() + 1,
+        scene.objects().len()
+    )
+}
+
 fn format_mirror(mesh: &MeshData) -> String {
     format!("Mir: {}", mesh.mirror_mode())
 }
+
+fn format_issues(editor_state: &EditorState, overlay: &ViewportOverlay) -> String {
+    if !editor_state.show_validation_overlay() {
+        return String::new();
+    }
+    format!("  Issues: {}", overlay.issues.len())
+}
+
+fn format_subdivision(editor_state: &EditorState) -> String {
+    match editor_state.subdivision_preview_level() {
+        0 => String::from("Sub: Off"),
+        level => format!("Sub: {:?} x{}", editor_state.subdivision_mode(), level),
+    }
+}
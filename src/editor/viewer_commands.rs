@@ -3,11 +3,12 @@
 //
 use crate::editor_panel_state::*;
 use crate::editor_state::*;
+use crate::global_commands::*;
 use crate::panes::*;
 use crate::screen_to_world::*;
 use crate::viewer_selection::*;
 use macroquad::prelude::*;
-use mesh_editor::mesh::Mesh as MeshData;
+use mesh_editor::scene::SceneObject;
 use std::f32::consts::PI;
 
 enum SelectedPanel<'a> {
@@ -18,12 +19,29 @@ enum SelectedPanel<'a> {
 
 type SelectedPanelInfo<'a> = (SelectedPanel<'a>, Rect);
 
+//
+// clicks pick the verts of the active object where it is drawn (its transform applied),
+// the bisect line and insert cursor are mapped back into its own space, where they are
+// drawn and used
+//
+#[allow(clippy::needless_lifetimes)]
 pub fn handle_viewer_commands<'a>(
     editor_state: &'a mut EditorState,
-    mesh: &MeshData,
+    object: &SceneObject,
     panes: &Panes,
 ) {
     let current_mouse_coords = mouse_position().into();
+    let is_inserting = matches!(editor_state.input_mode(), InputMode::InsertVerts);
+    let modifiers = check_modifier_keys();
+
+    if is_mouse_button_released(MouseButton::Left)
+        && let Some(bisect_line) = editor_state.bisect_line_mut()
+    {
+        bisect_line.is_dragging = false;
+    }
+    let is_dragging_bisect_line = editor_state
+        .bisect_line()
+        .is_some_and(|bisect_line| bisect_line.is_dragging);
 
     let Some((panel, viewport)) =
         get_panel_under_coords_mut(current_mouse_coords, editor_state, panes)
@@ -31,13 +49,33 @@ pub fn handle_viewer_commands<'a>(
         return;
     };
 
+    let mut clicked_vert = None;
+    let mut clicked_insert_coord = None;
+    let mut bisect_drag = None;
+
     if let SelectedPanel::Panel2DView(panel) = panel {
         if is_mouse_button_down(MouseButton::Right) {
             handle_mouse_pan(panel, viewport);
         } else if is_mouse_button_pressed(MouseButton::Middle) {
             handle_reset_pan(panel);
+        } else if is_mouse_button_down(MouseButton::Left)
+            && (modifiers.alt_key || is_dragging_bisect_line)
+        {
+            let panel_coord =
+                mouse_coord_to_world_coord_vec2(current_mouse_coords, panel, viewport);
+            let is_start = is_mouse_button_pressed(MouseButton::Left);
+            bisect_drag = Some((panel_coord, panel.viewing_plane(), is_start));
+        } else if is_mouse_button_pressed(MouseButton::Left) && is_inserting {
+            let panel_coord =
+                mouse_coord_to_world_coord_vec2(current_mouse_coords, panel, viewport);
+            clicked_insert_coord = Some((panel_coord, panel.viewing_plane()));
         } else if is_mouse_button_pressed(MouseButton::Left) {
-            select_point_under_mouse(current_mouse_coords, mesh, panel, viewport);
+            clicked_vert = Some(select_point_under_mouse(
+                current_mouse_coords,
+                &object.baked_mesh(),
+                panel,
+                viewport,
+            ));
         }
         handle_mouse_wheel_2d(panel);
     } else if let SelectedPanel::PanelFreeCam(panel) = panel {
@@ -48,6 +86,53 @@ pub fn handle_viewer_commands<'a>(
         }
         handle_mouse_wheel_free_cam(panel);
     }
+
+    let to_object = object.transform.inverse_matrix();
+
+    // alt + drag draws the bisect line, it stays in the pane it was started in
+    if let Some((panel_coord, viewing_plane, is_start)) = bisect_drag {
+        let coord = to_object.transform_point3(panel_coord_to_world_coord_vec3(
+            panel_coord,
+            viewing_plane,
+            Vec3::ZERO,
+        ));
+        let view_axis = to_object
+            .transform_vector3(panel_view_axis(viewing_plane))
+            .normalize();
+        if is_start {
+            editor_state.set_bisect_line(Some(BisectLine {
+                start: coord,
+                end: coord,
+                view_axis,
+                is_dragging: true,
+            }));
+        } else if let Some(bisect_line) = editor_state.bisect_line_mut()
+            && bisect_line.view_axis == view_axis
+        {
+            bisect_line.end = coord;
+        }
+    }
+
+    // insert mode clicks move the insert cursor within the clicked plane
+    if let Some((panel_coord, viewing_plane)) = clicked_insert_coord {
+        let base = editor_state
+            .insert_preview()
+            .vert()
+            .copied()
+            .unwrap_or(Vec3::ZERO);
+        let base = object.transform.matrix().transform_point3(base);
+        let coord = to_object.transform_point3(panel_coord_to_world_coord_vec3(
+            panel_coord,
+            viewing_plane,
+            base,
+        ));
+        editor_state.insert_preview_mut().set_vert(coord);
+    }
+
+    if let Some(clicked_vert) = clicked_vert {
+        let add_to_selection = modifiers.shift_key;
+        update_vert_selection(editor_state.selection_mut(), clicked_vert, add_to_selection);
+    }
 }
 
 fn handle_mouse_pan(panel: &mut PanelState2D, viewport: Rect) {
@@ -86,6 +171,7 @@ fn handle_reset_free_cam(panel: &mut PanelStateFreeCam) {
     *panel.distance_mut() = 10.0;
 }
 
+#[allow(clippy::needless_return, clippy::question_mark)]
 fn get_panel_under_coords_mut<'a>(
     coords: Vec2,
     editor_state: &'a mut EditorState,
@@ -109,6 +195,7 @@ fn get_panel_under_coords_mut<'a>(
     return Some((panel, pane.viewport_rect));
 }
 
+#[allow(clippy::needless_return, clippy::unnecessary_cast)]
 pub fn rotation_from_mouse_delta(mouse_delta: Vec2, viewport: Rect) -> Vec2 {
     let screen_width_fraction = (viewport.w as f32) / screen_width();
     let screen_height_fraction = (viewport.h as f32) / screen_height();
@@ -3,25 +3,47 @@
 //
 use crate::editor_panel_state::*;
 use crate::editor_state::*;
+#[allow(unused_imports)]
 use crate::panes::*;
 use crate::screen_to_world::*;
 use macroquad::prelude::*;
 use mesh_editor::mesh::{Mesh as MeshData, *};
 
+#[allow(clippy::needless_borrow)]
 pub fn select_point_under_mouse(
     mouse_coord: Vec2,
     mesh: &MeshData,
     panel: &PanelState2D,
     viewport: Rect,
-) {
+) -> Option<VertIndex> {
     let world_coord = mouse_coord_to_world_coord_vec2(mouse_coord, &panel, viewport);
-    println!("world_coord: {world_coord}");
     let found_verts = get_verts_from_mesh_near_coord(world_coord, panel.viewing_plane(), mesh);
 
-    if let Some(vert) = found_verts.get(0) {
-        println!("Found vert: {}", vert)
-    } else {
-        println!("No vert found.")
+    found_verts.first().copied()
+}
+
+//
+// click replaces the selection, shift + click adds to the selected verts
+//
+pub fn update_vert_selection(
+    selection: &mut Selection,
+    clicked_vert: Option<VertIndex>,
+    add_to_selection: bool,
+) {
+    let Some(vert) = clicked_vert else {
+        if !add_to_selection {
+            *selection = Selection::None;
+        }
+        return;
+    };
+
+    match selection {
+        Selection::Verticies(verts) if add_to_selection => {
+            if !verts.contains(&vert) {
+                verts.push(vert);
+            }
+        }
+        _ => *selection = Selection::Verticies(vec![vert]),
     }
 }
 
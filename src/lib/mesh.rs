@@ -4,9 +4,60 @@
 
 use itertools::Itertools;
 use macroquad::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
+mod attributes;
+mod bisect;
+mod color;
+mod csg;
+mod decimate;
+mod fill;
+mod gltf;
+mod hull;
+mod lathe;
+mod line_policy;
+mod loop_cut;
+mod material;
+mod merge;
+mod native;
+mod obj;
+mod ply;
+mod primitives;
+mod selection_set;
+mod separate;
+mod smooth;
+mod stl;
+mod subdivide;
+mod svg;
+mod topology;
+mod transform;
+mod uv;
+mod validate;
+mod winding;
+pub use bisect::*;
+use color::*;
+pub use csg::*;
+pub use decimate::*;
+pub use fill::*;
+pub use gltf::*;
+pub use hull::*;
+pub use lathe::*;
+pub use line_policy::*;
+pub use material::*;
+pub use native::*;
+pub use obj::*;
+pub use ply::*;
+pub use primitives::*;
+pub use selection_set::*;
+pub use smooth::*;
+pub use stl::*;
+pub use subdivide::*;
+pub use svg::*;
+pub use topology::*;
+pub use uv::*;
+pub use validate::*;
+
 pub type VertIndex = usize;
 pub type LineIndex = usize;
 pub type PolyIndex = usize;
@@ -15,26 +66,51 @@ pub type Line = (VertIndex, VertIndex);
 pub type Poly = Vec<VertIndex>;
 pub type TriangleVerts = (Vec3, Vec3, Vec3);
 
+#[derive(Clone)]
 pub enum MirrorMode {
     None,
     Bilateral,
     Radial(u8),
 }
 
+#[derive(Clone)]
 pub struct Mesh {
     mirror_mode: MirrorMode,
     verticies: Vec<Vec3>,
     lines: Vec<Line>,
     polys: Vec<Poly>,
+    // one entry per line, parallel to lines: set for the lines added for a poly edge
+    // (the only ones the line policy removes again)
+    derived_lines: Vec<bool>,
+    // one entry per vert, parallel to verticies
+    vert_colors: Vec<Option<Color>>,
+    // one entry per poly, parallel to polys
+    poly_uvs: Vec<PolyUvs>,
+    poly_colors: Vec<Option<Color>>,
+    poly_materials: Vec<Option<MaterialIndex>>,
+    materials: Vec<MaterialSlot>,
+    selection_sets: Vec<SelectionSet>,
+    line_policy: LinePolicy,
+    topology: MeshTopology,
 }
 
 impl Mesh {
+    #[allow(clippy::needless_return, clippy::new_without_default)]
     pub fn new() -> Mesh {
         return Mesh {
             mirror_mode: MirrorMode::None,
             verticies: Vec::new(),
             lines: Vec::new(),
             polys: Vec::new(),
+            derived_lines: Vec::new(),
+            vert_colors: Vec::new(),
+            poly_uvs: Vec::new(),
+            poly_colors: Vec::new(),
+            poly_materials: Vec::new(),
+            materials: Vec::new(),
+            selection_sets: Vec::new(),
+            line_policy: LinePolicy::Manual,
+            topology: MeshTopology::default(),
         };
     }
 
@@ -58,56 +134,114 @@ impl Mesh {
         self.mirror_mode = mode;
     }
 
+    #[allow(clippy::needless_return)]
     pub fn add_vert(&mut self, coord: Vec3) -> VertIndex {
         self.verticies.push(coord);
+        self.vert_colors.push(None);
+        self.topology.insert_vert();
         return self.verticies.len() - 1;
     }
 
+    #[allow(clippy::needless_return)]
     pub fn update_vert(&mut self, index: VertIndex, coord: Vec3) -> Option<()> {
-        *self.verticies.get_mut(index as usize)? = coord;
+        *self.verticies.get_mut(index)? = coord;
         return Some(());
     }
 
+    #[allow(clippy::needless_return)]
     pub fn delete_vert(&mut self, index: VertIndex) -> Option<Vec3> {
         if index >= self.verticies.len() {
             return None;
         }
         let last_vert_index = self.verticies.len() - 1;
-        let removed_value = self.verticies.swap_remove(index);
+        let touched_edges = self.poly_edges_around_vert(index, last_vert_index);
         self.remove_lines_containing_vert(index);
+
+        // polys around the deleted vert and the one swapped in are taken out of the
+        // topology while their corners change
+        let changed_polys: Vec<PolyIndex> = self
+            .vert_polys(index)
+            .iter()
+            .chain(self.vert_polys(last_vert_index))
+            .copied()
+            .unique()
+            .collect();
+        for &poly_index in changed_polys.iter() {
+            self.topology
+                .remove_poly(poly_index, &self.polys[poly_index]);
+        }
+
+        let removed_value = self.verticies.swap_remove(index);
+        self.vert_colors.swap_remove(index);
+        self.topology.swap_remove_vert(index);
+        self.remap_selection_sets_after_swap_remove(SetKind::Verts, index, last_vert_index);
         if index != last_vert_index {
             self.remap_swaped_vertex_indicies(last_vert_index, index);
         }
-        self.cleanup_polys_after_point_removal(index, last_vert_index);
+        self.cleanup_polys_after_point_removal(index, last_vert_index, &changed_polys);
+        self.derive_lines_after_vert_deleted(touched_edges);
         return Some(removed_value);
     }
 
+    #[allow(clippy::needless_return)]
     pub fn add_line(&mut self, line: Line) -> Option<()> {
         self.validate_line_indicies(line)?;
         self.lines.push(line);
+        self.derived_lines.push(false);
+        self.topology.insert_line(self.lines.len() - 1, line);
         return Some(());
     }
 
+    #[allow(clippy::needless_return)]
     pub fn add_poly(&mut self, poly: Poly) -> Option<()> {
         self.validate_poly_indicies(&poly)?;
+        self.topology.insert_poly(self.polys.len(), &poly);
         self.polys.push(poly);
+        self.poly_uvs.push(PolyUvs::new());
+        self.poly_colors.push(None);
+        self.poly_materials.push(None);
+        self.derive_lines_after_poly_added(self.polys.len() - 1);
         return Some(());
     }
 
+    #[allow(clippy::needless_return)]
     pub fn remove_line(&mut self, index: LineIndex) -> Option<Line> {
         if index >= self.lines.len() {
             return None;
         }
-        return Some(self.lines.swap_remove(index));
+        let last_line_index = self.lines.len() - 1;
+        let removed_line = self.lines.swap_remove(index);
+        self.derived_lines.swap_remove(index);
+        self.remap_selection_sets_after_swap_remove(SetKind::Lines, index, last_line_index);
+        self.topology.remove_line(index, removed_line);
+        if index != last_line_index {
+            self.topology
+                .rename_line(last_line_index, index, self.lines[index]);
+        }
+        return Some(removed_line);
     }
 
+    #[allow(clippy::needless_return)]
     pub fn remove_poly(&mut self, index: PolyIndex) -> Option<Poly> {
         if index >= self.polys.len() {
             return None;
         }
-        return Some(self.polys.swap_remove(index));
+        let last_poly_index = self.polys.len() - 1;
+        let removed_poly = self.polys.swap_remove(index);
+        self.poly_uvs.swap_remove(index);
+        self.poly_colors.swap_remove(index);
+        self.poly_materials.swap_remove(index);
+        self.remap_selection_sets_after_swap_remove(SetKind::Polys, index, last_poly_index);
+        self.topology.remove_poly(index, &removed_poly);
+        if index != last_poly_index {
+            self.topology
+                .rename_poly(last_poly_index, index, &self.polys[index]);
+        }
+        self.derive_lines_after_poly_removed(&removed_poly);
+        return Some(removed_poly);
     }
 
+    #[allow(clippy::needless_return)]
     pub fn lines_to_vert_pairs(&self) -> Vec<(Vec3, Vec3)> {
         return self
             .lines
@@ -123,8 +257,7 @@ impl Mesh {
     pub fn polys_to_triangle_indicies(&self) -> Vec<VertIndex> {
         self.polys
             .iter()
-            .map(|poly| Self::poly_indicies_to_triangle_indicies(poly))
-            .flatten()
+            .flat_map(Self::poly_indicies_to_triangle_indicies)
             .collect()
     }
 
@@ -140,22 +273,20 @@ impl Mesh {
 
         poly.windows(2)
             .skip(1)
-            .map(|slice| {
+            .flat_map(|slice| {
                 let v1 = slice[0];
                 let v2 = slice[1];
                 [v0, v1, v2]
             })
-            .flatten()
             .collect()
     }
 
     fn remove_lines_containing_vert(&mut self, vert_index: VertIndex) {
         let line_indicies_to_remove: Vec<LineIndex> = self
-            .lines
+            .vert_lines(vert_index)
             .iter()
-            .enumerate()
-            .filter(|(_, line)| line.0 == vert_index || line.1 == vert_index)
-            .map(|(i, _)| i)
+            .copied()
+            .sorted()
             .collect();
 
         for line_index in line_indicies_to_remove.into_iter().rev() {
@@ -163,8 +294,28 @@ impl Mesh {
         }
     }
 
+    //
+    // edges of the polys around a vert that don't touch it,
+    // with the index of the vert that swap_remove will move already remapped
+    //
+    fn poly_edges_around_vert(
+        &self,
+        vert_index: VertIndex,
+        last_vert_index: VertIndex,
+    ) -> Vec<Line> {
+        let remap = |v: VertIndex| if v == last_vert_index { vert_index } else { v };
+        self.vert_polys(vert_index)
+            .iter()
+            .flat_map(|&poly_index| poly_edges(&self.polys[poly_index]))
+            .filter(|edge| edge.0 != vert_index && edge.1 != vert_index)
+            .map(|edge| (remap(edge.0), remap(edge.1)))
+            .collect()
+    }
+
+    // only the lines of the vert that moved (now at new_index in the topology) can point at it
     fn remap_swaped_vertex_indicies(&mut self, old_index: VertIndex, new_index: VertIndex) {
-        for line in self.lines.iter_mut() {
+        for line_index in self.vert_lines(new_index).to_vec() {
+            let line = &mut self.lines[line_index];
             if line.0 == old_index {
                 line.0 = new_index;
             }
@@ -174,6 +325,7 @@ impl Mesh {
         }
     }
 
+    #[allow(clippy::needless_return)]
     fn validate_line_indicies(&self, line: Line) -> Option<()> {
         _ = self.verticies.get(line.0)?;
         _ = self.verticies.get(line.1)?;
@@ -197,15 +349,25 @@ impl Mesh {
         !poly.iter().all(|i| seen.insert(i))
     }
 
+    //
+    // the changed polys lose the removed corner and follow the swapped vert, then go back
+    // into the topology; those left with fewer than 3 corners are removed
+    //
     fn cleanup_polys_after_point_removal(
         &mut self,
         removed_vert_index: VertIndex,
         replaced_vert_index: VertIndex,
+        changed_polys: &[PolyIndex],
     ) {
-        let poly_iter = self.polys.iter_mut();
-
-        for poly in poly_iter {
-            poly.retain(|&vert_index| vert_index != removed_vert_index);
+        for &poly_index in changed_polys {
+            let poly = &mut self.polys[poly_index];
+            let uvs = &mut self.poly_uvs[poly_index];
+            if let Some(corner) = poly.iter().position(|&v| v == removed_vert_index) {
+                poly.remove(corner);
+                if !uvs.is_empty() {
+                    uvs.remove(corner);
+                }
+            }
 
             for vert_index in poly.iter_mut() {
                 // acount for remapping caused by swap_remove
@@ -213,9 +375,46 @@ impl Mesh {
                     *vert_index = removed_vert_index;
                 }
             }
+            self.topology
+                .insert_poly(poly_index, &self.polys[poly_index]);
         }
 
-        self.polys.retain(|poly| poly.len() > 2);
+        let degenerate_polys = changed_polys
+            .iter()
+            .copied()
+            .filter(|&poly_index| self.polys[poly_index].len() < 3)
+            .sorted();
+        for poly_index in degenerate_polys.rev() {
+            self.remove_poly(poly_index);
+        }
+    }
+}
+
+//
+// Edge helpers (shared by topology operations)
+//
+
+// order-independent key for an edge, so (a, b) and (b, a) compare equal
+pub fn edge_key(a: VertIndex, b: VertIndex) -> Line {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+// edges of a poly in winding order, including the closing edge
+pub fn poly_edges(poly: &Poly) -> impl Iterator<Item = Line> + '_ {
+    poly.iter()
+        .enumerate()
+        .map(|(i, &v)| (v, poly[(i + 1) % poly.len()]))
+}
+
+impl Mesh {
+    pub fn line_indicies_for_edges(&self, edges: &[Line]) -> Vec<LineIndex> {
+        let edge_set: HashSet<Line> = edges.iter().map(|e| edge_key(e.0, e.1)).collect();
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| edge_set.contains(&edge_key(line.0, line.1)))
+            .map(|(i, _)| i)
+            .collect()
     }
 }
 
@@ -274,27 +473,16 @@ impl Mesh {
 }
 
 fn add_cube_lines_and_faces(mesh: &mut Mesh) {
-    // 12 edges
-    mesh.add_line((0, 1)); // front bottom
-    mesh.add_line((1, 2)); // front right
-    mesh.add_line((2, 3)); // front top
-    mesh.add_line((3, 0)); // front left
-    mesh.add_line((4, 5)); // back bottom
-    mesh.add_line((5, 6)); // back right
-    mesh.add_line((6, 7)); // back top
-    mesh.add_line((7, 4)); // back left
-    mesh.add_line((0, 4)); // bottom left
-    mesh.add_line((1, 5)); // bottom right
-    mesh.add_line((2, 6)); // top right
-    mesh.add_line((3, 7)); // top left
-
-    // 6 faces (vertex indices in winding order)
-    mesh.add_poly(vec![0, 1, 2, 3]); // front
-    mesh.add_poly(vec![5, 4, 7, 6]); // back
-    mesh.add_poly(vec![0, 4, 5, 1]); // bottom
-    mesh.add_poly(vec![3, 2, 6, 7]); // top
-    mesh.add_poly(vec![0, 3, 7, 4]); // left
-    mesh.add_poly(vec![1, 5, 6, 2]); // right
+    // 12 edges come from the faces
+    mesh.set_line_policy(LinePolicy::DeriveFromPolys);
+
+    // 6 faces (vertex indices in winding order, counter-clockwise seen from outside)
+    mesh.add_poly(vec![0, 3, 2, 1]); // front
+    mesh.add_poly(vec![5, 6, 7, 4]); // back
+    mesh.add_poly(vec![0, 1, 5, 4]); // bottom
+    mesh.add_poly(vec![3, 7, 6, 2]); // top
+    mesh.add_poly(vec![0, 4, 7, 3]); // left
+    mesh.add_poly(vec![1, 2, 6, 5]); // right
 }
 
 //
@@ -303,6 +491,7 @@ fn add_cube_lines_and_faces(mesh: &mut Mesh) {
 //
 
 impl Mesh {
+    #[allow(clippy::needless_return)]
     pub fn find_verts_xy(&self, target_coord_xy: Vec2, radius: f32) -> Vec<VertIndex> {
         self.verticies
             .iter()
@@ -316,6 +505,7 @@ impl Mesh {
             .collect()
     }
 
+    #[allow(clippy::needless_return)]
     pub fn find_verts_xz(&self, target_coord_xz: Vec2, radius: f32) -> Vec<VertIndex> {
         self.verticies
             .iter()
@@ -329,6 +519,7 @@ impl Mesh {
             .collect()
     }
 
+    #[allow(clippy::needless_return)]
     pub fn find_verts_yz(&self, target_coord_yz: Vec2, radius: f32) -> Vec<VertIndex> {
         self.verticies
             .iter()
@@ -345,43 +536,62 @@ impl Mesh {
     pub fn lines_in_vertex_indicies(&self, indicies: Vec<VertIndex>) -> Vec<LineIndex> {
         let index_set: HashSet<VertIndex> = indicies.iter().copied().collect();
 
-        self.lines
-            .iter()
-            .enumerate()
-            .filter(|(_, l)| index_set.contains(&l.0) && index_set.contains(&l.1))
-            .map(|(i, _)| i)
+        self.candidate_lines(&indicies)
+            .filter(|&i| {
+                let l = self.lines[i];
+                index_set.contains(&l.0) && index_set.contains(&l.1)
+            })
             .collect()
     }
 
+    #[allow(clippy::needless_return)]
     pub fn polys_in_vertex_indicies(&self, indicies: Vec<VertIndex>) -> Vec<PolyIndex> {
         let index_set: HashSet<VertIndex> = indicies.iter().copied().collect();
 
-        self.polys
-            .iter()
-            .enumerate()
-            .filter(|(_, poly)| {
+        self.candidate_polys(&indicies)
+            .filter(|&i| {
+                let poly = &self.polys[i];
                 let matching_indicies_count = poly.iter().filter(|i| index_set.contains(i)).count();
                 return matching_indicies_count == poly.len();
             })
-            .map(|(i, _)| i)
             .collect()
     }
 
+    #[allow(clippy::needless_return)]
     pub fn polys_partially_in_vertex_indicies(
         &self,
         indicies: Vec<VertIndex>,
         min_matching_verts: usize,
     ) -> Vec<PolyIndex> {
+        if min_matching_verts == 0 {
+            return (0..self.polys.len()).collect();
+        }
         let index_set: HashSet<VertIndex> = indicies.iter().copied().collect();
 
-        self.polys
-            .iter()
-            .enumerate()
-            .filter(|(_, poly)| {
+        self.candidate_polys(&indicies)
+            .filter(|&i| {
+                let poly = &self.polys[i];
                 let matching_indicies_count = poly.iter().filter(|i| index_set.contains(i)).count();
                 return matching_indicies_count >= min_matching_verts;
             })
-            .map(|(i, _)| i)
             .collect()
     }
+
+    // lines touching any of the verts, in index order
+    fn candidate_lines(&self, indicies: &[VertIndex]) -> impl Iterator<Item = LineIndex> {
+        indicies
+            .iter()
+            .flat_map(|&i| self.vert_lines(i).iter().copied())
+            .sorted()
+            .dedup()
+    }
+
+    // polys touching any of the verts, in index order
+    fn candidate_polys(&self, indicies: &[VertIndex]) -> impl Iterator<Item = PolyIndex> {
+        indicies
+            .iter()
+            .flat_map(|&i| self.vert_polys(i).iter().copied())
+            .sorted()
+            .dedup()
+    }
 }
@@ -0,0 +1,56 @@
+//
+// attributes -> data kept parallel to the verts and polys (colors, UVs, materials), and the helpers
+// that keep it in step when verts and polys are added, dropped or reordered in bulk
+//
+
+use super::*;
+
+impl Mesh {
+    // drops polys (and their attributes) in place, keeping the order of the rest
+    pub(super) fn retain_polys(&mut self, mut keep: impl FnMut(PolyIndex, &Poly) -> bool) {
+        let flags: Vec<bool> = self
+            .polys
+            .iter()
+            .enumerate()
+            .map(|(poly_index, poly)| keep(poly_index, poly))
+            .collect();
+        retain_flagged(&mut self.polys, &flags);
+        retain_flagged(&mut self.poly_uvs, &flags);
+        retain_flagged(&mut self.poly_colors, &flags);
+        retain_flagged(&mut self.poly_materials, &flags);
+        self.remap_selection_sets_after_retain(SetKind::Polys, &flags);
+    }
+
+    // drops lines (and their derived flags) in place, keeping the order of the rest
+    pub(super) fn retain_lines(&mut self, flags: &[bool]) {
+        retain_flagged(&mut self.lines, flags);
+        retain_flagged(&mut self.derived_lines, flags);
+        self.remap_selection_sets_after_retain(SetKind::Lines, flags);
+    }
+
+    // flips the winding of a poly, its UVs stay with their corners
+    pub(super) fn reverse_poly(&mut self, poly_index: PolyIndex) {
+        self.polys[poly_index].reverse();
+        if let Some(uvs) = self.poly_uvs.get_mut(poly_index) {
+            uvs.reverse();
+        }
+    }
+
+    //
+    // vert at a + (b - a) * t without touching the topology (for bulk edits that rebuild it),
+    // colored in between when both ends are colored
+    //
+    pub(super) fn push_vert_between(&mut self, a: VertIndex, b: VertIndex, t: f32) -> VertIndex {
+        let coord = self.verticies[a].lerp(self.verticies[b], t);
+        let color = lerp_colors(self.vert_colors[a], self.vert_colors[b], t);
+        self.verticies.push(coord);
+        self.vert_colors.push(color);
+        self.verticies.len() - 1
+    }
+}
+
+// keeps the items whose flag is set (flags parallel to the items)
+pub(super) fn retain_flagged<T>(items: &mut Vec<T>, flags: &[bool]) {
+    let mut flags = flags.iter();
+    items.retain(|_| flags.next().copied().unwrap_or(false));
+}
@@ -0,0 +1,352 @@
+//
+// bisect -> cut the mesh along a plane, optionally dropping one side and filling the cut
+//
+
+use super::*;
+
+// verts closer than this to the plane count as on it
+const PLANE_TOLERANCE: f32 = 1e-5;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Plane {
+    pub point: Vec3,
+    // unit length, points to the front side
+    pub normal: Vec3,
+}
+
+// verts added where edges cross the plane, shared by both polys of an edge
+#[derive(Default)]
+struct CutVerts {
+    by_edge: HashMap<Line, VertIndex>,
+    // cut vert -> the edge it splits (ends in edge_key order)
+    edges: HashMap<VertIndex, Line>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum BisectKeep {
+    Both,
+    // side the normal points to
+    Front,
+    Back,
+}
+
+impl Plane {
+    // None for a zero normal (no front or back side)
+    pub fn new(point: Vec3, normal: Vec3) -> Option<Plane> {
+        Some(Plane {
+            point,
+            normal: normal.try_normalize()?,
+        })
+    }
+
+    //
+    // plane through the line from a to b that also runs along direction
+    // (e.g. a line drawn in a 2D pane, extended along the viewing direction)
+    //
+    pub fn containing_line(a: Vec3, b: Vec3, direction: Vec3) -> Option<Plane> {
+        let normal = (b - a).cross(direction);
+        if normal.length_squared() < f32::EPSILON {
+            return None;
+        }
+        Plane::new(a, normal)
+    }
+
+    pub fn signed_distance(&self, p: Vec3) -> f32 {
+        (p - self.point).dot(self.normal)
+    }
+
+    // 1 in front, -1 behind, 0 on the plane
+    fn side(&self, p: Vec3) -> i8 {
+        let distance = self.signed_distance(p);
+        if distance > PLANE_TOLERANCE {
+            1
+        } else if distance < -PLANE_TOLERANCE {
+            -1
+        } else {
+            0
+        }
+    }
+}
+
+impl Mesh {
+    //
+    // Splits every line and poly crossing the plane at the intersection, then drops the
+    // side that isn't kept. Filling closes the cut with n-gons: with both sides kept the
+    // halves are first separated so each one gets its own cap.
+    // Returns the lines lying on the plane (the cut).
+    //
+    pub fn bisect(&mut self, plane: Plane, keep: BisectKeep, fill_cut: bool) -> Vec<LineIndex> {
+        let mut cut_verts = CutVerts::default();
+
+        let mut polys: Vec<Poly> = Vec::new();
+        let mut poly_uvs: Vec<PolyUvs> = Vec::new();
+        let mut poly_colors: Vec<Option<Color>> = Vec::new();
+        let mut poly_materials: Vec<Option<MaterialIndex>> = Vec::new();
+        let mut cut_lines: Vec<Line> = Vec::new();
+        let mut poly_children: Vec<Vec<PolyIndex>> = Vec::with_capacity(self.polys.len());
+        for (poly_index, poly) in self.polys.clone().into_iter().enumerate() {
+            let (pieces, cuts) = self.split_poly_by_plane(&poly, &plane, &mut cut_verts);
+            poly_children.push((polys.len()..polys.len() + pieces.len()).collect());
+            poly_uvs.extend(pieces.iter().map(|piece| {
+                self.piece_uvs(&poly, &self.poly_uvs[poly_index], piece, &plane, &cut_verts)
+            }));
+            poly_colors.extend(std::iter::repeat_n(
+                self.poly_color(poly_index),
+                pieces.len(),
+            ));
+            poly_materials.extend(std::iter::repeat_n(
+                self.poly_material(poly_index),
+                pieces.len(),
+            ));
+            polys.extend(pieces);
+            cut_lines.extend(cuts);
+        }
+
+        // pieces of a line keep its derived flag, the cuts across polys are poly edges
+        let mut lines: Vec<(Line, bool)> = Vec::new();
+        let mut line_children: Vec<Vec<Line>> = Vec::with_capacity(self.lines.len());
+        for (line_index, (a, b)) in self.lines.clone().into_iter().enumerate() {
+            let children = match self.cut_vert(a, b, &plane, &mut cut_verts) {
+                Some(cut) => vec![(a, cut), (cut, b)],
+                None => vec![(a, b)],
+            };
+            let is_derived = self.derived_lines[line_index];
+            lines.extend(children.iter().map(|&child| (child, is_derived)));
+            line_children.push(children);
+        }
+        lines.extend(cut_lines.into_iter().map(|cut| (cut, true)));
+
+        let mut seen: HashSet<Line> = HashSet::new();
+        lines.retain(|(line, _)| seen.insert(edge_key(line.0, line.1)));
+        (self.lines, self.derived_lines) = lines.into_iter().unzip();
+        self.polys = polys;
+        self.poly_uvs = poly_uvs;
+        self.poly_colors = poly_colors;
+        self.poly_materials = poly_materials;
+        self.remap_selection_sets(SetKind::Polys, |poly_index| {
+            poly_children[poly_index].clone()
+        });
+        self.remap_line_sets_to_edges(|line_index| line_children[line_index].clone());
+
+        if keep != BisectKeep::Both {
+            let dropped_side = if keep == BisectKeep::Front { -1 } else { 1 };
+            let keep_verts: Vec<bool> = self
+                .verticies
+                .iter()
+                .map(|&v| plane.side(v) != dropped_side)
+                .collect();
+            self.retain_verts(&keep_verts);
+        } else if fill_cut {
+            self.separate_at_plane(&plane);
+        }
+
+        self.rebuild_topology();
+        if self.line_policy == LinePolicy::DeriveFromPolys {
+            self.sync_lines_with_polys();
+        }
+
+        if fill_cut {
+            let cut_loops: Vec<Vec<VertIndex>> = self
+                .boundary_loops()
+                .into_iter()
+                .filter(|boundary_loop| {
+                    boundary_loop
+                        .iter()
+                        .all(|&v| plane.side(self.verticies[v]) == 0)
+                })
+                .collect();
+            for cut_loop in cut_loops {
+                // loops are at least 3 unique verts, so this can't fail
+                let _ = self.fill_loop(&cut_loop, FillMode::NGon);
+            }
+        }
+
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| {
+                plane.side(self.verticies[line.0]) == 0 && plane.side(self.verticies[line.1]) == 0
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    //
+    // the vert where the edge crosses the plane (shared by both polys of the edge),
+    // None if the edge doesn't cross it
+    //
+    fn cut_vert(
+        &mut self,
+        a: VertIndex,
+        b: VertIndex,
+        plane: &Plane,
+        cut_verts: &mut CutVerts,
+    ) -> Option<VertIndex> {
+        let (a, b) = edge_key(a, b);
+        let (pa, pb) = (self.verticies[a], self.verticies[b]);
+        if plane.side(pa) * plane.side(pb) != -1 {
+            return None;
+        }
+        if let Some(&cut) = cut_verts.by_edge.get(&(a, b)) {
+            return Some(cut);
+        }
+        let cut = self.push_vert_between(a, b, cut_fraction(plane, pa, pb));
+        cut_verts.by_edge.insert((a, b), cut);
+        cut_verts.edges.insert(cut, (a, b));
+        Some(cut)
+    }
+
+    //
+    // UVs of a piece of the poly: its corners keep theirs, cut verts get theirs
+    // interpolated along the edge they split (none if the poly has none)
+    //
+    fn piece_uvs(
+        &self,
+        poly: &Poly,
+        uvs: &PolyUvs,
+        piece: &Poly,
+        plane: &Plane,
+        cut_verts: &CutVerts,
+    ) -> PolyUvs {
+        let corner_uv = |v: VertIndex| {
+            let corner = poly.iter().position(|&corner| corner == v)?;
+            uvs.get(corner).copied()
+        };
+        piece
+            .iter()
+            .map(|&v| {
+                corner_uv(v).or_else(|| {
+                    let &(a, b) = cut_verts.edges.get(&v)?;
+                    let t = cut_fraction(plane, self.verticies[a], self.verticies[b]);
+                    Some(corner_uv(a)?.lerp(corner_uv(b)?, t))
+                })
+            })
+            .collect::<Option<PolyUvs>>()
+            .unwrap_or_default()
+    }
+
+    //
+    // pieces of the poly on either side of the plane, plus the new edges along the cut
+    //
+    fn split_poly_by_plane(
+        &mut self,
+        poly: &Poly,
+        plane: &Plane,
+        cut_verts: &mut CutVerts,
+    ) -> (Vec<Poly>, Vec<Line>) {
+        let sides: Vec<i8> = poly
+            .iter()
+            .map(|&v| plane.side(self.verticies[v]))
+            .collect();
+        if !sides.contains(&1) || !sides.contains(&-1) {
+            return (vec![poly.clone()], vec![]);
+        }
+
+        // poly with the crossing points inserted, and which entries sit on the plane
+        let mut ring: Vec<VertIndex> = Vec::new();
+        let mut on_plane: Vec<usize> = Vec::new();
+        for (i, (a, b)) in poly_edges(poly).enumerate() {
+            if sides[i] == 0 {
+                on_plane.push(ring.len());
+            }
+            ring.push(a);
+            if let Some(cut) = self.cut_vert(a, b, plane, cut_verts) {
+                on_plane.push(ring.len());
+                ring.push(cut);
+            }
+        }
+
+        if on_plane.len() == 2 {
+            let (first, second) = (on_plane[0], on_plane[1]);
+            let inner: Poly = ring[first..=second].to_vec();
+            let outer: Poly = ring[second..]
+                .iter()
+                .chain(ring[..=first].iter())
+                .copied()
+                .collect();
+            return (vec![inner, outer], vec![(ring[first], ring[second])]);
+        }
+
+        // concave polys crossing more than once are split as triangles
+        let mut pieces = Vec::new();
+        let mut cuts = Vec::new();
+        for triangle in self.triangulate_loop(poly) {
+            let (triangle_pieces, triangle_cuts) =
+                self.split_poly_by_plane(&triangle.to_vec(), plane, cut_verts);
+            pieces.extend(triangle_pieces);
+            cuts.extend(triangle_cuts);
+        }
+        (pieces, cuts)
+    }
+
+    //
+    // gives the polys behind the plane their own copy of the verts on it
+    //
+    fn separate_at_plane(&mut self, plane: &Plane) {
+        let sides: Vec<i8> = self.verticies.iter().map(|&v| plane.side(v)).collect();
+        let mut copies: HashMap<VertIndex, VertIndex> = HashMap::new();
+
+        for poly_index in 0..self.polys.len() {
+            if !self.polys[poly_index].iter().any(|&v| sides[v] == -1) {
+                continue;
+            }
+            for i in 0..self.polys[poly_index].len() {
+                let v = self.polys[poly_index][i];
+                if sides[v] == 0 {
+                    let copy = *copies
+                        .entry(v)
+                        .or_insert_with(|| self.push_vert_between(v, v, 0.0));
+                    self.polys[poly_index][i] = copy;
+                }
+            }
+        }
+
+        // lines from the back side onto the plane follow their polys
+        for line in self.lines.iter_mut() {
+            let (a, b) = *line;
+            if sides[a] == -1 && sides[b] == 0 {
+                line.1 = copies.get(&b).copied().unwrap_or(b);
+            } else if sides[b] == -1 && sides[a] == 0 {
+                line.0 = copies.get(&a).copied().unwrap_or(a);
+            }
+        }
+    }
+
+    //
+    // Drops the verts not flagged to keep along with every line and poly using them,
+    // remapping the remaining indicies (order is preserved). Topology must be rebuilt after.
+    //
+    pub(super) fn retain_verts(&mut self, keep: &[bool]) {
+        attributes::retain_flagged(&mut self.vert_colors, keep);
+        self.remap_selection_sets_after_retain(SetKind::Verts, keep);
+        let mut new_index: Vec<Option<VertIndex>> = vec![None; self.verticies.len()];
+        let mut verticies = Vec::new();
+        for (i, &v) in self.verticies.iter().enumerate() {
+            if keep[i] {
+                new_index[i] = Some(verticies.len());
+                verticies.push(v);
+            }
+        }
+
+        self.verticies = verticies;
+        let line_flags: Vec<bool> = self
+            .lines
+            .iter()
+            .map(|&(a, b)| new_index[a].is_some() && new_index[b].is_some())
+            .collect();
+        self.retain_lines(&line_flags);
+        for (a, b) in self.lines.iter_mut() {
+            (*a, *b) = (new_index[*a].unwrap(), new_index[*b].unwrap());
+        }
+        self.retain_polys(|_, poly| poly.iter().all(|&v| new_index[v].is_some()));
+        for vert_index in self.polys.iter_mut().flatten() {
+            *vert_index = new_index[*vert_index].unwrap();
+        }
+    }
+}
+
+// how far along from a to b the plane is crossed
+fn cut_fraction(plane: &Plane, a: Vec3, b: Vec3) -> f32 {
+    let (da, db) = (plane.signed_distance(a), plane.signed_distance(b));
+    da / (da - db)
+}
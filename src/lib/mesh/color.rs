@@ -0,0 +1,95 @@
+//
+// color -> optional colors per vert and per poly (e.g. to color-code parts for review)
+//
+// A poly color paints the whole poly, otherwise each corner shows the color of its vert,
+// and failing that the color of the poly's material slot. Unset colors are None, renderers
+// and exporters pick their own default for them.
+//
+
+use super::*;
+
+impl Mesh {
+    pub fn vert_color(&self, vert_index: VertIndex) -> Option<Color> {
+        self.vert_colors.get(vert_index).copied().flatten()
+    }
+
+    pub fn poly_color(&self, poly_index: PolyIndex) -> Option<Color> {
+        self.poly_colors.get(poly_index).copied().flatten()
+    }
+
+    // None for the color removes it, None returned if the vert doesn't exist
+    pub fn set_vert_color(&mut self, vert_index: VertIndex, color: Option<Color>) -> Option<()> {
+        *self.vert_colors.get_mut(vert_index)? = color;
+        Some(())
+    }
+
+    pub fn set_poly_color(&mut self, poly_index: PolyIndex, color: Option<Color>) -> Option<()> {
+        *self.poly_colors.get_mut(poly_index)? = color;
+        Some(())
+    }
+
+    // returns the number of verts painted (indicies out of range are skipped)
+    pub fn paint_verts(&mut self, vert_indicies: &[VertIndex], color: Option<Color>) -> usize {
+        vert_indicies
+            .iter()
+            .unique()
+            .filter(|&&vert_index| self.set_vert_color(vert_index, color).is_some())
+            .count()
+    }
+
+    pub fn paint_polys(&mut self, poly_indicies: &[PolyIndex], color: Option<Color>) -> usize {
+        poly_indicies
+            .iter()
+            .unique()
+            .filter(|&&poly_index| self.set_poly_color(poly_index, color).is_some())
+            .count()
+    }
+
+    pub fn has_vert_colors(&self) -> bool {
+        self.vert_colors.iter().any(Option::is_some)
+    }
+
+    pub fn has_poly_colors(&self) -> bool {
+        self.poly_colors.iter().any(Option::is_some)
+    }
+
+    pub fn clear_colors(&mut self) {
+        self.vert_colors.fill(None);
+        self.poly_colors.fill(None);
+    }
+
+    //
+    // color shown at a corner of a poly: the poly color, else the color of the corner's vert,
+    // else the color of the poly's material
+    //
+    pub fn corner_color(&self, poly_index: PolyIndex, corner: usize) -> Option<Color> {
+        self.poly_color(poly_index)
+            .or_else(|| {
+                let vert_index = *self.polys.get(poly_index)?.get(corner)?;
+                self.vert_color(vert_index)
+            })
+            .or_else(|| {
+                let material = self.poly_material(poly_index)?;
+                Some(self.materials[material].color)
+            })
+    }
+}
+
+//
+// Blending (for verts made from other verts, e.g. by cutting an edge)
+//
+
+// a + (b - a) * t, only when both are colored
+pub(super) fn lerp_colors(a: Option<Color>, b: Option<Color>, t: f32) -> Option<Color> {
+    Some(Color::from_vec(a?.to_vec().lerp(b?.to_vec(), t)))
+}
+
+// average of the colors, only when there are some and all are colored
+pub(super) fn mix_colors(colors: impl IntoIterator<Item = Option<Color>>) -> Option<Color> {
+    let colors: Vec<Color> = colors.into_iter().collect::<Option<_>>()?;
+    if colors.is_empty() {
+        return None;
+    }
+    let sum: Vec4 = colors.iter().map(|color| color.to_vec()).sum();
+    Some(Color::from_vec(sum / colors.len() as f32))
+}
@@ -0,0 +1,565 @@
+//
+// csg -> boolean union / difference / intersection of closed meshes (BSP tree clipping)
+//
+// Both operands are split against each other's BSP tree. Faces lying in the same plane
+// are kept on one side only, by comparing their facing, so touching / coplanar faces
+// come out without gaps or doubled walls. The fragments are then welded back together
+// and T-junctions are split so the result is watertight.
+//
+
+use super::*;
+
+// distance under which a point counts as on a plane
+const CSG_PLANE_TOLERANCE: f32 = 1e-5;
+// fragments share verts closer than this
+const CSG_WELD_DISTANCE: f32 = 1e-4;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum BooleanOp {
+    Union,
+    Difference,
+    Intersection,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CsgError {
+    // the first / second operand has open or non-manifold edges (or no polys)
+    FirstNotClosed,
+    SecondNotClosed,
+}
+
+impl Mesh {
+    //
+    // Boolean of this mesh with the other, as a new mesh with lines derived from the polys.
+    // Both must be closed; their winding is fixed up (outward) before cutting.
+    //
+    pub fn boolean(&self, other: &Mesh, op: BooleanOp) -> Result<Mesh, CsgError> {
+        // material slots of other with a name already here join that slot
+        let (materials, other_slots) = merged_materials(&self.materials, &other.materials);
+        let own_slots: Vec<MaterialIndex> = (0..self.materials.len()).collect();
+        let first = closed_polygons(self, &own_slots).ok_or(CsgError::FirstNotClosed)?;
+        let second = closed_polygons(other, &other_slots).ok_or(CsgError::SecondNotClosed)?;
+
+        let polygons = match op {
+            BooleanOp::Union => csg_union(first, second),
+            BooleanOp::Difference => csg_difference(first, second),
+            BooleanOp::Intersection => csg_intersection(first, second),
+        };
+
+        let mut result = polygons_to_mesh(&polygons);
+        result.materials = materials;
+        result.mirror_mode = self.mirror_mode.clone();
+        result.line_policy = self.line_policy;
+        Ok(result)
+    }
+
+    //
+    // Boolean between two groups of polys of this mesh (e.g. two connected components).
+    // The groups are replaced by the result, returns the new poly indicies.
+    //
+    pub fn boolean_polys(
+        &mut self,
+        first: &[PolyIndex],
+        second: &[PolyIndex],
+        op: BooleanOp,
+    ) -> Result<Vec<PolyIndex>, CsgError> {
+        let result = self
+            .extract_polys(first)
+            .boolean(&self.extract_polys(second), op)?;
+
+        // the operands go, along with their verts unless something else still uses them
+        let operands: Vec<PolyIndex> = first.iter().chain(second).copied().collect();
+        self.remove_polys(&operands);
+
+        let first_new_poly = self.polys.len();
+        self.append(&result, Vec3::ZERO);
+        Ok((first_new_poly..self.polys.len()).collect())
+    }
+
+    //
+    // copy of the polys (and the verts they use) as a mesh of their own
+    //
+    pub fn extract_polys(&self, poly_indicies: &[PolyIndex]) -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.mirror_mode = self.mirror_mode.clone();
+        mesh.materials = self.materials.clone();
+        mesh.set_line_policy(LinePolicy::DeriveFromPolys);
+
+        let mut new_index: HashMap<VertIndex, VertIndex> = HashMap::new();
+        for &poly_index in poly_indicies.iter().unique() {
+            let poly: Poly = self.polys[poly_index]
+                .iter()
+                .map(|&v| {
+                    *new_index.entry(v).or_insert_with(|| {
+                        let copy = mesh.add_vert(self.verticies[v]);
+                        mesh.vert_colors[copy] = self.vert_colors[v];
+                        copy
+                    })
+                })
+                .collect();
+            mesh.add_poly(poly);
+            mesh.poly_uvs[mesh.polys.len() - 1] = self.poly_uvs[poly_index].clone();
+            mesh.poly_colors[mesh.polys.len() - 1] = self.poly_colors[poly_index];
+            mesh.poly_materials[mesh.polys.len() - 1] = self.poly_materials[poly_index];
+        }
+        mesh
+    }
+}
+
+//
+// Polygons (convex, planar, with their own vert positions)
+//
+
+#[derive(Clone)]
+struct CsgPolygon {
+    verts: Vec<Vec3>,
+    normal: Vec3,
+    // plane offset, normal.dot(p) == w on the plane
+    w: f32,
+    // of the poly it came from, pieces keep them
+    color: Option<Color>,
+    material: Option<MaterialIndex>,
+}
+
+impl CsgPolygon {
+    fn new(
+        verts: Vec<Vec3>,
+        normal: Vec3,
+        color: Option<Color>,
+        material: Option<MaterialIndex>,
+    ) -> CsgPolygon {
+        let w = normal.dot(verts[0]);
+        CsgPolygon {
+            verts,
+            normal,
+            w,
+            color,
+            material,
+        }
+    }
+
+    fn flip(&mut self) {
+        self.verts.reverse();
+        self.normal = -self.normal;
+        self.w = -self.w;
+    }
+}
+
+#[derive(Copy, Clone)]
+struct CsgPlane {
+    normal: Vec3,
+    w: f32,
+}
+
+impl CsgPlane {
+    fn flip(&mut self) {
+        self.normal = -self.normal;
+        self.w = -self.w;
+    }
+
+    //
+    // sorts the polygon (or its pieces) into the lists on either side of the plane
+    //
+    fn split_polygon(
+        &self,
+        polygon: &CsgPolygon,
+        coplanar_front: &mut Vec<CsgPolygon>,
+        coplanar_back: &mut Vec<CsgPolygon>,
+        front: &mut Vec<CsgPolygon>,
+        back: &mut Vec<CsgPolygon>,
+    ) {
+        const COPLANAR: u8 = 0;
+        const FRONT: u8 = 1;
+        const BACK: u8 = 2;
+        const SPANNING: u8 = 3;
+
+        let types: Vec<u8> = polygon
+            .verts
+            .iter()
+            .map(|&v| {
+                let t = self.normal.dot(v) - self.w;
+                if t < -CSG_PLANE_TOLERANCE {
+                    BACK
+                } else if t > CSG_PLANE_TOLERANCE {
+                    FRONT
+                } else {
+                    COPLANAR
+                }
+            })
+            .collect();
+        let polygon_type = types.iter().fold(COPLANAR, |all, t| all | t);
+
+        match polygon_type {
+            COPLANAR => {
+                if self.normal.dot(polygon.normal) > 0.0 {
+                    coplanar_front.push(polygon.clone());
+                } else {
+                    coplanar_back.push(polygon.clone());
+                }
+            }
+            FRONT => front.push(polygon.clone()),
+            BACK => back.push(polygon.clone()),
+            _ => {
+                let mut front_verts = Vec::new();
+                let mut back_verts = Vec::new();
+                let len = polygon.verts.len();
+                for i in 0..len {
+                    let j = (i + 1) % len;
+                    let (ti, tj) = (types[i], types[j]);
+                    let (vi, vj) = (polygon.verts[i], polygon.verts[j]);
+                    if ti != BACK {
+                        front_verts.push(vi);
+                    }
+                    if ti != FRONT {
+                        back_verts.push(vi);
+                    }
+                    if ti | tj == SPANNING {
+                        let t = (self.w - self.normal.dot(vi)) / self.normal.dot(vj - vi);
+                        let v = vi.lerp(vj, t);
+                        front_verts.push(v);
+                        back_verts.push(v);
+                    }
+                }
+                if front_verts.len() >= 3 {
+                    front.push(CsgPolygon::new(
+                        front_verts,
+                        polygon.normal,
+                        polygon.color,
+                        polygon.material,
+                    ));
+                }
+                if back_verts.len() >= 3 {
+                    back.push(CsgPolygon::new(
+                        back_verts,
+                        polygon.normal,
+                        polygon.color,
+                        polygon.material,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+//
+// BSP tree
+//
+
+#[derive(Default)]
+struct CsgNode {
+    plane: Option<CsgPlane>,
+    front: Option<Box<CsgNode>>,
+    back: Option<Box<CsgNode>>,
+    polygons: Vec<CsgPolygon>,
+}
+
+impl CsgNode {
+    fn new(polygons: Vec<CsgPolygon>) -> CsgNode {
+        let mut node = CsgNode::default();
+        node.build(polygons);
+        node
+    }
+
+    // solid space becomes empty space and the other way around
+    fn invert(&mut self) {
+        for polygon in self.polygons.iter_mut() {
+            polygon.flip();
+        }
+        if let Some(plane) = self.plane.as_mut() {
+            plane.flip();
+        }
+        if let Some(front) = self.front.as_mut() {
+            front.invert();
+        }
+        if let Some(back) = self.back.as_mut() {
+            back.invert();
+        }
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    // the parts of the polygons outside of this tree's solid
+    fn clip_polygons(&self, polygons: Vec<CsgPolygon>) -> Vec<CsgPolygon> {
+        let Some(plane) = self.plane else {
+            return polygons;
+        };
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        for polygon in polygons.iter() {
+            let mut coplanar_front = Vec::new();
+            let mut coplanar_back = Vec::new();
+            plane.split_polygon(
+                polygon,
+                &mut coplanar_front,
+                &mut coplanar_back,
+                &mut front,
+                &mut back,
+            );
+            front.extend(coplanar_front);
+            back.extend(coplanar_back);
+        }
+
+        let mut clipped = match &self.front {
+            Some(node) => node.clip_polygons(front),
+            None => front,
+        };
+        // behind a leaf is inside the solid
+        if let Some(node) = &self.back {
+            clipped.extend(node.clip_polygons(back));
+        }
+        clipped
+    }
+
+    // removes everything inside the other tree's solid
+    fn clip_to(&mut self, other: &CsgNode) {
+        self.polygons = other.clip_polygons(std::mem::take(&mut self.polygons));
+        if let Some(front) = self.front.as_mut() {
+            front.clip_to(other);
+        }
+        if let Some(back) = self.back.as_mut() {
+            back.clip_to(other);
+        }
+    }
+
+    fn all_polygons(&self) -> Vec<CsgPolygon> {
+        let mut polygons = self.polygons.clone();
+        if let Some(front) = &self.front {
+            polygons.extend(front.all_polygons());
+        }
+        if let Some(back) = &self.back {
+            polygons.extend(back.all_polygons());
+        }
+        polygons
+    }
+
+    fn build(&mut self, polygons: Vec<CsgPolygon>) {
+        let Some(first) = polygons.first() else {
+            return;
+        };
+        let plane = *self.plane.get_or_insert(CsgPlane {
+            normal: first.normal,
+            w: first.w,
+        });
+
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        let mut coplanar = Vec::new();
+        for polygon in polygons.iter() {
+            let mut coplanar_back = Vec::new();
+            plane.split_polygon(
+                polygon,
+                &mut coplanar,
+                &mut coplanar_back,
+                &mut front,
+                &mut back,
+            );
+            coplanar.extend(coplanar_back);
+        }
+        self.polygons.extend(coplanar);
+
+        if !front.is_empty() {
+            self.front.get_or_insert_with(Box::default).build(front);
+        }
+        if !back.is_empty() {
+            self.back.get_or_insert_with(Box::default).build(back);
+        }
+    }
+}
+
+fn csg_union(first: Vec<CsgPolygon>, second: Vec<CsgPolygon>) -> Vec<CsgPolygon> {
+    let mut a = CsgNode::new(first);
+    let mut b = CsgNode::new(second);
+    a.clip_to(&b);
+    b.clip_to(&a);
+    b.invert();
+    b.clip_to(&a);
+    b.invert();
+    a.build(b.all_polygons());
+    a.all_polygons()
+}
+
+fn csg_difference(first: Vec<CsgPolygon>, second: Vec<CsgPolygon>) -> Vec<CsgPolygon> {
+    let mut a = CsgNode::new(first);
+    let mut b = CsgNode::new(second);
+    a.invert();
+    a.clip_to(&b);
+    b.clip_to(&a);
+    b.invert();
+    b.clip_to(&a);
+    b.invert();
+    a.build(b.all_polygons());
+    a.invert();
+    a.all_polygons()
+}
+
+fn csg_intersection(first: Vec<CsgPolygon>, second: Vec<CsgPolygon>) -> Vec<CsgPolygon> {
+    let mut a = CsgNode::new(first);
+    let mut b = CsgNode::new(second);
+    a.invert();
+    b.clip_to(&a);
+    b.invert();
+    a.clip_to(&b);
+    b.clip_to(&a);
+    a.build(b.all_polygons());
+    a.invert();
+    a.all_polygons()
+}
+
+//
+// Mesh <-> polygons
+//
+
+//
+// outward wound convex planar polygons of a closed mesh, None if it isn't closed
+// (concave or non-planar polys are triangulated, the tree splits need convex pieces);
+// the material slots of the mesh become those at their index in slots
+//
+fn closed_polygons(mesh: &Mesh, slots: &[MaterialIndex]) -> Option<Vec<CsgPolygon>> {
+    let all_polys: Vec<PolyIndex> = (0..mesh.polys.len()).collect();
+    if all_polys.is_empty() || !mesh.is_closed_component(&all_polys) {
+        return None;
+    }
+    let mut oriented = mesh.clone();
+    oriented.orient_outward();
+
+    let polygons = oriented
+        .polys
+        .iter()
+        .enumerate()
+        .flat_map(|(poly_index, poly)| {
+            let source = (
+                oriented.poly_color(poly_index),
+                oriented.poly_material(poly_index).map(|index| slots[index]),
+            );
+            if oriented.is_convex_planar(poly) {
+                vec![(poly.clone(), source)]
+            } else {
+                oriented
+                    .triangulate_loop(poly)
+                    .into_iter()
+                    .map(|triangle| (triangle.to_vec(), source))
+                    .collect()
+            }
+        })
+        .filter_map(|(poly, (color, material))| {
+            let normal = oriented.poly_newell_normal(&poly).normalize_or_zero();
+            (normal != Vec3::ZERO).then(|| {
+                let verts = poly.iter().map(|&v| oriented.verticies[v]).collect();
+                CsgPolygon::new(verts, normal, color, material)
+            })
+        })
+        .collect();
+    Some(polygons)
+}
+
+//
+// welds the fragments and splits edges at verts lying on them so neighbors line up
+//
+fn polygons_to_mesh(polygons: &[CsgPolygon]) -> Mesh {
+    let mut mesh = Mesh::new();
+    for polygon in polygons {
+        let poly: Poly = polygon.verts.iter().map(|&v| mesh.add_vert(v)).collect();
+        mesh.polys.push(poly);
+        mesh.poly_uvs.push(PolyUvs::new());
+        mesh.poly_colors.push(polygon.color);
+        mesh.poly_materials.push(polygon.material);
+    }
+    mesh.merge_by_distance(CSG_WELD_DISTANCE);
+    mesh.split_t_junctions();
+    mesh.rebuild_topology();
+    mesh.sync_lines_with_polys();
+    mesh
+}
+
+impl Mesh {
+    fn is_convex_planar(&self, poly: &Poly) -> bool {
+        let normal = self.poly_newell_normal(poly).normalize_or_zero();
+        let centroid = self.poly_centroid(poly);
+        let is_planar = poly
+            .iter()
+            .all(|&v| (self.verticies[v] - centroid).dot(normal).abs() < CSG_PLANE_TOLERANCE);
+        let len = poly.len();
+        let is_convex = (0..len).all(|i| {
+            let (a, b, c) = (
+                self.verticies[poly[i]],
+                self.verticies[poly[(i + 1) % len]],
+                self.verticies[poly[(i + 2) % len]],
+            );
+            (b - a).cross(c - b).dot(normal) > -CSG_PLANE_TOLERANCE
+        });
+        is_planar && is_convex
+    }
+
+    //
+    // inserts every vert that lies inside a poly edge into that poly. The verts are
+    // bucketed in a grid (cells about one edge long), each edge only checks the cells
+    // along it.
+    //
+    fn split_t_junctions(&mut self) {
+        let edge_lengths: Vec<f32> = self
+            .polys
+            .iter()
+            .flat_map(poly_edges)
+            .map(|(a, b)| self.verticies[a].distance(self.verticies[b]))
+            .collect();
+        let mean_length = edge_lengths.iter().sum::<f32>() / edge_lengths.len().max(1) as f32;
+        // a vert near the edge is at most half a cell from the closest sample, plus the
+        // weld distance, so it lands in a neighbor of that sample's cell
+        let cell_size = mean_length.max(CSG_WELD_DISTANCE * 2.0);
+        let cell_of = |p: Vec3| (p / cell_size).floor().as_ivec3();
+        let mut cells: HashMap<IVec3, Vec<VertIndex>> = HashMap::new();
+        for (i, &p) in self.verticies.iter().enumerate() {
+            cells.entry(cell_of(p)).or_default().push(i);
+        }
+
+        let verticies = &self.verticies;
+        for poly in self.polys.iter_mut() {
+            let mut split: Poly = Vec::new();
+            for (a, b) in poly_edges(poly) {
+                split.push(a);
+                let (pa, pb) = (verticies[a], verticies[b]);
+                let edge = pb - pa;
+                let length_squared = edge.length_squared();
+                let steps = (edge.length() / cell_size).ceil().max(1.0) as usize;
+                let mut candidates: Vec<VertIndex> = (0..=steps)
+                    .map(|i| cell_of(pa + edge * (i as f32 / steps as f32)))
+                    .flat_map(|cell| {
+                        (-1..=1).flat_map(move |x| {
+                            (-1..=1).flat_map(move |y| (-1..=1).map(move |z| cell + ivec3(x, y, z)))
+                        })
+                    })
+                    .flat_map(|cell| cells.get(&cell).into_iter().flatten())
+                    .copied()
+                    .collect();
+                candidates.sort_unstable();
+                candidates.dedup();
+
+                let mut on_edge: Vec<(f32, VertIndex)> = candidates
+                    .into_iter()
+                    .filter(|&v| v != a && v != b)
+                    .filter_map(|v| {
+                        let p = verticies[v];
+                        let t = (p - pa).dot(edge) / length_squared;
+                        let off_edge = (pa + edge * t).distance(p);
+                        (t > 0.0 && t < 1.0 && off_edge < CSG_WELD_DISTANCE).then_some((t, v))
+                    })
+                    .collect();
+                on_edge.sort_by(|x, y| x.0.total_cmp(&y.0));
+                split.extend(on_edge.into_iter().map(|(_, v)| v));
+            }
+            *poly = split;
+        }
+    }
+}
+
+//
+// Formatting
+//
+
+impl fmt::Display for CsgError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsgError::FirstNotClosed => write!(formatter, "first operand is not closed"),
+            CsgError::SecondNotClosed => write!(formatter, "second operand is not closed"),
+        }
+    }
+}
@@ -0,0 +1,686 @@
+//
+// decimate -> quadric error metric edge collapse (Garland & Heckbert) on a triangulated copy
+//
+// boundary verts, verts on wire lines and verts on non-manifold edges never move (they can
+// only absorb a neighbor), so open edges keep their exact shape.
+//
+// With symmetry respected, MirrorMode::Bilateral is taken as mirror symmetry across x = 0
+// and MirrorMode::Radial(n) as n-fold symmetry around the y axis: mirrored edges collapse
+// together and verts on the mirror stay on it.
+//
+
+use super::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f32::consts::TAU;
+
+// max distance between a vert and the mirrored position of its partner
+const SYMMETRY_TOLERANCE: f32 = 1e-4;
+// collapses may not turn a triangle further than this (cos of the angle between normals)
+const MIN_NORMAL_AGREEMENT: f32 = 0.2;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DecimateTarget {
+    // stop once the mesh has at most this many triangles
+    FaceCount(usize),
+    // collapse every edge whose quadric error stays below this
+    MaxError(f32),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DecimateReport {
+    pub verts_before: usize,
+    pub verts_after: usize,
+    pub polys_before: usize,
+    pub polys_after: usize,
+    pub collapsed_edges: usize,
+    pub max_error: f32,
+}
+
+impl Mesh {
+    //
+    // Triangulates the polys and collapses the cheapest edges until the target is reached
+    // (or nothing more can be collapsed without folding the surface).
+    // A mesh already within a face count target is left untouched.
+    //
+    pub fn decimate(&mut self, target: DecimateTarget, respect_symmetry: bool) -> DecimateReport {
+        let mut report = DecimateReport {
+            verts_before: self.verticies.len(),
+            polys_before: self.polys.len(),
+            ..DecimateReport::default()
+        };
+        // a poly with n corners triangulates to n - 2 triangles
+        let triangle_count: usize = self
+            .polys
+            .iter()
+            .map(|poly| poly.len().saturating_sub(2))
+            .sum();
+        if let DecimateTarget::FaceCount(count) = target
+            && triangle_count <= count
+        {
+            report.verts_after = report.verts_before;
+            report.polys_after = report.polys_before;
+            return report;
+        }
+
+        let symmetry = if respect_symmetry {
+            SymmetryTransform::for_mirror_mode(&self.mirror_mode)
+        } else {
+            Vec::new()
+        };
+        let mut decimator = Decimator::new(self, symmetry);
+        decimator.run(target, &mut report);
+        decimator.write_back(self);
+
+        report.verts_after = self.verticies.len();
+        report.polys_after = self.polys.len();
+        report
+    }
+}
+
+//
+// Symmetry
+//
+
+#[derive(Copy, Clone, Debug)]
+enum SymmetryTransform {
+    MirrorX,
+    RotateY(f32),
+}
+
+impl SymmetryTransform {
+    fn for_mirror_mode(mode: &MirrorMode) -> Vec<SymmetryTransform> {
+        match mode {
+            MirrorMode::None => Vec::new(),
+            MirrorMode::Bilateral => vec![SymmetryTransform::MirrorX],
+            MirrorMode::Radial(count) => (1..*count)
+                .map(|i| SymmetryTransform::RotateY(TAU * i as f32 / *count as f32))
+                .collect(),
+        }
+    }
+
+    fn apply(&self, p: Vec3) -> Vec3 {
+        match self {
+            SymmetryTransform::MirrorX => vec3(-p.x, p.y, p.z),
+            SymmetryTransform::RotateY(angle) => Quat::from_rotation_y(*angle) * p,
+        }
+    }
+
+    // closest point that the transform leaves in place
+    fn project_to_fixed(&self, p: Vec3) -> Vec3 {
+        match self {
+            SymmetryTransform::MirrorX => vec3(0.0, p.y, p.z),
+            SymmetryTransform::RotateY(_) => vec3(0.0, p.y, 0.0),
+        }
+    }
+}
+
+//
+// Quadrics (symmetric 4x4, upper triangle)
+//
+
+#[derive(Copy, Clone, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn from_plane(normal: Vec3, point: Vec3, weight: f64) -> Quadric {
+        let (a, b, c) = (normal.x as f64, normal.y as f64, normal.z as f64);
+        let d = -(normal.dot(point) as f64);
+        Quadric([
+            a * a,
+            a * b,
+            a * c,
+            a * d,
+            b * b,
+            b * c,
+            b * d,
+            c * c,
+            c * d,
+            d * d,
+        ])
+        .scaled(weight)
+    }
+
+    fn scaled(self, weight: f64) -> Quadric {
+        Quadric(self.0.map(|q| q * weight))
+    }
+
+    fn add(&self, other: &Quadric) -> Quadric {
+        let mut sum = *self;
+        for (q, o) in sum.0.iter_mut().zip(other.0.iter()) {
+            *q += o;
+        }
+        sum
+    }
+
+    fn error(&self, p: Vec3) -> f64 {
+        let q = &self.0;
+        let (x, y, z) = (p.x as f64, p.y as f64, p.z as f64);
+        let error = q[0] * x * x
+            + 2.0 * q[1] * x * y
+            + 2.0 * q[2] * x * z
+            + 2.0 * q[3] * x
+            + q[4] * y * y
+            + 2.0 * q[5] * y * z
+            + 2.0 * q[6] * y
+            + q[7] * z * z
+            + 2.0 * q[8] * z
+            + q[9];
+        error.max(0.0)
+    }
+
+    // point of least error, None if the quadric is (close to) singular
+    fn optimum(&self) -> Option<Vec3> {
+        let q = &self.0;
+        let m = [[q[0], q[1], q[2]], [q[1], q[4], q[5]], [q[2], q[5], q[7]]];
+        let rhs = [-q[3], -q[6], -q[8]];
+        let det = determinant(m);
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        // Cramer's rule
+        let solve = |column: usize| {
+            let mut replaced = m;
+            for (row, value) in replaced.iter_mut().zip(rhs.iter()) {
+                row[column] = *value;
+            }
+            (determinant(replaced) / det) as f32
+        };
+        Some(vec3(solve(0), solve(1), solve(2)))
+    }
+}
+
+fn determinant(m: [[f64; 3]; 3]) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+//
+// Candidate collapses, cheapest first (stale ones are skipped by vert versions)
+//
+
+struct Candidate {
+    cost: f64,
+    keep: VertIndex,
+    remove: VertIndex,
+    versions: (u32, u32),
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // reversed so the BinaryHeap pops the lowest cost
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| (other.keep, other.remove).cmp(&(self.keep, self.remove)))
+    }
+}
+
+//
+// Working state
+//
+
+struct Decimator {
+    positions: Vec<Vec3>,
+    triangles: Vec<[VertIndex; 3]>,
+    // poly each triangle came from, and the UVs of its corners (when the poly has them)
+    triangle_polys: Vec<PolyIndex>,
+    triangle_uvs: Vec<Option<[Vec2; 3]>>,
+    alive: Vec<bool>,
+    vert_tris: Vec<Vec<usize>>,
+    quadrics: Vec<Quadric>,
+    locked: Vec<bool>,
+    removed: Vec<bool>,
+    versions: Vec<u32>,
+    // partners[t][v] -> vert at the transformed position of v
+    symmetry: Vec<SymmetryTransform>,
+    partners: Vec<Vec<Option<VertIndex>>>,
+    // collapsed verts point at the vert they were merged into
+    merged_into: Vec<VertIndex>,
+    alive_count: usize,
+}
+
+impl Decimator {
+    fn new(mesh: &Mesh, symmetry: Vec<SymmetryTransform>) -> Decimator {
+        let positions = mesh.verticies.clone();
+        let (triangles, triangle_polys): (Vec<[VertIndex; 3]>, Vec<PolyIndex>) = mesh
+            .polys
+            .iter()
+            .enumerate()
+            .flat_map(|(poly_index, poly)| {
+                mesh.triangulate_loop(poly)
+                    .into_iter()
+                    .map(move |triangle| (triangle, poly_index))
+            })
+            .unzip();
+        let triangle_uvs = triangles
+            .iter()
+            .zip(triangle_polys.iter())
+            .map(|(triangle, &poly_index)| {
+                let uvs = mesh.poly_uvs(poly_index)?;
+                let poly = &mesh.polys[poly_index];
+                let corner_uv = |v| uvs[poly.iter().position(|&corner| corner == v).unwrap()];
+                Some(triangle.map(corner_uv))
+            })
+            .collect();
+
+        let mut vert_tris = vec![Vec::new(); positions.len()];
+        let mut quadrics = vec![Quadric::default(); positions.len()];
+        for (tri_index, triangle) in triangles.iter().enumerate() {
+            let [a, b, c] = triangle.map(|v| positions[v]);
+            let cross = (b - a).cross(c - a);
+            let plane = Quadric::from_plane(cross.normalize_or_zero(), a, cross.length() as f64);
+            for &v in triangle {
+                vert_tris[v].push(tri_index);
+                quadrics[v] = quadrics[v].add(&plane);
+            }
+        }
+
+        let mut locked = vec![false; positions.len()];
+        for (edge, polys) in mesh.topology.edge_polys_map() {
+            if polys.len() != 2 {
+                locked[edge.0] = true;
+                locked[edge.1] = true;
+            }
+        }
+        for line_index in mesh.wire_lines() {
+            let (a, b) = mesh.lines[line_index];
+            locked[a] = true;
+            locked[b] = true;
+        }
+
+        let partners = symmetry
+            .iter()
+            .map(|transform| find_partners(&positions, transform))
+            .collect();
+
+        Decimator {
+            alive: vec![true; triangles.len()],
+            alive_count: triangles.len(),
+            removed: vec![false; positions.len()],
+            versions: vec![0; positions.len()],
+            merged_into: (0..positions.len()).collect(),
+            positions,
+            triangles,
+            triangle_polys,
+            triangle_uvs,
+            vert_tris,
+            quadrics,
+            locked,
+            symmetry,
+            partners,
+        }
+    }
+
+    fn run(&mut self, target: DecimateTarget, report: &mut DecimateReport) {
+        let mut heap = BinaryHeap::new();
+        for v in 0..self.positions.len() {
+            for n in self.neighbors(v) {
+                if v < n {
+                    self.push_candidate(&mut heap, v, n);
+                }
+            }
+        }
+
+        while let Some(candidate) = heap.pop() {
+            match target {
+                DecimateTarget::FaceCount(count) if self.alive_count <= count => break,
+                DecimateTarget::MaxError(max_error) if candidate.cost > max_error as f64 => break,
+                _ => {}
+            }
+            let (keep, remove) = (candidate.keep, candidate.remove);
+            if self.removed[keep]
+                || self.removed[remove]
+                || (self.versions[keep], self.versions[remove]) != candidate.versions
+            {
+                continue;
+            }
+
+            let Some(orbit) = self.collapse_orbit(keep, remove) else {
+                continue;
+            };
+            for &(keep, remove, target) in orbit.iter() {
+                self.collapse(keep, remove, target);
+                // an edge across the mirror collapses onto it
+                for partners in self.partners.iter_mut() {
+                    if partners[keep] == Some(remove) {
+                        partners[keep] = Some(keep);
+                    }
+                }
+                report.collapsed_edges += 1;
+                report.max_error = report.max_error.max(candidate.cost as f32);
+            }
+            for &(keep, _, _) in orbit.iter() {
+                for n in self.neighbors(keep) {
+                    self.push_candidate(&mut heap, keep, n);
+                }
+            }
+        }
+    }
+
+    fn push_candidate(&self, heap: &mut BinaryHeap<Candidate>, a: VertIndex, b: VertIndex) {
+        if self.locked[a] && self.locked[b] {
+            return;
+        }
+        // a locked vert can only absorb its neighbor
+        let (a, b) = if self.locked[b] { (b, a) } else { (a, b) };
+        let target = self.collapse_target(a, b);
+        let quadric = self.quadrics[a].add(&self.quadrics[b]);
+        heap.push(Candidate {
+            cost: quadric.error(target),
+            keep: a,
+            remove: b,
+            versions: (self.versions[a], self.versions[b]),
+        });
+    }
+
+    //
+    // optimal point of the combined quadric (or the best of the ends / middle),
+    // kept on the mirror wherever an end or the edge itself lies on it
+    //
+    fn collapse_target(&self, a: VertIndex, b: VertIndex) -> Vec3 {
+        let quadric = self.quadrics[a].add(&self.quadrics[b]);
+        let (pa, pb) = (self.positions[a], self.positions[b]);
+        if self.locked[a] {
+            return pa;
+        }
+        if self.locked[b] {
+            return pb;
+        }
+        let mut target = quadric.optimum().unwrap_or_else(|| {
+            [pa, pb, (pa + pb) / 2.0]
+                .into_iter()
+                .min_by(|p, q| quadric.error(*p).total_cmp(&quadric.error(*q)))
+                .unwrap()
+        });
+
+        for (t, transform) in self.symmetry.iter().enumerate() {
+            let partner = |v: VertIndex| self.partners[t][v];
+            let is_fixed = partner(a) == Some(a) || partner(b) == Some(b);
+            let is_self_mirrored = partner(a) == Some(b) && partner(b) == Some(a);
+            if is_fixed || is_self_mirrored {
+                target = transform.project_to_fixed(target);
+            }
+        }
+        target
+    }
+
+    //
+    // the collapse plus its mirrored copies, None if any of them is not allowed
+    // (or they'd overlap, which would break the symmetry)
+    //
+    fn collapse_orbit(
+        &self,
+        keep: VertIndex,
+        remove: VertIndex,
+    ) -> Option<Vec<(VertIndex, VertIndex, Vec3)>> {
+        let target = self.collapse_target(keep, remove);
+        let mut orbit = vec![(keep, remove, target)];
+
+        for (t, transform) in self.symmetry.iter().enumerate() {
+            let (Some(mirror_keep), Some(mirror_remove)) =
+                (self.partners[t][keep], self.partners[t][remove])
+            else {
+                continue;
+            };
+            let mirror_edge = edge_key(mirror_keep, mirror_remove);
+            if orbit.iter().any(|&(k, r, _)| edge_key(k, r) == mirror_edge) {
+                continue;
+            }
+            orbit.push((mirror_keep, mirror_remove, transform.apply(target)));
+        }
+
+        // mirrored copies sharing a vert (e.g. both touching the mirror) can't all collapse
+        let orbit_verts: Vec<VertIndex> = orbit.iter().flat_map(|&(k, r, _)| [k, r]).collect();
+        if !orbit_verts.iter().all_unique() {
+            return None;
+        }
+        orbit
+            .iter()
+            .all(|&(k, r, p)| self.can_collapse(k, r, p))
+            .then_some(orbit)
+    }
+
+    //
+    // link condition (keeps the surface manifold) and no triangle flips or degenerates
+    //
+    fn can_collapse(&self, keep: VertIndex, remove: VertIndex, target: Vec3) -> bool {
+        if self.locked[remove] || self.removed[keep] || self.removed[remove] {
+            return false;
+        }
+        let shared: Vec<usize> = self.vert_tris[keep]
+            .iter()
+            .copied()
+            .filter(|&t| self.alive[t] && self.triangles[t].contains(&remove))
+            .collect();
+        if shared.is_empty() {
+            return false;
+        }
+        let keep_neighbors = self.neighbors(keep);
+        let common = self
+            .neighbors(remove)
+            .into_iter()
+            .filter(|n| keep_neighbors.contains(n))
+            .count();
+        if common != shared.len() {
+            return false;
+        }
+
+        self.vert_tris[keep]
+            .iter()
+            .chain(self.vert_tris[remove].iter())
+            .filter(|&&t| self.alive[t] && !shared.contains(&t))
+            .all(|&t| {
+                let triangle = self.triangles[t];
+                let before = triangle.map(|v| self.positions[v]);
+                let after = triangle.map(|v| {
+                    if v == keep || v == remove {
+                        target
+                    } else {
+                        self.positions[v]
+                    }
+                });
+                let normal_before = (before[1] - before[0])
+                    .cross(before[2] - before[0])
+                    .normalize_or_zero();
+                let normal_after = (after[1] - after[0])
+                    .cross(after[2] - after[0])
+                    .normalize_or_zero();
+                normal_after != Vec3::ZERO && normal_before.dot(normal_after) > MIN_NORMAL_AGREEMENT
+            })
+    }
+
+    fn collapse(&mut self, keep: VertIndex, remove: VertIndex, target: Vec3) {
+        let target_uvs = self.collapse_uvs(keep, remove, target);
+        self.positions[keep] = target;
+        self.quadrics[keep] = self.quadrics[keep].add(&self.quadrics[remove]);
+        self.removed[remove] = true;
+        self.merged_into[remove] = keep;
+
+        for t in self.vert_tris[remove].clone() {
+            if !self.alive[t] {
+                continue;
+            }
+            if self.triangles[t].contains(&keep) {
+                self.alive[t] = false;
+                self.alive_count -= 1;
+            } else {
+                for v in self.triangles[t].iter_mut() {
+                    if *v == remove {
+                        *v = keep;
+                    }
+                }
+                self.vert_tris[keep].push(t);
+            }
+        }
+        let alive = &self.alive;
+        self.vert_tris[keep].retain(|&t| alive[t]);
+        self.vert_tris[keep].sort_unstable();
+        self.vert_tris[keep].dedup();
+        self.vert_tris[remove].clear();
+
+        // the merged vert's corners take the UV at the target (per poly, as seams differ)
+        let fallback_uv = target_uvs.values().next().copied();
+        for &t in &self.vert_tris[keep] {
+            let uv = target_uvs.get(&self.triangle_polys[t]).copied();
+            let corner = self.triangles[t].iter().position(|&v| v == keep).unwrap();
+            if let (Some(uvs), Some(uv)) = (&mut self.triangle_uvs[t], uv.or(fallback_uv)) {
+                uvs[corner] = uv;
+            }
+        }
+
+        self.versions[keep] += 1;
+        self.versions[remove] += 1;
+    }
+
+    //
+    // UV at the target along the collapsed edge, for each poly the edge runs through
+    // (from the triangles that are about to go)
+    //
+    fn collapse_uvs(
+        &self,
+        keep: VertIndex,
+        remove: VertIndex,
+        target: Vec3,
+    ) -> HashMap<PolyIndex, Vec2> {
+        let (from, to) = (self.positions[keep], self.positions[remove]);
+        let fraction = ((target - from).dot(to - from) / from.distance_squared(to)).clamp(0.0, 1.0);
+        let fraction = if fraction.is_nan() { 0.0 } else { fraction };
+        self.vert_tris[remove]
+            .iter()
+            .filter(|&&t| self.alive[t])
+            .filter_map(|&t| {
+                let uvs = self.triangle_uvs[t]?;
+                let corner = |v| self.triangles[t].iter().position(|&corner| corner == v);
+                let (keep_corner, remove_corner) = (corner(keep)?, corner(remove)?);
+                let uv = uvs[keep_corner].lerp(uvs[remove_corner], fraction);
+                Some((self.triangle_polys[t], uv))
+            })
+            .collect()
+    }
+
+    fn neighbors(&self, v: VertIndex) -> Vec<VertIndex> {
+        self.vert_tris[v]
+            .iter()
+            .filter(|&&t| self.alive[t])
+            .flat_map(|&t| self.triangles[t])
+            .filter(|&n| n != v)
+            .unique()
+            .collect()
+    }
+
+    fn final_index(&self, mut v: VertIndex) -> VertIndex {
+        while self.merged_into[v] != v {
+            v = self.merged_into[v];
+        }
+        v
+    }
+
+    //
+    // replaces the mesh polys with the remaining triangles (colored, in the material of the
+    // poly they came from and keeping their corner UVs), lines follow their verts
+    //
+    fn write_back(&self, mesh: &mut Mesh) {
+        mesh.verticies = self.positions.clone();
+        let alive: Vec<usize> = (0..self.triangles.len())
+            .filter(|&t| self.alive[t])
+            .collect();
+        let triangles: Vec<Poly> = alive.iter().map(|&t| self.triangles[t].to_vec()).collect();
+        let sources: Vec<PolyIndex> = alive.iter().map(|&t| self.triangle_polys[t]).collect();
+        let mut poly_children: Vec<Vec<PolyIndex>> = vec![Vec::new(); mesh.polys.len()];
+        for (triangle_index, &poly_index) in sources.iter().enumerate() {
+            poly_children[poly_index].push(triangle_index);
+        }
+        mesh.polys = triangles;
+        mesh.poly_colors = sources.iter().map(|&p| mesh.poly_color(p)).collect();
+        mesh.poly_materials = sources.iter().map(|&p| mesh.poly_material(p)).collect();
+        mesh.remap_selection_sets(SetKind::Polys, |poly_index| {
+            poly_children[poly_index].clone()
+        });
+        mesh.poly_uvs = alive
+            .iter()
+            .map(|&t| self.triangle_uvs[t].map_or_else(PolyUvs::new, |uvs| uvs.to_vec()))
+            .collect();
+
+        let mut seen: HashSet<Line> = HashSet::new();
+        for (a, b) in mesh.lines.iter_mut() {
+            (*a, *b) = (self.final_index(*a), self.final_index(*b));
+        }
+        let line_flags: Vec<bool> = mesh
+            .lines
+            .iter()
+            .map(|&(a, b)| a != b && seen.insert(edge_key(a, b)))
+            .collect();
+        mesh.retain_lines(&line_flags);
+        mesh.remap_selection_sets(SetKind::Verts, |v| Some(self.final_index(v)));
+
+        let keep: Vec<bool> = self.removed.iter().map(|removed| !removed).collect();
+        mesh.retain_verts(&keep);
+        mesh.rebuild_topology();
+        if mesh.line_policy == LinePolicy::DeriveFromPolys {
+            mesh.sync_lines_with_polys();
+        }
+    }
+}
+
+//
+// vert at the transformed position of each vert (itself for verts on the mirror)
+//
+fn find_partners(positions: &[Vec3], transform: &SymmetryTransform) -> Vec<Option<VertIndex>> {
+    let cell_of = |p: Vec3| (p / SYMMETRY_TOLERANCE).floor().as_ivec3();
+    let mut cells: HashMap<IVec3, Vec<VertIndex>> = HashMap::new();
+    for (i, &p) in positions.iter().enumerate() {
+        cells.entry(cell_of(p)).or_default().push(i);
+    }
+
+    positions
+        .iter()
+        .map(|&p| {
+            let mirrored = transform.apply(p);
+            let cell = cell_of(mirrored);
+            (-1..=1)
+                .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| ivec3(x, y, z))))
+                .flat_map(|offset| cells.get(&(cell + offset)).into_iter().flatten())
+                .copied()
+                .filter(|&other| positions[other].distance(mirrored) <= SYMMETRY_TOLERANCE)
+                .min_by(|&a, &b| {
+                    positions[a]
+                        .distance(mirrored)
+                        .total_cmp(&positions[b].distance(mirrored))
+                })
+        })
+        .collect()
+}
+
+//
+// Formatting
+//
+
+impl fmt::Display for DecimateReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "polys {} -> {}, verts {} -> {}, max error {:.5}",
+            self.polys_before,
+            self.polys_after,
+            self.verts_before,
+            self.verts_after,
+            self.max_error
+        )
+    }
+}
@@ -0,0 +1,274 @@
+//
+// fill -> closing open boundaries: fill a loop with a poly / triangles, bridge two loops
+//
+
+use super::*;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum FillMode {
+    NGon,
+    Triangles,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FillError {
+    // fewer than 3 verts, a vert used twice, or a vert that doesn't exist
+    InvalidLoop,
+    LoopsShareVerts,
+}
+
+impl Mesh {
+    //
+    // boundary loops that contain any of the verts (e.g. to turn a selection into loops)
+    //
+    pub fn boundary_loops_touching(&self, verts: &[VertIndex]) -> Vec<Vec<VertIndex>> {
+        self.boundary_loops()
+            .into_iter()
+            .filter(|boundary_loop| boundary_loop.iter().any(|v| verts.contains(v)))
+            .collect()
+    }
+
+    //
+    // Closes the loop of verts with one poly or a triangulated patch. Boundary loops are
+    // filled against the winding of their polys, other loops (e.g. drawn with lines)
+    // keep the given order. Returns the new poly indicies.
+    //
+    pub fn fill_loop(
+        &mut self,
+        loop_verts: &[VertIndex],
+        mode: FillMode,
+    ) -> Result<Vec<PolyIndex>, FillError> {
+        if !self.is_valid_loop(loop_verts) {
+            return Err(FillError::InvalidLoop);
+        }
+
+        let mut fill: Poly = loop_verts.to_vec();
+        if self.loop_owner_direction(loop_verts) == Some(true) {
+            fill.reverse();
+        }
+
+        let new_polys = match mode {
+            FillMode::NGon => vec![fill],
+            FillMode::Triangles => self
+                .triangulate_loop(&fill)
+                .into_iter()
+                .map(|triangle| triangle.to_vec())
+                .collect(),
+        };
+        Ok(self.add_polys_with_lines(new_polys))
+    }
+
+    //
+    // Connects two loops with a strip of quads. When the vert counts differ, the longer
+    // loop's extra verts get triangles. The twist (start offset and direction) with the
+    // shortest total rung length is used. Returns the new poly indicies.
+    //
+    pub fn bridge_loops(
+        &mut self,
+        first: &[VertIndex],
+        second: &[VertIndex],
+    ) -> Result<Vec<PolyIndex>, FillError> {
+        if !self.is_valid_loop(first) || !self.is_valid_loop(second) {
+            return Err(FillError::InvalidLoop);
+        }
+        if first.iter().any(|v| second.contains(v)) {
+            return Err(FillError::LoopsShareVerts);
+        }
+
+        let (long, short) = if first.len() >= second.len() {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let long_owner = self.loop_owner_direction(long);
+        let short_owner = self.loop_owner_direction(short);
+
+        // matched[i] -> short vert paired with long[i]
+        let matched = self.best_loop_match(long, short, long_owner, short_owner);
+
+        // each step walks the long loop forward and the short loop backward
+        let mut strip: Vec<Poly> = (0..long.len())
+            .map(|i| {
+                let next = (i + 1) % long.len();
+                [long[i], long[next], matched[next], matched[i]]
+                    .into_iter()
+                    .dedup()
+                    .collect()
+            })
+            .collect();
+
+        let short_walked_forward = |strip_poly: &Poly| {
+            poly_edges(strip_poly).any(|(a, b)| {
+                let i = short.iter().position(|&v| v == a);
+                i.is_some_and(|i| short[(i + 1) % short.len()] == b)
+            })
+        };
+        let should_flip = match (long_owner, short_owner) {
+            (Some(forward), _) => forward,
+            (None, Some(forward)) => strip.iter().any(short_walked_forward) == forward,
+            (None, None) => !self.strip_faces_out(&strip),
+        };
+        if should_flip {
+            for poly in strip.iter_mut() {
+                poly.reverse();
+            }
+        }
+        Ok(self.add_polys_with_lines(strip))
+    }
+
+    //
+    // Ear clipping in the plane of the loop, triangles keep the loop's winding.
+    // Falls back to cutting off the next corner if no clean ear is left (e.g. self-overlap).
+    //
+    pub fn triangulate_loop(&self, loop_verts: &[VertIndex]) -> Vec<[VertIndex; 3]> {
+        let normal = self
+            .poly_newell_normal(&loop_verts.to_vec())
+            .normalize_or_zero();
+        let u = normal.any_orthonormal_vector();
+        let v = normal.cross(u);
+        let flat = |vert: VertIndex| {
+            let p = self.verticies[vert];
+            vec2(p.dot(u), p.dot(v))
+        };
+
+        let mut remaining: Vec<VertIndex> = loop_verts.to_vec();
+        let mut triangles = Vec::new();
+        while remaining.len() > 3 {
+            let len = remaining.len();
+            let corner = |i: usize| {
+                (
+                    remaining[(i + len - 1) % len],
+                    remaining[i],
+                    remaining[(i + 1) % len],
+                )
+            };
+            let is_ear = |i: usize| {
+                let (a, b, c) = corner(i);
+                let (pa, pb, pc) = (flat(a), flat(b), flat(c));
+                (pb - pa).perp_dot(pc - pb) > 0.0
+                    && remaining
+                        .iter()
+                        .filter(|&&other| other != a && other != b && other != c)
+                        .all(|&other| !point_in_triangle(flat(other), pa, pb, pc))
+            };
+            let ear = (0..len).find(|&i| is_ear(i)).unwrap_or(0);
+            let (a, b, c) = corner(ear);
+            triangles.push([a, b, c]);
+            remaining.remove(ear);
+        }
+        if remaining.len() == 3 {
+            triangles.push([remaining[0], remaining[1], remaining[2]]);
+        }
+        triangles
+    }
+
+    //
+    // Some(true) if the polys along the loop walk it in the given order, Some(false) if
+    // against it, None if no poly uses its first edge
+    //
+    fn loop_owner_direction(&self, loop_verts: &[VertIndex]) -> Option<bool> {
+        let edge = (loop_verts[0], loop_verts[1]);
+        let owner = *self.edge_polys(edge).first()?;
+        Some(poly_edges(&self.polys[owner]).any(|e| e == edge))
+    }
+
+    //
+    // Pairs each long loop vert with a short loop vert, trying every start offset and
+    // both directions (only the direction that keeps the winding consistent if both loops
+    // already have polys)
+    //
+    fn best_loop_match(
+        &self,
+        long: &[VertIndex],
+        short: &[VertIndex],
+        long_owner: Option<bool>,
+        short_owner: Option<bool>,
+    ) -> Vec<VertIndex> {
+        let reversed: Vec<VertIndex> = short.iter().rev().copied().collect();
+        let directions: Vec<&[VertIndex]> = match (long_owner, short_owner) {
+            // the strip walks long forward and short backward, owners must walk the opposite
+            (Some(long_forward), Some(short_forward)) if long_forward == short_forward => {
+                vec![&reversed]
+            }
+            (Some(_), Some(_)) => vec![short],
+            _ => vec![short, &reversed],
+        };
+
+        let (long_len, short_len) = (long.len(), short.len());
+        directions
+            .into_iter()
+            .flat_map(|direction| {
+                (0..short_len).map(move |offset| {
+                    (0..long_len)
+                        .map(|i| direction[(i * short_len / long_len + offset) % short_len])
+                        .collect::<Vec<VertIndex>>()
+                })
+            })
+            .min_by(|a, b| {
+                let cost = |matched: &Vec<VertIndex>| -> f32 {
+                    long.iter()
+                        .zip(matched.iter())
+                        .map(|(&l, &s)| self.verticies[l].distance(self.verticies[s]))
+                        .sum()
+                };
+                cost(a).total_cmp(&cost(b))
+            })
+            .unwrap_or_default()
+    }
+
+    // for loops without polys, the strip should face away from its center
+    fn strip_faces_out(&self, strip: &[Poly]) -> bool {
+        let strip_verts: Vec<Vec3> = strip.iter().flatten().map(|&v| self.verticies[v]).collect();
+        let center = strip_verts.iter().sum::<Vec3>() / strip_verts.len().max(1) as f32;
+        let outwardness: f32 = strip
+            .iter()
+            .map(|poly| {
+                self.poly_newell_normal(poly)
+                    .dot(self.poly_centroid(poly) - center)
+            })
+            .sum();
+        outwardness >= 0.0
+    }
+
+    // new polys plus a line for every poly edge that doesn't have one yet
+    fn add_polys_with_lines(&mut self, polys: Vec<Poly>) -> Vec<PolyIndex> {
+        let mut new_polys = Vec::new();
+        for poly in polys {
+            let edges: Vec<Line> = poly_edges(&poly).collect();
+            if self.add_poly(poly).is_none() {
+                continue;
+            }
+            new_polys.push(self.polys.len() - 1);
+            for edge in edges {
+                self.ensure_line_for_edge(edge);
+            }
+        }
+        new_polys
+    }
+
+    fn is_valid_loop(&self, loop_verts: &[VertIndex]) -> bool {
+        loop_verts.len() >= 3
+            && loop_verts.iter().all_unique()
+            && loop_verts.iter().all(|&v| v < self.verticies.len())
+    }
+}
+
+fn point_in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    let d1 = (b - a).perp_dot(p - a);
+    let d2 = (c - b).perp_dot(p - b);
+    let d3 = (a - c).perp_dot(p - c);
+    d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0
+}
+
+//
+// Formatting
+//
+
+impl fmt::Display for FillError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FillError::InvalidLoop => write!(formatter, "not a loop of at least 3 verts"),
+            FillError::LoopsShareVerts => write!(formatter, "loops share verts"),
+        }
+    }
+}
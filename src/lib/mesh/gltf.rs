@@ -0,0 +1,565 @@
+//
+// gltf -> export glTF 2.0 (.gltf json + .bin buffer, or a single binary .glb)
+//
+// Polys are triangulated into one TRIANGLES primitive with POSITION + NORMAL (+ TEXCOORD_0
+// when the mesh has UVs), the lines can go into a second LINES primitive. Normals are smooth
+// (shared verts, split only at UV seams) or flat (verts split per poly). With material slots
+// there is a TRIANGLES primitive per slot in use (sharing the vert attributes, polys without
+// a material first and without a material of their own), the slots become glTF materials
+// with their color as the base color.
+//
+// Several meshes can go into one document as nodes, each with its own translation, rotation
+// and scale (scene graph export); their material slots are merged by name.
+//
+
+use super::*;
+use crate::json::Json;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const GLTF_FLOAT: f64 = 5126.0;
+const GLTF_UNSIGNED_INT: f64 = 5125.0;
+const GLTF_ARRAY_BUFFER: f64 = 34962.0;
+const GLTF_ELEMENT_ARRAY_BUFFER: f64 = 34963.0;
+const GLTF_MODE_LINES: f64 = 1.0;
+const GLTF_MODE_TRIANGLES: f64 = 4.0;
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_VERSION: u32 = 2;
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+const GLB_HEADER_SIZE: usize = 12;
+const GLB_CHUNK_HEADER_SIZE: usize = 8;
+
+const GLTF_GENERATOR: &str = "mesh_editor";
+const GLTF_MESH_NAME: &str = "mesh";
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GltfExportOptions {
+    // split verts per poly so each poly is shaded flat
+    pub flat_normals: bool,
+    // add the lines as a LINES primitive
+    pub include_lines: bool,
+}
+
+//
+// A mesh placed in the document (scene graph export), the identity placement is left out
+//
+#[derive(Copy, Clone)]
+pub struct GltfNode<'a> {
+    pub name: &'a str,
+    pub mesh: &'a Mesh,
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl<'a> GltfNode<'a> {
+    pub fn new(name: &'a str, mesh: &'a Mesh) -> GltfNode<'a> {
+        GltfNode {
+            name,
+            mesh,
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            scale: Vec3::ONE,
+        }
+    }
+}
+
+impl Mesh {
+    //
+    // json document + binary buffer, the document points at the buffer by bin_uri
+    //
+    pub fn to_gltf(&self, options: GltfExportOptions, bin_uri: &str) -> (String, Vec<u8>) {
+        nodes_to_gltf(&[GltfNode::new(GLTF_MESH_NAME, self)], options, bin_uri)
+    }
+
+    pub fn to_glb(&self, options: GltfExportOptions) -> Vec<u8> {
+        nodes_to_glb(&[GltfNode::new(GLTF_MESH_NAME, self)], options)
+    }
+
+    //
+    // writes the .gltf and a .bin with the same name next to it
+    //
+    pub fn save_gltf(&self, path: &Path, options: GltfExportOptions) -> io::Result<()> {
+        save_nodes_gltf(&[GltfNode::new(GLTF_MESH_NAME, self)], path, options)
+    }
+
+    pub fn save_glb(&self, path: &Path, options: GltfExportOptions) -> io::Result<()> {
+        save_nodes_glb(&[GltfNode::new(GLTF_MESH_NAME, self)], path, options)
+    }
+
+    //
+    // the primitives of the mesh, material slots looked up in the document's materials
+    // through material_remap
+    //
+    fn gltf_primitives(
+        &self,
+        builder: &mut GltfBuilder,
+        options: GltfExportOptions,
+        material_remap: &[usize],
+    ) -> Vec<Json> {
+        let mut primitives = Vec::new();
+
+        let triangles = self.gltf_triangles(options.flat_normals);
+        if !triangles.indicies.is_empty() {
+            let position = builder.add_vec3_accessor(&triangles.positions);
+            let normal = builder.add_vec3_accessor(&triangles.normals);
+            let mut attributes = Json::object([
+                ("POSITION", Json::Number(position as f64)),
+                ("NORMAL", Json::Number(normal as f64)),
+            ]);
+            if !triangles.uvs.is_empty() {
+                let uv = builder.add_vec2_accessor(&triangles.uvs);
+                attributes.set("TEXCOORD_0", Json::Number(uv as f64));
+            }
+            for (material, indicies) in self.gltf_material_groups(&triangles) {
+                let indicies = builder.add_index_accessor(&indicies);
+                let mut primitive = Json::object([
+                    ("attributes", attributes.clone()),
+                    ("indices", Json::Number(indicies as f64)),
+                    ("mode", Json::Number(GLTF_MODE_TRIANGLES)),
+                ]);
+                if let Some(material) = material {
+                    let material = material_remap[material];
+                    primitive.set("material", Json::Number(material as f64));
+                }
+                primitives.push(primitive);
+            }
+        }
+
+        if options.include_lines && !self.lines.is_empty() {
+            // lines index the mesh's own verts, flat export split those up
+            let position = builder.add_vec3_accessor(&self.verticies);
+            let indicies: Vec<u32> = self
+                .lines
+                .iter()
+                .flat_map(|&(a, b)| [a as u32, b as u32])
+                .collect();
+            let indicies = builder.add_index_accessor(&indicies);
+            primitives.push(Json::object([
+                (
+                    "attributes",
+                    Json::object([("POSITION", Json::Number(position as f64))]),
+                ),
+                ("indices", Json::Number(indicies as f64)),
+                ("mode", Json::Number(GLTF_MODE_LINES)),
+            ]));
+        }
+        primitives
+    }
+
+    //
+    // triangle indicies split by the material of their poly, in slot order with the polys
+    // without a material first; groups without triangles are left out
+    //
+    fn gltf_material_groups(
+        &self,
+        triangles: &GltfTriangles,
+    ) -> Vec<(Option<MaterialIndex>, Vec<u32>)> {
+        let mut groups: Vec<(Option<MaterialIndex>, Vec<u32>)> = std::iter::once(None)
+            .chain((0..self.materials.len()).map(Some))
+            .map(|material| (material, Vec::new()))
+            .collect();
+        for (triangle, &poly_index) in triangles
+            .indicies
+            .chunks(3)
+            .zip(triangles.triangle_polys.iter())
+        {
+            let group = self.poly_material(poly_index).map_or(0, |index| index + 1);
+            groups[group].1.extend(triangle);
+        }
+        groups.retain(|(_, indicies)| !indicies.is_empty());
+        groups
+    }
+
+    fn gltf_triangles(&self, flat_normals: bool) -> GltfTriangles {
+        let poly_triangles: Vec<Vec<[VertIndex; 3]>> = self
+            .polys
+            .iter()
+            .map(|poly| {
+                if poly.len() == 3 {
+                    vec![[poly[0], poly[1], poly[2]]]
+                } else {
+                    self.triangulate_loop(poly)
+                }
+            })
+            .collect();
+        let triangle_polys: Vec<PolyIndex> = poly_triangles
+            .iter()
+            .enumerate()
+            .flat_map(|(poly_index, triangles)| std::iter::repeat_n(poly_index, triangles.len()))
+            .collect();
+
+        let has_uvs = self.has_uvs();
+        // glTF has v pointing down
+        let corner_uv = |poly_index: PolyIndex, corner: usize| match self.poly_uvs(poly_index) {
+            Some(uvs) => Vec2::new(uvs[corner].x, 1.0 - uvs[corner].y),
+            None => Vec2::new(0.0, 1.0),
+        };
+
+        if flat_normals {
+            let mut triangles = GltfTriangles {
+                triangle_polys,
+                ..GltfTriangles::default()
+            };
+            for (poly_index, (poly, poly_triangles)) in
+                self.polys.iter().zip(poly_triangles).enumerate()
+            {
+                let first = triangles.positions.len();
+                let normal = unit_normal_or_up(self.poly_newell_normal(poly));
+                for (corner, &v) in poly.iter().enumerate() {
+                    triangles.positions.push(self.verticies[v]);
+                    triangles.normals.push(normal);
+                    if has_uvs {
+                        triangles.uvs.push(corner_uv(poly_index, corner));
+                    }
+                }
+                let corner =
+                    |v: VertIndex| (first + poly.iter().position(|&p| p == v).unwrap()) as u32;
+                triangles.indicies.extend(
+                    poly_triangles
+                        .iter()
+                        .flat_map(|triangle| triangle.map(corner)),
+                );
+            }
+            return triangles;
+        }
+
+        // area weighted average of the polys around each vert
+        let mut normals = vec![Vec3::ZERO; self.verticies.len()];
+        for poly in self.polys.iter() {
+            let normal = self.poly_newell_normal(poly);
+            for &v in poly.iter() {
+                normals[v] += normal;
+            }
+        }
+        let normals: Vec<Vec3> = normals.into_iter().map(unit_normal_or_up).collect();
+        if !has_uvs {
+            return GltfTriangles {
+                positions: self.verticies.clone(),
+                normals,
+                uvs: Vec::new(),
+                triangle_polys,
+                indicies: poly_triangles
+                    .iter()
+                    .flatten()
+                    .flat_map(|triangle| triangle.map(|v| v as u32))
+                    .collect(),
+            };
+        }
+
+        // verts are shared by the corners that agree on the UV, seams split them
+        let mut triangles = GltfTriangles {
+            triangle_polys,
+            ..GltfTriangles::default()
+        };
+        let mut shared: HashMap<(VertIndex, [u32; 2]), u32> = HashMap::new();
+        for (poly_index, (poly, poly_triangles)) in
+            self.polys.iter().zip(poly_triangles).enumerate()
+        {
+            let corner_indicies: Vec<u32> = poly
+                .iter()
+                .enumerate()
+                .map(|(corner, &v)| {
+                    let uv = corner_uv(poly_index, corner);
+                    *shared
+                        .entry((v, uv.to_array().map(f32::to_bits)))
+                        .or_insert_with(|| {
+                            triangles.positions.push(self.verticies[v]);
+                            triangles.normals.push(normals[v]);
+                            triangles.uvs.push(uv);
+                            triangles.positions.len() as u32 - 1
+                        })
+                })
+                .collect();
+            let corner = |v: VertIndex| corner_indicies[poly.iter().position(|&p| p == v).unwrap()];
+            triangles.indicies.extend(
+                poly_triangles
+                    .iter()
+                    .flat_map(|triangle| triangle.map(corner)),
+            );
+        }
+        triangles
+    }
+}
+
+// glTF wants unit normals, verts without any area around them get +y
+fn unit_normal_or_up(normal: Vec3) -> Vec3 {
+    match normal.try_normalize() {
+        Some(normal) => normal,
+        None => Vec3::Y,
+    }
+}
+
+//
+// Documents of one or more nodes
+//
+
+pub fn nodes_to_gltf(
+    nodes: &[GltfNode],
+    options: GltfExportOptions,
+    bin_uri: &str,
+) -> (String, Vec<u8>) {
+    let (mut document, buffer) = gltf_document(nodes, options);
+    if let Some(Json::Array(buffers)) = document.get("buffers") {
+        let mut buffers = buffers.clone();
+        buffers[0].set("uri", Json::string(bin_uri));
+        document.set("buffers", Json::Array(buffers));
+    }
+    (document.to_string(), buffer)
+}
+
+pub fn nodes_to_glb(nodes: &[GltfNode], options: GltfExportOptions) -> Vec<u8> {
+    let (document, buffer) = gltf_document(nodes, options);
+    write_glb(&document.to_string(), &buffer)
+}
+
+//
+// writes the .gltf and a .bin with the same name next to it
+//
+pub fn save_nodes_gltf(
+    nodes: &[GltfNode],
+    path: &Path,
+    options: GltfExportOptions,
+) -> io::Result<()> {
+    let bin_path = path.with_extension("bin");
+    let bin_uri = bin_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("mesh.bin");
+    let (document, buffer) = nodes_to_gltf(nodes, options, bin_uri);
+    fs::write(path, document)?;
+    fs::write(&bin_path, buffer)
+}
+
+pub fn save_nodes_glb(
+    nodes: &[GltfNode],
+    path: &Path,
+    options: GltfExportOptions,
+) -> io::Result<()> {
+    fs::write(path, nodes_to_glb(nodes, options))
+}
+
+fn gltf_document(nodes: &[GltfNode], options: GltfExportOptions) -> (Json, Vec<u8>) {
+    let mut builder = GltfBuilder::default();
+    let mut materials: Vec<MaterialSlot> = Vec::new();
+    let mut node_entries = Vec::new();
+    let mut meshes = Vec::new();
+
+    for node in nodes {
+        let material_remap;
+        (materials, material_remap) = merged_materials(&materials, &node.mesh.materials);
+        let primitives = node
+            .mesh
+            .gltf_primitives(&mut builder, options, &material_remap);
+
+        let mut entry = Json::object([("name", Json::string(node.name))]);
+        if !primitives.is_empty() {
+            entry.set("mesh", Json::Number(meshes.len() as f64));
+            meshes.push(Json::object([
+                ("name", Json::string(node.name)),
+                ("primitives", Json::Array(primitives)),
+            ]));
+        }
+        if node.translation != Vec3::ZERO {
+            let translation = node.translation.to_array().map(|c| c as f64);
+            entry.set("translation", Json::numbers(translation));
+        }
+        if node.rotation != Quat::IDENTITY {
+            let rotation = node.rotation.to_array().map(|c| c as f64);
+            entry.set("rotation", Json::numbers(rotation));
+        }
+        if node.scale != Vec3::ONE {
+            entry.set(
+                "scale",
+                Json::numbers(node.scale.to_array().map(|c| c as f64)),
+            );
+        }
+        node_entries.push(entry);
+    }
+
+    let (mut document, buffer) = builder.document(node_entries, meshes);
+    if !materials.is_empty() {
+        document.set("materials", gltf_materials(&materials));
+    }
+    (document, buffer)
+}
+
+fn gltf_materials(materials: &[MaterialSlot]) -> Json {
+    let materials = materials.iter().map(|material| {
+        let color = material.color.to_vec().to_array().map(|c| c as f64);
+        Json::object([
+            ("name", Json::string(&material.name)),
+            (
+                "pbrMetallicRoughness",
+                Json::object([("baseColorFactor", Json::numbers(color))]),
+            ),
+        ])
+    });
+    Json::Array(materials.collect())
+}
+
+#[derive(Default)]
+struct GltfTriangles {
+    positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    // empty when the mesh has no UVs
+    uvs: Vec<Vec2>,
+    indicies: Vec<u32>,
+    // poly each triangle (3 indicies) came from
+    triangle_polys: Vec<PolyIndex>,
+}
+
+//
+// Buffer, buffer views and accessors (one view per accessor, 4 byte aligned)
+//
+
+#[derive(Default)]
+struct GltfBuilder {
+    buffer: Vec<u8>,
+    buffer_views: Vec<Json>,
+    accessors: Vec<Json>,
+}
+
+impl GltfBuilder {
+    fn add_buffer_view(&mut self, bytes: &[u8], target: f64) -> usize {
+        self.buffer.resize(self.buffer.len().next_multiple_of(4), 0);
+        self.buffer_views.push(Json::object([
+            ("buffer", Json::Number(0.0)),
+            ("byteOffset", Json::Number(self.buffer.len() as f64)),
+            ("byteLength", Json::Number(bytes.len() as f64)),
+            ("target", Json::Number(target)),
+        ]));
+        self.buffer.extend(bytes);
+        self.buffer_views.len() - 1
+    }
+
+    fn add_vec3_accessor(&mut self, values: &[Vec3]) -> usize {
+        let bytes: Vec<u8> = values
+            .iter()
+            .flat_map(|v| v.to_array())
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        let view = self.add_buffer_view(&bytes, GLTF_ARRAY_BUFFER);
+        let (min, max) = values.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), &v| (min.min(v), max.max(v)),
+        );
+        self.accessors.push(Json::object([
+            ("bufferView", Json::Number(view as f64)),
+            ("componentType", Json::Number(GLTF_FLOAT)),
+            ("count", Json::Number(values.len() as f64)),
+            ("type", Json::string("VEC3")),
+            ("min", Json::numbers(min.to_array().map(|c| c as f64))),
+            ("max", Json::numbers(max.to_array().map(|c| c as f64))),
+        ]));
+        self.accessors.len() - 1
+    }
+
+    fn add_vec2_accessor(&mut self, values: &[Vec2]) -> usize {
+        let bytes: Vec<u8> = values
+            .iter()
+            .flat_map(|v| v.to_array())
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        let view = self.add_buffer_view(&bytes, GLTF_ARRAY_BUFFER);
+        let (min, max) = values.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), &v| (min.min(v), max.max(v)),
+        );
+        self.accessors.push(Json::object([
+            ("bufferView", Json::Number(view as f64)),
+            ("componentType", Json::Number(GLTF_FLOAT)),
+            ("count", Json::Number(values.len() as f64)),
+            ("type", Json::string("VEC2")),
+            ("min", Json::numbers(min.to_array().map(|c| c as f64))),
+            ("max", Json::numbers(max.to_array().map(|c| c as f64))),
+        ]));
+        self.accessors.len() - 1
+    }
+
+    fn add_index_accessor(&mut self, indicies: &[u32]) -> usize {
+        let bytes: Vec<u8> = indicies.iter().flat_map(|i| i.to_le_bytes()).collect();
+        let view = self.add_buffer_view(&bytes, GLTF_ELEMENT_ARRAY_BUFFER);
+        let min = indicies.iter().min().copied().unwrap_or(0);
+        let max = indicies.iter().max().copied().unwrap_or(0);
+        self.accessors.push(Json::object([
+            ("bufferView", Json::Number(view as f64)),
+            ("componentType", Json::Number(GLTF_UNSIGNED_INT)),
+            ("count", Json::Number(indicies.len() as f64)),
+            ("type", Json::string("SCALAR")),
+            ("min", Json::numbers([min as f64])),
+            ("max", Json::numbers([max as f64])),
+        ]));
+        self.accessors.len() - 1
+    }
+
+    //
+    // the whole document with one scene holding every node; the buffer has no uri (glb)
+    //
+    fn document(mut self, nodes: Vec<Json>, meshes: Vec<Json>) -> (Json, Vec<u8>) {
+        self.buffer.resize(self.buffer.len().next_multiple_of(4), 0);
+        let scene_nodes = Json::numbers((0..nodes.len()).map(|i| i as f64));
+        let mut document = Json::object([
+            (
+                "asset",
+                Json::object([
+                    ("version", Json::string("2.0")),
+                    ("generator", Json::string(GLTF_GENERATOR)),
+                ]),
+            ),
+            ("scene", Json::Number(0.0)),
+            (
+                "scenes",
+                Json::Array(vec![Json::object([("nodes", scene_nodes)])]),
+            ),
+            ("nodes", Json::Array(nodes)),
+        ]);
+        if !meshes.is_empty() {
+            document.set("meshes", Json::Array(meshes));
+        }
+        if !self.buffer.is_empty() {
+            document.set(
+                "buffers",
+                Json::Array(vec![Json::object([(
+                    "byteLength",
+                    Json::Number(self.buffer.len() as f64),
+                )])]),
+            );
+            document.set("bufferViews", Json::Array(self.buffer_views));
+            document.set("accessors", Json::Array(self.accessors));
+        }
+        (document, self.buffer)
+    }
+}
+
+//
+// header, json chunk (space padded), bin chunk (zero padded)
+//
+fn write_glb(json: &str, buffer: &[u8]) -> Vec<u8> {
+    let mut json_chunk = json.as_bytes().to_vec();
+    json_chunk.resize(json_chunk.len().next_multiple_of(4), b' ');
+    let mut bin_chunk = buffer.to_vec();
+    bin_chunk.resize(bin_chunk.len().next_multiple_of(4), 0);
+
+    let mut total_length = GLB_HEADER_SIZE + GLB_CHUNK_HEADER_SIZE + json_chunk.len();
+    if !bin_chunk.is_empty() {
+        total_length += GLB_CHUNK_HEADER_SIZE + bin_chunk.len();
+    }
+
+    let mut bytes = Vec::with_capacity(total_length);
+    for word in [GLB_MAGIC, GLB_VERSION, total_length as u32] {
+        bytes.extend(word.to_le_bytes());
+    }
+    bytes.extend((json_chunk.len() as u32).to_le_bytes());
+    bytes.extend(GLB_CHUNK_JSON.to_le_bytes());
+    bytes.extend(json_chunk);
+    if !bin_chunk.is_empty() {
+        bytes.extend((bin_chunk.len() as u32).to_le_bytes());
+        bytes.extend(GLB_CHUNK_BIN.to_le_bytes());
+        bytes.extend(bin_chunk);
+    }
+    bytes
+}
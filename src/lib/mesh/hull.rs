@@ -0,0 +1,406 @@
+//
+// hull -> convex hull of a point set (3D quickhull), e.g. as a collision proxy
+//
+// Coplanar hull triangles are merged back into n-gons. Point sets that are flat give a
+// single (open) n-gon, coincident or collinear points are an error.
+//
+
+use super::*;
+
+// relative to the size of the point set
+const HULL_TOLERANCE: f32 = 1e-5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HullError {
+    // fewer than 3 distinct points
+    NotEnoughPoints,
+    // every point is on one line, so there is no surface to wrap around them
+    Collinear,
+}
+
+impl Mesh {
+    //
+    // Closed, outward wound hull around the given verts (a single n-gon when they are flat,
+    // indicies out of range are skipped), as a new mesh. See Mesh::new_convex_hull.
+    //
+    pub fn convex_hull(&self, verts: &[VertIndex]) -> Result<Mesh, HullError> {
+        let points: Vec<Vec3> = verts
+            .iter()
+            .filter_map(|&v| self.verticies.get(v).copied())
+            .collect();
+        Mesh::new_convex_hull(&points)
+    }
+
+    //
+    // Hull mesh using only the points on its corners, lines derived from the polys.
+    //
+    pub fn new_convex_hull(points: &[Vec3]) -> Result<Mesh, HullError> {
+        if points.len() < 3 {
+            return Err(HullError::NotEnoughPoints);
+        }
+        let (min, max) = points.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), &p| (min.min(p), max.max(p)),
+        );
+        let tolerance = HULL_TOLERANCE * (max - min).max_element().max(1.0);
+
+        let faces = match initial_simplex(points, tolerance)? {
+            Simplex::Flat(normal) => flat_hull(points, normal, tolerance),
+            Simplex::Solid(corners) => {
+                let mut hull = Quickhull::new(points, corners, tolerance);
+                hull.run();
+                hull.merged_faces()
+            }
+        };
+
+        let mut mesh = Mesh::new();
+        mesh.set_line_policy(LinePolicy::DeriveFromPolys);
+        let mut new_index: HashMap<usize, VertIndex> = HashMap::new();
+        for face in faces {
+            let poly: Poly = face
+                .iter()
+                .map(|&p| {
+                    *new_index
+                        .entry(p)
+                        .or_insert_with(|| mesh.add_vert(points[p]))
+                })
+                .collect();
+            mesh.add_poly(poly);
+        }
+        Ok(mesh)
+    }
+}
+
+enum Simplex {
+    // normal of the plane every point lies in
+    Flat(Vec3),
+    // tetrahedron: base corners (wound facing away from the apex), then the apex
+    Solid([usize; 4]),
+}
+
+//
+// two far apart extreme points, the point farthest from their line,
+// then the point farthest from the plane of those three
+//
+fn initial_simplex(points: &[Vec3], tolerance: f32) -> Result<Simplex, HullError> {
+    let extremes: Vec<usize> = (0..3)
+        .flat_map(|axis| {
+            let by_axis = |&a: &usize, &b: &usize| points[a][axis].total_cmp(&points[b][axis]);
+            let indicies = 0..points.len();
+            [
+                indicies.clone().min_by(by_axis).unwrap(),
+                indicies.max_by(by_axis).unwrap(),
+            ]
+        })
+        .collect();
+    let (a, b) = extremes
+        .iter()
+        .tuple_combinations()
+        .map(|(&a, &b)| (a, b))
+        .max_by(|x, y| {
+            let length = |(a, b): (usize, usize)| points[a].distance_squared(points[b]);
+            length(*x).total_cmp(&length(*y))
+        })
+        .unwrap();
+    if points[a].distance(points[b]) < tolerance {
+        return Err(HullError::NotEnoughPoints);
+    }
+
+    let direction = (points[b] - points[a]).normalize();
+    let line_distance = |p: Vec3| (p - points[a]).reject_from_normalized(direction).length();
+    let c = farthest(points, line_distance);
+    if line_distance(points[c]) < tolerance {
+        return Err(HullError::Collinear);
+    }
+
+    let normal = (points[b] - points[a])
+        .cross(points[c] - points[a])
+        .normalize();
+    let plane_distance = |p: Vec3| (p - points[a]).dot(normal).abs();
+    let d = farthest(points, plane_distance);
+    if plane_distance(points[d]) < tolerance {
+        return Ok(Simplex::Flat(normal));
+    }
+
+    // base faces away from the apex
+    if (points[d] - points[a]).dot(normal) > 0.0 {
+        Ok(Simplex::Solid([a, c, b, d]))
+    } else {
+        Ok(Simplex::Solid([a, b, c, d]))
+    }
+}
+
+fn farthest(points: &[Vec3], distance: impl Fn(Vec3) -> f32) -> usize {
+    (0..points.len())
+        .max_by(|&x, &y| distance(points[x]).total_cmp(&distance(points[y])))
+        .unwrap()
+}
+
+//
+// 2D hull (monotone chain) in the plane, as one n-gon facing along the normal
+//
+fn flat_hull(points: &[Vec3], normal: Vec3, tolerance: f32) -> Vec<Vec<usize>> {
+    let u = normal.any_orthonormal_vector();
+    let v = normal.cross(u);
+    let flat = |p: usize| Vec2::new(points[p].dot(u), points[p].dot(v));
+    let sorted: Vec<usize> = (0..points.len())
+        .sorted_by(|&a, &b| {
+            let (a, b) = (flat(a), flat(b));
+            a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+        })
+        .collect();
+
+    let turns_left = |o: usize, a: usize, b: usize| {
+        (flat(a) - flat(o)).perp_dot(flat(b) - flat(o)) > tolerance * tolerance
+    };
+    let chain = |points: &mut dyn Iterator<Item = &usize>| {
+        let mut half: Vec<usize> = Vec::new();
+        for &p in points {
+            while half.len() >= 2 && !turns_left(half[half.len() - 2], half[half.len() - 1], p) {
+                half.pop();
+            }
+            half.push(p);
+        }
+        half.pop();
+        half
+    };
+    // u x v is the normal, so counter-clockwise in (u, v) faces along it
+    let mut outline = chain(&mut sorted.iter());
+    outline.extend(chain(&mut sorted.iter().rev()));
+    vec![outline]
+}
+
+//
+// Quickhull state: triangle faces with the points still outside each one
+//
+
+struct HullFace {
+    verts: [usize; 3],
+    normal: Vec3,
+    offset: f32,
+    outside: Vec<usize>,
+    is_alive: bool,
+}
+
+struct Quickhull<'a> {
+    points: &'a [Vec3],
+    tolerance: f32,
+    faces: Vec<HullFace>,
+    // directed edge -> face walking it (counter-clockwise)
+    edge_faces: HashMap<(usize, usize), usize>,
+}
+
+impl<'a> Quickhull<'a> {
+    fn new(points: &'a [Vec3], corners: [usize; 4], tolerance: f32) -> Quickhull<'a> {
+        let [a, b, c, d] = corners;
+        let mut hull = Quickhull {
+            points,
+            tolerance,
+            faces: Vec::new(),
+            edge_faces: HashMap::new(),
+        };
+        for verts in [[a, b, c], [a, d, b], [b, d, c], [c, d, a]] {
+            hull.add_face(verts);
+        }
+        let new_faces: Vec<usize> = (0..4).collect();
+        let candidates: Vec<usize> = (0..points.len()).filter(|p| !corners.contains(p)).collect();
+        hull.assign_outside(&candidates, &new_faces);
+        hull
+    }
+
+    fn add_face(&mut self, verts: [usize; 3]) -> usize {
+        let [a, b, c] = verts.map(|v| self.points[v]);
+        let normal = (b - a).cross(c - a).normalize_or_zero();
+        let face_index = self.faces.len();
+        for i in 0..3 {
+            self.edge_faces
+                .insert((verts[i], verts[(i + 1) % 3]), face_index);
+        }
+        self.faces.push(HullFace {
+            verts,
+            normal,
+            offset: normal.dot(a),
+            outside: Vec::new(),
+            is_alive: true,
+        });
+        face_index
+    }
+
+    fn distance(&self, face_index: usize, p: usize) -> f32 {
+        let face = &self.faces[face_index];
+        face.normal.dot(self.points[p]) - face.offset
+    }
+
+    // points inside every face are dropped
+    fn assign_outside(&mut self, candidates: &[usize], faces: &[usize]) {
+        for &p in candidates {
+            if let Some(&face_index) = faces
+                .iter()
+                .find(|&&face_index| self.distance(face_index, p) > self.tolerance)
+            {
+                self.faces[face_index].outside.push(p);
+            }
+        }
+    }
+
+    fn run(&mut self) {
+        while let Some(face_index) = (0..self.faces.len())
+            .find(|&f| self.faces[f].is_alive && !self.faces[f].outside.is_empty())
+        {
+            let eye = *self.faces[face_index]
+                .outside
+                .iter()
+                .max_by(|&&x, &&y| {
+                    self.distance(face_index, x)
+                        .total_cmp(&self.distance(face_index, y))
+                })
+                .unwrap();
+            self.add_point(face_index, eye);
+        }
+    }
+
+    //
+    // replaces every face the eye point can see with a cone from the horizon to the eye
+    //
+    fn add_point(&mut self, seed_face: usize, eye: usize) {
+        let mut visible = vec![seed_face];
+        let mut is_visible: HashSet<usize> = HashSet::from([seed_face]);
+        let mut i = 0;
+        while i < visible.len() {
+            let verts = self.faces[visible[i]].verts;
+            for k in 0..3 {
+                let twin = (verts[(k + 1) % 3], verts[k]);
+                if let Some(&neighbor) = self.edge_faces.get(&twin)
+                    && !is_visible.contains(&neighbor)
+                    && self.distance(neighbor, eye) > self.tolerance
+                {
+                    is_visible.insert(neighbor);
+                    visible.push(neighbor);
+                }
+            }
+            i += 1;
+        }
+
+        // edges between a visible face and one that isn't
+        let visible_edges: Vec<(usize, usize)> = visible
+            .iter()
+            .flat_map(|&face_index| {
+                let verts = self.faces[face_index].verts;
+                (0..3).map(move |k| (verts[k], verts[(k + 1) % 3]))
+            })
+            .collect();
+        let horizon: Vec<(usize, usize)> = visible_edges
+            .iter()
+            .filter(|&&(a, b)| {
+                !self
+                    .edge_faces
+                    .get(&(b, a))
+                    .is_some_and(|neighbor| is_visible.contains(neighbor))
+            })
+            .copied()
+            .collect();
+        for edge in visible_edges.iter() {
+            self.edge_faces.remove(edge);
+        }
+
+        let mut orphans: Vec<usize> = Vec::new();
+        for &face_index in visible.iter() {
+            let face = &mut self.faces[face_index];
+            face.is_alive = false;
+            orphans.extend(face.outside.drain(..).filter(|&p| p != eye));
+        }
+
+        let new_faces: Vec<usize> = horizon
+            .into_iter()
+            .map(|(a, b)| self.add_face([a, b, eye]))
+            .collect();
+        self.assign_outside(&orphans, &new_faces);
+    }
+
+    //
+    // live triangles, with neighbors in the same plane joined into one n-gon
+    //
+    fn merged_faces(&self) -> Vec<Vec<usize>> {
+        let alive: Vec<usize> = (0..self.faces.len())
+            .filter(|&f| self.faces[f].is_alive)
+            .collect();
+        let mut group_of: HashMap<usize, usize> = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+
+        for &seed in alive.iter() {
+            if group_of.contains_key(&seed) {
+                continue;
+            }
+            let mut group = vec![seed];
+            group_of.insert(seed, groups.len());
+            let mut i = 0;
+            while i < group.len() {
+                let verts = self.faces[group[i]].verts;
+                for k in 0..3 {
+                    let twin = (verts[(k + 1) % 3], verts[k]);
+                    if let Some(&neighbor) = self.edge_faces.get(&twin)
+                        && !group_of.contains_key(&neighbor)
+                        && self.is_coplanar(seed, neighbor)
+                    {
+                        group_of.insert(neighbor, groups.len());
+                        group.push(neighbor);
+                    }
+                }
+                i += 1;
+            }
+            groups.push(group);
+        }
+
+        groups
+            .iter()
+            .map(|group| self.group_outline(group, &group_of))
+            .collect()
+    }
+
+    fn is_coplanar(&self, first: usize, second: usize) -> bool {
+        let second_verts = self.faces[second].verts;
+        second_verts
+            .iter()
+            .all(|&p| self.distance(first, p).abs() < self.tolerance)
+            && self.faces[first].normal.dot(self.faces[second].normal) > 0.0
+    }
+
+    // the group's boundary edges chained into a loop (hull faces are convex, so it's one)
+    fn group_outline(&self, group: &[usize], group_of: &HashMap<usize, usize>) -> Vec<usize> {
+        let group_index = group_of[&group[0]];
+        let next: HashMap<usize, usize> = group
+            .iter()
+            .flat_map(|&f| {
+                let verts = self.faces[f].verts;
+                (0..3).map(move |k| (verts[k], verts[(k + 1) % 3]))
+            })
+            .filter(|&(a, b)| {
+                self.edge_faces
+                    .get(&(b, a))
+                    .is_none_or(|neighbor| group_of[neighbor] != group_index)
+            })
+            .collect();
+
+        let start = *next.keys().min().unwrap();
+        let mut outline = vec![start];
+        let mut current = next[&start];
+        while current != start && outline.len() <= next.len() {
+            outline.push(current);
+            current = next[&current];
+        }
+        outline
+    }
+}
+
+//
+// Formatting
+//
+
+impl fmt::Display for HullError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HullError::NotEnoughPoints => write!(formatter, "needs at least 3 distinct points"),
+            HullError::Collinear => write!(formatter, "points are all on one line"),
+        }
+    }
+}
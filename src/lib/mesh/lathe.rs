@@ -0,0 +1,227 @@
+//
+// lathe -> revolve a profile chain of verts around a world axis into a surface
+//
+
+use super::*;
+use std::f32::consts::TAU;
+
+const MIN_LATHE_STEPS: usize = 3;
+// verts this close to the axis are poles, shared by every step instead of copied
+const POLE_TOLERANCE: f32 = 1e-4;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub fn unit(&self) -> Vec3 {
+        match self {
+            Axis::X => Vec3::X,
+            Axis::Y => Vec3::Y,
+            Axis::Z => Vec3::Z,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LatheError {
+    // the verts are not connected by lines into a single open or closed chain
+    NotAChain,
+    // every profile vert is on the axis, so nothing would be swept
+    ProfileOnAxis,
+}
+
+//
+// Ordered verts of a profile, open chains start at one end
+//
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertChain {
+    pub verts: Vec<VertIndex>,
+    pub is_closed: bool,
+}
+
+impl Mesh {
+    //
+    // Orders the verts along the lines connecting them.
+    // Fails if they branch, fall apart into pieces or have no lines between them.
+    //
+    pub fn vert_chain(&self, verts: &[VertIndex]) -> Result<VertChain, LatheError> {
+        let mut neighbors: HashMap<VertIndex, Vec<VertIndex>> =
+            verts.iter().map(|&v| (v, Vec::new())).collect();
+        for line_index in self.lines_in_vertex_indicies(verts.to_vec()) {
+            let (a, b) = self.lines[line_index];
+            if a == b || neighbors[&a].contains(&b) {
+                continue;
+            }
+            neighbors.get_mut(&a).unwrap().push(b);
+            neighbors.get_mut(&b).unwrap().push(a);
+        }
+
+        if neighbors.len() < 2 || neighbors.values().any(|n| n.is_empty() || n.len() > 2) {
+            return Err(LatheError::NotAChain);
+        }
+
+        let ends: Vec<VertIndex> = neighbors
+            .iter()
+            .filter(|(_, n)| n.len() == 1)
+            .map(|(&v, _)| v)
+            .sorted()
+            .collect();
+        let is_closed = ends.is_empty();
+        let start = if is_closed {
+            *neighbors.keys().min().unwrap()
+        } else {
+            ends[0]
+        };
+
+        let mut chain = vec![start];
+        let mut previous = None;
+        let mut current = start;
+        while let Some(&next) = neighbors[&current]
+            .iter()
+            .filter(|&&n| Some(n) != previous && n != start)
+            .min()
+        {
+            chain.push(next);
+            previous = Some(current);
+            current = next;
+        }
+
+        if chain.len() != neighbors.len() {
+            return Err(LatheError::NotAChain);
+        }
+        Ok(VertChain {
+            verts: chain,
+            is_closed,
+        })
+    }
+
+    //
+    // Revolves the chain of verts around the axis (through the origin) in `steps` steps.
+    // See Mesh::new_lathe.
+    //
+    pub fn lathe(
+        &self,
+        chain: &VertChain,
+        axis: Axis,
+        steps: usize,
+        cap_ends: bool,
+    ) -> Result<Mesh, LatheError> {
+        let profile: Vec<Vec3> = chain.verts.iter().map(|&v| self.verticies[v]).collect();
+        Mesh::new_lathe(&profile, chain.is_closed, axis, steps, cap_ends)
+    }
+
+    //
+    // Quad strips swept by each profile segment, triangles where a segment touches the
+    // axis (poles are welded), and optionally an n-gon closing each open end that is
+    // off the axis. Polys face away from the axis.
+    //
+    pub fn new_lathe(
+        profile: &[Vec3],
+        is_closed: bool,
+        axis: Axis,
+        steps: usize,
+        cap_ends: bool,
+    ) -> Result<Mesh, LatheError> {
+        if profile.len() < 2 {
+            return Err(LatheError::NotAChain);
+        }
+        let steps = steps.max(MIN_LATHE_STEPS);
+        let axis = axis.unit();
+        let is_pole = |v: Vec3| (v - axis * v.dot(axis)).length() < POLE_TOLERANCE;
+        if profile.iter().all(|&v| is_pole(v)) {
+            return Err(LatheError::ProfileOnAxis);
+        }
+
+        let mut mesh = Mesh::new();
+        mesh.set_line_policy(LinePolicy::DeriveFromPolys);
+
+        // rings[i][k] -> profile vert i at step k (poles repeat the same index)
+        let rings: Vec<Vec<VertIndex>> = profile
+            .iter()
+            .map(|&v| {
+                if is_pole(v) {
+                    vec![mesh.add_vert(v); steps]
+                } else {
+                    (0..steps)
+                        .map(|k| {
+                            let rotation =
+                                Quat::from_axis_angle(axis, TAU * k as f32 / steps as f32);
+                            mesh.add_vert(rotation * v)
+                        })
+                        .collect()
+                }
+            })
+            .collect();
+
+        let segments: Vec<(usize, usize)> = if is_closed {
+            (0..profile.len()).circular_tuple_windows().collect()
+        } else {
+            (0..profile.len()).tuple_windows().collect()
+        };
+        for (i, j) in segments {
+            for k in 0..steps {
+                let next = (k + 1) % steps;
+                let poly: Poly = [rings[i][k], rings[i][next], rings[j][next], rings[j][k]]
+                    .into_iter()
+                    .dedup()
+                    .collect();
+                if poly.len() >= 3 && poly.first() != poly.last() {
+                    mesh.add_poly(poly);
+                }
+            }
+        }
+        let strip_poly_count = mesh.polys.len();
+
+        // caps walk their ring against the neighboring strip polys
+        if cap_ends && !is_closed {
+            let last = profile.len() - 1;
+            if !is_pole(profile[0]) {
+                mesh.add_poly(rings[0].iter().rev().copied().collect());
+            }
+            if !is_pole(profile[last]) {
+                mesh.add_poly(rings[last].clone());
+            }
+        }
+
+        // which way the strips face depends on the profile direction, so check against the axis
+        let outwardness: f32 = (0..strip_poly_count)
+            .map(|poly_index| {
+                let centroid = mesh.poly_centroid(&mesh.polys[poly_index]);
+                let radial = centroid - axis * centroid.dot(axis);
+                mesh.poly_newell_normal(&mesh.polys[poly_index]).dot(radial)
+            })
+            .sum();
+        if outwardness < 0.0 {
+            let all_polys: Vec<PolyIndex> = (0..mesh.polys.len()).collect();
+            mesh.flip_polys(&all_polys);
+        }
+        Ok(mesh)
+    }
+}
+
+//
+// Formatting
+//
+
+impl fmt::Display for Axis {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Axis::X => write!(formatter, "X"),
+            Axis::Y => write!(formatter, "Y"),
+            Axis::Z => write!(formatter, "Z"),
+        }
+    }
+}
+
+impl fmt::Display for LatheError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LatheError::NotAChain => write!(formatter, "verts don't form a single chain"),
+            LatheError::ProfileOnAxis => write!(formatter, "profile lies on the axis"),
+        }
+    }
+}
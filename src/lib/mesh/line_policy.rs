@@ -0,0 +1,122 @@
+//
+// line_policy -> keeping the edge lines of polys in sync with the polys themselves
+//
+
+use super::*;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum LinePolicy {
+    // lines are only ever added / removed explicitly
+    Manual,
+    // every poly edge has a line, lines added for a poly edge are removed once no poly
+    // uses them, lines drawn by hand stay (as wire lines when no poly uses them)
+    DeriveFromPolys,
+}
+
+impl Mesh {
+    pub fn line_policy(&self) -> LinePolicy {
+        self.line_policy
+    }
+
+    pub fn set_line_policy(&mut self, policy: LinePolicy) {
+        self.line_policy = policy;
+        if policy == LinePolicy::DeriveFromPolys {
+            self.sync_lines_with_polys();
+        }
+    }
+
+    //
+    // adds a line for every poly edge that doesn't have one yet
+    //
+    pub fn sync_lines_with_polys(&mut self) {
+        let edges: Vec<Line> = self.polys.iter().flat_map(poly_edges).collect();
+        for edge in edges {
+            self.ensure_line_for_edge(edge);
+        }
+    }
+
+    //
+    // lines that are not an edge of any poly
+    //
+    pub fn wire_lines(&self) -> Vec<LineIndex> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| self.edge_polys(**line).is_empty())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub fn find_line(&self, edge: Line) -> Option<LineIndex> {
+        let key = edge_key(edge.0, edge.1);
+        self.vert_lines(edge.0)
+            .iter()
+            .copied()
+            .find(|&i| edge_key(self.lines[i].0, self.lines[i].1) == key)
+    }
+
+    pub(super) fn ensure_line_for_edge(&mut self, edge: Line) {
+        let vert_count = self.verticies.len();
+        if edge.0 < vert_count && edge.1 < vert_count && self.find_line(edge).is_none() {
+            self.lines.push(edge);
+            self.derived_lines.push(true);
+            self.topology.insert_line(self.lines.len() - 1, edge);
+        }
+    }
+
+    //
+    // Hooks called by the basic edit functions
+    //
+
+    pub(super) fn derive_lines_after_poly_added(&mut self, poly_index: PolyIndex) {
+        if self.line_policy != LinePolicy::DeriveFromPolys {
+            return;
+        }
+        let edges: Vec<Line> = poly_edges(&self.polys[poly_index]).collect();
+        for edge in edges {
+            self.ensure_line_for_edge(edge);
+        }
+    }
+
+    pub(super) fn derive_lines_after_poly_removed(&mut self, removed_poly: &Poly) {
+        if self.line_policy != LinePolicy::DeriveFromPolys {
+            return;
+        }
+        self.remove_unused_edge_lines(poly_edges(removed_poly).collect());
+    }
+
+    //
+    // polys touching a deleted vert can shrink or vanish, so re-derive around them
+    // (edges are given with indicies from after the delete)
+    //
+    pub(super) fn derive_lines_after_vert_deleted(&mut self, touched_edges: Vec<Line>) {
+        if self.line_policy != LinePolicy::DeriveFromPolys {
+            return;
+        }
+        self.remove_unused_edge_lines(touched_edges.clone());
+
+        let touched_polys: Vec<PolyIndex> = touched_edges
+            .iter()
+            .flat_map(|edge| [edge.0, edge.1])
+            .flat_map(|v| self.vert_polys(v).to_vec())
+            .unique()
+            .collect();
+        for poly_index in touched_polys {
+            self.derive_lines_after_poly_added(poly_index);
+        }
+    }
+
+    fn remove_unused_edge_lines(&mut self, edges: Vec<Line>) {
+        let mut lines_to_remove: Vec<LineIndex> = edges
+            .into_iter()
+            .filter(|edge| self.edge_polys(*edge).is_empty())
+            .filter_map(|edge| self.find_line(edge))
+            .filter(|&line_index| self.derived_lines[line_index])
+            .collect();
+        lines_to_remove.sort_unstable();
+        lines_to_remove.dedup();
+        for line_index in lines_to_remove.into_iter().rev() {
+            self.remove_line(line_index);
+        }
+    }
+}
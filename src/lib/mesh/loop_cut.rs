@@ -0,0 +1,292 @@
+//
+// loop_cut -> edge ring / edge loop traversal and loop cut insertion through quad strips
+//
+
+use super::*;
+
+// edges of a ring (all oriented parallel to the start edge) and the quads between them,
+// each quad stored with the oriented ring edge it was entered through
+struct EdgeRing {
+    edges: Vec<Line>,
+    quads: Vec<(PolyIndex, Line)>,
+}
+
+impl Mesh {
+    //
+    // Selection helpers (return indicies into lines, e.g. for Selection::Lines)
+    //
+
+    pub fn select_edge_ring(&self, start: Line) -> Vec<LineIndex> {
+        self.line_indicies_for_edges(&self.edge_ring(start))
+    }
+
+    pub fn select_edge_loop(&self, start: Line) -> Vec<LineIndex> {
+        self.line_indicies_for_edges(&self.edge_loop(start))
+    }
+
+    //
+    // edges crossed when walking across opposite sides of adjacent quads
+    //
+    pub fn edge_ring(&self, start: Line) -> Vec<Line> {
+        self.walk_edge_ring(start).edges
+    }
+
+    //
+    // edges continuing straight through regular (4 quad) verts
+    //
+    pub fn edge_loop(&self, start: Line) -> Vec<Line> {
+        if self.edge_polys(start).is_empty() {
+            return vec![start];
+        }
+
+        let (forward, closed) = self.walk_edge_loop(start);
+        if closed {
+            return [vec![start], forward].concat();
+        }
+
+        let (backward, _) = self.walk_edge_loop((start.1, start.0));
+        backward
+            .into_iter()
+            .rev()
+            .map(|(a, b)| (b, a))
+            .chain(std::iter::once(start))
+            .chain(forward)
+            .collect()
+    }
+
+    //
+    // Inserts a new edge loop through the ring containing start. Each ring edge (a, b)
+    // is split at a + (b - a) * t. Returns the line indicies of the new loop.
+    //
+    pub fn loop_cut(&mut self, start: Line, t: f32) -> Vec<LineIndex> {
+        let ring = self.walk_edge_ring(start);
+        if ring.quads.is_empty() {
+            return Vec::new();
+        }
+        let t = t.clamp(0.0, 1.0);
+
+        let mut cut_verts: HashMap<Line, VertIndex> = HashMap::new();
+        for &(a, b) in ring.edges.iter() {
+            let key = edge_key(a, b);
+            if cut_verts.contains_key(&key) {
+                continue;
+            }
+            let coord = self.verticies[a].lerp(self.verticies[b], t);
+            let cut_vert = self.add_vert(coord);
+            self.vert_colors[cut_vert] = lerp_colors(self.vert_colors[a], self.vert_colors[b], t);
+            cut_verts.insert(key, cut_vert);
+        }
+
+        // split the ring quads in two
+        let mut loop_lines: Vec<Line> = Vec::new();
+        let ring_quad_set: HashSet<PolyIndex> = ring.quads.iter().map(|(q, _)| *q).collect();
+        for &(quad_index, entry) in ring.quads.iter() {
+            let quad = self.polys[quad_index].clone();
+            let entry_key = edge_key(entry.0, entry.1);
+            let Some(i) = (0..4).find(|&i| edge_key(quad[i], quad[(i + 1) % 4]) == entry_key)
+            else {
+                continue;
+            };
+            let (a, b, c, d) = (
+                quad[i],
+                quad[(i + 1) % 4],
+                quad[(i + 2) % 4],
+                quad[(i + 3) % 4],
+            );
+            let p = cut_verts[&edge_key(a, b)];
+            let q = cut_verts[&edge_key(c, d)];
+
+            self.polys[quad_index] = vec![a, p, q, d];
+            self.polys.push(vec![p, b, c, q]);
+            self.poly_colors.push(self.poly_colors[quad_index]);
+            self.poly_materials.push(self.poly_materials[quad_index]);
+            let uvs = std::mem::take(&mut self.poly_uvs[quad_index]);
+            if uvs.is_empty() {
+                self.poly_uvs.push(PolyUvs::new());
+            } else {
+                let (ua, ub, uc, ud) =
+                    (uvs[i], uvs[(i + 1) % 4], uvs[(i + 2) % 4], uvs[(i + 3) % 4]);
+                let up = ua.lerp(ub, edge_fraction(&self.verticies, a, b, p));
+                let uq = ud.lerp(uc, edge_fraction(&self.verticies, d, c, q));
+                self.poly_uvs[quad_index] = vec![ua, up, uq, ud];
+                self.poly_uvs.push(vec![up, ub, uc, uq]);
+            }
+            loop_lines.push((p, q));
+        }
+
+        // polys bordering the ring get the cut vert inserted into the split edge
+        let verticies = &self.verticies;
+        let polys = self.polys.iter_mut().zip(self.poly_uvs.iter_mut());
+        for (poly_index, (poly, uvs)) in polys.enumerate() {
+            if ring_quad_set.contains(&poly_index) {
+                continue;
+            }
+            let mut i = 0;
+            while i < poly.len() {
+                let next = (i + 1) % poly.len();
+                if let Some(&cut_vert) = cut_verts.get(&edge_key(poly[i], poly[next])) {
+                    if !uvs.is_empty() {
+                        let t = edge_fraction(verticies, poly[i], poly[next], cut_vert);
+                        uvs.insert(i + 1, uvs[i].lerp(uvs[next], t));
+                    }
+                    poly.insert(i + 1, cut_vert);
+                    i += 1;
+                }
+                i += 1;
+            }
+        }
+
+        // split lines along the cut edges
+        let line_count = self.lines.len();
+        for line_index in 0..line_count {
+            let (a, b) = self.lines[line_index];
+            if let Some(&cut_vert) = cut_verts.get(&edge_key(a, b)) {
+                self.lines[line_index] = (a, cut_vert);
+                self.lines.push((cut_vert, b));
+                self.derived_lines.push(self.derived_lines[line_index]);
+            }
+        }
+
+        let first_loop_line = self.lines.len();
+        self.derived_lines.extend(vec![true; loop_lines.len()]);
+        self.lines.extend(loop_lines);
+        self.rebuild_topology();
+        (first_loop_line..self.lines.len()).collect()
+    }
+
+    fn walk_edge_ring(&self, start: Line) -> EdgeRing {
+        let start_faces = self.edge_polys(start);
+        if start_faces.is_empty() {
+            return EdgeRing {
+                edges: vec![start],
+                quads: Vec::new(),
+            };
+        }
+
+        let (forward, closed) = self.walk_ring_from_face(start, start_faces[0]);
+        if closed || start_faces.len() != 2 {
+            return EdgeRing {
+                edges: [vec![start], forward.edges].concat(),
+                quads: forward.quads,
+            };
+        }
+
+        // open ring, also walk the other way and stitch the halves together
+        let (backward, _) = self.walk_ring_from_face(start, start_faces[1]);
+        let edges = backward
+            .edges
+            .into_iter()
+            .rev()
+            .chain(std::iter::once(start))
+            .chain(forward.edges)
+            .collect();
+        let quads = backward
+            .quads
+            .into_iter()
+            .rev()
+            .chain(forward.quads)
+            .collect();
+        EdgeRing { edges, quads }
+    }
+
+    fn walk_ring_from_face(&self, start: Line, first_face: PolyIndex) -> (EdgeRing, bool) {
+        let start_key = edge_key(start.0, start.1);
+        let mut ring = EdgeRing {
+            edges: Vec::new(),
+            quads: Vec::new(),
+        };
+        let mut visited: HashSet<PolyIndex> = HashSet::new();
+        let mut edge = start;
+        let mut face = first_face;
+
+        loop {
+            if !visited.insert(face) {
+                return (ring, false);
+            }
+            let Some(opposite) = Self::quad_opposite_edge(&self.polys[face], edge) else {
+                return (ring, false);
+            };
+            ring.quads.push((face, edge));
+
+            let opposite_key = edge_key(opposite.0, opposite.1);
+            if opposite_key == start_key {
+                return (ring, true);
+            }
+            ring.edges.push(opposite);
+
+            let faces = self.edge_polys(opposite);
+            if faces.len() != 2 {
+                return (ring, false);
+            }
+            face = if faces[0] == face { faces[1] } else { faces[0] };
+            edge = opposite;
+        }
+    }
+
+    //
+    // for quad [a, b, c, d] and edge a -> b returns the parallel opposite edge d -> c
+    //
+    fn quad_opposite_edge(poly: &Poly, edge: Line) -> Option<Line> {
+        if poly.len() != 4 {
+            return None;
+        }
+        let i =
+            (0..4).find(|&i| edge_key(poly[i], poly[(i + 1) % 4]) == edge_key(edge.0, edge.1))?;
+        let (a, c, d) = (poly[i], poly[(i + 2) % 4], poly[(i + 3) % 4]);
+        if a == edge.0 {
+            Some((d, c))
+        } else {
+            Some((c, d))
+        }
+    }
+
+    fn walk_edge_loop(&self, start: Line) -> (Vec<Line>, bool) {
+        let start_key = edge_key(start.0, start.1);
+        let mut edges: Vec<Line> = Vec::new();
+        let mut edge = start;
+
+        loop {
+            let vert = edge.1;
+            let vert_polys = self.vert_polys(vert);
+            let incident = self.vert_edges(vert);
+            if incident.len() != 4
+                || vert_polys.len() != 4
+                || vert_polys.iter().any(|&p| self.polys[p].len() != 4)
+            {
+                return (edges, false);
+            }
+
+            // the edge across the vert shares no poly with the current edge
+            let edge_polys = self.edge_polys(edge);
+            let next = incident.into_iter().find(|e| {
+                let faces = self.edge_polys(*e);
+                faces.len() == 2 && !faces.iter().any(|f| edge_polys.contains(f))
+            });
+            let Some(next) = next else {
+                return (edges, false);
+            };
+
+            if edge_key(next.0, next.1) == start_key {
+                return (edges, true);
+            }
+            if edges
+                .iter()
+                .any(|e| edge_key(e.0, e.1) == edge_key(next.0, next.1))
+            {
+                return (edges, false);
+            }
+            edges.push(next);
+            edge = next;
+        }
+    }
+}
+
+// how far along a -> b the cut vert sits (the ring may have cut the edge from either end)
+fn edge_fraction(verticies: &[Vec3], a: VertIndex, b: VertIndex, cut: VertIndex) -> f32 {
+    let edge = verticies[b] - verticies[a];
+    let length_squared = edge.length_squared();
+    if length_squared <= f32::EPSILON {
+        return 0.5;
+    }
+    (verticies[cut] - verticies[a]).dot(edge) / length_squared
+}
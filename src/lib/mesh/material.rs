@@ -0,0 +1,151 @@
+//
+// material -> named material slots, each poly in one slot or none
+//
+// Slots group the polys into surfaces (e.g. written as OBJ usemtl groups or glTF primitives).
+// Names are unique within a mesh, each slot has a color to show it by in the viewport.
+//
+
+use super::*;
+use macroquad::color::hsl_to_rgb;
+
+pub type MaterialIndex = usize;
+
+// hue step between consecutive slots, the golden ratio keeps them apart
+const MATERIAL_HUE_STEP: f32 = 0.618_034;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaterialSlot {
+    pub name: String,
+    // viewport color, written as the diffuse / base color by exporters
+    pub color: Color,
+}
+
+impl MaterialSlot {
+    pub fn new(name: &str, color: Color) -> MaterialSlot {
+        MaterialSlot {
+            name: name.to_string(),
+            color,
+        }
+    }
+}
+
+impl Mesh {
+    pub fn materials(&self) -> &[MaterialSlot] {
+        &self.materials
+    }
+
+    pub fn material_index(&self, name: &str) -> Option<MaterialIndex> {
+        self.materials
+            .iter()
+            .position(|material| material.name == name)
+    }
+
+    //
+    // slot with this name, added with a color of its own when there is none yet
+    //
+    pub fn add_material(&mut self, name: &str) -> MaterialIndex {
+        if let Some(index) = self.material_index(name) {
+            return index;
+        }
+        let color = material_color(self.materials.len());
+        self.materials.push(MaterialSlot::new(name, color));
+        self.materials.len() - 1
+    }
+
+    // None if the slot doesn't exist or another slot already has the name
+    pub fn rename_material(&mut self, index: MaterialIndex, name: &str) -> Option<()> {
+        if self
+            .material_index(name)
+            .is_some_and(|other| other != index)
+        {
+            return None;
+        }
+        self.materials.get_mut(index)?.name = name.to_string();
+        Some(())
+    }
+
+    pub fn set_material_color(&mut self, index: MaterialIndex, color: Color) -> Option<()> {
+        self.materials.get_mut(index)?.color = color;
+        Some(())
+    }
+
+    //
+    // polys in the slot are left without a material, later slots move down by one
+    //
+    pub fn remove_material(&mut self, index: MaterialIndex) -> Option<MaterialSlot> {
+        if index >= self.materials.len() {
+            return None;
+        }
+        for material in self.poly_materials.iter_mut() {
+            *material = match *material {
+                Some(slot) if slot == index => None,
+                Some(slot) if slot > index => Some(slot - 1),
+                other => other,
+            };
+        }
+        Some(self.materials.remove(index))
+    }
+
+    pub fn poly_material(&self, poly_index: PolyIndex) -> Option<MaterialIndex> {
+        self.poly_materials.get(poly_index).copied().flatten()
+    }
+
+    //
+    // puts the polys in the slot (None takes them out of theirs), returns how many were
+    // assigned; nothing is assigned to a slot that doesn't exist
+    //
+    pub fn assign_material(
+        &mut self,
+        poly_indicies: &[PolyIndex],
+        material: Option<MaterialIndex>,
+    ) -> usize {
+        if material.is_some_and(|index| index >= self.materials.len()) {
+            return 0;
+        }
+        let mut assigned_count = 0;
+        for &poly_index in poly_indicies.iter().unique() {
+            if let Some(slot) = self.poly_materials.get_mut(poly_index) {
+                *slot = material;
+                assigned_count += 1;
+            }
+        }
+        assigned_count
+    }
+
+    // in index order, None gives the polys without a material
+    pub fn polys_with_material(&self, material: Option<MaterialIndex>) -> Vec<PolyIndex> {
+        (0..self.polys.len())
+            .filter(|&poly_index| self.poly_material(poly_index) == material)
+            .collect()
+    }
+}
+
+// distinct colors for consecutive slots
+pub fn material_color(index: MaterialIndex) -> Color {
+    let hue = (index as f32 * MATERIAL_HUE_STEP).fract();
+    hsl_to_rgb(hue, 0.6, 0.55)
+}
+
+//
+// the slots of both meshes (those of other matched up by name, missing ones added at the
+// end) and where each slot of other ended up
+//
+pub(super) fn merged_materials(
+    materials: &[MaterialSlot],
+    other: &[MaterialSlot],
+) -> (Vec<MaterialSlot>, Vec<MaterialIndex>) {
+    let mut merged = materials.to_vec();
+    let remap = other
+        .iter()
+        .map(|material| {
+            merged
+                .iter()
+                .position(|existing| existing.name == material.name)
+                .unwrap_or_else(|| {
+                    merged.push(material.clone());
+                    merged.len() - 1
+                })
+        })
+        .collect();
+    (merged, remap)
+}
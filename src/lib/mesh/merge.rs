@@ -0,0 +1,123 @@
+//
+// merge -> weld verts that are closer than a threshold (merge by distance)
+//
+
+use super::*;
+
+type CellCoord = (i64, i64, i64);
+
+impl Mesh {
+    //
+    // Welds every vert into the first vert found within threshold, remaps lines + polys and
+    // drops whatever collapses. Uses a spatial hash with cell size = threshold so each vert only
+    // checks its 27 neighboring cells. Returns the number of verts removed.
+    //
+    pub fn merge_by_distance(&mut self, threshold: f32) -> usize {
+        let remap = self.weld_remap(threshold);
+        let kept_count = remap.iter().max().map_or(0, |max| max + 1);
+        let removed_count = self.verticies.len() - kept_count;
+        if removed_count == 0 {
+            return 0;
+        }
+
+        // representatives (the first vert of each group) keep their position and color
+        let mut kept_verts: Vec<Vec3> = vec![Vec3::ZERO; kept_count];
+        let mut kept_colors: Vec<Option<Color>> = vec![None; kept_count];
+        for (old_index, &new_index) in remap.iter().enumerate().rev() {
+            kept_verts[new_index] = self.verticies[old_index];
+            kept_colors[new_index] = self.vert_colors[old_index];
+        }
+        self.verticies = kept_verts;
+        self.vert_colors = kept_colors;
+        self.remap_selection_sets(SetKind::Verts, |v| Some(remap[v]));
+
+        self.remap_lines_after_merge(&remap);
+        self.remap_polys_after_merge(&remap);
+        self.rebuild_topology();
+        removed_count
+    }
+
+    //
+    // old vert index -> new vert index, representatives keep their relative order
+    //
+    fn weld_remap(&self, threshold: f32) -> Vec<VertIndex> {
+        let cell_size = threshold.max(f32::EPSILON);
+        let cell_of = |v: Vec3| -> CellCoord {
+            (
+                (v.x / cell_size).floor() as i64,
+                (v.y / cell_size).floor() as i64,
+                (v.z / cell_size).floor() as i64,
+            )
+        };
+
+        let mut cells: HashMap<CellCoord, Vec<VertIndex>> = HashMap::new();
+        let mut remap: Vec<VertIndex> = Vec::with_capacity(self.verticies.len());
+        let mut representative_count = 0;
+
+        for (vert_index, &v) in self.verticies.iter().enumerate() {
+            let cell = cell_of(v);
+            let found = Self::neighbor_cells(cell)
+                .filter_map(|neighbor| cells.get(&neighbor))
+                .flatten()
+                .copied()
+                .find(|&other| self.verticies[other].distance(v) <= threshold);
+
+            match found {
+                Some(representative) => remap.push(remap[representative]),
+                None => {
+                    cells.entry(cell).or_default().push(vert_index);
+                    remap.push(representative_count);
+                    representative_count += 1;
+                }
+            }
+        }
+        remap
+    }
+
+    fn neighbor_cells(cell: CellCoord) -> impl Iterator<Item = CellCoord> {
+        (-1..=1).flat_map(move |dx| {
+            (-1..=1)
+                .flat_map(move |dy| (-1..=1).map(move |dz| (cell.0 + dx, cell.1 + dy, cell.2 + dz)))
+        })
+    }
+
+    fn remap_lines_after_merge(&mut self, remap: &[VertIndex]) {
+        let mut seen: HashSet<Line> = HashSet::new();
+        for line in self.lines.iter_mut() {
+            *line = (remap[line.0], remap[line.1]);
+        }
+        let flags: Vec<bool> = self
+            .lines
+            .iter()
+            .map(|line| line.0 != line.1 && seen.insert(edge_key(line.0, line.1)))
+            .collect();
+        // collapsed lines and the later of doubled ones leave their sets
+        self.retain_lines(&flags);
+    }
+
+    fn remap_polys_after_merge(&mut self, remap: &[VertIndex]) {
+        for (poly, uvs) in self.polys.iter_mut().zip(self.poly_uvs.iter_mut()) {
+            for vert_index in poly.iter_mut() {
+                *vert_index = remap[*vert_index];
+            }
+            // collapse welded neighbors (including the wrap around), UVs follow their corners
+            let mut corner = 1;
+            while corner < poly.len() {
+                if poly[corner] == poly[corner - 1] {
+                    poly.remove(corner);
+                    if !uvs.is_empty() {
+                        uvs.remove(corner);
+                    }
+                } else {
+                    corner += 1;
+                }
+            }
+            while poly.len() > 1 && poly.first() == poly.last() {
+                poly.pop();
+                uvs.pop();
+            }
+        }
+
+        self.retain_polys(|_, poly| poly.len() > 2 && !Self::check_for_poly_dup_indicies(poly));
+    }
+}
@@ -0,0 +1,432 @@
+//
+// native -> the editor's own file format, a JSON document holding everything a mesh has
+//
+// {"format": "mesh_editor", "version": 1, "mirror_mode": "Radial(4)", "line_policy": "manual",
+//  "verts": [[x, y, z], ...], "lines": [[a, b], ...], "polys": [[v, v, v, ...], ...],
+//  "derived_lines": [line index, ...] (lines added for a poly edge),
+//  "vert_colors": [[r, g, b, a] or null, ...], "poly_colors": [...],
+//  "poly_uvs": [[[u, v], ...] or null, ...],
+//  "materials": [{"name": "...", "color": [r, g, b, a]}, ...], "poly_materials": [slot or null, ...],
+//  "selection_sets": [{"name": "...", "kind": "verts" / "lines" / "polys", "indicies": [...]}, ...]}
+//
+// The per vert / per poly lists (and derived_lines) are left out when the mesh has none of
+// that data. Floats that aren't finite are written as "NaN", "Infinity" or "-Infinity".
+//
+
+use super::*;
+use crate::json::Json;
+use std::fs;
+use std::path::Path;
+
+const NATIVE_FORMAT: &str = "mesh_editor";
+const NATIVE_VERSION: usize = 1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NativeError {
+    Io(String),
+    // not JSON, the message names the byte offset
+    Parse(String),
+    // not a mesh_editor document, or one written by a newer version
+    Format(String),
+    // field missing or not what it should be
+    Invalid(String),
+    // a line or poly pointing at a vert that doesn't exist (field, entry)
+    IndexOutOfRange { field: String, index: usize },
+}
+
+impl Mesh {
+    pub fn to_native(&self) -> String {
+        self.to_native_json().to_string()
+    }
+
+    pub fn to_native_json(&self) -> Json {
+        let mut document = Json::object([
+            ("format", Json::string(NATIVE_FORMAT)),
+            ("version", Json::Number(NATIVE_VERSION as f64)),
+            ("mirror_mode", Json::string(&self.mirror_mode.to_string())),
+            (
+                "line_policy",
+                Json::string(line_policy_name(self.line_policy)),
+            ),
+            (
+                "verts",
+                Json::Array(
+                    self.verticies
+                        .iter()
+                        .map(|v| floats_json(&v.to_array()))
+                        .collect(),
+                ),
+            ),
+            (
+                "lines",
+                Json::Array(
+                    self.lines
+                        .iter()
+                        .map(|&(a, b)| Json::numbers([a as f64, b as f64]))
+                        .collect(),
+                ),
+            ),
+            (
+                "polys",
+                Json::Array(
+                    self.polys
+                        .iter()
+                        .map(|poly| Json::numbers(poly.iter().map(|&v| v as f64)))
+                        .collect(),
+                ),
+            ),
+        ]);
+
+        if self.derived_lines.contains(&true) {
+            let derived = (0..self.lines.len()).filter(|&i| self.derived_lines[i]);
+            document.set("derived_lines", Json::numbers(derived.map(|i| i as f64)));
+        }
+        if self.has_vert_colors() {
+            document.set("vert_colors", colors_json(&self.vert_colors));
+        }
+        if self.has_poly_colors() {
+            document.set("poly_colors", colors_json(&self.poly_colors));
+        }
+        if self.has_uvs() {
+            let uvs = (0..self.polys.len()).map(|poly_index| match self.poly_uvs(poly_index) {
+                Some(uvs) => {
+                    Json::Array(uvs.iter().map(|uv| floats_json(&uv.to_array())).collect())
+                }
+                None => Json::Null,
+            });
+            document.set("poly_uvs", Json::Array(uvs.collect()));
+        }
+        if !self.materials.is_empty() {
+            let materials = self.materials.iter().map(|material| {
+                Json::object([
+                    ("name", Json::string(&material.name)),
+                    ("color", floats_json(&material.color.to_vec().to_array())),
+                ])
+            });
+            document.set("materials", Json::Array(materials.collect()));
+        }
+        if self.poly_materials.iter().any(Option::is_some) {
+            let slots = self.poly_materials.iter().map(|material| match material {
+                Some(index) => Json::Number(*index as f64),
+                None => Json::Null,
+            });
+            document.set("poly_materials", Json::Array(slots.collect()));
+        }
+        if !self.selection_sets.is_empty() {
+            let sets = self.selection_sets.iter().map(|set| {
+                Json::object([
+                    ("name", Json::string(&set.name)),
+                    ("kind", Json::string(&set.kind.to_string())),
+                    (
+                        "indicies",
+                        Json::numbers(set.indicies.iter().map(|&i| i as f64)),
+                    ),
+                ])
+            });
+            document.set("selection_sets", Json::Array(sets.collect()));
+        }
+        document
+    }
+
+    pub fn from_native(text: &str) -> Result<Mesh, NativeError> {
+        let document = Json::parse(text).map_err(NativeError::Parse)?;
+        Mesh::from_native_json(&document)
+    }
+
+    pub fn from_native_json(document: &Json) -> Result<Mesh, NativeError> {
+        if document.get("format").and_then(Json::as_str) != Some(NATIVE_FORMAT) {
+            return Err(NativeError::Format(format!(
+                "not a {} document",
+                NATIVE_FORMAT
+            )));
+        }
+        let version = document
+            .get("version")
+            .and_then(Json::as_usize)
+            .ok_or_else(|| invalid("version"))?;
+        if version > NATIVE_VERSION {
+            return Err(NativeError::Format(format!(
+                "version {} is newer than {}",
+                version, NATIVE_VERSION
+            )));
+        }
+
+        let mut mesh = Mesh::new();
+        mesh.mirror_mode = document
+            .get("mirror_mode")
+            .and_then(Json::as_str)
+            .and_then(parse_mirror_mode)
+            .ok_or_else(|| invalid("mirror_mode"))?;
+        let line_policy = document
+            .get("line_policy")
+            .and_then(Json::as_str)
+            .and_then(parse_line_policy)
+            .ok_or_else(|| invalid("line_policy"))?;
+
+        for entry in field_array(document, "verts")? {
+            let [x, y, z] = parse_floats(entry).ok_or_else(|| invalid("verts"))?;
+            mesh.add_vert(Vec3::new(x, y, z));
+        }
+
+        let out_of_range = |field: &str, index: usize| NativeError::IndexOutOfRange {
+            field: field.to_string(),
+            index,
+        };
+        for (index, entry) in field_array(document, "lines")?.iter().enumerate() {
+            let [a, b] = parse_indicies(entry).ok_or_else(|| invalid("lines"))?;
+            mesh.validate_line_indicies((a, b))
+                .ok_or_else(|| out_of_range("lines", index))?;
+            mesh.lines.push((a, b));
+            mesh.derived_lines.push(false);
+        }
+        for (index, entry) in field_array(document, "polys")?.iter().enumerate() {
+            let poly: Poly = entry
+                .as_array()
+                .and_then(|corners| corners.iter().map(Json::as_usize).collect())
+                .ok_or_else(|| invalid("polys"))?;
+            if poly.iter().any(|&v| v >= mesh.verticies.len()) {
+                return Err(out_of_range("polys", index));
+            }
+            mesh.validate_poly_indicies(&poly)
+                .ok_or_else(|| invalid("polys"))?;
+            mesh.polys.push(poly);
+            mesh.poly_uvs.push(PolyUvs::new());
+            mesh.poly_colors.push(None);
+            mesh.poly_materials.push(None);
+        }
+
+        if let Some(derived) = document.get("derived_lines") {
+            for entry in derived.as_array().ok_or_else(|| invalid("derived_lines"))? {
+                let flag = entry
+                    .as_usize()
+                    .and_then(|line_index| mesh.derived_lines.get_mut(line_index))
+                    .ok_or_else(|| invalid("derived_lines"))?;
+                *flag = true;
+            }
+        }
+
+        let vert_count = mesh.verticies.len();
+        let poly_count = mesh.polys.len();
+        if let Some(colors) = optional_list(document, "vert_colors", vert_count, parse_color)? {
+            mesh.vert_colors = colors;
+        }
+        if let Some(colors) = optional_list(document, "poly_colors", poly_count, parse_color)? {
+            mesh.poly_colors = colors;
+        }
+        let parse_uvs = |entry: &Json| -> Option<PolyUvs> {
+            entry
+                .as_array()?
+                .iter()
+                .map(|uv| parse_floats(uv).map(Vec2::from_array))
+                .collect()
+        };
+        if let Some(uvs) = optional_list(document, "poly_uvs", poly_count, parse_uvs)? {
+            mesh.poly_uvs = uvs.into_iter().map(|uvs| uvs.unwrap_or_default()).collect();
+            // one UV per corner (or none)
+            let fits = |(poly, uvs): (&Poly, &PolyUvs)| uvs.is_empty() || uvs.len() == poly.len();
+            if !mesh.polys.iter().zip(mesh.poly_uvs.iter()).all(fits) {
+                return Err(invalid("poly_uvs"));
+            }
+        }
+        if let Some(materials) = document.get("materials") {
+            for entry in materials.as_array().ok_or_else(|| invalid("materials"))? {
+                let name = entry.get("name").and_then(Json::as_str);
+                let color = entry.get("color").and_then(parse_color);
+                let (Some(name), Some(color)) = (name, color) else {
+                    return Err(invalid("materials"));
+                };
+                if mesh.material_index(name).is_some() {
+                    return Err(invalid("materials"));
+                }
+                mesh.materials.push(MaterialSlot::new(name, color));
+            }
+        }
+        let material_count = mesh.materials.len();
+        let parse_slot = |entry: &Json| entry.as_usize().filter(|&slot| slot < material_count);
+        if let Some(slots) = optional_list(document, "poly_materials", poly_count, parse_slot)? {
+            mesh.poly_materials = slots;
+        }
+
+        // lines are stored as they were, not derived again
+        mesh.rebuild_topology();
+        mesh.line_policy = line_policy;
+
+        if let Some(sets) = document.get("selection_sets") {
+            for entry in sets.as_array().ok_or_else(|| invalid("selection_sets"))? {
+                let set = parse_selection_set(entry).ok_or_else(|| invalid("selection_sets"))?;
+                if mesh.selection_set(&set.name).is_some()
+                    || mesh.save_selection_set(&set.name, set.kind, &set.indicies)
+                        != set.indicies.len()
+                {
+                    return Err(invalid("selection_sets"));
+                }
+            }
+        }
+        Ok(mesh)
+    }
+
+    pub fn load_native(path: &Path) -> Result<Mesh, NativeError> {
+        let text = fs::read_to_string(path).map_err(|error| NativeError::Io(error.to_string()))?;
+        Mesh::from_native(&text)
+    }
+
+    pub fn save_native(&self, path: &Path) -> Result<(), NativeError> {
+        fs::write(path, self.to_native()).map_err(|error| NativeError::Io(error.to_string()))
+    }
+}
+
+//
+// Writing helpers
+//
+
+// the shortest decimals that read back as the same f32 (NaN / infinities as strings)
+fn float_json(value: f32) -> Json {
+    Json::float(value.to_string().parse().unwrap_or(value as f64))
+}
+
+fn floats_json(values: &[f32]) -> Json {
+    Json::Array(values.iter().map(|&value| float_json(value)).collect())
+}
+
+fn colors_json(colors: &[Option<Color>]) -> Json {
+    Json::Array(
+        colors
+            .iter()
+            .map(|color| match color {
+                Some(color) => floats_json(&color.to_vec().to_array()),
+                None => Json::Null,
+            })
+            .collect(),
+    )
+}
+
+fn line_policy_name(policy: LinePolicy) -> &'static str {
+    match policy {
+        LinePolicy::Manual => "manual",
+        LinePolicy::DeriveFromPolys => "derive_from_polys",
+    }
+}
+
+//
+// Parsing helpers
+//
+
+fn field_array<'a>(document: &'a Json, field: &str) -> Result<&'a Vec<Json>, NativeError> {
+    document
+        .get(field)
+        .and_then(Json::as_array)
+        .ok_or_else(|| invalid(field))
+}
+
+//
+// one entry per vert / poly (null where unset), None when the field isn't there
+//
+fn optional_list<T>(
+    document: &Json,
+    field: &str,
+    count: usize,
+    parse: impl Fn(&Json) -> Option<T>,
+) -> Result<Option<Vec<Option<T>>>, NativeError> {
+    let Some(entries) = document.get(field) else {
+        return Ok(None);
+    };
+    let entries = entries
+        .as_array()
+        .filter(|entries| entries.len() == count)
+        .ok_or_else(|| invalid(field))?;
+    entries
+        .iter()
+        .map(|entry| match entry {
+            Json::Null => Ok(None),
+            entry => parse(entry).map(Some).ok_or_else(|| invalid(field)),
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+fn parse_floats<const N: usize>(value: &Json) -> Option<[f32; N]> {
+    let values = value.as_array().filter(|values| values.len() == N)?;
+    let mut floats = [0.0; N];
+    for (float, value) in floats.iter_mut().zip(values) {
+        *float = value.as_float()? as f32;
+    }
+    Some(floats)
+}
+
+fn parse_indicies<const N: usize>(value: &Json) -> Option<[usize; N]> {
+    let values = value.as_array().filter(|values| values.len() == N)?;
+    let mut indicies = [0; N];
+    for (index, value) in indicies.iter_mut().zip(values) {
+        *index = value.as_usize()?;
+    }
+    Some(indicies)
+}
+
+fn parse_color(value: &Json) -> Option<Color> {
+    let [r, g, b, a] = parse_floats(value)?;
+    Some(Color::new(r, g, b, a))
+}
+
+// indicies as written, not checked against the mesh yet
+fn parse_selection_set(value: &Json) -> Option<SelectionSet> {
+    let name = value.get("name")?.as_str()?;
+    let kind = match value.get("kind")?.as_str()? {
+        "verts" => SetKind::Verts,
+        "lines" => SetKind::Lines,
+        "polys" => SetKind::Polys,
+        _ => return None,
+    };
+    let indicies: Vec<usize> = value
+        .get("indicies")?
+        .as_array()?
+        .iter()
+        .map(Json::as_usize)
+        .collect::<Option<_>>()?;
+    Some(SelectionSet::new(name, kind, &indicies))
+}
+
+// as written by the Display of MirrorMode
+fn parse_mirror_mode(text: &str) -> Option<MirrorMode> {
+    match text {
+        "None" => Some(MirrorMode::None),
+        "Bilateral" => Some(MirrorMode::Bilateral),
+        _ => {
+            let axes = text.strip_prefix("Radial(")?.strip_suffix(')')?;
+            axes.parse().ok().map(MirrorMode::Radial)
+        }
+    }
+}
+
+fn parse_line_policy(text: &str) -> Option<LinePolicy> {
+    match text {
+        "manual" => Some(LinePolicy::Manual),
+        "derive_from_polys" => Some(LinePolicy::DeriveFromPolys),
+        _ => None,
+    }
+}
+
+fn invalid(field: &str) -> NativeError {
+    NativeError::Invalid(field.to_string())
+}
+
+//
+// Formatting
+//
+
+impl fmt::Display for NativeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NativeError::Io(message) => write!(formatter, "{}", message),
+            NativeError::Parse(message) => write!(formatter, "not JSON: {}", message),
+            NativeError::Format(message) => write!(formatter, "{}", message),
+            NativeError::Invalid(field) => write!(formatter, "'{}' is missing or invalid", field),
+            NativeError::IndexOutOfRange { field, index } => {
+                write!(
+                    formatter,
+                    "{} {} points at a vert that doesn't exist",
+                    field, index
+                )
+            }
+        }
+    }
+}
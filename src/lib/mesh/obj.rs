@@ -0,0 +1,333 @@
+//
+// obj -> read / write Wavefront OBJ (verts, per-corner UVs, polys and lines)
+//
+// Export writes `v`, `vt` (each distinct UV once), `f v/vt` (plain `v` for polys without UVs)
+// and `l` for the lines: all of them when managed by hand, only the wire lines when they are
+// derived from the polys. Vert colors use the common `v x y z r g b` extension (white where
+// unset), poly colors have no place in OBJ. Material slots become `usemtl` groups (polys
+// without a material first, then slot by slot) with their colors as `Kd` in a `.mtl` file
+// saved next to the OBJ. Import reads the same statements (normals, groups, smoothing and the
+// like are skipped) into a mesh whose lines are derived from the polys, `l` lines kept as
+// wire lines; loading from a file also takes the colors from the `mtllib` files it names.
+//
+
+use super::*;
+use std::fs;
+use std::path::Path;
+
+const OBJ_HEADER: &str = "# mesh_editor";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ObjError {
+    Io(String),
+    // 1 based line number and what was wrong on it
+    Parse { line: usize, message: String },
+    // index as written in the file (1 based, or negative counting back from the end)
+    IndexOutOfRange { line: usize, index: i64 },
+}
+
+impl Mesh {
+    pub fn from_obj(text: &str) -> Result<Mesh, ObjError> {
+        let mut mesh = Mesh::new();
+        let mut uvs: Vec<Vec2> = Vec::new();
+        let mut lines: Vec<Line> = Vec::new();
+        let mut material: Option<MaterialIndex> = None;
+
+        for (line_index, text_line) in text.lines().enumerate() {
+            let line = line_index + 1;
+            let text_line = text_line.split('#').next().unwrap_or("");
+            let mut tokens = text_line.split_whitespace();
+            let Some(keyword) = tokens.next() else {
+                continue;
+            };
+            let tokens: Vec<&str> = tokens.collect();
+
+            match keyword {
+                "v" => {
+                    let coord = parse_floats(&tokens, 3, line)?;
+                    let vert_index = mesh.add_vert(Vec3::new(coord[0], coord[1], coord[2]));
+                    // x y z r g b, anything else after x y z is a w
+                    if coord.len() >= 6 {
+                        let color = Color::new(coord[3], coord[4], coord[5], 1.0);
+                        mesh.vert_colors[vert_index] = Some(color);
+                    }
+                }
+                "vt" => {
+                    let uv = parse_floats(&tokens, 1, line)?;
+                    uvs.push(Vec2::new(uv[0], uv.get(1).copied().unwrap_or(0.0)));
+                }
+                "f" => {
+                    let mut poly: Poly = Vec::new();
+                    let mut poly_uvs: PolyUvs = Vec::new();
+                    for token in tokens.iter() {
+                        let mut parts = token.split('/');
+                        let v = parts.next().unwrap_or("");
+                        poly.push(resolve_index(v, mesh.verticies.len(), line)?);
+                        match parts.next().filter(|vt| !vt.is_empty()) {
+                            Some(vt) => poly_uvs.push(uvs[resolve_index(vt, uvs.len(), line)?]),
+                            None => poly_uvs.clear(),
+                        }
+                    }
+                    if mesh.validate_poly_indicies(&poly).is_none() {
+                        return Err(parse_error(line, "face needs 3 or more different verts"));
+                    }
+                    // UVs only when every corner has one
+                    if poly_uvs.len() != poly.len() {
+                        poly_uvs.clear();
+                    }
+                    mesh.polys.push(poly);
+                    mesh.poly_uvs.push(poly_uvs);
+                    mesh.poly_colors.push(None);
+                    mesh.poly_materials.push(material);
+                }
+                "usemtl" => {
+                    if tokens.is_empty() {
+                        return Err(parse_error(line, "usemtl needs a material name"));
+                    }
+                    material = Some(mesh.add_material(&tokens.join(" ")));
+                }
+                "l" => {
+                    let polyline: Vec<VertIndex> = tokens
+                        .iter()
+                        .map(|token| {
+                            let v = token.split('/').next().unwrap_or("");
+                            resolve_index(v, mesh.verticies.len(), line)
+                        })
+                        .collect::<Result<_, _>>()?;
+                    if polyline.len() < 2 {
+                        return Err(parse_error(line, "line needs 2 or more verts"));
+                    }
+                    lines.extend(polyline.windows(2).map(|pair| (pair[0], pair[1])));
+                }
+                _ => {}
+            }
+        }
+
+        let mut seen: HashSet<Line> = HashSet::new();
+        mesh.lines = lines
+            .into_iter()
+            .filter(|&(a, b)| a != b && seen.insert(edge_key(a, b)))
+            .collect();
+        // lines from the file count as drawn by hand
+        mesh.derived_lines = vec![false; mesh.lines.len()];
+        mesh.rebuild_topology();
+        mesh.set_line_policy(LinePolicy::DeriveFromPolys);
+        Ok(mesh)
+    }
+
+    //
+    // the material colors come from the mtllib files next to the OBJ, those that are missing
+    // leave the slots with their own colors
+    //
+    pub fn load_obj(path: &Path) -> Result<Mesh, ObjError> {
+        let text = fs::read_to_string(path).map_err(|error| ObjError::Io(error.to_string()))?;
+        let mut mesh = Mesh::from_obj(&text)?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+        for library in mtl_libraries(&text) {
+            if let Ok(mtl) = fs::read_to_string(directory.join(library)) {
+                mesh.apply_mtl(&mtl);
+            }
+        }
+        Ok(mesh)
+    }
+
+    //
+    // colors (Kd) of the materials in an MTL file for the slots with the same name,
+    // returns how many slots were colored
+    //
+    pub fn apply_mtl(&mut self, text: &str) -> usize {
+        let mut colored_count = 0;
+        let mut material: Option<MaterialIndex> = None;
+        for text_line in text.lines() {
+            let text_line = text_line.split('#').next().unwrap_or("");
+            let tokens: Vec<&str> = text_line.split_whitespace().collect();
+            match tokens.split_first() {
+                Some((&"newmtl", name)) => material = self.material_index(&name.join(" ")),
+                Some((&"Kd", rgb)) => {
+                    let Some(index) = material else {
+                        continue;
+                    };
+                    if let Ok(rgb) = parse_floats(rgb, 3, 0) {
+                        self.materials[index].color = Color::new(rgb[0], rgb[1], rgb[2], 1.0);
+                        colored_count += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+        colored_count
+    }
+
+    pub fn to_obj(&self) -> String {
+        self.obj_text(None)
+    }
+
+    // the materials of the mesh as an MTL file, their color as the diffuse color
+    pub fn to_mtl(&self) -> String {
+        let mut mtl = format!("{}\n", OBJ_HEADER);
+        for material in self.materials.iter() {
+            let color = material.color;
+            mtl += &format!(
+                "newmtl {}\nKd {} {} {}\n",
+                material.name, color.r, color.g, color.b
+            );
+        }
+        mtl
+    }
+
+    fn obj_text(&self, mtl_library: Option<&str>) -> String {
+        let mut obj = format!("{}\n", OBJ_HEADER);
+        if let Some(library) = mtl_library {
+            obj += &format!("mtllib {}\n", library);
+        }
+        let has_vert_colors = self.has_vert_colors();
+        for (vert_index, v) in self.verticies.iter().enumerate() {
+            match has_vert_colors {
+                true => {
+                    let color = self.vert_color(vert_index).unwrap_or(WHITE);
+                    obj += &format!(
+                        "v {} {} {} {} {} {}\n",
+                        v.x, v.y, v.z, color.r, color.g, color.b
+                    );
+                }
+                false => obj += &format!("v {} {} {}\n", v.x, v.y, v.z),
+            }
+        }
+
+        // each distinct UV written once, 1 based like the verts
+        let mut uv_indicies: HashMap<[u32; 2], usize> = HashMap::new();
+        for uv in self.poly_uvs.iter().flatten() {
+            let key = uv.to_array().map(f32::to_bits);
+            if !uv_indicies.contains_key(&key) {
+                uv_indicies.insert(key, uv_indicies.len() + 1);
+                obj += &format!("vt {} {}\n", uv.x, uv.y);
+            }
+        }
+
+        // grouped by material, those without one first
+        let mut poly_order: Vec<PolyIndex> = (0..self.polys.len()).collect();
+        poly_order.sort_by_key(|&poly_index| self.poly_material(poly_index));
+        let mut current_material: Option<MaterialIndex> = None;
+        for poly_index in poly_order {
+            let poly = &self.polys[poly_index];
+            let material = self.poly_material(poly_index);
+            if let Some(index) = material.filter(|_| material != current_material) {
+                obj += &format!("usemtl {}\n", self.materials[index].name);
+                current_material = material;
+            }
+            let corners = match self.poly_uvs(poly_index) {
+                Some(uvs) => poly
+                    .iter()
+                    .zip(uvs)
+                    .map(|(v, uv)| {
+                        format!(
+                            "{}/{}",
+                            v + 1,
+                            uv_indicies[&uv.to_array().map(f32::to_bits)]
+                        )
+                    })
+                    .join(" "),
+                None => poly.iter().map(|v| (v + 1).to_string()).join(" "),
+            };
+            obj += &format!("f {}\n", corners);
+        }
+
+        let lines: Vec<Line> = if self.line_policy == LinePolicy::DeriveFromPolys {
+            self.wire_lines().iter().map(|&l| self.lines[l]).collect()
+        } else {
+            self.lines.clone()
+        };
+        for (a, b) in lines {
+            obj += &format!("l {} {}\n", a + 1, b + 1);
+        }
+        obj
+    }
+
+    //
+    // with materials, their .mtl file is written next to the OBJ (same name, .mtl extension)
+    //
+    pub fn save_obj(&self, path: &Path) -> Result<(), ObjError> {
+        let io_error = |error: std::io::Error| ObjError::Io(error.to_string());
+        if self.materials.is_empty() {
+            return fs::write(path, self.to_obj()).map_err(io_error);
+        }
+        let mtl_path = path.with_extension("mtl");
+        let library = mtl_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        fs::write(&mtl_path, self.to_mtl()).map_err(io_error)?;
+        fs::write(path, self.obj_text(Some(&library))).map_err(io_error)
+    }
+}
+
+//
+// Parsing helpers
+//
+
+// at least min_count numbers, all finite
+fn parse_floats(tokens: &[&str], min_count: usize, line: usize) -> Result<Vec<f32>, ObjError> {
+    if tokens.len() < min_count {
+        return Err(parse_error(
+            line,
+            &format!("expected {} numbers, found {}", min_count, tokens.len()),
+        ));
+    }
+    tokens
+        .iter()
+        .map(|token| {
+            token
+                .parse::<f32>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .ok_or_else(|| parse_error(line, &format!("invalid number '{}'", token)))
+        })
+        .collect()
+}
+
+// file names after the mtllib statements
+fn mtl_libraries(text: &str) -> Vec<&str> {
+    text.lines()
+        .filter_map(|text_line| text_line.split('#').next()?.trim().strip_prefix("mtllib "))
+        .flat_map(str::split_whitespace)
+        .collect()
+}
+
+// 1 based, or negative relative to the count read so far
+fn resolve_index(token: &str, count: usize, line: usize) -> Result<usize, ObjError> {
+    let index: i64 = token
+        .parse()
+        .map_err(|_| parse_error(line, &format!("invalid index '{}'", token)))?;
+    let resolved = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(ObjError::IndexOutOfRange { line, index });
+    }
+    Ok(resolved as usize)
+}
+
+fn parse_error(line: usize, message: &str) -> ObjError {
+    ObjError::Parse {
+        line,
+        message: message.to_string(),
+    }
+}
+
+//
+// Formatting
+//
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io(message) => write!(formatter, "{}", message),
+            ObjError::Parse { line, message } => write!(formatter, "line {}: {}", line, message),
+            ObjError::IndexOutOfRange { line, index } => {
+                write!(formatter, "line {}: index {} is out of range", line, index)
+            }
+        }
+    }
+}
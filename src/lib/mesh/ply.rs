@@ -0,0 +1,878 @@
+//
+// ply -> read / write PLY (ascii, binary little / big endian)
+//
+// Vertex x y z, face vertex_indices and edge vertex1 vertex2 make up the mesh, vertex and
+// face red / green / blue (/ alpha) its vert and poly colors. Every other property (normals,
+// confidence, ...) and element is kept in PlyExtras so it can be written back out unchanged.
+// The colors are kept there too, but on export colors of the mesh replace them.
+//
+
+use super::*;
+use std::fs;
+use std::path::Path;
+
+const PLY_VERTEX: &str = "vertex";
+const PLY_FACE: &str = "face";
+const PLY_EDGE: &str = "edge";
+const PLY_COLOR_CHANNELS: [&str; 4] = ["red", "green", "blue", "alpha"];
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PlyScalar {
+    Char,
+    UChar,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Float,
+    Double,
+}
+
+//
+// one property of an element, values[i] is the i-th instance (one entry unless a list)
+//
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlyProperty {
+    pub name: String,
+    pub scalar: PlyScalar,
+    // type of the length prefix for list properties
+    pub list_count: Option<PlyScalar>,
+    pub values: Vec<Vec<f64>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlyElement {
+    pub name: String,
+    pub count: usize,
+    pub properties: Vec<PlyProperty>,
+}
+
+//
+// Everything in a PLY file that isn't mesh geometry. The vertex / face / edge entries only
+// hold their extra properties, and are dropped on export if the mesh no longer has as many
+// verts / polys / lines.
+//
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct PlyExtras {
+    pub comments: Vec<String>,
+    pub elements: Vec<PlyElement>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlyError {
+    Io(String),
+    // 1 based header line and what was wrong on it
+    Header {
+        line: usize,
+        message: String,
+    },
+    // element data ended early (element name, instance index)
+    Truncated {
+        element: String,
+        index: usize,
+    },
+    // ascii data: 1 based line and what was wrong on it
+    Parse {
+        line: usize,
+        message: String,
+    },
+    // a face or edge points at a vertex that doesn't exist
+    IndexOutOfRange {
+        element: String,
+        index: usize,
+    },
+    // an instance that can't be used, e.g. an empty list or a face with a repeated vertex
+    Invalid {
+        element: String,
+        index: usize,
+        message: String,
+    },
+    // e.g. a vertex element without z
+    MissingProperty {
+        element: String,
+        property: String,
+    },
+}
+
+impl Mesh {
+    pub fn from_ply(bytes: &[u8]) -> Result<(Mesh, PlyExtras), PlyError> {
+        let (header, body_start) = read_header(bytes)?;
+        let mut elements = match header.format {
+            PlyFormat::Ascii => read_ascii_body(&header, &bytes[body_start..], header.line_count)?,
+            _ => read_binary_body(&header, &bytes[body_start..])?,
+        };
+
+        let mut mesh = Mesh::new();
+        let vertex = take_element(&mut elements, PLY_VERTEX);
+        if let Some(mut vertex) = vertex {
+            let coords: Vec<Vec<Vec<f64>>> = ["x", "y", "z"]
+                .iter()
+                .map(|name| take_property(&mut vertex, name).map(|p| p.values))
+                .collect::<Option<_>>()
+                .ok_or_else(|| missing_property(PLY_VERTEX, "x, y or z"))?;
+            for (i, ((x, y), z)) in coords[0].iter().zip(&coords[1]).zip(&coords[2]).enumerate() {
+                let [x, y, z] = [x, y, z].map(|values| first_value(PLY_VERTEX, i, values));
+                let coord = Vec3::new(x? as f32, y? as f32, z? as f32);
+                if !coord.is_finite() {
+                    return Err(PlyError::Invalid {
+                        element: String::from(PLY_VERTEX),
+                        index: i,
+                        message: String::from("coordinate isn't a finite number"),
+                    });
+                }
+                mesh.add_vert(coord);
+            }
+            if let Some(colors) = element_colors(&vertex)? {
+                mesh.vert_colors = colors.into_iter().map(Some).collect();
+            }
+            elements.insert(0, vertex);
+        }
+
+        let vert_count = mesh.verticies.len();
+        let check_index = |element: &str, index: usize, v: f64| {
+            if v >= 0.0 && (v as usize) < vert_count {
+                Ok(v as usize)
+            } else {
+                Err(PlyError::IndexOutOfRange {
+                    element: element.to_string(),
+                    index,
+                })
+            }
+        };
+
+        if let Some(face_index) = elements.iter().position(|e| e.name == PLY_FACE) {
+            let face = &mut elements[face_index];
+            let indicies = take_property(face, "vertex_indices")
+                .or_else(|| take_property(face, "vertex_index"))
+                .ok_or_else(|| missing_property(PLY_FACE, "vertex_indices"))?;
+            for (i, corners) in indicies.values.iter().enumerate() {
+                let poly: Poly = corners
+                    .iter()
+                    .map(|&v| check_index(PLY_FACE, i, v))
+                    .collect::<Result<_, _>>()?;
+                if mesh.validate_poly_indicies(&poly).is_none() {
+                    return Err(PlyError::Invalid {
+                        element: PLY_FACE.to_string(),
+                        index: i,
+                        message: String::from("face needs 3 or more different vertices"),
+                    });
+                }
+                mesh.polys.push(poly);
+                mesh.poly_uvs.push(PolyUvs::new());
+                mesh.poly_colors.push(None);
+                mesh.poly_materials.push(None);
+            }
+            if let Some(colors) = element_colors(face)? {
+                mesh.poly_colors = colors.into_iter().map(Some).collect();
+            }
+        }
+
+        let edge_index = elements.iter().position(|e| e.name == PLY_EDGE);
+        if let Some(edge_index) = edge_index {
+            let edge = &mut elements[edge_index];
+            let (first, second) = take_property(edge, "vertex1")
+                .zip(take_property(edge, "vertex2"))
+                .ok_or_else(|| missing_property(PLY_EDGE, "vertex1 or vertex2"))?;
+            for (i, (a, b)) in first.values.iter().zip(second.values.iter()).enumerate() {
+                mesh.lines.push((
+                    check_index(PLY_EDGE, i, first_value(PLY_EDGE, i, a)?)?,
+                    check_index(PLY_EDGE, i, first_value(PLY_EDGE, i, b)?)?,
+                ));
+                mesh.derived_lines.push(false);
+            }
+        }
+
+        mesh.rebuild_topology();
+        // without edges the lines follow the faces, with them they are exactly the edges
+        if edge_index.is_none() {
+            mesh.set_line_policy(LinePolicy::DeriveFromPolys);
+        }
+
+        elements.retain(|e| !e.properties.is_empty() || !is_geometry_element(&e.name));
+        let extras = PlyExtras {
+            comments: header.comments,
+            elements,
+        };
+        Ok((mesh, extras))
+    }
+
+    pub fn load_ply(path: &Path) -> Result<(Mesh, PlyExtras), PlyError> {
+        let bytes = fs::read(path).map_err(|error| PlyError::Io(error.to_string()))?;
+        Mesh::from_ply(&bytes)
+    }
+
+    //
+    // Verts, polys and lines plus the extras (see PlyExtras). Lines are written as edges
+    // when they are managed by hand, derived lines only write the wire lines. Vert / poly
+    // colors are written as uchar channels (white where unset) when the mesh has any.
+    //
+    pub fn to_ply(&self, format: PlyFormat, extras: &PlyExtras) -> Vec<u8> {
+        let edges: Vec<Line> = if self.line_policy == LinePolicy::DeriveFromPolys {
+            self.wire_lines().iter().map(|&l| self.lines[l]).collect()
+        } else {
+            self.lines.clone()
+        };
+
+        // colors of the mesh replace those in the extras
+        let extra_properties = |name: &str, count: usize, colors: &[Option<Color>]| {
+            let mut properties: Vec<PlyProperty> = extras
+                .elements
+                .iter()
+                .find(|e| e.name == name && e.count == count)
+                .map_or(Vec::new(), |e| e.properties.clone());
+            if colors.iter().any(Option::is_some) {
+                let colors: Vec<Color> = colors.iter().map(|c| c.unwrap_or(WHITE)).collect();
+                replace_color_properties(&mut properties, &colors);
+            }
+            properties
+        };
+        let mut elements = Vec::new();
+
+        let mut vertex = PlyElement {
+            name: PLY_VERTEX.to_string(),
+            count: self.verticies.len(),
+            properties: ["x", "y", "z"]
+                .iter()
+                .enumerate()
+                .map(|(axis, name)| {
+                    let values = self.verticies.iter().map(|v| vec![v[axis] as f64]);
+                    PlyProperty::new(name, PlyScalar::Float, None, values.collect())
+                })
+                .collect(),
+        };
+        vertex.properties.extend(extra_properties(
+            PLY_VERTEX,
+            vertex.count,
+            &self.vert_colors,
+        ));
+        elements.push(vertex);
+
+        if !self.polys.is_empty() {
+            // the usual uchar corner count, unless a poly has more corners than that holds
+            let max_corners = self.polys.iter().map(Vec::len).max().unwrap_or(0);
+            let count_scalar = if max_corners <= u8::MAX as usize {
+                PlyScalar::UChar
+            } else {
+                PlyScalar::UInt
+            };
+            let values = self
+                .polys
+                .iter()
+                .map(|poly| poly.iter().map(|&v| v as f64).collect())
+                .collect();
+            let mut face = PlyElement {
+                name: PLY_FACE.to_string(),
+                count: self.polys.len(),
+                properties: vec![PlyProperty::new(
+                    "vertex_indices",
+                    PlyScalar::Int,
+                    Some(count_scalar),
+                    values,
+                )],
+            };
+            face.properties
+                .extend(extra_properties(PLY_FACE, face.count, &self.poly_colors));
+            elements.push(face);
+        }
+
+        if !edges.is_empty() {
+            let ends = |pick: fn(&Line) -> VertIndex| {
+                edges.iter().map(|line| vec![pick(line) as f64]).collect()
+            };
+            let mut edge = PlyElement {
+                name: PLY_EDGE.to_string(),
+                count: edges.len(),
+                properties: vec![
+                    PlyProperty::new("vertex1", PlyScalar::Int, None, ends(|l| l.0)),
+                    PlyProperty::new("vertex2", PlyScalar::Int, None, ends(|l| l.1)),
+                ],
+            };
+            edge.properties
+                .extend(extra_properties(PLY_EDGE, edge.count, &[]));
+            elements.push(edge);
+        }
+
+        elements.extend(
+            extras
+                .elements
+                .iter()
+                .filter(|e| !is_geometry_element(&e.name))
+                .cloned(),
+        );
+        write_ply(format, &extras.comments, &elements)
+    }
+
+    pub fn save_ply(
+        &self,
+        path: &Path,
+        format: PlyFormat,
+        extras: &PlyExtras,
+    ) -> Result<(), PlyError> {
+        fs::write(path, self.to_ply(format, extras))
+            .map_err(|error| PlyError::Io(error.to_string()))
+    }
+}
+
+impl PlyProperty {
+    pub fn new(
+        name: &str,
+        scalar: PlyScalar,
+        list_count: Option<PlyScalar>,
+        values: Vec<Vec<f64>>,
+    ) -> PlyProperty {
+        PlyProperty {
+            name: name.to_string(),
+            scalar,
+            list_count,
+            values,
+        }
+    }
+}
+
+impl PlyExtras {
+    pub fn element(&self, name: &str) -> Option<&PlyElement> {
+        self.elements.iter().find(|e| e.name == name)
+    }
+
+    //
+    // red / green / blue (/ alpha) of the vertex element, integer channels are 0..255
+    //
+    pub fn vertex_colors(&self) -> Option<Vec<Color>> {
+        element_colors(self.element(PLY_VERTEX)?).ok()?
+    }
+
+    //
+    // replaces the vertex color channels with uchar red / green / blue / alpha
+    //
+    pub fn set_vertex_colors(&mut self, colors: &[Color]) {
+        let index = match self.elements.iter().position(|e| e.name == PLY_VERTEX) {
+            Some(index) if self.elements[index].count == colors.len() => index,
+            found => {
+                if let Some(index) = found {
+                    self.elements.remove(index);
+                }
+                self.elements.insert(
+                    0,
+                    PlyElement {
+                        name: PLY_VERTEX.to_string(),
+                        count: colors.len(),
+                        properties: Vec::new(),
+                    },
+                );
+                0
+            }
+        };
+        let vertex = &mut self.elements[index];
+        vertex
+            .properties
+            .retain(|p| !PLY_COLOR_CHANNELS.contains(&p.name.as_str()));
+        vertex.properties.extend(color_properties(colors));
+    }
+}
+
+//
+// Colors
+//
+
+// red / green / blue (/ alpha) of an element, integer channels are 0..255
+fn element_colors(element: &PlyElement) -> Result<Option<Vec<Color>>, PlyError> {
+    let channel = |name: &str| element.properties.iter().find(|p| p.name == name);
+    let [red, green, blue] = ["red", "green", "blue"].map(channel);
+    let (Some(red), Some(green), Some(blue)) = (red, green, blue) else {
+        return Ok(None);
+    };
+    let alpha = channel("alpha");
+
+    let unit = |property: &PlyProperty, i: usize| {
+        let value = first_value(&element.name, i, &property.values[i])? as f32;
+        if property.scalar.is_float() {
+            Ok(value)
+        } else {
+            Ok(value / u8::MAX as f32)
+        }
+    };
+    (0..element.count)
+        .map(|i| {
+            Ok(Color::new(
+                unit(red, i)?,
+                unit(green, i)?,
+                unit(blue, i)?,
+                alpha.map_or(Ok(1.0), |alpha| unit(alpha, i))?,
+            ))
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+//
+// writes the colors into the channels that are there (keeping their type), missing ones are
+// added as uchar, alpha only when some color isn't opaque
+//
+fn replace_color_properties(properties: &mut Vec<PlyProperty>, colors: &[Color]) {
+    let needs_alpha = colors.iter().any(|color| color.a < 1.0);
+    let mut next_position = 0;
+    for (channel, name) in PLY_COLOR_CHANNELS.iter().enumerate() {
+        let position = properties.iter().position(|p| p.name == *name);
+        if position.is_none() && *name == "alpha" && !needs_alpha {
+            continue;
+        }
+        let scalar = position.map_or(PlyScalar::UChar, |i| properties[i].scalar);
+        let values = colors
+            .iter()
+            .map(|color| {
+                let unit = color.to_vec()[channel].clamp(0.0, 1.0);
+                match scalar.is_float() {
+                    true => vec![unit as f64],
+                    false => vec![(unit * u8::MAX as f32).round() as f64],
+                }
+            })
+            .collect();
+        let property = PlyProperty::new(name, scalar, None, values);
+        next_position = match position {
+            Some(i) => {
+                properties[i] = property;
+                i + 1
+            }
+            None => {
+                properties.insert(next_position, property);
+                next_position + 1
+            }
+        };
+    }
+}
+
+// uchar red / green / blue / alpha
+fn color_properties(colors: &[Color]) -> Vec<PlyProperty> {
+    PLY_COLOR_CHANNELS
+        .iter()
+        .enumerate()
+        .map(|(channel, name)| {
+            let values = colors
+                .iter()
+                .map(|color| {
+                    let unit = color.to_vec()[channel].clamp(0.0, 1.0);
+                    vec![(unit * u8::MAX as f32).round() as f64]
+                })
+                .collect();
+            PlyProperty::new(name, PlyScalar::UChar, None, values)
+        })
+        .collect()
+}
+
+impl PlyScalar {
+    fn parse(name: &str) -> Option<PlyScalar> {
+        match name {
+            "char" | "int8" => Some(PlyScalar::Char),
+            "uchar" | "uint8" => Some(PlyScalar::UChar),
+            "short" | "int16" => Some(PlyScalar::Short),
+            "ushort" | "uint16" => Some(PlyScalar::UShort),
+            "int" | "int32" => Some(PlyScalar::Int),
+            "uint" | "uint32" => Some(PlyScalar::UInt),
+            "float" | "float32" => Some(PlyScalar::Float),
+            "double" | "float64" => Some(PlyScalar::Double),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            PlyScalar::Char => "char",
+            PlyScalar::UChar => "uchar",
+            PlyScalar::Short => "short",
+            PlyScalar::UShort => "ushort",
+            PlyScalar::Int => "int",
+            PlyScalar::UInt => "uint",
+            PlyScalar::Float => "float",
+            PlyScalar::Double => "double",
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            PlyScalar::Char | PlyScalar::UChar => 1,
+            PlyScalar::Short | PlyScalar::UShort => 2,
+            PlyScalar::Int | PlyScalar::UInt | PlyScalar::Float => 4,
+            PlyScalar::Double => 8,
+        }
+    }
+
+    fn is_float(&self) -> bool {
+        matches!(self, PlyScalar::Float | PlyScalar::Double)
+    }
+
+    fn decode(&self, bytes: &[u8], is_little_endian: bool) -> f64 {
+        macro_rules! decode_as {
+            ($t:ty) => {{
+                let array = bytes.try_into().unwrap();
+                if is_little_endian {
+                    <$t>::from_le_bytes(array) as f64
+                } else {
+                    <$t>::from_be_bytes(array) as f64
+                }
+            }};
+        }
+        match self {
+            PlyScalar::Char => decode_as!(i8),
+            PlyScalar::UChar => decode_as!(u8),
+            PlyScalar::Short => decode_as!(i16),
+            PlyScalar::UShort => decode_as!(u16),
+            PlyScalar::Int => decode_as!(i32),
+            PlyScalar::UInt => decode_as!(u32),
+            PlyScalar::Float => decode_as!(f32),
+            PlyScalar::Double => decode_as!(f64),
+        }
+    }
+
+    fn encode(&self, value: f64, is_little_endian: bool) -> Vec<u8> {
+        macro_rules! encode_as {
+            ($t:ty) => {{
+                let value = value as $t;
+                if is_little_endian {
+                    value.to_le_bytes().to_vec()
+                } else {
+                    value.to_be_bytes().to_vec()
+                }
+            }};
+        }
+        match self {
+            PlyScalar::Char => encode_as!(i8),
+            PlyScalar::UChar => encode_as!(u8),
+            PlyScalar::Short => encode_as!(i16),
+            PlyScalar::UShort => encode_as!(u16),
+            PlyScalar::Int => encode_as!(i32),
+            PlyScalar::UInt => encode_as!(u32),
+            PlyScalar::Float => encode_as!(f32),
+            PlyScalar::Double => encode_as!(f64),
+        }
+    }
+
+    fn format_ascii(&self, value: f64) -> String {
+        match self {
+            PlyScalar::Float => format!("{}", value as f32),
+            PlyScalar::Double => format!("{}", value),
+            _ => format!("{}", value as i64),
+        }
+    }
+}
+
+fn is_geometry_element(name: &str) -> bool {
+    [PLY_VERTEX, PLY_FACE, PLY_EDGE].contains(&name)
+}
+
+fn missing_property(element: &str, property: &str) -> PlyError {
+    PlyError::MissingProperty {
+        element: element.to_string(),
+        property: property.to_string(),
+    }
+}
+
+// value of a single valued property, which may be declared as a list (and be empty)
+fn first_value(element: &str, index: usize, values: &[f64]) -> Result<f64, PlyError> {
+    values.first().copied().ok_or_else(|| PlyError::Invalid {
+        element: element.to_string(),
+        index,
+        message: String::from("empty list where one value is needed"),
+    })
+}
+
+fn take_element(elements: &mut Vec<PlyElement>, name: &str) -> Option<PlyElement> {
+    let index = elements.iter().position(|e| e.name == name)?;
+    Some(elements.remove(index))
+}
+
+fn take_property(element: &mut PlyElement, name: &str) -> Option<PlyProperty> {
+    let index = element.properties.iter().position(|p| p.name == name)?;
+    Some(element.properties.remove(index))
+}
+
+//
+// Reading
+//
+
+struct PlyHeader {
+    format: PlyFormat,
+    comments: Vec<String>,
+    // elements with their properties, no values yet
+    elements: Vec<PlyElement>,
+    line_count: usize,
+}
+
+// the parsed header and where the element data starts
+fn read_header(bytes: &[u8]) -> Result<(PlyHeader, usize), PlyError> {
+    let header_error = |line: usize, message: &str| PlyError::Header {
+        line,
+        message: message.to_string(),
+    };
+    let mut format = None;
+    let mut comments = Vec::new();
+    let mut elements: Vec<PlyElement> = Vec::new();
+
+    let mut offset = 0;
+    let mut line = 0;
+    loop {
+        let Some(length) = bytes[offset..].iter().position(|&b| b == b'\n') else {
+            return Err(header_error(line + 1, "header has no end_header"));
+        };
+        let text = std::str::from_utf8(&bytes[offset..offset + length])
+            .map_err(|_| header_error(line + 1, "header is not valid text"))?;
+        offset += length + 1;
+        line += 1;
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+
+        if line == 1 {
+            if tokens != ["ply"] {
+                return Err(header_error(line, "not a PLY file (expected ply)"));
+            }
+            continue;
+        }
+        match tokens.as_slice() {
+            [] => {}
+            ["end_header"] => break,
+            ["format", name, "1.0"] => {
+                format = Some(match *name {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+                    "binary_big_endian" => PlyFormat::BinaryBigEndian,
+                    _ => return Err(header_error(line, "unknown format")),
+                });
+            }
+            ["comment", ..] => {
+                let comment = text.trim_start().strip_prefix("comment").unwrap_or("");
+                comments.push(comment.trim().to_string());
+            }
+            ["obj_info", ..] => {}
+            ["element", name, count] => {
+                let count = count
+                    .parse()
+                    .map_err(|_| header_error(line, "invalid element count"))?;
+                elements.push(PlyElement {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            ["property", "list", count_type, value_type, name] => {
+                let property = PlyProperty::new(
+                    name,
+                    PlyScalar::parse(value_type)
+                        .ok_or_else(|| header_error(line, "unknown property type"))?,
+                    Some(
+                        PlyScalar::parse(count_type)
+                            .ok_or_else(|| header_error(line, "unknown property type"))?,
+                    ),
+                    Vec::new(),
+                );
+                elements
+                    .last_mut()
+                    .ok_or_else(|| header_error(line, "property before any element"))?
+                    .properties
+                    .push(property);
+            }
+            ["property", value_type, name] => {
+                let scalar = PlyScalar::parse(value_type)
+                    .ok_or_else(|| header_error(line, "unknown property type"))?;
+                elements
+                    .last_mut()
+                    .ok_or_else(|| header_error(line, "property before any element"))?
+                    .properties
+                    .push(PlyProperty::new(name, scalar, None, Vec::new()));
+            }
+            _ => return Err(header_error(line, "unrecognized header line")),
+        }
+    }
+
+    let format = format.ok_or_else(|| header_error(line, "header has no format line"))?;
+    let header = PlyHeader {
+        format,
+        comments,
+        elements,
+        line_count: line,
+    };
+    Ok((header, offset))
+}
+
+fn read_ascii_body(
+    header: &PlyHeader,
+    body: &[u8],
+    header_lines: usize,
+) -> Result<Vec<PlyElement>, PlyError> {
+    let text = String::from_utf8_lossy(body);
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (header_lines + i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let mut elements = header.elements.clone();
+    for element in elements.iter_mut() {
+        for index in 0..element.count {
+            let (line, text) = lines.next().ok_or_else(|| PlyError::Truncated {
+                element: element.name.clone(),
+                index,
+            })?;
+            let parse_error = |message: String| PlyError::Parse { line, message };
+            let mut tokens = text.split_whitespace();
+            let mut next_number = || -> Result<f64, PlyError> {
+                let token = tokens
+                    .next()
+                    .ok_or_else(|| parse_error(String::from("too few values")))?;
+                token
+                    .parse()
+                    .map_err(|_| parse_error(format!("invalid number {}", token)))
+            };
+
+            for property in element.properties.iter_mut() {
+                let count = match property.list_count {
+                    Some(_) => next_number()? as usize,
+                    None => 1,
+                };
+                let values = (0..count)
+                    .map(|_| next_number())
+                    .collect::<Result<_, _>>()?;
+                property.values.push(values);
+            }
+            if tokens.next().is_some() {
+                return Err(parse_error(String::from("too many values")));
+            }
+        }
+    }
+    Ok(elements)
+}
+
+fn read_binary_body(header: &PlyHeader, body: &[u8]) -> Result<Vec<PlyElement>, PlyError> {
+    let is_little_endian = header.format == PlyFormat::BinaryLittleEndian;
+    let mut offset = 0;
+
+    let mut elements = header.elements.clone();
+    for element in elements.iter_mut() {
+        for index in 0..element.count {
+            let truncated = || PlyError::Truncated {
+                element: element.name.clone(),
+                index,
+            };
+            let mut read = |scalar: PlyScalar| -> Result<f64, PlyError> {
+                let bytes = body
+                    .get(offset..offset + scalar.size())
+                    .ok_or_else(truncated)?;
+                offset += scalar.size();
+                Ok(scalar.decode(bytes, is_little_endian))
+            };
+
+            for property in element.properties.iter_mut() {
+                let count = match property.list_count {
+                    Some(count_scalar) => read(count_scalar)? as usize,
+                    None => 1,
+                };
+                let values = (0..count)
+                    .map(|_| read(property.scalar))
+                    .collect::<Result<_, _>>()?;
+                property.values.push(values);
+            }
+        }
+    }
+    Ok(elements)
+}
+
+//
+// Writing
+//
+
+fn write_ply(format: PlyFormat, comments: &[String], elements: &[PlyElement]) -> Vec<u8> {
+    let format_name = match format {
+        PlyFormat::Ascii => "ascii",
+        PlyFormat::BinaryLittleEndian => "binary_little_endian",
+        PlyFormat::BinaryBigEndian => "binary_big_endian",
+    };
+    let mut header = format!("ply\nformat {} 1.0\n", format_name);
+    for comment in comments {
+        header += &format!("comment {}\n", comment);
+    }
+    for element in elements {
+        header += &format!("element {} {}\n", element.name, element.count);
+        for property in element.properties.iter() {
+            match property.list_count {
+                Some(count) => {
+                    header += &format!(
+                        "property list {} {} {}\n",
+                        count.name(),
+                        property.scalar.name(),
+                        property.name
+                    )
+                }
+                None => {
+                    header += &format!("property {} {}\n", property.scalar.name(), property.name)
+                }
+            }
+        }
+    }
+    header += "end_header\n";
+
+    let mut bytes = header.into_bytes();
+    let is_little_endian = format == PlyFormat::BinaryLittleEndian;
+    for element in elements {
+        for i in 0..element.count {
+            let mut tokens: Vec<String> = Vec::new();
+            for property in element.properties.iter() {
+                let values = &property.values[i];
+                if let Some(count) = property.list_count {
+                    match format {
+                        PlyFormat::Ascii => tokens.push(values.len().to_string()),
+                        _ => bytes.extend(count.encode(values.len() as f64, is_little_endian)),
+                    }
+                }
+                for &value in values {
+                    match format {
+                        PlyFormat::Ascii => tokens.push(property.scalar.format_ascii(value)),
+                        _ => bytes.extend(property.scalar.encode(value, is_little_endian)),
+                    }
+                }
+            }
+            if format == PlyFormat::Ascii {
+                bytes.extend(tokens.join(" ").into_bytes());
+                bytes.push(b'\n');
+            }
+        }
+    }
+    bytes
+}
+
+//
+// Formatting
+//
+
+impl fmt::Display for PlyError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlyError::Io(message) => write!(formatter, "{}", message),
+            PlyError::Header { line, message } => {
+                write!(formatter, "header line {}: {}", line, message)
+            }
+            PlyError::Truncated { element, index } => {
+                write!(formatter, "data ends at {} {}", element, index)
+            }
+            PlyError::Parse { line, message } => write!(formatter, "line {}: {}", line, message),
+            PlyError::IndexOutOfRange { element, index } => {
+                write!(
+                    formatter,
+                    "{} {} uses a vertex that doesn't exist",
+                    element, index
+                )
+            }
+            PlyError::MissingProperty { element, property } => {
+                write!(formatter, "{} element has no {}", element, property)
+            }
+            PlyError::Invalid {
+                element,
+                index,
+                message,
+            } => write!(formatter, "{} {}: {}", element, index, message),
+        }
+    }
+}
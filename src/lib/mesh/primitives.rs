@@ -0,0 +1,562 @@
+//
+// primitives -> parameterized generators for common shapes
+//
+// all shapes are centered on the origin with +y up, wound counter-clockwise seen from
+// outside, and derive their lines from their polys
+//
+
+use super::*;
+use std::f32::consts::{PI, TAU};
+
+const MIN_SEGMENTS: usize = 3;
+const MIN_RINGS: usize = 2;
+const MAX_ICOSPHERE_SUBDIVISIONS: usize = 6;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Primitive {
+    UvSphere {
+        radius: f32,
+        segments: usize,
+        rings: usize,
+    },
+    Icosphere {
+        radius: f32,
+        subdivisions: usize,
+    },
+    Cylinder {
+        radius: f32,
+        depth: f32,
+        segments: usize,
+    },
+    Cone {
+        radius: f32,
+        depth: f32,
+        segments: usize,
+    },
+    Torus {
+        major_radius: f32,
+        minor_radius: f32,
+        major_segments: usize,
+        minor_segments: usize,
+    },
+    Grid {
+        size: f32,
+        subdivisions: usize,
+    },
+    Disc {
+        radius: f32,
+        segments: usize,
+    },
+    Capsule {
+        radius: f32,
+        depth: f32,
+        segments: usize,
+        rings: usize,
+    },
+}
+
+impl Primitive {
+    //
+    // one of each shape with reasonable defaults (in a fixed order, e.g. for cycling)
+    //
+    pub fn defaults() -> Vec<Primitive> {
+        vec![
+            Primitive::UvSphere {
+                radius: 1.0,
+                segments: 16,
+                rings: 8,
+            },
+            Primitive::Icosphere {
+                radius: 1.0,
+                subdivisions: 2,
+            },
+            Primitive::Cylinder {
+                radius: 1.0,
+                depth: 2.0,
+                segments: 16,
+            },
+            Primitive::Cone {
+                radius: 1.0,
+                depth: 2.0,
+                segments: 16,
+            },
+            Primitive::Torus {
+                major_radius: 1.0,
+                minor_radius: 0.25,
+                major_segments: 24,
+                minor_segments: 8,
+            },
+            Primitive::Grid {
+                size: 2.0,
+                subdivisions: 4,
+            },
+            Primitive::Disc {
+                radius: 1.0,
+                segments: 16,
+            },
+            Primitive::Capsule {
+                radius: 0.5,
+                depth: 2.0,
+                segments: 16,
+                rings: 4,
+            },
+        ]
+    }
+
+    pub fn build(&self) -> Mesh {
+        match *self {
+            Primitive::UvSphere {
+                radius,
+                segments,
+                rings,
+            } => Mesh::new_uv_sphere(radius, segments, rings),
+            Primitive::Icosphere {
+                radius,
+                subdivisions,
+            } => Mesh::new_icosphere(radius, subdivisions),
+            Primitive::Cylinder {
+                radius,
+                depth,
+                segments,
+            } => Mesh::new_cylinder(radius, depth, segments),
+            Primitive::Cone {
+                radius,
+                depth,
+                segments,
+            } => Mesh::new_cone(radius, depth, segments),
+            Primitive::Torus {
+                major_radius,
+                minor_radius,
+                major_segments,
+                minor_segments,
+            } => Mesh::new_torus(major_radius, minor_radius, major_segments, minor_segments),
+            Primitive::Grid { size, subdivisions } => Mesh::new_grid(size, subdivisions),
+            Primitive::Disc { radius, segments } => Mesh::new_disc(radius, segments),
+            Primitive::Capsule {
+                radius,
+                depth,
+                segments,
+                rings,
+            } => Mesh::new_capsule(radius, depth, segments, rings),
+        }
+    }
+
+    //
+    // same shape with every length multiplied by the factor
+    //
+    pub fn resized(&self, factor: f32) -> Primitive {
+        let mut primitive = *self;
+        match &mut primitive {
+            Primitive::UvSphere { radius, .. }
+            | Primitive::Icosphere { radius, .. }
+            | Primitive::Disc { radius, .. } => *radius *= factor,
+            Primitive::Cylinder { radius, depth, .. }
+            | Primitive::Cone { radius, depth, .. }
+            | Primitive::Capsule { radius, depth, .. } => {
+                *radius *= factor;
+                *depth *= factor;
+            }
+            Primitive::Torus {
+                major_radius,
+                minor_radius,
+                ..
+            } => {
+                *major_radius *= factor;
+                *minor_radius *= factor;
+            }
+            Primitive::Grid { size, .. } => *size *= factor,
+        }
+        primitive
+    }
+
+    //
+    // same shape with more (positive steps) or fewer (negative steps) segments / rings,
+    // clamped to what the shape needs to stay valid
+    //
+    pub fn refined(&self, steps: i32) -> Primitive {
+        let step = |count: usize, scale: usize, min: usize| {
+            count
+                .saturating_add_signed(steps as isize * scale as isize)
+                .max(min)
+        };
+        let mut primitive = *self;
+        match &mut primitive {
+            Primitive::UvSphere {
+                segments, rings, ..
+            }
+            | Primitive::Capsule {
+                segments, rings, ..
+            } => {
+                *segments = step(*segments, 2, MIN_SEGMENTS);
+                *rings = step(*rings, 1, MIN_RINGS);
+            }
+            Primitive::Icosphere { subdivisions, .. } => {
+                *subdivisions = step(*subdivisions, 1, 0).min(MAX_ICOSPHERE_SUBDIVISIONS);
+            }
+            Primitive::Cylinder { segments, .. }
+            | Primitive::Cone { segments, .. }
+            | Primitive::Disc { segments, .. } => *segments = step(*segments, 2, MIN_SEGMENTS),
+            Primitive::Torus {
+                major_segments,
+                minor_segments,
+                ..
+            } => {
+                *major_segments = step(*major_segments, 2, MIN_SEGMENTS);
+                *minor_segments = step(*minor_segments, 1, MIN_SEGMENTS);
+            }
+            Primitive::Grid { subdivisions, .. } => *subdivisions = step(*subdivisions, 1, 1),
+        }
+        primitive
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Primitive::UvSphere { .. } => "UV Sphere",
+            Primitive::Icosphere { .. } => "Icosphere",
+            Primitive::Cylinder { .. } => "Cylinder",
+            Primitive::Cone { .. } => "Cone",
+            Primitive::Torus { .. } => "Torus",
+            Primitive::Grid { .. } => "Grid",
+            Primitive::Disc { .. } => "Disc",
+            Primitive::Capsule { .. } => "Capsule",
+        }
+    }
+}
+
+//
+// Generators
+//
+
+impl Mesh {
+    pub fn new_uv_sphere(radius: f32, segments: usize, rings: usize) -> Mesh {
+        let segments = segments.max(MIN_SEGMENTS);
+        let rings = rings.max(MIN_RINGS);
+        let mut mesh = new_derived_mesh();
+
+        // rings listed top to bottom, the poles close them off
+        let ring_verts: Vec<Vec<VertIndex>> = (1..rings)
+            .map(|ring| {
+                let (sin, cos) = (PI * ring as f32 / rings as f32).sin_cos();
+                add_ring(&mut mesh, radius * sin, radius * cos, segments)
+            })
+            .collect();
+        let top = mesh.add_vert(vec3(0.0, radius, 0.0));
+        let bottom = mesh.add_vert(vec3(0.0, -radius, 0.0));
+
+        fan_to_pole(&mut mesh, &ring_verts[0], top, true);
+        for (upper, lower) in ring_verts.iter().tuple_windows() {
+            bridge_rings(&mut mesh, lower, upper);
+        }
+        fan_to_pole(&mut mesh, &ring_verts[ring_verts.len() - 1], bottom, false);
+        mesh
+    }
+
+    pub fn new_icosphere(radius: f32, subdivisions: usize) -> Mesh {
+        let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+        let mut verts: Vec<Vec3> = [
+            (-1.0, t, 0.0),
+            (1.0, t, 0.0),
+            (-1.0, -t, 0.0),
+            (1.0, -t, 0.0),
+            (0.0, -1.0, t),
+            (0.0, 1.0, t),
+            (0.0, -1.0, -t),
+            (0.0, 1.0, -t),
+            (t, 0.0, -1.0),
+            (t, 0.0, 1.0),
+            (-t, 0.0, -1.0),
+            (-t, 0.0, 1.0),
+        ]
+        .iter()
+        .map(|&(x, y, z)| vec3(x, y, z).normalize())
+        .collect();
+        let mut triangles: Vec<[VertIndex; 3]> = vec![
+            [0, 11, 5],
+            [0, 5, 1],
+            [0, 1, 7],
+            [0, 7, 10],
+            [0, 10, 11],
+            [1, 5, 9],
+            [5, 11, 4],
+            [11, 10, 2],
+            [10, 7, 6],
+            [7, 1, 8],
+            [3, 9, 4],
+            [3, 4, 2],
+            [3, 2, 6],
+            [3, 6, 8],
+            [3, 8, 9],
+            [4, 9, 5],
+            [2, 4, 11],
+            [6, 2, 10],
+            [8, 6, 7],
+            [9, 8, 1],
+        ];
+
+        // each level splits every triangle in 4, sharing the new midpoints
+        for _ in 0..subdivisions.min(MAX_ICOSPHERE_SUBDIVISIONS) {
+            let mut midpoints: HashMap<Line, VertIndex> = HashMap::new();
+            let mut midpoint = |a: VertIndex, b: VertIndex| {
+                *midpoints.entry(edge_key(a, b)).or_insert_with(|| {
+                    verts.push(((verts[a] + verts[b]) / 2.0).normalize());
+                    verts.len() - 1
+                })
+            };
+            triangles = triangles
+                .iter()
+                .flat_map(|&[a, b, c]| {
+                    let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                    [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        let mut mesh = new_derived_mesh();
+        for v in verts {
+            mesh.add_vert(v * radius);
+        }
+        for triangle in triangles {
+            mesh.add_poly(triangle.to_vec());
+        }
+        mesh
+    }
+
+    pub fn new_cylinder(radius: f32, depth: f32, segments: usize) -> Mesh {
+        let segments = segments.max(MIN_SEGMENTS);
+        let mut mesh = new_derived_mesh();
+        let bottom = add_ring(&mut mesh, radius, -depth / 2.0, segments);
+        let top = add_ring(&mut mesh, radius, depth / 2.0, segments);
+
+        bridge_rings(&mut mesh, &bottom, &top);
+        cap_ring(&mut mesh, &top, true);
+        cap_ring(&mut mesh, &bottom, false);
+        mesh
+    }
+
+    pub fn new_cone(radius: f32, depth: f32, segments: usize) -> Mesh {
+        let segments = segments.max(MIN_SEGMENTS);
+        let mut mesh = new_derived_mesh();
+        let bottom = add_ring(&mut mesh, radius, -depth / 2.0, segments);
+        let apex = mesh.add_vert(vec3(0.0, depth / 2.0, 0.0));
+
+        fan_to_pole(&mut mesh, &bottom, apex, true);
+        cap_ring(&mut mesh, &bottom, false);
+        mesh
+    }
+
+    //
+    // ring of tubes around the y axis
+    //
+    pub fn new_torus(
+        major_radius: f32,
+        minor_radius: f32,
+        major_segments: usize,
+        minor_segments: usize,
+    ) -> Mesh {
+        let major_segments = major_segments.max(MIN_SEGMENTS);
+        let minor_segments = minor_segments.max(MIN_SEGMENTS);
+        let mut mesh = new_derived_mesh();
+
+        // one ring around the y axis per step around the tube, starting on the outside
+        let rings: Vec<Vec<VertIndex>> = (0..minor_segments)
+            .map(|i| {
+                let (sin, cos) = (TAU * i as f32 / minor_segments as f32).sin_cos();
+                let ring_radius = major_radius + minor_radius * cos;
+                add_ring(&mut mesh, ring_radius, minor_radius * sin, major_segments)
+            })
+            .collect();
+
+        for (lower, upper) in rings.iter().circular_tuple_windows() {
+            bridge_rings(&mut mesh, lower, upper);
+        }
+        mesh
+    }
+
+    //
+    // square in the xz plane facing +y, split into subdivisions x subdivisions quads
+    //
+    pub fn new_grid(size: f32, subdivisions: usize) -> Mesh {
+        let subdivisions = subdivisions.max(1);
+        let mut mesh = new_derived_mesh();
+        let step = size / subdivisions as f32;
+        let offset = size / 2.0;
+        let row_len = subdivisions + 1;
+
+        for i in 0..row_len {
+            for j in 0..row_len {
+                mesh.add_vert(vec3(
+                    i as f32 * step - offset,
+                    0.0,
+                    j as f32 * step - offset,
+                ));
+            }
+        }
+
+        let vert = |i: usize, j: usize| i * row_len + j;
+        for i in 0..subdivisions {
+            for j in 0..subdivisions {
+                mesh.add_poly(vec![
+                    vert(i, j),
+                    vert(i, j + 1),
+                    vert(i + 1, j + 1),
+                    vert(i + 1, j),
+                ]);
+            }
+        }
+        mesh
+    }
+
+    //
+    // flat circle in the xz plane facing +y, made of triangles around a center vert
+    //
+    pub fn new_disc(radius: f32, segments: usize) -> Mesh {
+        let segments = segments.max(MIN_SEGMENTS);
+        let mut mesh = new_derived_mesh();
+        let ring = add_ring(&mut mesh, radius, 0.0, segments);
+        let center = mesh.add_vert(Vec3::ZERO);
+
+        fan_to_pole(&mut mesh, &ring, center, true);
+        mesh
+    }
+
+    //
+    // cylinder of the given depth with a hemisphere (of `rings` rings) on each end
+    //
+    pub fn new_capsule(radius: f32, depth: f32, segments: usize, rings: usize) -> Mesh {
+        let segments = segments.max(MIN_SEGMENTS);
+        let rings = rings.max(1);
+        let mut mesh = new_derived_mesh();
+        let half_depth = depth / 2.0;
+
+        // top hemisphere from just below the pole down to the equator, then mirrored below
+        let mut ring_verts: Vec<Vec<VertIndex>> = Vec::new();
+        for ring in 1..=rings {
+            let (sin, cos) = (PI / 2.0 * ring as f32 / rings as f32).sin_cos();
+            ring_verts.push(add_ring(
+                &mut mesh,
+                radius * sin,
+                half_depth + radius * cos,
+                segments,
+            ));
+        }
+        for ring in (1..=rings).rev() {
+            let (sin, cos) = (PI / 2.0 * ring as f32 / rings as f32).sin_cos();
+            ring_verts.push(add_ring(
+                &mut mesh,
+                radius * sin,
+                -half_depth - radius * cos,
+                segments,
+            ));
+        }
+        let top = mesh.add_vert(vec3(0.0, half_depth + radius, 0.0));
+        let bottom = mesh.add_vert(vec3(0.0, -half_depth - radius, 0.0));
+
+        fan_to_pole(&mut mesh, &ring_verts[0], top, true);
+        for (upper, lower) in ring_verts.iter().tuple_windows() {
+            bridge_rings(&mut mesh, lower, upper);
+        }
+        fan_to_pole(&mut mesh, &ring_verts[ring_verts.len() - 1], bottom, false);
+        mesh
+    }
+}
+
+//
+// Inserting
+//
+
+impl Mesh {
+    //
+    // Copies the verts, lines and polys (with their attributes and selection sets) of the
+    // other mesh into this one, moved by offset.
+    // Returns the indicies of the copied verts.
+    //
+    pub fn append(&mut self, other: &Mesh, offset: Vec3) -> Vec<VertIndex> {
+        let first_vert = self.verticies.len();
+        let first_line = self.lines.len();
+        let first_poly = self.polys.len();
+        self.verticies
+            .extend(other.verticies.iter().map(|&v| v + offset));
+        self.lines.extend(
+            other
+                .lines
+                .iter()
+                .map(|&(a, b)| (a + first_vert, b + first_vert)),
+        );
+        self.derived_lines
+            .extend(other.derived_lines.iter().copied());
+        self.polys.extend(
+            other
+                .polys
+                .iter()
+                .map(|poly| poly.iter().map(|&v| v + first_vert).collect::<Poly>()),
+        );
+        self.vert_colors.extend(other.vert_colors.iter().copied());
+        self.poly_uvs.extend(other.poly_uvs.iter().cloned());
+        self.poly_colors.extend(other.poly_colors.iter().copied());
+        // slots of other with a name already here join that slot
+        let (materials, slots) = merged_materials(&self.materials, &other.materials);
+        self.materials = materials;
+        self.poly_materials.extend(
+            other
+                .poly_materials
+                .iter()
+                .map(|material| material.map(|index| slots[index])),
+        );
+        self.append_selection_sets(other, first_vert, first_line, first_poly);
+        self.rebuild_topology();
+        if self.line_policy == LinePolicy::DeriveFromPolys {
+            self.sync_lines_with_polys();
+        }
+        (first_vert..self.verticies.len()).collect()
+    }
+}
+
+//
+// Ring helpers (rings go counter-clockwise seen from below, i.e. angle from +x toward +z)
+//
+
+fn new_derived_mesh() -> Mesh {
+    let mut mesh = Mesh::new();
+    mesh.set_line_policy(LinePolicy::DeriveFromPolys);
+    mesh
+}
+
+fn add_ring(mesh: &mut Mesh, radius: f32, y: f32, segments: usize) -> Vec<VertIndex> {
+    (0..segments)
+        .map(|i| {
+            let (sin, cos) = (TAU * i as f32 / segments as f32).sin_cos();
+            mesh.add_vert(vec3(radius * cos, y, radius * sin))
+        })
+        .collect()
+}
+
+// quads facing away from the axis between two rings of the same length
+fn bridge_rings(mesh: &mut Mesh, lower: &[VertIndex], upper: &[VertIndex]) {
+    let len = lower.len();
+    for i in 0..len {
+        let next = (i + 1) % len;
+        mesh.add_poly(vec![lower[i], upper[i], upper[next], lower[next]]);
+    }
+}
+
+// triangles from the ring to a pole above (is_top) or below it
+fn fan_to_pole(mesh: &mut Mesh, ring: &[VertIndex], pole: VertIndex, is_top: bool) {
+    let len = ring.len();
+    for i in 0..len {
+        let next = (i + 1) % len;
+        if is_top {
+            mesh.add_poly(vec![ring[i], pole, ring[next]]);
+        } else {
+            mesh.add_poly(vec![pole, ring[i], ring[next]]);
+        }
+    }
+}
+
+// single n-gon facing up (is_top) or down
+fn cap_ring(mesh: &mut Mesh, ring: &[VertIndex], is_top: bool) {
+    let mut poly: Poly = ring.to_vec();
+    if is_top {
+        poly.reverse();
+    }
+    mesh.add_poly(poly);
+}
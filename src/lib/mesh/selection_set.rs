@@ -0,0 +1,197 @@
+//
+// selection_set -> named sets of verts, lines or polys saved with the mesh (vertex groups)
+//
+// The indicies follow their elements when those are swap removed (delete_vert, remove_line,
+// remove_poly), dropped in bulk (retain_polys / retain_verts), welded (merge / decimate) or
+// split up (subdivide / bisect pieces). Other bulk rebuilds keep what is still in range.
+//
+
+use super::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SetKind {
+    Verts,
+    Lines,
+    Polys,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelectionSet {
+    pub name: String,
+    pub kind: SetKind,
+    // sorted, no duplicates
+    pub indicies: Vec<usize>,
+}
+
+impl SelectionSet {
+    pub fn new(name: &str, kind: SetKind, indicies: &[usize]) -> SelectionSet {
+        SelectionSet {
+            name: name.to_string(),
+            kind,
+            indicies: indicies.iter().copied().sorted().dedup().collect(),
+        }
+    }
+}
+
+impl Mesh {
+    pub fn selection_sets(&self) -> &[SelectionSet] {
+        &self.selection_sets
+    }
+
+    pub fn selection_set(&self, name: &str) -> Option<&SelectionSet> {
+        self.selection_sets.iter().find(|set| set.name == name)
+    }
+
+    //
+    // saves the indicies under the name (replacing a set with the same name), indicies
+    // that don't exist are left out; returns how many were saved
+    //
+    pub fn save_selection_set(&mut self, name: &str, kind: SetKind, indicies: &[usize]) -> usize {
+        let count = self.element_count(kind);
+        let indicies: Vec<usize> = indicies.iter().copied().filter(|&i| i < count).collect();
+        let set = SelectionSet::new(name, kind, &indicies);
+        let saved_count = set.indicies.len();
+        match self.selection_sets.iter().position(|set| set.name == name) {
+            Some(position) => self.selection_sets[position] = set,
+            None => self.selection_sets.push(set),
+        }
+        saved_count
+    }
+
+    pub fn remove_selection_set(&mut self, name: &str) -> Option<SelectionSet> {
+        let position = self
+            .selection_sets
+            .iter()
+            .position(|set| set.name == name)?;
+        Some(self.selection_sets.remove(position))
+    }
+
+    fn element_count(&self, kind: SetKind) -> usize {
+        match kind {
+            SetKind::Verts => self.verticies.len(),
+            SetKind::Lines => self.lines.len(),
+            SetKind::Polys => self.polys.len(),
+        }
+    }
+
+    //
+    // the sets of other with its elements starting at the given indicies, sets with the
+    // same name and kind are joined (a name used for another kind keeps the set here)
+    //
+    pub(super) fn append_selection_sets(
+        &mut self,
+        other: &Mesh,
+        first_vert: VertIndex,
+        first_line: LineIndex,
+        first_poly: PolyIndex,
+    ) {
+        for set in other.selection_sets.iter() {
+            let first = match set.kind {
+                SetKind::Verts => first_vert,
+                SetKind::Lines => first_line,
+                SetKind::Polys => first_poly,
+            };
+            let mut indicies: Vec<usize> = set.indicies.iter().map(|&i| i + first).collect();
+            match self.selection_set(&set.name) {
+                Some(existing) if existing.kind != set.kind => continue,
+                Some(existing) => indicies.extend(&existing.indicies),
+                None => {}
+            }
+            self.save_selection_set(&set.name, set.kind, &indicies);
+        }
+    }
+
+    //
+    // Keeping the indicies in step (old index -> new indicies, none when the element is gone)
+    //
+
+    pub(super) fn remap_selection_sets<I: IntoIterator<Item = usize>>(
+        &mut self,
+        kind: SetKind,
+        remap: impl Fn(usize) -> I,
+    ) {
+        for set in self
+            .selection_sets
+            .iter_mut()
+            .filter(|set| set.kind == kind)
+        {
+            let indicies: Vec<usize> = set.indicies.iter().flat_map(|&i| remap(i)).collect();
+            *set = SelectionSet::new(&set.name, kind, &indicies);
+        }
+    }
+
+    //
+    // after lines were rebuilt: each old line index -> the edges it became, looked up among
+    // the current lines
+    //
+    pub(super) fn remap_line_sets_to_edges(&mut self, edges: impl Fn(LineIndex) -> Vec<Line>) {
+        let line_indicies: HashMap<Line, LineIndex> = self
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(line_index, line)| (edge_key(line.0, line.1), line_index))
+            .collect();
+        self.remap_selection_sets(SetKind::Lines, |line_index| {
+            edges(line_index)
+                .into_iter()
+                .filter_map(|edge| line_indicies.get(&edge_key(edge.0, edge.1)).copied())
+                .collect::<Vec<LineIndex>>()
+        });
+    }
+
+    // after swap_remove(index) on a list that had last_index as its last entry
+    pub(super) fn remap_selection_sets_after_swap_remove(
+        &mut self,
+        kind: SetKind,
+        index: usize,
+        last_index: usize,
+    ) {
+        self.remap_selection_sets(kind, |i| match i {
+            _ if i == index => None,
+            _ if i == last_index => Some(index),
+            _ => Some(i),
+        });
+    }
+
+    // after keeping the entries whose flag is set
+    pub(super) fn remap_selection_sets_after_retain(&mut self, kind: SetKind, flags: &[bool]) {
+        let new_index = retained_indicies(flags);
+        self.remap_selection_sets(kind, |i| new_index.get(i).copied().flatten());
+    }
+
+    // indicies past the end (after bulk rebuilds) are dropped
+    pub(super) fn sync_selection_sets(&mut self) {
+        for kind in [SetKind::Verts, SetKind::Lines, SetKind::Polys] {
+            let count = self.element_count(kind);
+            self.remap_selection_sets(kind, |i| (i < count).then_some(i));
+        }
+    }
+}
+
+// old index -> index among the kept entries
+fn retained_indicies(flags: &[bool]) -> Vec<Option<usize>> {
+    let mut kept_count = 0;
+    flags
+        .iter()
+        .map(|&keep| {
+            keep.then(|| {
+                kept_count += 1;
+                kept_count - 1
+            })
+        })
+        .collect()
+}
+
+//
+// Formatting
+//
+
+impl fmt::Display for SetKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetKind::Verts => write!(formatter, "verts"),
+            SetKind::Lines => write!(formatter, "lines"),
+            SetKind::Polys => write!(formatter, "polys"),
+        }
+    }
+}
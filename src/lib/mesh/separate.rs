@@ -0,0 +1,56 @@
+//
+// separate -> moving polys out of a mesh, e.g. into a mesh (or scene object) of their own
+//
+
+use super::*;
+
+impl Mesh {
+    //
+    // Removes the polys along with the verts nothing else uses any more. Derived lines along
+    // their edges go with them, lines drawn by hand stay (and keep their verts).
+    // Returns how many polys were removed.
+    //
+    pub fn remove_polys(&mut self, poly_indicies: &[PolyIndex]) -> usize {
+        let removed: HashSet<PolyIndex> = poly_indicies
+            .iter()
+            .copied()
+            .filter(|&poly_index| poly_index < self.polys.len())
+            .collect();
+        if removed.is_empty() {
+            return 0;
+        }
+        let removed_polys: Vec<Poly> = removed
+            .iter()
+            .map(|&poly_index| self.polys[poly_index].clone())
+            .collect();
+
+        self.retain_polys(|poly_index, _| !removed.contains(&poly_index));
+        self.rebuild_topology();
+        for poly in removed_polys.iter() {
+            self.derive_lines_after_poly_removed(poly);
+        }
+
+        let mut keep_verts = vec![true; self.verticies.len()];
+        for &v in removed_polys.iter().flatten() {
+            keep_verts[v] = false;
+        }
+        for &v in self.polys.iter().flatten() {
+            keep_verts[v] = true;
+        }
+        for &(a, b) in self.lines.iter() {
+            (keep_verts[a], keep_verts[b]) = (true, true);
+        }
+        self.retain_verts(&keep_verts);
+        self.rebuild_topology();
+        removed.len()
+    }
+
+    //
+    // the polys as a mesh of their own (see extract_polys), removed from this one
+    //
+    pub fn separate_polys(&mut self, poly_indicies: &[PolyIndex]) -> Mesh {
+        let separated = self.extract_polys(poly_indicies);
+        self.remove_polys(poly_indicies);
+        separated
+    }
+}
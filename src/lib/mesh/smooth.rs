@@ -0,0 +1,74 @@
+//
+// smooth -> laplacian and taubin (non-shrinking) smoothing of selected verts
+//
+// boundary verts, pinned verts and wire chain ends stay where they are
+//
+
+use super::*;
+
+// taubin pass band frequency, mu is picked so frequencies below this are kept
+const TAUBIN_PASS_BAND: f32 = 0.1;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SmoothingMode {
+    Laplacian,
+    Taubin,
+}
+
+impl Mesh {
+    //
+    // Moves each free vert toward the average of its neighbors by `strength` (0..1],
+    // `iterations` times. Taubin follows each step with a slightly larger step back,
+    // which keeps the volume. Returns the number of verts that were free to move
+    // (none for a strength of 0 or less, strengths above 1 are clamped).
+    //
+    pub fn smooth_verts(
+        &mut self,
+        verts: &[VertIndex],
+        pinned: &[VertIndex],
+        mode: SmoothingMode,
+        strength: f32,
+        iterations: usize,
+    ) -> usize {
+        if strength.is_nan() || strength <= 0.0 {
+            return 0;
+        }
+        let strength = strength.min(1.0);
+        let free: Vec<(VertIndex, Vec<VertIndex>)> = verts
+            .iter()
+            .copied()
+            .unique()
+            .filter(|&v| v < self.verticies.len() && !pinned.contains(&v))
+            .filter(|&v| !self.is_boundary_vert(v))
+            .map(|v| (v, self.one_ring(v)))
+            .filter(|(_, neighbors)| neighbors.len() > 1)
+            .collect();
+
+        let steps: Vec<f32> = match mode {
+            SmoothingMode::Laplacian => vec![strength],
+            SmoothingMode::Taubin => vec![strength, 1.0 / (TAUBIN_PASS_BAND - 1.0 / strength)],
+        };
+
+        for _ in 0..iterations {
+            for &factor in steps.iter() {
+                self.laplacian_step(&free, factor);
+            }
+        }
+        free.len()
+    }
+
+    // every vert moves based on the positions from before the step
+    fn laplacian_step(&mut self, free: &[(VertIndex, Vec<VertIndex>)], factor: f32) {
+        let moved: Vec<Vec3> = free
+            .iter()
+            .map(|(v, neighbors)| {
+                let average = neighbors.iter().map(|&n| self.verticies[n]).sum::<Vec3>()
+                    / neighbors.len() as f32;
+                self.verticies[*v] + (average - self.verticies[*v]) * factor
+            })
+            .collect();
+        for ((v, _), position) in free.iter().zip(moved) {
+            self.verticies[*v] = position;
+        }
+    }
+}
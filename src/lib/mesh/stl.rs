@@ -0,0 +1,454 @@
+//
+// stl -> read / write ASCII and binary STL (triangle soup with face normals)
+//
+// Import welds the triangle corners into shared verts and can join coplanar triangle
+// pairs back into quads. Export triangulates the polys (lines are not part of STL).
+//
+
+use super::*;
+use std::fs;
+use std::path::Path;
+
+const STL_HEADER_SIZE: usize = 80;
+// header + triangle count
+const STL_PREAMBLE_SIZE: usize = STL_HEADER_SIZE + 4;
+// normal + 3 corners (f32 xyz each) + attribute byte count
+const STL_TRIANGLE_SIZE: usize = 12 * 4 + 2;
+const STL_SOLID_NAME: &str = "mesh_editor";
+// triangles whose normals are closer than this (1 - cos) can be joined into a quad
+const QUAD_JOIN_TOLERANCE: f32 = 1e-4;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum StlFormat {
+    Ascii,
+    Binary,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StlError {
+    Io(String),
+    // binary file shorter than its triangle count needs
+    Truncated {
+        expected_bytes: usize,
+        actual_bytes: usize,
+    },
+    // ASCII file: 1 based line number and what was wrong on it
+    Parse {
+        line: usize,
+        message: String,
+    },
+    // ASCII file ended inside a solid
+    UnexpectedEnd,
+    // a corner coordinate is NaN or infinite
+    NonFiniteCoord {
+        triangle: usize,
+    },
+}
+
+impl Mesh {
+    //
+    // Reads ASCII or binary STL (detected from the content). Corners closer than the
+    // weld tolerance become one vert, rebuild_quads joins coplanar triangle pairs.
+    //
+    pub fn from_stl(
+        bytes: &[u8],
+        weld_tolerance: f32,
+        rebuild_quads: bool,
+    ) -> Result<Mesh, StlError> {
+        let triangles = if is_binary_stl(bytes) {
+            read_binary_triangles(bytes)?
+        } else {
+            read_ascii_triangles(bytes)?
+        };
+        if let Some(triangle) = triangles
+            .iter()
+            .position(|corners| !corners.iter().all(|corner| corner.is_finite()))
+        {
+            return Err(StlError::NonFiniteCoord { triangle });
+        }
+
+        let mut mesh = Mesh::new();
+        for corners in triangles {
+            let poly: Poly = corners.iter().map(|&v| mesh.add_vert(v)).collect();
+            mesh.add_poly(poly);
+        }
+        mesh.merge_by_distance(weld_tolerance);
+        mesh.rebuild_topology();
+        if rebuild_quads {
+            mesh.join_coplanar_triangles();
+        }
+        mesh.set_line_policy(LinePolicy::DeriveFromPolys);
+        Ok(mesh)
+    }
+
+    pub fn load_stl(
+        path: &Path,
+        weld_tolerance: f32,
+        rebuild_quads: bool,
+    ) -> Result<Mesh, StlError> {
+        let bytes = fs::read(path).map_err(|error| StlError::Io(error.to_string()))?;
+        Mesh::from_stl(&bytes, weld_tolerance, rebuild_quads)
+    }
+
+    //
+    // every poly as triangles (ear clipped) with the triangle's own unit normal
+    //
+    pub fn to_stl(&self, format: StlFormat) -> Vec<u8> {
+        let triangles = self.stl_triangles();
+        match format {
+            StlFormat::Ascii => write_ascii_triangles(&triangles).into_bytes(),
+            StlFormat::Binary => write_binary_triangles(&triangles),
+        }
+    }
+
+    pub fn save_stl(&self, path: &Path, format: StlFormat) -> Result<(), StlError> {
+        fs::write(path, self.to_stl(format)).map_err(|error| StlError::Io(error.to_string()))
+    }
+
+    //
+    // Joins pairs of neighboring triangles that lie in one plane and make a convex quad,
+    // preferring the longest shared edge (usually the diagonal). Returns the quad count.
+    //
+    pub fn join_coplanar_triangles(&mut self) -> usize {
+        let mut is_joined = vec![false; self.polys.len()];
+        let mut joined_count = 0;
+        let mut diagonals: HashSet<Line> = HashSet::new();
+
+        for poly_index in 0..self.polys.len() {
+            if is_joined[poly_index] || self.polys[poly_index].len() != 3 {
+                continue;
+            }
+            let best = poly_edges(&self.polys[poly_index])
+                .filter_map(|edge| {
+                    let other = self.triangle_across(poly_index, edge)?;
+                    (!is_joined[other]).then_some((edge, other))
+                })
+                .filter_map(|(edge, other)| {
+                    let quad = self.joined_quad(poly_index, other, edge)?;
+                    let length = self.verticies[edge.0].distance(self.verticies[edge.1]);
+                    Some((length, edge, other, quad))
+                })
+                .max_by(|x, y| x.0.total_cmp(&y.0));
+
+            if let Some((_, edge, other, quad)) = best {
+                self.polys[poly_index] = quad;
+                self.poly_uvs[poly_index].clear();
+                is_joined[poly_index] = true;
+                is_joined[other] = true;
+                diagonals.insert(edge_key(edge.0, edge.1));
+                joined_count += 1;
+            }
+        }
+
+        // the other half of each pair was absorbed into its quad
+        self.retain_polys(|poly_index, poly| !is_joined[poly_index] || poly.len() == 4);
+        if self.line_policy == LinePolicy::DeriveFromPolys {
+            let line_flags: Vec<bool> = self
+                .lines
+                .iter()
+                .map(|line| !diagonals.contains(&edge_key(line.0, line.1)))
+                .collect();
+            self.retain_lines(&line_flags);
+        }
+        self.rebuild_topology();
+        joined_count
+    }
+
+    // the only other triangle on the edge, walking it the other way
+    fn triangle_across(&self, poly_index: PolyIndex, edge: Line) -> Option<PolyIndex> {
+        let [first, second] = self.edge_polys(edge) else {
+            return None;
+        };
+        let other = if *first == poly_index {
+            *second
+        } else {
+            *first
+        };
+        let other_poly = &self.polys[other];
+        (other_poly.len() == 3 && poly_edges(other_poly).any(|e| e == (edge.1, edge.0)))
+            .then_some(other)
+    }
+
+    fn joined_quad(&self, first: PolyIndex, second: PolyIndex, edge: Line) -> Option<Poly> {
+        let (a, b) = edge;
+        let opposite = |poly_index: PolyIndex| {
+            self.polys[poly_index]
+                .iter()
+                .copied()
+                .find(|&v| v != a && v != b)
+        };
+        // first walks a -> b -> c, second walks b -> a -> d
+        let (c, d) = (opposite(first)?, opposite(second)?);
+        let first_normal = self.poly_normal(first);
+        if first_normal.dot(self.poly_normal(second)) < 1.0 - QUAD_JOIN_TOLERANCE {
+            return None;
+        }
+
+        let quad: Poly = vec![a, d, b, c];
+        let is_convex = (0..4).all(|i| {
+            let p = |k: usize| self.verticies[quad[(i + k) % 4]];
+            (p(1) - p(0)).cross(p(2) - p(1)).dot(first_normal) > f32::EPSILON
+        });
+        is_convex.then_some(quad)
+    }
+
+    fn stl_triangles(&self) -> Vec<[Vec3; 3]> {
+        self.polys
+            .iter()
+            .flat_map(|poly| {
+                if poly.len() == 3 {
+                    vec![[poly[0], poly[1], poly[2]]]
+                } else {
+                    self.triangulate_loop(poly)
+                }
+            })
+            .map(|triangle| triangle.map(|v| self.verticies[v]))
+            .collect()
+    }
+}
+
+//
+// Reading
+//
+
+//
+// binary files give their size away through the triangle count (84 + 50 * count bytes),
+// which is checked first since their header may start with "solid" too. Anything else
+// is ASCII if it starts with "solid".
+//
+fn is_binary_stl(bytes: &[u8]) -> bool {
+    if bytes.len() >= STL_PREAMBLE_SIZE {
+        let count = u32::from_le_bytes(
+            bytes[STL_HEADER_SIZE..STL_PREAMBLE_SIZE]
+                .try_into()
+                .unwrap(),
+        );
+        let expected_size = (count as usize)
+            .checked_mul(STL_TRIANGLE_SIZE)
+            .and_then(|size| size.checked_add(STL_PREAMBLE_SIZE));
+        if expected_size == Some(bytes.len()) {
+            return true;
+        }
+    }
+    !bytes.trim_ascii_start().starts_with(b"solid")
+}
+
+fn read_binary_triangles(bytes: &[u8]) -> Result<Vec<[Vec3; 3]>, StlError> {
+    if bytes.len() < STL_PREAMBLE_SIZE {
+        return Err(StlError::Truncated {
+            expected_bytes: STL_PREAMBLE_SIZE,
+            actual_bytes: bytes.len(),
+        });
+    }
+    let count = u32::from_le_bytes(
+        bytes[STL_HEADER_SIZE..STL_PREAMBLE_SIZE]
+            .try_into()
+            .unwrap(),
+    );
+    let expected_bytes = STL_PREAMBLE_SIZE + count as usize * STL_TRIANGLE_SIZE;
+    // some writers pad the end, only missing data is an error
+    if bytes.len() < expected_bytes {
+        return Err(StlError::Truncated {
+            expected_bytes,
+            actual_bytes: bytes.len(),
+        });
+    }
+
+    let read_f32 =
+        |offset: usize| f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    let read_vec3 =
+        |offset: usize| Vec3::new(read_f32(offset), read_f32(offset + 4), read_f32(offset + 8));
+    Ok((0..count as usize)
+        .map(|i| {
+            // skips the stored normal, the corner order decides the facing
+            let corners = STL_PREAMBLE_SIZE + i * STL_TRIANGLE_SIZE + 12;
+            [
+                read_vec3(corners),
+                read_vec3(corners + 12),
+                read_vec3(corners + 24),
+            ]
+        })
+        .collect())
+}
+
+//
+// solid / facet normal / outer loop / vertex x3 / endloop / endfacet ... endsolid
+// (several solids in one file are read as one mesh)
+//
+fn read_ascii_triangles(bytes: &[u8]) -> Result<Vec<[Vec3; 3]>, StlError> {
+    let text = std::str::from_utf8(bytes).map_err(|error| {
+        let line = bytes[..error.valid_up_to()]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        StlError::Parse {
+            line: line + 1,
+            message: String::from("not valid text"),
+        }
+    })?;
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split_whitespace().collect::<Vec<&str>>()))
+        .filter(|(_, tokens)| !tokens.is_empty());
+
+    let mut triangles = Vec::new();
+    let mut has_solid = false;
+    loop {
+        let Some((line, tokens)) = lines.next() else {
+            return match has_solid {
+                true => Ok(triangles),
+                false => Err(StlError::UnexpectedEnd),
+            };
+        };
+        expect_keyword(line, &tokens, "solid")?;
+        has_solid = true;
+
+        loop {
+            let (line, tokens) = lines.next().ok_or(StlError::UnexpectedEnd)?;
+            match tokens[0] {
+                "endsolid" => break,
+                "facet" => {}
+                other => {
+                    return Err(parse_error(
+                        line,
+                        format!("expected facet, found {}", other),
+                    ));
+                }
+            }
+            let (line, tokens) = lines.next().ok_or(StlError::UnexpectedEnd)?;
+            if tokens != ["outer", "loop"] {
+                return Err(parse_error(line, String::from("expected outer loop")));
+            }
+
+            let mut corners = Vec::new();
+            let endloop_line = loop {
+                let (line, tokens) = lines.next().ok_or(StlError::UnexpectedEnd)?;
+                match tokens[0] {
+                    "endloop" => break line,
+                    "vertex" => corners.push(parse_vec3(line, &tokens[1..])?),
+                    other => {
+                        return Err(parse_error(
+                            line,
+                            format!("expected vertex, found {}", other),
+                        ));
+                    }
+                }
+            };
+            if corners.len() != 3 {
+                return Err(parse_error(
+                    endloop_line,
+                    format!("facet has {} vertices instead of 3", corners.len()),
+                ));
+            }
+            triangles.push([corners[0], corners[1], corners[2]]);
+
+            let (line, tokens) = lines.next().ok_or(StlError::UnexpectedEnd)?;
+            expect_keyword(line, &tokens, "endfacet")?;
+        }
+    }
+}
+
+fn expect_keyword(line: usize, tokens: &[&str], keyword: &str) -> Result<(), StlError> {
+    if tokens[0] == keyword {
+        Ok(())
+    } else {
+        Err(parse_error(
+            line,
+            format!("expected {}, found {}", keyword, tokens[0]),
+        ))
+    }
+}
+
+fn parse_vec3(line: usize, tokens: &[&str]) -> Result<Vec3, StlError> {
+    if tokens.len() != 3 {
+        return Err(parse_error(
+            line,
+            format!("expected 3 numbers, found {}", tokens.len()),
+        ));
+    }
+    let mut coords = [0.0; 3];
+    for (coord, token) in coords.iter_mut().zip(tokens) {
+        *coord = token
+            .parse()
+            .map_err(|_| parse_error(line, format!("invalid number {}", token)))?;
+    }
+    Ok(Vec3::from_array(coords))
+}
+
+fn parse_error(line: usize, message: String) -> StlError {
+    StlError::Parse { line, message }
+}
+
+//
+// Writing
+//
+
+fn triangle_normal(corners: &[Vec3; 3]) -> Vec3 {
+    // adding zero turns -0 into 0, so ASCII files don't get "-0" components
+    (corners[1] - corners[0])
+        .cross(corners[2] - corners[0])
+        .normalize_or_zero()
+        + Vec3::ZERO
+}
+
+fn write_ascii_triangles(triangles: &[[Vec3; 3]]) -> String {
+    let mut text = format!("solid {}\n", STL_SOLID_NAME);
+    for corners in triangles {
+        let normal = triangle_normal(corners);
+        text += &format!("  facet normal {} {} {}\n", normal.x, normal.y, normal.z);
+        text += "    outer loop\n";
+        for corner in corners {
+            text += &format!("      vertex {} {} {}\n", corner.x, corner.y, corner.z);
+        }
+        text += "    endloop\n";
+        text += "  endfacet\n";
+    }
+    text += &format!("endsolid {}\n", STL_SOLID_NAME);
+    text
+}
+
+fn write_binary_triangles(triangles: &[[Vec3; 3]]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(STL_PREAMBLE_SIZE + triangles.len() * STL_TRIANGLE_SIZE);
+    // the header must not start with "solid", readers would take it for ASCII
+    let mut header = format!("binary STL from {}", STL_SOLID_NAME).into_bytes();
+    header.resize(STL_HEADER_SIZE, 0);
+    bytes.extend(header);
+    bytes.extend((triangles.len() as u32).to_le_bytes());
+
+    for corners in triangles {
+        let normal = triangle_normal(corners);
+        for v in std::iter::once(&normal).chain(corners) {
+            for coord in v.to_array() {
+                bytes.extend(coord.to_le_bytes());
+            }
+        }
+        bytes.extend(0u16.to_le_bytes());
+    }
+    bytes
+}
+
+//
+// Formatting
+//
+
+impl fmt::Display for StlError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StlError::Io(message) => write!(formatter, "{}", message),
+            StlError::Truncated {
+                expected_bytes,
+                actual_bytes,
+            } => write!(
+                formatter,
+                "file is truncated ({} of {} bytes)",
+                actual_bytes, expected_bytes
+            ),
+            StlError::Parse { line, message } => write!(formatter, "line {}: {}", line, message),
+            StlError::UnexpectedEnd => write!(formatter, "file ends inside a solid"),
+            StlError::NonFiniteCoord { triangle } => {
+                write!(formatter, "triangle {} has an invalid coordinate", triangle)
+            }
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//
+// subdivide -> simple (linear) and catmull-clark subdivision of n-gon polys
+//
+
+use super::*;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SubdivisionMode {
+    Simple,
+    CatmullClark,
+}
+
+impl Mesh {
+    //
+    // destructive subdivision, returns the line indicies of the creases after the split
+    //
+    pub fn subdivide(&mut self, mode: SubdivisionMode, creases: &[LineIndex]) -> Vec<LineIndex> {
+        let crease_set = self.crease_set_from_line_indicies(creases);
+        let (subdivided, new_creases) = self.subdivide_once(mode, &crease_set);
+        *self = subdivided;
+
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| new_creases.contains(&edge_key(line.0, line.1)))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    //
+    // non destructive subdivision (e.g. used for the viewport preview)
+    //
+    pub fn subdivided(&self, mode: SubdivisionMode, levels: usize, creases: &[LineIndex]) -> Mesh {
+        let mut crease_set = self.crease_set_from_line_indicies(creases);
+        let mut mesh = self.clone();
+
+        for _ in 0..levels {
+            (mesh, crease_set) = mesh.subdivide_once(mode, &crease_set);
+        }
+        mesh
+    }
+
+    fn crease_set_from_line_indicies(&self, creases: &[LineIndex]) -> HashSet<Line> {
+        creases
+            .iter()
+            .filter_map(|&i| self.lines.get(i))
+            .map(|line| edge_key(line.0, line.1))
+            .collect()
+    }
+
+    //
+    // Every poly with n corners becomes n quads (corner, edge point, face point, edge point).
+    // Original verts keep their indicies, followed by edge points, then face points.
+    //
+    fn subdivide_once(
+        &self,
+        mode: SubdivisionMode,
+        creases: &HashSet<Line>,
+    ) -> (Mesh, HashSet<Line>) {
+        let mut edge_faces: HashMap<Line, Vec<PolyIndex>> = HashMap::new();
+        let mut edge_order: Vec<Line> = Vec::new();
+        for (poly_index, poly) in self.polys.iter().enumerate() {
+            for (a, b) in poly_edges(poly) {
+                let faces = edge_faces.entry(edge_key(a, b)).or_insert_with(|| {
+                    edge_order.push(edge_key(a, b));
+                    Vec::new()
+                });
+                faces.push(poly_index);
+            }
+        }
+
+        let is_sharp_edge = |edge: &Line| edge_faces[edge].len() != 2 || creases.contains(edge);
+
+        let face_points: Vec<Vec3> = self
+            .polys
+            .iter()
+            .map(|poly| self.poly_centroid(poly))
+            .collect();
+
+        let mut result = Mesh::new();
+        result.mirror_mode = self.mirror_mode.clone();
+        result.line_policy = self.line_policy;
+        result.materials = self.materials.clone();
+
+        // vertex points
+        let vertex_points = match mode {
+            SubdivisionMode::Simple => self.verticies.clone(),
+            SubdivisionMode::CatmullClark => {
+                self.catmull_clark_vertex_points(&edge_order, &edge_faces, &face_points, creases)
+            }
+        };
+        result.verticies.extend(vertex_points);
+        result.vert_colors.extend(self.vert_colors.iter().copied());
+
+        // edge points
+        let mut edge_points: HashMap<Line, VertIndex> = HashMap::new();
+        for edge in edge_order.iter() {
+            let midpoint = (self.verticies[edge.0] + self.verticies[edge.1]) / 2.0;
+            let edge_point = if mode == SubdivisionMode::CatmullClark && !is_sharp_edge(edge) {
+                let faces = &edge_faces[edge];
+                (midpoint * 2.0 + face_points[faces[0]] + face_points[faces[1]]) / 4.0
+            } else {
+                midpoint
+            };
+            let edge_index = result.add_vert(edge_point);
+            result.vert_colors[edge_index] =
+                mix_colors([self.vert_color(edge.0), self.vert_color(edge.1)]);
+            edge_points.insert(*edge, edge_index);
+        }
+
+        // face points + new quads (colored, in the material of their poly, UVs interpolated
+        // from its corners: edge midpoints and the average at the face point)
+        let mut poly_children: Vec<Vec<PolyIndex>> = Vec::with_capacity(self.polys.len());
+        for (poly_index, (poly, face_point)) in
+            self.polys.iter().zip(face_points.iter()).enumerate()
+        {
+            let face_index = result.add_vert(*face_point);
+            result.vert_colors[face_index] = mix_colors(poly.iter().map(|&v| self.vert_color(v)));
+            let len = poly.len();
+            let uvs = self.poly_uvs(poly_index);
+            let face_uv = uvs.map(|uvs| uvs.iter().sum::<Vec2>() / len as f32);
+            poly_children.push((result.polys.len()..result.polys.len() + len).collect());
+            for i in 0..len {
+                let prev = poly[(i + len - 1) % len];
+                let curr = poly[i];
+                let next = poly[(i + 1) % len];
+                result.polys.push(vec![
+                    curr,
+                    edge_points[&edge_key(curr, next)],
+                    face_index,
+                    edge_points[&edge_key(prev, curr)],
+                ]);
+                result.poly_uvs.push(match (uvs, face_uv) {
+                    (Some(uvs), Some(face_uv)) => vec![
+                        uvs[i],
+                        (uvs[i] + uvs[(i + 1) % len]) / 2.0,
+                        face_uv,
+                        (uvs[(i + len - 1) % len] + uvs[i]) / 2.0,
+                    ],
+                    _ => PolyUvs::new(),
+                });
+                result.poly_colors.push(self.poly_color(poly_index));
+                result.poly_materials.push(self.poly_material(poly_index));
+            }
+        }
+
+        // lines along the new poly edges
+        let mut seen_lines: HashSet<Line> = HashSet::new();
+        for poly in result.polys.iter() {
+            for (a, b) in poly_edges(poly) {
+                if seen_lines.insert(edge_key(a, b)) {
+                    result.lines.push((a, b));
+                    result.derived_lines.push(true);
+                }
+            }
+        }
+
+        // wire lines that are not part of any poly are split at their midpoint
+        let mut midpoints = edge_points.clone();
+        for (line, &is_derived) in self.lines.iter().zip(self.derived_lines.iter()) {
+            let key = edge_key(line.0, line.1);
+            if edge_faces.contains_key(&key) || !seen_lines.insert(key) {
+                continue;
+            }
+            let midpoint = (self.verticies[line.0] + self.verticies[line.1]) / 2.0;
+            let mid_index = result.add_vert(midpoint);
+            result.vert_colors[mid_index] =
+                mix_colors([self.vert_color(line.0), self.vert_color(line.1)]);
+            result.lines.push((line.0, mid_index));
+            result.lines.push((mid_index, line.1));
+            result.derived_lines.extend([is_derived, is_derived]);
+            midpoints.insert(key, mid_index);
+        }
+
+        // verts keep their index, polys and lines become their pieces
+        result.selection_sets = self.selection_sets.clone();
+        result.remap_selection_sets(SetKind::Polys, |poly_index| {
+            poly_children[poly_index].clone()
+        });
+        result.remap_line_sets_to_edges(|line_index| {
+            let (a, b) = self.lines[line_index];
+            match midpoints.get(&edge_key(a, b)) {
+                Some(&mid_index) => vec![(a, mid_index), (mid_index, b)],
+                None => Vec::new(),
+            }
+        });
+
+        let new_creases = creases
+            .iter()
+            .filter_map(|edge| {
+                let mid_index = *edge_points.get(edge)?;
+                Some([edge_key(edge.0, mid_index), edge_key(mid_index, edge.1)])
+            })
+            .flatten()
+            .collect();
+
+        result.rebuild_topology();
+        (result, new_creases)
+    }
+
+    fn catmull_clark_vertex_points(
+        &self,
+        edge_order: &[Line],
+        edge_faces: &HashMap<Line, Vec<PolyIndex>>,
+        face_points: &[Vec3],
+        creases: &HashSet<Line>,
+    ) -> Vec<Vec3> {
+        let vert_count = self.verticies.len();
+        let mut vert_faces: Vec<Vec<PolyIndex>> = vec![Vec::new(); vert_count];
+        let mut vert_edges: Vec<Vec<Line>> = vec![Vec::new(); vert_count];
+
+        for (poly_index, poly) in self.polys.iter().enumerate() {
+            for &vert_index in poly.iter() {
+                vert_faces[vert_index].push(poly_index);
+            }
+        }
+        for edge in edge_order.iter() {
+            vert_edges[edge.0].push(*edge);
+            vert_edges[edge.1].push(*edge);
+        }
+
+        (0..vert_count)
+            .map(|vert_index| {
+                let v = self.verticies[vert_index];
+                let faces = &vert_faces[vert_index];
+                let edges = &vert_edges[vert_index];
+                if faces.is_empty() {
+                    return v;
+                }
+
+                let sharp_neighbors: Vec<Vec3> = edges
+                    .iter()
+                    .filter(|edge| edge_faces[*edge].len() != 2 || creases.contains(*edge))
+                    .map(|edge| {
+                        let other = if edge.0 == vert_index { edge.1 } else { edge.0 };
+                        self.verticies[other]
+                    })
+                    .collect();
+
+                match sharp_neighbors.len() {
+                    // smooth (or dart) vertex
+                    0 | 1 => {
+                        let n = edges.len() as f32;
+                        let face_avg = faces.iter().map(|&f| face_points[f]).sum::<Vec3>()
+                            / faces.len() as f32;
+                        let edge_mid_avg = edges
+                            .iter()
+                            .map(|edge| (self.verticies[edge.0] + self.verticies[edge.1]) / 2.0)
+                            .sum::<Vec3>()
+                            / n;
+                        (face_avg + edge_mid_avg * 2.0 + v * (n - 3.0)) / n
+                    }
+                    // boundary or crease vertex
+                    2 => v * 0.75 + (sharp_neighbors[0] + sharp_neighbors[1]) * 0.125,
+                    // corner vertex
+                    _ => v,
+                }
+            })
+            .collect()
+    }
+
+    pub fn poly_centroid(&self, poly: &Poly) -> Vec3 {
+        poly.iter().map(|&i| self.verticies[i]).sum::<Vec3>() / poly.len() as f32
+    }
+}
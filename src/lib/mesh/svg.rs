@@ -0,0 +1,412 @@
+//
+// svg -> orthographic blueprint of one of the panel projections as SVG
+//
+// Lines (and every poly edge) are drawn as strokes, polys can be filled back to front, and
+// hidden-line removal cuts away the parts of lines behind polys closer to the viewer.
+// Sizes are in millimetres, `scale` of them per world unit.
+//
+
+use super::*;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const SVG_LINE_WIDTH: f32 = 0.35;
+const SVG_FRAME_LINE_WIDTH: f32 = 0.18;
+const SVG_FRONT_FILL: &str = "#e0e0e0";
+const SVG_BACK_FILL: &str = "#b0b0b0";
+const SVG_MARGIN: f32 = 10.0;
+// distance of the dimension lines from the frame, and their text size
+const SVG_DIMENSION_OFFSET: f32 = 6.0;
+const SVG_TEXT_SIZE: f32 = 3.5;
+const SVG_ARROW_SIZE: f32 = 1.5;
+// relative to the size of the mesh: how much closer a poly must be to hide a line
+const HIDDEN_DEPTH_TOLERANCE: f32 = 1e-4;
+
+//
+// The three panel projections, seen from the default (unflipped) side of each pane:
+// XY looks down -z, XZ looks down -y (top view), YZ looks down -x.
+//
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ProjectionPlane {
+    XY,
+    XZ,
+    YZ,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SvgExportOptions {
+    pub plane: ProjectionPlane,
+    // seen from the other side, like a flipped pane
+    pub is_flipped: bool,
+    pub fill_polys: bool,
+    pub hidden_line_removal: bool,
+    // bounding box frame with its width and height dimensioned
+    pub dimension_frame: bool,
+    // millimetres per world unit
+    pub scale: f32,
+}
+
+impl ProjectionPlane {
+    // world directions of drawing right, drawing up and toward the viewer
+    pub fn axes(&self, is_flipped: bool) -> (Vec3, Vec3, Vec3) {
+        let (right, up, toward_viewer) = match self {
+            ProjectionPlane::XY => (Vec3::X, Vec3::Y, Vec3::Z),
+            ProjectionPlane::XZ => (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+            ProjectionPlane::YZ => (Vec3::NEG_Z, Vec3::Y, Vec3::X),
+        };
+        match is_flipped {
+            true => (-right, up, -toward_viewer),
+            false => (right, up, toward_viewer),
+        }
+    }
+
+    // axis names along drawing right and drawing up, for dimension labels
+    fn axis_names(&self) -> (&'static str, &'static str) {
+        match self {
+            ProjectionPlane::XY => ("X", "Y"),
+            ProjectionPlane::XZ => ("X", "Z"),
+            ProjectionPlane::YZ => ("Z", "Y"),
+        }
+    }
+}
+
+impl Mesh {
+    pub fn to_svg(&self, options: &SvgExportOptions) -> String {
+        let projection = Projection::new(options);
+        let projected: Vec<Vec3> = self
+            .verticies
+            .iter()
+            .map(|&v| projection.apply(v))
+            .collect();
+
+        let (min, max) = projected.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), p| (min.min(p.truncate()), max.max(p.truncate())),
+        );
+        let (min, max) = if projected.is_empty() {
+            (Vec2::ZERO, Vec2::ZERO)
+        } else {
+            (min, max)
+        };
+        let mut view_min = min - Vec2::splat(SVG_MARGIN);
+        let view_max = max + Vec2::splat(SVG_MARGIN);
+        if options.dimension_frame {
+            // room for the dimension lines on the left and bottom
+            view_min.x -= SVG_DIMENSION_OFFSET + SVG_TEXT_SIZE;
+        }
+        let view_max = if options.dimension_frame {
+            view_max + Vec2::new(0.0, SVG_DIMENSION_OFFSET + SVG_TEXT_SIZE)
+        } else {
+            view_max
+        };
+        let size = view_max - view_min;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}mm\" height=\"{}mm\" viewBox=\"{} {} {} {}\">\n",
+            svg_number(size.x),
+            svg_number(size.y),
+            svg_number(view_min.x),
+            svg_number(view_min.y),
+            svg_number(size.x),
+            svg_number(size.y),
+        );
+
+        let triangles = self.projected_triangles(&projected);
+        if options.fill_polys {
+            svg += &self.svg_polys(&projected);
+        }
+
+        svg += &format!(
+            "<g stroke=\"black\" stroke-width=\"{}\" stroke-linecap=\"round\" fill=\"none\">\n",
+            svg_number(SVG_LINE_WIDTH)
+        );
+        let extent = (max - min).max_element().max(1.0);
+        for (a, b) in self.drawn_edges() {
+            let (pa, pb) = (projected[a], projected[b]);
+            if pa.truncate().distance(pb.truncate()) < f32::EPSILON {
+                continue;
+            }
+            let visible = if options.hidden_line_removal {
+                visible_parts(pa, pb, &triangles, extent * HIDDEN_DEPTH_TOLERANCE)
+            } else {
+                vec![(0.0, 1.0)]
+            };
+            for (t0, t1) in visible {
+                let (start, end) = (pa.lerp(pb, t0), pa.lerp(pb, t1));
+                svg += &svg_line(start.truncate(), end.truncate());
+            }
+        }
+        svg += "</g>\n";
+
+        if options.dimension_frame && !projected.is_empty() {
+            svg += &dimension_frame(min, max, options);
+        }
+        svg += "</svg>\n";
+        svg
+    }
+
+    pub fn save_svg(&self, path: &Path, options: &SvgExportOptions) -> io::Result<()> {
+        fs::write(path, self.to_svg(options))
+    }
+
+    // lines plus the poly edges that have no line, each edge once
+    fn drawn_edges(&self) -> Vec<Line> {
+        let mut seen: HashSet<Line> = HashSet::new();
+        self.lines
+            .iter()
+            .copied()
+            .chain(self.polys.iter().flat_map(poly_edges))
+            .filter(|&(a, b)| a != b && seen.insert(edge_key(a, b)))
+            .collect()
+    }
+
+    // polys sorted far to near, front and back facing in different grays
+    fn svg_polys(&self, projected: &[Vec3]) -> String {
+        let sorted = self.polys.iter().sorted_by(|a, b| {
+            let depth =
+                |poly: &Poly| poly.iter().map(|&v| projected[v].z).sum::<f32>() / poly.len() as f32;
+            depth(a).total_cmp(&depth(b))
+        });
+
+        let mut svg = String::from("<g stroke=\"none\">\n");
+        for poly in sorted {
+            let points: Vec<Vec3> = poly.iter().map(|&v| projected[v]).collect();
+            // projected with y down, so counter-clockwise in the drawing has negative area
+            let area: f32 = points
+                .iter()
+                .circular_tuple_windows()
+                .map(|(a, b)| a.x * b.y - b.x * a.y)
+                .sum();
+            if area.abs() < f32::EPSILON {
+                continue;
+            }
+            let fill = if area < 0.0 {
+                SVG_FRONT_FILL
+            } else {
+                SVG_BACK_FILL
+            };
+            let points = points
+                .iter()
+                .map(|p| format!("{},{}", svg_number(p.x), svg_number(p.y)))
+                .join(" ");
+            svg += &format!("<polygon points=\"{}\" fill=\"{}\"/>\n", points, fill);
+        }
+        svg += "</g>\n";
+        svg
+    }
+
+    fn projected_triangles(&self, projected: &[Vec3]) -> Vec<[Vec3; 3]> {
+        self.polys
+            .iter()
+            .flat_map(|poly| {
+                if poly.len() == 3 {
+                    vec![[poly[0], poly[1], poly[2]]]
+                } else {
+                    self.triangulate_loop(poly)
+                }
+            })
+            .map(|triangle| triangle.map(|v| projected[v]))
+            .collect()
+    }
+}
+
+//
+// world -> drawing coords (mm, y down) with z = distance toward the viewer
+//
+struct Projection {
+    right: Vec3,
+    up: Vec3,
+    toward_viewer: Vec3,
+    scale: f32,
+}
+
+impl Projection {
+    fn new(options: &SvgExportOptions) -> Projection {
+        let (right, up, toward_viewer) = options.plane.axes(options.is_flipped);
+        Projection {
+            right,
+            up,
+            toward_viewer,
+            scale: options.scale,
+        }
+    }
+
+    fn apply(&self, v: Vec3) -> Vec3 {
+        Vec3::new(
+            v.dot(self.right) * self.scale,
+            -v.dot(self.up) * self.scale,
+            v.dot(self.toward_viewer) * self.scale,
+        )
+    }
+}
+
+//
+// Hidden-line removal
+//
+
+//
+// parameter ranges (0..1) of the segment not covered by a triangle closer to the viewer
+//
+fn visible_parts(a: Vec3, b: Vec3, triangles: &[[Vec3; 3]], tolerance: f32) -> Vec<(f32, f32)> {
+    let mut hidden: Vec<(f32, f32)> = triangles
+        .iter()
+        .filter_map(|triangle| hidden_part(a, b, triangle, tolerance))
+        .collect();
+    hidden.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    let mut visible = Vec::new();
+    let mut start = 0.0;
+    for (t0, t1) in hidden {
+        if t0 > start {
+            visible.push((start, t0));
+        }
+        start = f32::max(start, t1);
+    }
+    if start < 1.0 {
+        visible.push((start, 1.0));
+    }
+    // slivers left between neighboring triangles aren't worth a stroke
+    visible.retain(|(t0, t1)| (t1 - t0) * a.truncate().distance(b.truncate()) > tolerance);
+    visible
+}
+
+//
+// The range of the segment inside the triangle's outline (clipped against each side) where
+// the triangle's plane is closer to the viewer than the segment.
+//
+fn hidden_part(a: Vec3, b: Vec3, triangle: &[Vec3; 3], tolerance: f32) -> Option<(f32, f32)> {
+    let [p0, p1, p2] = triangle.map(|p| p.truncate());
+    let area = (p1 - p0).perp_dot(p2 - p0);
+    if area.abs() < f32::EPSILON {
+        return None;
+    }
+    let (a2, b2) = (a.truncate(), b.truncate());
+
+    let (mut t0, mut t1) = (0.0f32, 1.0f32);
+    for (from, to) in [(p0, p1), (p1, p2), (p2, p0)] {
+        // positive inside for either winding
+        let inside = |p: Vec2| (to - from).perp_dot(p - from) * area.signum();
+        let (da, db) = (inside(a2), inside(b2));
+        if da < 0.0 && db < 0.0 {
+            return None;
+        }
+        if da < 0.0 {
+            t0 = t0.max(da / (da - db));
+        } else if db < 0.0 {
+            t1 = t1.min(da / (da - db));
+        }
+    }
+    if t1 <= t0 {
+        return None;
+    }
+
+    // depth of the triangle's plane over a point of the drawing (barycentric)
+    let plane_depth = |p: Vec2| {
+        let w1 = (p - p0).perp_dot(p2 - p0) / area;
+        let w2 = (p1 - p0).perp_dot(p - p0) / area;
+        triangle[0].z + (triangle[1].z - triangle[0].z) * w1 + (triangle[2].z - triangle[0].z) * w2
+    };
+    // linear along the segment, so the covered part is where it is positive
+    let in_front = |t: f32| plane_depth(a2.lerp(b2, t)) - a.lerp(b, t).z - tolerance;
+    let (f0, f1) = (in_front(t0), in_front(t1));
+    match (f0 > 0.0, f1 > 0.0) {
+        (true, true) => Some((t0, t1)),
+        (false, false) => None,
+        (true, false) => Some((t0, t0 + (t1 - t0) * f0 / (f0 - f1))),
+        (false, true) => Some((t0 + (t1 - t0) * f0 / (f0 - f1), t1)),
+    }
+}
+
+//
+// Frame + dimensions
+//
+
+fn dimension_frame(min: Vec2, max: Vec2, options: &SvgExportOptions) -> String {
+    let (right_name, up_name) = options.plane.axis_names();
+    let size = (max - min) / options.scale;
+
+    let mut svg = format!(
+        "<g stroke=\"black\" stroke-width=\"{}\" fill=\"none\">\n",
+        svg_number(SVG_FRAME_LINE_WIDTH)
+    );
+    svg += &format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" stroke-dasharray=\"2,1\"/>\n",
+        svg_number(min.x),
+        svg_number(min.y),
+        svg_number(max.x - min.x),
+        svg_number(max.y - min.y)
+    );
+
+    // width below the frame, height to its left
+    let below = max.y + SVG_DIMENSION_OFFSET;
+    let left = min.x - SVG_DIMENSION_OFFSET;
+    svg += &svg_line(Vec2::new(min.x, max.y), Vec2::new(min.x, below));
+    svg += &svg_line(Vec2::new(max.x, max.y), Vec2::new(max.x, below));
+    svg += &dimension_line(Vec2::new(min.x, below), Vec2::new(max.x, below));
+    svg += &svg_line(Vec2::new(min.x, min.y), Vec2::new(left, min.y));
+    svg += &svg_line(Vec2::new(min.x, max.y), Vec2::new(left, max.y));
+    svg += &dimension_line(Vec2::new(left, max.y), Vec2::new(left, min.y));
+    svg += "</g>\n";
+
+    svg += &format!(
+        "<g font-family=\"sans-serif\" font-size=\"{}\" text-anchor=\"middle\" fill=\"black\">\n",
+        svg_number(SVG_TEXT_SIZE)
+    );
+    svg += &format!(
+        "<text x=\"{}\" y=\"{}\">{} {}</text>\n",
+        svg_number((min.x + max.x) / 2.0),
+        svg_number(below + SVG_TEXT_SIZE),
+        right_name,
+        svg_number(size.x)
+    );
+    let text_center = Vec2::new(left - SVG_TEXT_SIZE * 0.5, (min.y + max.y) / 2.0);
+    svg += &format!(
+        "<text x=\"{x}\" y=\"{y}\" transform=\"rotate(-90 {x} {y})\">{} {}</text>\n",
+        up_name,
+        svg_number(size.y),
+        x = svg_number(text_center.x),
+        y = svg_number(text_center.y),
+    );
+    svg += "</g>\n";
+    svg
+}
+
+// line with an arrow head on both ends
+fn dimension_line(start: Vec2, end: Vec2) -> String {
+    let direction = (end - start).normalize_or_zero();
+    let side = direction.perp() * SVG_ARROW_SIZE * 0.5;
+    let arrow = |tip: Vec2, back: Vec2| {
+        let base = tip + back * SVG_ARROW_SIZE;
+        format!(
+            "<polygon points=\"{},{} {},{} {},{}\" fill=\"black\"/>\n",
+            svg_number(tip.x),
+            svg_number(tip.y),
+            svg_number((base + side).x),
+            svg_number((base + side).y),
+            svg_number((base - side).x),
+            svg_number((base - side).y)
+        )
+    };
+    svg_line(start, end) + &arrow(start, direction) + &arrow(end, -direction)
+}
+
+fn svg_line(start: Vec2, end: Vec2) -> String {
+    format!(
+        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n",
+        svg_number(start.x),
+        svg_number(start.y),
+        svg_number(end.x),
+        svg_number(end.y)
+    )
+}
+
+// 3 decimals (a thousandth of a mm) without trailing zeros
+fn svg_number(value: f32) -> String {
+    let text = format!("{:.3}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" | "" => String::from("0"),
+        text => text.to_string(),
+    }
+}
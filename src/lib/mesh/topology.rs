@@ -0,0 +1,273 @@
+//
+// topology -> adjacency index (vert -> polys / lines, edge -> polys) kept in sync with the mesh
+//
+
+use super::*;
+
+#[derive(Clone, Default)]
+pub struct MeshTopology {
+    vert_polys: Vec<Vec<PolyIndex>>,
+    vert_lines: Vec<Vec<LineIndex>>,
+    edge_polys: HashMap<Line, Vec<PolyIndex>>,
+}
+
+//
+// Build + incremental updates (driven by the Mesh edit functions)
+//
+
+impl MeshTopology {
+    pub fn build(vert_count: usize, lines: &[Line], polys: &[Poly]) -> MeshTopology {
+        let mut topology = MeshTopology {
+            vert_polys: vec![Vec::new(); vert_count],
+            vert_lines: vec![Vec::new(); vert_count],
+            edge_polys: HashMap::new(),
+        };
+        for (line_index, line) in lines.iter().enumerate() {
+            topology.insert_line(line_index, *line);
+        }
+        for (poly_index, poly) in polys.iter().enumerate() {
+            topology.insert_poly(poly_index, poly);
+        }
+        topology
+    }
+
+    pub(super) fn insert_vert(&mut self) {
+        self.vert_polys.push(Vec::new());
+        self.vert_lines.push(Vec::new());
+    }
+
+    // vert moved by swap_remove, the removed vert's lines and polys must be gone already
+    pub(super) fn swap_remove_vert(&mut self, vert_index: VertIndex) {
+        self.vert_polys.swap_remove(vert_index);
+        self.vert_lines.swap_remove(vert_index);
+    }
+
+    pub(super) fn insert_line(&mut self, line_index: LineIndex, line: Line) {
+        self.vert_lines[line.0].push(line_index);
+        if line.1 != line.0 {
+            self.vert_lines[line.1].push(line_index);
+        }
+    }
+
+    pub(super) fn remove_line(&mut self, line_index: LineIndex, line: Line) {
+        self.vert_lines[line.0].retain(|&i| i != line_index);
+        self.vert_lines[line.1].retain(|&i| i != line_index);
+    }
+
+    // line moved by swap_remove
+    pub(super) fn rename_line(&mut self, old_index: LineIndex, new_index: LineIndex, line: Line) {
+        for vert_index in [line.0, line.1] {
+            for i in self.vert_lines[vert_index].iter_mut() {
+                if *i == old_index {
+                    *i = new_index;
+                }
+            }
+        }
+    }
+
+    pub(super) fn insert_poly(&mut self, poly_index: PolyIndex, poly: &Poly) {
+        for &vert_index in poly.iter() {
+            self.vert_polys[vert_index].push(poly_index);
+        }
+        for (a, b) in poly_edges(poly) {
+            self.edge_polys
+                .entry(edge_key(a, b))
+                .or_default()
+                .push(poly_index);
+        }
+    }
+
+    pub(super) fn remove_poly(&mut self, poly_index: PolyIndex, poly: &Poly) {
+        for &vert_index in poly.iter() {
+            self.vert_polys[vert_index].retain(|&i| i != poly_index);
+        }
+        for (a, b) in poly_edges(poly) {
+            let key = edge_key(a, b);
+            if let Some(polys) = self.edge_polys.get_mut(&key) {
+                polys.retain(|&i| i != poly_index);
+                if polys.is_empty() {
+                    self.edge_polys.remove(&key);
+                }
+            }
+        }
+    }
+
+    // poly moved by swap_remove
+    pub(super) fn rename_poly(&mut self, old_index: PolyIndex, new_index: PolyIndex, poly: &Poly) {
+        let rename = |indicies: &mut Vec<PolyIndex>| {
+            for i in indicies.iter_mut() {
+                if *i == old_index {
+                    *i = new_index;
+                }
+            }
+        };
+        for &vert_index in poly.iter() {
+            rename(&mut self.vert_polys[vert_index]);
+        }
+        for (a, b) in poly_edges(poly) {
+            if let Some(polys) = self.edge_polys.get_mut(&edge_key(a, b)) {
+                rename(polys);
+            }
+        }
+    }
+
+    pub fn edge_polys_map(&self) -> &HashMap<Line, Vec<PolyIndex>> {
+        &self.edge_polys
+    }
+}
+
+//
+// Adjacency queries
+//
+
+impl Mesh {
+    pub fn topology(&self) -> &MeshTopology {
+        &self.topology
+    }
+
+    //
+    // for bulk edits that change lines / polys directly, which keep the vert and poly
+    // attributes in step themselves (selection set indicies past the end are dropped)
+    //
+    pub(super) fn rebuild_topology(&mut self) {
+        debug_assert_eq!(self.derived_lines.len(), self.lines.len());
+        debug_assert_eq!(self.vert_colors.len(), self.verticies.len());
+        debug_assert_eq!(self.poly_uvs.len(), self.polys.len());
+        debug_assert_eq!(self.poly_colors.len(), self.polys.len());
+        debug_assert_eq!(self.poly_materials.len(), self.polys.len());
+        debug_assert!(
+            self.polys
+                .iter()
+                .zip(self.poly_uvs.iter())
+                .all(|(poly, uvs)| uvs.is_empty() || uvs.len() == poly.len())
+        );
+        self.topology = MeshTopology::build(self.verticies.len(), &self.lines, &self.polys);
+        self.sync_selection_sets();
+    }
+
+    pub fn vert_polys(&self, vert_index: VertIndex) -> &[PolyIndex] {
+        self.topology
+            .vert_polys
+            .get(vert_index)
+            .map_or(&[], |polys| polys.as_slice())
+    }
+
+    pub fn vert_lines(&self, vert_index: VertIndex) -> &[LineIndex] {
+        self.topology
+            .vert_lines
+            .get(vert_index)
+            .map_or(&[], |lines| lines.as_slice())
+    }
+
+    pub fn edge_polys(&self, edge: Line) -> &[PolyIndex] {
+        self.topology
+            .edge_polys
+            .get(&edge_key(edge.0, edge.1))
+            .map_or(&[], |polys| polys.as_slice())
+    }
+
+    //
+    // polys sharing at least one edge with the poly
+    //
+    pub fn poly_neighbors(&self, poly_index: PolyIndex) -> Vec<PolyIndex> {
+        let Some(poly) = self.polys.get(poly_index) else {
+            return Vec::new();
+        };
+        poly_edges(poly)
+            .flat_map(|edge| self.edge_polys(edge).iter().copied())
+            .filter(|&other| other != poly_index)
+            .unique()
+            .collect()
+    }
+
+    //
+    // poly edges around a vert, each oriented away from it
+    //
+    pub fn vert_edges(&self, vert_index: VertIndex) -> Vec<Line> {
+        self.vert_polys(vert_index)
+            .iter()
+            .flat_map(|&poly_index| {
+                let poly = &self.polys[poly_index];
+                let len = poly.len();
+                let i = poly.iter().position(|&v| v == vert_index).unwrap_or(0);
+                [poly[(i + 1) % len], poly[(i + len - 1) % len]]
+            })
+            .unique()
+            .map(|other| (vert_index, other))
+            .collect()
+    }
+
+    //
+    // verts connected to the vert by a poly edge or a line
+    //
+    pub fn one_ring(&self, vert_index: VertIndex) -> Vec<VertIndex> {
+        let poly_neighbors = self.vert_edges(vert_index).into_iter().map(|edge| edge.1);
+        let line_neighbors = self.vert_lines(vert_index).iter().map(|&line_index| {
+            let line = self.lines[line_index];
+            if line.0 == vert_index { line.1 } else { line.0 }
+        });
+        poly_neighbors
+            .chain(line_neighbors)
+            .filter(|&other| other != vert_index)
+            .unique()
+            .collect()
+    }
+
+    pub fn is_boundary_edge(&self, edge: Line) -> bool {
+        self.edge_polys(edge).len() == 1
+    }
+
+    pub fn is_boundary_vert(&self, vert_index: VertIndex) -> bool {
+        self.vert_edges(vert_index)
+            .into_iter()
+            .any(|edge| self.is_boundary_edge(edge))
+    }
+
+    //
+    // edges used by exactly one poly, oriented the way that poly walks them
+    //
+    pub fn boundary_edges(&self) -> Vec<Line> {
+        self.topology
+            .edge_polys
+            .iter()
+            .filter(|(_, polys)| polys.len() == 1)
+            .flat_map(|(edge, polys)| {
+                poly_edges(&self.polys[polys[0]]).find(|e| edge_key(e.0, e.1) == *edge)
+            })
+            .sorted()
+            .collect()
+    }
+
+    //
+    // closed chains of boundary edges, verts listed in the order the owning polys walk them
+    // (chains that don't get back to their start, at non-manifold verts, are left out)
+    //
+    pub fn boundary_loops(&self) -> Vec<Vec<VertIndex>> {
+        let mut next_verts: HashMap<VertIndex, Vec<VertIndex>> = HashMap::new();
+        for (a, b) in self.boundary_edges() {
+            next_verts.entry(a).or_default().push(b);
+        }
+
+        let mut loops = Vec::new();
+        let starts: Vec<VertIndex> = next_verts.keys().copied().sorted().collect();
+        for start in starts {
+            while next_verts.get(&start).is_some_and(|next| !next.is_empty()) {
+                let mut boundary_loop = vec![start];
+                let mut current = start;
+                let mut is_closed = false;
+                while let Some(next) = next_verts.get_mut(&current).and_then(|next| next.pop()) {
+                    if next == start {
+                        is_closed = true;
+                        break;
+                    }
+                    boundary_loop.push(next);
+                    current = next;
+                }
+                if is_closed {
+                    loops.push(boundary_loop);
+                }
+            }
+        }
+        loops
+    }
+}
@@ -0,0 +1,22 @@
+//
+// transform -> moving, rotating and scaling all the verts at once (e.g. baking an object's
+// transform into its mesh)
+//
+
+use super::*;
+
+impl Mesh {
+    //
+    // every vert through the matrix; a mirroring matrix (negative determinant) would turn the
+    // polys inside out, so their winding is flipped to keep the normals pointing the same way
+    //
+    pub fn apply_matrix(&mut self, matrix: Mat4) {
+        for vert in self.verticies.iter_mut() {
+            *vert = matrix.transform_point3(*vert);
+        }
+        if matrix.determinant() < 0.0 {
+            let polys: Vec<PolyIndex> = (0..self.polys.len()).collect();
+            self.flip_polys(&polys);
+        }
+    }
+}
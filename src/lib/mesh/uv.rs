@@ -0,0 +1,222 @@
+//
+// uv -> per-corner texture coordinates of the polys, and the projections that make them
+//
+// Every poly has its own UV per corner (in winding order), so polys sharing a vert can use
+// different UVs there and seams are possible. Polys without UVs have an empty list.
+// v points up (OBJ convention), exporters flip it where the format wants it down.
+//
+
+use super::*;
+use std::f32::consts::TAU;
+
+pub type PolyUvs = Vec<Vec2>;
+
+// radial distance below which a vert is on the cylinder axis and has no angle
+const UV_AXIS_TOLERANCE: f32 = 1e-6;
+
+impl Mesh {
+    // None when the poly has no UVs
+    pub fn poly_uvs(&self, poly_index: PolyIndex) -> Option<&[Vec2]> {
+        self.poly_uvs
+            .get(poly_index)
+            .filter(|uvs| !uvs.is_empty())
+            .map(|uvs| uvs.as_slice())
+    }
+
+    pub fn has_uvs(&self) -> bool {
+        self.poly_uvs.iter().any(|uvs| !uvs.is_empty())
+    }
+
+    // one UV per corner, or none to remove them
+    pub fn set_poly_uvs(&mut self, poly_index: PolyIndex, uvs: PolyUvs) -> Option<()> {
+        let poly = self.polys.get(poly_index)?;
+        if !uvs.is_empty() && uvs.len() != poly.len() {
+            return None;
+        }
+        self.poly_uvs[poly_index] = uvs;
+        Some(())
+    }
+
+    pub fn clear_uvs(&mut self) {
+        for uvs in self.poly_uvs.iter_mut() {
+            uvs.clear();
+        }
+    }
+
+    //
+    // Projections: each sets the UVs of the given polys, scaled together so the largest
+    // extent of the polys spans 0..1 and centered on 0.5
+    //
+
+    //
+    // flat projection along the view axis of a pane, as the pane shows it
+    //
+    pub fn project_uvs_planar(
+        &mut self,
+        poly_indicies: &[PolyIndex],
+        plane: ProjectionPlane,
+        is_flipped: bool,
+    ) {
+        let poly_indicies = self.existing_polys(poly_indicies);
+        let (right, up, _) = plane.axes(is_flipped);
+        let Some((center, size)) = self.uv_bounds(&poly_indicies, right, up) else {
+            return;
+        };
+        for &poly_index in poly_indicies.iter() {
+            let uvs = self.polys[poly_index]
+                .iter()
+                .map(|&v| planar_uv(self.verticies[v], center, size, right, up))
+                .collect();
+            self.poly_uvs[poly_index] = uvs;
+        }
+    }
+
+    //
+    // each poly projected flat along the axis its normal is closest to, from the side it
+    // faces so the texture isn't mirrored
+    //
+    pub fn project_uvs_box(&mut self, poly_indicies: &[PolyIndex]) {
+        let poly_indicies = self.existing_polys(poly_indicies);
+        let Some((center, size)) = self.uv_bounds_3d(&poly_indicies) else {
+            return;
+        };
+        for &poly_index in poly_indicies.iter() {
+            let normal = self.poly_normal(poly_index);
+            let abs = normal.abs();
+            let (plane, component) = if abs.x >= abs.y && abs.x >= abs.z {
+                (ProjectionPlane::YZ, normal.x)
+            } else if abs.y >= abs.z {
+                (ProjectionPlane::XZ, normal.y)
+            } else {
+                (ProjectionPlane::XY, normal.z)
+            };
+            let (right, up, _) = plane.axes(component < 0.0);
+            let uvs = self.polys[poly_index]
+                .iter()
+                .map(|&v| planar_uv(self.verticies[v], center, size, right, up))
+                .collect();
+            self.poly_uvs[poly_index] = uvs;
+        }
+    }
+
+    //
+    // u goes once around the axis (through the center of the polys), v along it. Polys
+    // crossing the seam get u past 1 on the far side instead of wrapping back to 0.
+    //
+    pub fn project_uvs_cylindrical(&mut self, poly_indicies: &[PolyIndex], axis: Axis) {
+        let poly_indicies = self.existing_polys(poly_indicies);
+        let Some((center, _)) = self.uv_bounds_3d(&poly_indicies) else {
+            return;
+        };
+        let axis = axis.unit();
+        let (side, forward) = axis.any_orthonormal_pair();
+        let heights: Vec<f32> = poly_indicies
+            .iter()
+            .flat_map(|&poly_index| self.polys[poly_index].iter())
+            .map(|&v| (self.verticies[v] - center).dot(axis))
+            .collect();
+        let (low, high) = heights
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(low, high), &h| {
+                (low.min(h), high.max(h))
+            });
+        let height = if high - low > f32::EPSILON {
+            high - low
+        } else {
+            1.0
+        };
+
+        for &poly_index in poly_indicies.iter() {
+            let offsets: Vec<Vec3> = self.polys[poly_index]
+                .iter()
+                .map(|&v| self.verticies[v] - center)
+                .collect();
+            let angles: Vec<Option<f32>> = offsets
+                .iter()
+                .map(|&offset| {
+                    let radial = Vec2::new(offset.dot(side), offset.dot(forward));
+                    (radial.length() > UV_AXIS_TOLERANCE)
+                        .then(|| radial.y.atan2(radial.x) / TAU + 0.5)
+                })
+                .collect();
+
+            let mut us: Vec<f32> = angles.iter().flatten().copied().collect();
+            let crosses_seam = us.iter().copied().fold(f32::MIN, f32::max)
+                - us.iter().copied().fold(f32::MAX, f32::min)
+                > 0.5;
+            if crosses_seam {
+                for u in us.iter_mut().filter(|u| **u < 0.5) {
+                    *u += 1.0;
+                }
+            }
+            // verts on the axis take the middle of the poly's other corners
+            let axis_u = match us.is_empty() {
+                true => 0.5,
+                false => us.iter().sum::<f32>() / us.len() as f32,
+            };
+
+            let mut us = us.into_iter();
+            let uvs = angles
+                .iter()
+                .zip(offsets.iter())
+                .map(|(angle, offset)| {
+                    let u = match angle {
+                        Some(_) => us.next().unwrap(),
+                        None => axis_u,
+                    };
+                    Vec2::new(u, (offset.dot(axis) - low) / height)
+                })
+                .collect();
+            self.poly_uvs[poly_index] = uvs;
+        }
+    }
+
+    // each index once, out of range ones dropped
+    fn existing_polys(&self, poly_indicies: &[PolyIndex]) -> Vec<PolyIndex> {
+        poly_indicies
+            .iter()
+            .copied()
+            .filter(|&poly_index| poly_index < self.polys.len())
+            .unique()
+            .collect()
+    }
+
+    // center and largest extent of the polys' verts along right / up
+    fn uv_bounds(&self, poly_indicies: &[PolyIndex], right: Vec3, up: Vec3) -> Option<(Vec3, f32)> {
+        let (center, _) = self.uv_bounds_3d(poly_indicies)?;
+        let (min, max) = poly_indicies
+            .iter()
+            .flat_map(|&poly_index| self.polys[poly_index].iter())
+            .map(|&v| {
+                let offset = self.verticies[v] - center;
+                Vec2::new(offset.dot(right), offset.dot(up))
+            })
+            .fold((Vec2::MAX, Vec2::MIN), |(min, max), p| {
+                (min.min(p), max.max(p))
+            });
+        let center = center + right * (min.x + max.x) / 2.0 + up * (min.y + max.y) / 2.0;
+        Some((center, uv_size((max - min).max_element())))
+    }
+
+    fn uv_bounds_3d(&self, poly_indicies: &[PolyIndex]) -> Option<(Vec3, f32)> {
+        let (min, max) = poly_indicies
+            .iter()
+            .flat_map(|&poly_index| self.polys[poly_index].iter())
+            .map(|&v| self.verticies[v])
+            .fold(None, |bounds: Option<(Vec3, Vec3)>, p| match bounds {
+                Some((min, max)) => Some((min.min(p), max.max(p))),
+                None => Some((p, p)),
+            })?;
+        Some(((min + max) / 2.0, uv_size((max - min).max_element())))
+    }
+}
+
+fn planar_uv(coord: Vec3, center: Vec3, size: f32, right: Vec3, up: Vec3) -> Vec2 {
+    let offset = coord - center;
+    Vec2::new(offset.dot(right), offset.dot(up)) / size + Vec2::splat(0.5)
+}
+
+// polys without extent (a single vert) still get finite UVs
+fn uv_size(extent: f32) -> f32 {
+    if extent > f32::EPSILON { extent } else { 1.0 }
+}
@@ -0,0 +1,275 @@
+//
+// validate -> mesh invariant checks (diagnostics) and safe repairs
+//
+
+use super::*;
+
+const NON_PLANAR_TOLERANCE: f32 = 1e-4;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MeshIssue {
+    DegenerateLine(LineIndex),
+    DuplicateLine {
+        line: LineIndex,
+        duplicate_of: LineIndex,
+    },
+    DuplicatePoly {
+        poly: PolyIndex,
+        duplicate_of: PolyIndex,
+    },
+    NonManifoldEdge {
+        edge: Line,
+        polys: Vec<PolyIndex>,
+    },
+    InconsistentWinding {
+        edge: Line,
+        polys: (PolyIndex, PolyIndex),
+    },
+    NonPlanarPoly {
+        poly: PolyIndex,
+        deviation: f32,
+    },
+    IsolatedVert(VertIndex),
+    NonFiniteVert(VertIndex),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RepairReport {
+    pub removed_lines: usize,
+    pub removed_polys: usize,
+    pub removed_verts: usize,
+    // issues that could not be fixed automatically
+    pub remaining: Vec<MeshIssue>,
+}
+
+impl Mesh {
+    pub fn validate(&self) -> Vec<MeshIssue> {
+        let mut issues = Vec::new();
+        issues.extend(self.find_line_issues());
+        issues.extend(self.find_duplicate_polys());
+        issues.extend(self.find_edge_issues());
+        issues.extend(self.find_non_planar_polys());
+        issues.extend(self.find_vert_issues());
+        issues
+    }
+
+    //
+    // Removes degenerate + duplicate lines, duplicate polys and isolated verts.
+    // Anything that would need a modeling decision is left in the report.
+    //
+    pub fn repair(&mut self) -> RepairReport {
+        let mut report = RepairReport::default();
+        let issues = self.validate();
+
+        let mut lines_to_remove: Vec<LineIndex> = Vec::new();
+        let mut polys_to_remove: Vec<PolyIndex> = Vec::new();
+        let mut verts_to_remove: Vec<VertIndex> = Vec::new();
+
+        for issue in issues {
+            match issue {
+                MeshIssue::DegenerateLine(line) => lines_to_remove.push(line),
+                MeshIssue::DuplicateLine { line, .. } => lines_to_remove.push(line),
+                MeshIssue::DuplicatePoly { poly, .. } => polys_to_remove.push(poly),
+                MeshIssue::IsolatedVert(vert) => verts_to_remove.push(vert),
+                other => report.remaining.push(other),
+            }
+        }
+
+        // remove from the back so swap_remove never moves an index still to be removed
+        lines_to_remove.sort_unstable();
+        lines_to_remove.dedup();
+        for line_index in lines_to_remove.into_iter().rev() {
+            self.remove_line(line_index);
+            report.removed_lines += 1;
+        }
+
+        polys_to_remove.sort_unstable();
+        for poly_index in polys_to_remove.into_iter().rev() {
+            self.remove_poly(poly_index);
+            report.removed_polys += 1;
+        }
+
+        verts_to_remove.sort_unstable();
+        for vert_index in verts_to_remove.into_iter().rev() {
+            self.delete_vert(vert_index);
+            report.removed_verts += 1;
+        }
+
+        // indicies in the remaining issues may have moved, so re-check
+        if report.removed_lines + report.removed_polys + report.removed_verts > 0 {
+            report.remaining = self.validate();
+        }
+        report
+    }
+
+    fn find_line_issues(&self) -> Vec<MeshIssue> {
+        let mut issues = Vec::new();
+        let mut first_seen: HashMap<Line, LineIndex> = HashMap::new();
+
+        for (line_index, line) in self.lines.iter().enumerate() {
+            if line.0 == line.1 {
+                issues.push(MeshIssue::DegenerateLine(line_index));
+                continue;
+            }
+            match first_seen.get(&edge_key(line.0, line.1)) {
+                Some(&duplicate_of) => issues.push(MeshIssue::DuplicateLine {
+                    line: line_index,
+                    duplicate_of,
+                }),
+                None => {
+                    first_seen.insert(edge_key(line.0, line.1), line_index);
+                }
+            }
+        }
+        issues
+    }
+
+    fn find_duplicate_polys(&self) -> Vec<MeshIssue> {
+        let mut issues = Vec::new();
+        let mut first_seen: HashMap<Vec<VertIndex>, PolyIndex> = HashMap::new();
+
+        for (poly_index, poly) in self.polys.iter().enumerate() {
+            let key: Vec<VertIndex> = poly.iter().copied().sorted().collect();
+            match first_seen.get(&key) {
+                Some(&duplicate_of) => issues.push(MeshIssue::DuplicatePoly {
+                    poly: poly_index,
+                    duplicate_of,
+                }),
+                None => {
+                    first_seen.insert(key, poly_index);
+                }
+            }
+        }
+        issues
+    }
+
+    //
+    // non-manifold edges (> 2 polys) and neighbors walking a shared edge the same way
+    //
+    fn find_edge_issues(&self) -> Vec<MeshIssue> {
+        let mut issues = Vec::new();
+        let edge_polys = self.topology.edge_polys_map();
+
+        for (edge, polys) in edge_polys.iter().sorted_by_key(|(edge, _)| **edge) {
+            if polys.len() > 2 {
+                issues.push(MeshIssue::NonManifoldEdge {
+                    edge: *edge,
+                    polys: polys.clone(),
+                });
+            } else if polys.len() == 2 {
+                let first = self.poly_walks_edge(polys[0], *edge);
+                let second = self.poly_walks_edge(polys[1], *edge);
+                if first == second {
+                    issues.push(MeshIssue::InconsistentWinding {
+                        edge: *edge,
+                        polys: (polys[0], polys[1]),
+                    });
+                }
+            }
+        }
+        issues
+    }
+
+    // true if the poly walks the edge as (edge.0 -> edge.1)
+    fn poly_walks_edge(&self, poly_index: PolyIndex, edge: Line) -> bool {
+        poly_edges(&self.polys[poly_index]).any(|e| e == edge)
+    }
+
+    fn find_non_planar_polys(&self) -> Vec<MeshIssue> {
+        self.polys
+            .iter()
+            .enumerate()
+            .filter(|(_, poly)| poly.len() > 3)
+            .filter_map(|(poly_index, poly)| {
+                let deviation = self.poly_planarity_deviation(poly);
+                (deviation > NON_PLANAR_TOLERANCE).then_some(MeshIssue::NonPlanarPoly {
+                    poly: poly_index,
+                    deviation,
+                })
+            })
+            .collect()
+    }
+
+    //
+    // max distance of a poly vert from the plane through the centroid (newell normal)
+    //
+    fn poly_planarity_deviation(&self, poly: &Poly) -> f32 {
+        let normal = self.poly_newell_normal(poly).normalize_or_zero();
+        let centroid = self.poly_centroid(poly);
+        poly.iter()
+            .map(|&i| (self.verticies[i] - centroid).dot(normal).abs())
+            .fold(0.0, f32::max)
+    }
+
+    //
+    // area weighted normal that is robust for non-planar and concave polys
+    //
+    pub fn poly_newell_normal(&self, poly: &Poly) -> Vec3 {
+        poly_edges(poly).fold(Vec3::ZERO, |normal, (a, b)| {
+            let (curr, next) = (self.verticies[a], self.verticies[b]);
+            normal
+                + vec3(
+                    (curr.y - next.y) * (curr.z + next.z),
+                    (curr.z - next.z) * (curr.x + next.x),
+                    (curr.x - next.x) * (curr.y + next.y),
+                )
+        })
+    }
+
+    fn find_vert_issues(&self) -> Vec<MeshIssue> {
+        let mut used = vec![false; self.verticies.len()];
+        for line in self.lines.iter() {
+            used[line.0] = true;
+            used[line.1] = true;
+        }
+        for &vert_index in self.polys.iter().flatten() {
+            used[vert_index] = true;
+        }
+
+        let mut issues = Vec::new();
+        for (vert_index, v) in self.verticies.iter().enumerate() {
+            if !v.is_finite() {
+                issues.push(MeshIssue::NonFiniteVert(vert_index));
+            }
+            if !used[vert_index] {
+                issues.push(MeshIssue::IsolatedVert(vert_index));
+            }
+        }
+        issues
+    }
+}
+
+//
+// Formatting
+//
+
+impl fmt::Display for MeshIssue {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DegenerateLine(line) => write!(formatter, "degenerate line {}", line),
+            Self::DuplicateLine { line, duplicate_of } => {
+                write!(formatter, "line {} duplicates line {}", line, duplicate_of)
+            }
+            Self::DuplicatePoly { poly, duplicate_of } => {
+                write!(formatter, "poly {} duplicates poly {}", poly, duplicate_of)
+            }
+            Self::NonManifoldEdge { edge, polys } => write!(
+                formatter,
+                "edge ({}, {}) is shared by {} polys",
+                edge.0,
+                edge.1,
+                polys.len()
+            ),
+            Self::InconsistentWinding { edge, polys } => write!(
+                formatter,
+                "polys {} and {} wind edge ({}, {}) the same way",
+                polys.0, polys.1, edge.0, edge.1
+            ),
+            Self::NonPlanarPoly { poly, deviation } => {
+                write!(formatter, "poly {} is non-planar ({})", poly, deviation)
+            }
+            Self::IsolatedVert(vert) => write!(formatter, "vert {} is isolated", vert),
+            Self::NonFiniteVert(vert) => write!(formatter, "vert {} is not finite", vert),
+        }
+    }
+}
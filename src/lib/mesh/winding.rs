@@ -0,0 +1,144 @@
+//
+// winding -> poly winding order: flip, make consistent across neighbors, orient outward
+//
+// convention: polys wind counter-clockwise when seen from the side their normal points to,
+// so closed shells should have normals (and positive signed volume) pointing outward
+//
+
+use super::*;
+use std::collections::VecDeque;
+
+impl Mesh {
+    pub fn flip_polys(&mut self, poly_indicies: &[PolyIndex]) {
+        // edge keys and vert membership don't change, so the topology stays valid
+        for &poly_index in poly_indicies.iter().unique() {
+            if poly_index < self.polys.len() {
+                self.reverse_poly(poly_index);
+            }
+        }
+    }
+
+    pub fn poly_normal(&self, poly_index: PolyIndex) -> Vec3 {
+        self.poly_newell_normal(&self.polys[poly_index])
+            .normalize_or_zero()
+    }
+
+    //
+    // groups of polys connected through shared edges
+    //
+    pub fn connected_components(&self) -> Vec<Vec<PolyIndex>> {
+        let mut component_of: Vec<Option<usize>> = vec![None; self.polys.len()];
+        let mut components: Vec<Vec<PolyIndex>> = Vec::new();
+
+        for seed in 0..self.polys.len() {
+            if component_of[seed].is_some() {
+                continue;
+            }
+            let component_index = components.len();
+            let mut component = vec![seed];
+            component_of[seed] = Some(component_index);
+
+            let mut i = 0;
+            while i < component.len() {
+                for neighbor in self.poly_neighbors(component[i]) {
+                    if component_of[neighbor].is_none() {
+                        component_of[neighbor] = Some(component_index);
+                        component.push(neighbor);
+                    }
+                }
+                i += 1;
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    //
+    // Propagates the winding of the lowest poly in each component through its manifold edges
+    // (neighbors must walk a shared edge in opposite directions). Returns the flipped count.
+    //
+    pub fn make_winding_consistent(&mut self) -> usize {
+        let mut visited = vec![false; self.polys.len()];
+        let mut flipped_count = 0;
+
+        for seed in 0..self.polys.len() {
+            if visited[seed] {
+                continue;
+            }
+            visited[seed] = true;
+            let mut queue = VecDeque::from([seed]);
+
+            while let Some(poly_index) = queue.pop_front() {
+                let edges: Vec<Line> = poly_edges(&self.polys[poly_index]).collect();
+                for edge in edges {
+                    let edge_polys = self.edge_polys(edge);
+                    if edge_polys.len() != 2 {
+                        continue;
+                    }
+                    let neighbor = if edge_polys[0] == poly_index {
+                        edge_polys[1]
+                    } else {
+                        edge_polys[0]
+                    };
+                    if visited[neighbor] {
+                        continue;
+                    }
+                    visited[neighbor] = true;
+                    if poly_edges(&self.polys[neighbor]).any(|e| e == edge) {
+                        self.reverse_poly(neighbor);
+                        flipped_count += 1;
+                    }
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        flipped_count
+    }
+
+    //
+    // Makes winding consistent, then flips every closed component with negative signed volume.
+    // Open components can't be oriented by volume and keep the propagated winding.
+    // Returns the number of polys that ended up reversed.
+    //
+    pub fn orient_outward(&mut self) -> usize {
+        let before: Vec<Poly> = self.polys.clone();
+        self.make_winding_consistent();
+
+        for component in self.connected_components() {
+            if self.is_closed_component(&component) && self.signed_volume(&component) < 0.0 {
+                self.flip_polys(&component);
+            }
+        }
+
+        before
+            .iter()
+            .zip(self.polys.iter())
+            .filter(|(old, new)| old != new)
+            .count()
+    }
+
+    pub fn is_closed_component(&self, poly_indicies: &[PolyIndex]) -> bool {
+        poly_indicies
+            .iter()
+            .flat_map(|&poly_index| poly_edges(&self.polys[poly_index]))
+            .all(|edge| self.edge_polys(edge).len() == 2)
+    }
+
+    //
+    // sum of the signed tetrahedron volumes (origin + fan triangle) of the polys,
+    // positive when a closed shell is wound outward
+    //
+    pub fn signed_volume(&self, poly_indicies: &[PolyIndex]) -> f32 {
+        poly_indicies
+            .iter()
+            .flat_map(|&poly_index| {
+                Self::poly_indicies_to_triangle_indicies(&self.polys[poly_index])
+            })
+            .tuples()
+            .map(|(a, b, c)| {
+                let (a, b, c) = (self.verticies[a], self.verticies[b], self.verticies[c]);
+                a.dot(b.cross(c)) / 6.0
+            })
+            .sum()
+    }
+}
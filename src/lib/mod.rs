@@ -1,4 +1,6 @@
+pub mod json;
 pub mod mesh;
+pub mod scene;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,302 @@
+//
+// scene -> named objects, each with its own mesh and transform, one of them active
+//
+// The editor edits the active object's mesh; geometry moves between objects by separating
+// polys into a new object or joining other objects into the active one. A scene always
+// holds at least one object.
+//
+// Each object's transform is kept apart from its verts until it is applied (baked in).
+// Exports either bake every transform into one mesh or keep the objects as glTF nodes.
+//
+
+use crate::mesh::*;
+use macroquad::prelude::{EulerRot, Mat4, Quat, Vec3};
+use std::io;
+use std::path::Path;
+
+mod native;
+
+pub type ObjectIndex = usize;
+
+//
+// Placement of an object's mesh in the scene (the verts stay in object space): scaled
+// first, then rotated, then moved to the location
+//
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub location: Vec3,
+    // euler angles in radians, applied x, y, z (as the free cam rotation)
+    pub rotation: Vec3,
+    pub scale: Vec3,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        location: Vec3::ZERO,
+        rotation: Vec3::ZERO,
+        scale: Vec3::ONE,
+    };
+
+    pub fn from_location(location: Vec3) -> Transform {
+        Transform {
+            location,
+            ..Transform::IDENTITY
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Transform::IDENTITY
+    }
+
+    pub fn quat(&self) -> Quat {
+        Quat::from_euler(
+            EulerRot::XYZ,
+            self.rotation.x,
+            self.rotation.y,
+            self.rotation.z,
+        )
+    }
+
+    // object space -> scene space
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.quat(), self.location)
+    }
+
+    // scene space -> object space
+    pub fn inverse_matrix(&self) -> Mat4 {
+        self.matrix().inverse()
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform::IDENTITY
+    }
+}
+
+#[derive(Clone)]
+pub struct SceneObject {
+    pub name: String,
+    pub mesh: Mesh,
+    pub transform: Transform,
+}
+
+impl SceneObject {
+    pub fn new(name: &str, mesh: Mesh, transform: Transform) -> SceneObject {
+        SceneObject {
+            name: name.to_string(),
+            mesh,
+            transform,
+        }
+    }
+
+    // copy of the mesh with the transform baked in (same vert, line and poly indicies)
+    pub fn baked_mesh(&self) -> Mesh {
+        let mut mesh = self.mesh.clone();
+        if !self.transform.is_identity() {
+            mesh.apply_matrix(self.transform.matrix());
+        }
+        mesh
+    }
+
+    //
+    // bakes the transform into the verts, the object stays where it is with an identity
+    // transform
+    //
+    pub fn apply_transform(&mut self) {
+        self.mesh = self.baked_mesh();
+        self.transform = Transform::IDENTITY;
+    }
+}
+
+#[derive(Clone)]
+pub struct Scene {
+    // never empty, names unique
+    objects: Vec<SceneObject>,
+    active: ObjectIndex,
+}
+
+impl Scene {
+    pub fn with_object(name: &str, mesh: Mesh) -> Scene {
+        Scene {
+            objects: vec![SceneObject::new(name, mesh, Transform::IDENTITY)],
+            active: 0,
+        }
+    }
+
+    pub fn objects(&self) -> &[SceneObject] {
+        &self.objects
+    }
+
+    pub fn object_index(&self, name: &str) -> Option<ObjectIndex> {
+        self.objects.iter().position(|object| object.name == name)
+    }
+
+    pub fn active_index(&self) -> ObjectIndex {
+        self.active
+    }
+
+    pub fn active_object(&self) -> &SceneObject {
+        &self.objects[self.active]
+    }
+
+    pub fn active_object_mut(&mut self) -> &mut SceneObject {
+        &mut self.objects[self.active]
+    }
+
+    pub fn active_mesh(&self) -> &Mesh {
+        &self.objects[self.active].mesh
+    }
+
+    pub fn active_mesh_mut(&mut self) -> &mut Mesh {
+        &mut self.objects[self.active].mesh
+    }
+
+    pub fn set_active(&mut self, index: ObjectIndex) -> Option<()> {
+        if index >= self.objects.len() {
+            return None;
+        }
+        self.active = index;
+        Some(())
+    }
+
+    // the active object after this one, wrapping around (backwards with is_reverse)
+    pub fn cycle_active(&mut self, is_reverse: bool) -> ObjectIndex {
+        let count = self.objects.len();
+        self.active = match is_reverse {
+            true => (self.active + count - 1) % count,
+            false => (self.active + 1) % count,
+        };
+        self.active
+    }
+
+    //
+    // adds the object under the name, or the name with a number after it when the name is
+    // taken; returns its index (the active object stays as it was)
+    //
+    pub fn add_object(&mut self, name: &str, mesh: Mesh, transform: Transform) -> ObjectIndex {
+        let name = self.unique_name(name);
+        self.objects.push(SceneObject::new(&name, mesh, transform));
+        self.objects.len() - 1
+    }
+
+    //
+    // None for the last object left (or one that doesn't exist), the active object moves
+    // along with its index
+    //
+    pub fn remove_object(&mut self, index: ObjectIndex) -> Option<SceneObject> {
+        if index >= self.objects.len() || self.objects.len() == 1 {
+            return None;
+        }
+        let removed = self.objects.remove(index);
+        if self.active > index || self.active == self.objects.len() {
+            self.active -= 1;
+        }
+        Some(removed)
+    }
+
+    // None if the object doesn't exist or another object already has the name
+    pub fn rename_object(&mut self, index: ObjectIndex, name: &str) -> Option<()> {
+        if self.object_index(name).is_some_and(|other| other != index) {
+            return None;
+        }
+        self.objects.get_mut(index)?.name = name.to_string();
+        Some(())
+    }
+
+    //
+    // moves the polys of the active object into a new object in the same place (named after
+    // the active one), returns its index; None when none of the polys exist
+    //
+    pub fn separate_polys(&mut self, poly_indicies: &[PolyIndex]) -> Option<ObjectIndex> {
+        let poly_count = self.active_mesh().polys().len();
+        let poly_indicies: Vec<PolyIndex> = poly_indicies
+            .iter()
+            .copied()
+            .filter(|&poly_index| poly_index < poly_count)
+            .collect();
+        if poly_indicies.is_empty() {
+            return None;
+        }
+        let active = self.active_object_mut();
+        let separated = active.mesh.separate_polys(&poly_indicies);
+        let (name, transform) = (active.name.clone(), active.transform);
+        Some(self.add_object(&name, separated, transform))
+    }
+
+    //
+    // appends the meshes of the objects to the active one where they are in the scene (in
+    // the active object's space) and removes them (the active object itself is skipped);
+    // returns how many were joined
+    //
+    pub fn join_objects(&mut self, indicies: &[ObjectIndex]) -> usize {
+        let active = self.active;
+        let mut joined: Vec<ObjectIndex> = indicies
+            .iter()
+            .copied()
+            .filter(|&index| index != active && index < self.objects.len())
+            .collect();
+        joined.sort_unstable();
+        joined.dedup();
+
+        let to_active = self.objects[active].transform.matrix().inverse();
+        for &index in joined.iter() {
+            let other = &self.objects[index];
+            let mut mesh = other.mesh.clone();
+            mesh.apply_matrix(to_active * other.transform.matrix());
+            self.objects[active].mesh.append(&mesh, Vec3::ZERO);
+        }
+        for &index in joined.iter().rev() {
+            self.remove_object(index);
+        }
+        joined.len()
+    }
+
+    //
+    // every object with its transform baked in, as one mesh (material slots and selection
+    // sets merged by name)
+    //
+    pub fn baked_mesh(&self) -> Mesh {
+        let mut mesh = Mesh::new();
+        for object in self.objects.iter() {
+            mesh.append(&object.baked_mesh(), Vec3::ZERO);
+        }
+        mesh
+    }
+
+    //
+    // Scene graph export: a glTF node per object carrying its transform, the meshes as they
+    // are in object space
+    //
+
+    pub fn gltf_nodes(&self) -> Vec<GltfNode<'_>> {
+        self.objects
+            .iter()
+            .map(|object| GltfNode {
+                translation: object.transform.location,
+                rotation: object.transform.quat(),
+                scale: object.transform.scale,
+                ..GltfNode::new(&object.name, &object.mesh)
+            })
+            .collect()
+    }
+
+    pub fn save_gltf(&self, path: &Path, options: GltfExportOptions) -> io::Result<()> {
+        save_nodes_gltf(&self.gltf_nodes(), path, options)
+    }
+
+    pub fn save_glb(&self, path: &Path, options: GltfExportOptions) -> io::Result<()> {
+        save_nodes_glb(&self.gltf_nodes(), path, options)
+    }
+
+    // the name, or the name with the first free number after it
+    fn unique_name(&self, name: &str) -> String {
+        if self.object_index(name).is_none() {
+            return name.to_string();
+        }
+        (1..)
+            .map(|number| format!("{}.{:03}", name, number))
+            .find(|candidate| self.object_index(candidate).is_none())
+            .unwrap()
+    }
+}
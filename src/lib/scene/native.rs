@@ -0,0 +1,145 @@
+//
+// native -> a whole scene in one file, each object's mesh in the mesh native format
+//
+// {"format": "mesh_editor_scene", "version": 1, "active": 0,
+//  "objects": [{"name": "...", "location": [x, y, z], "rotation": [x, y, z], "scale": [x, y, z],
+//               "mesh": {"format": "mesh_editor", ...}}, ...]}
+//
+// Rotation and scale are left out when they are the identity. A plain mesh document loads as
+// a scene holding just that mesh.
+//
+
+use super::*;
+use crate::json::Json;
+use std::fs;
+use std::path::Path;
+
+const SCENE_FORMAT: &str = "mesh_editor_scene";
+const SCENE_VERSION: usize = 1;
+// name of the object a plain mesh document loads into
+const DEFAULT_OBJECT_NAME: &str = "Object";
+
+impl Scene {
+    pub fn to_native(&self) -> String {
+        let objects = self.objects.iter().map(|object| {
+            let transform = object.transform;
+            let mut entry = Json::object([
+                ("name", Json::string(&object.name)),
+                ("location", vec3_json(transform.location)),
+            ]);
+            if transform.rotation != Transform::IDENTITY.rotation {
+                entry.set("rotation", vec3_json(transform.rotation));
+            }
+            if transform.scale != Transform::IDENTITY.scale {
+                entry.set("scale", vec3_json(transform.scale));
+            }
+            entry.set("mesh", object.mesh.to_native_json());
+            entry
+        });
+        Json::object([
+            ("format", Json::string(SCENE_FORMAT)),
+            ("version", Json::Number(SCENE_VERSION as f64)),
+            ("active", Json::Number(self.active as f64)),
+            ("objects", Json::Array(objects.collect())),
+        ])
+        .to_string()
+    }
+
+    pub fn from_native(text: &str) -> Result<Scene, NativeError> {
+        let document = Json::parse(text).map_err(NativeError::Parse)?;
+        match document.get("format").and_then(Json::as_str) {
+            Some(SCENE_FORMAT) => {}
+            _ if document.get("objects").is_none() => {
+                let mesh = Mesh::from_native_json(&document)?;
+                return Ok(Scene::with_object(DEFAULT_OBJECT_NAME, mesh));
+            }
+            _ => {
+                return Err(NativeError::Format(format!(
+                    "not a {} document",
+                    SCENE_FORMAT
+                )));
+            }
+        }
+        let version = document
+            .get("version")
+            .and_then(Json::as_usize)
+            .ok_or_else(|| invalid("version"))?;
+        if version > SCENE_VERSION {
+            return Err(NativeError::Format(format!(
+                "version {} is newer than {}",
+                version, SCENE_VERSION
+            )));
+        }
+
+        let mut objects: Vec<SceneObject> = Vec::new();
+        let entries = document
+            .get("objects")
+            .and_then(Json::as_array)
+            .ok_or_else(|| invalid("objects"))?;
+        for entry in entries {
+            let name = entry
+                .get("name")
+                .and_then(Json::as_str)
+                .filter(|name| objects.iter().all(|object| object.name != *name))
+                .ok_or_else(|| invalid("objects"))?;
+            let location = entry
+                .get("location")
+                .and_then(parse_vec3)
+                .ok_or_else(|| invalid("location"))?;
+            let optional_vec3 = |field: &str, default: Vec3| match entry.get(field) {
+                Some(value) => parse_vec3(value).ok_or_else(|| invalid(field)),
+                None => Ok(default),
+            };
+            let transform = Transform {
+                location,
+                rotation: optional_vec3("rotation", Transform::IDENTITY.rotation)?,
+                scale: optional_vec3("scale", Transform::IDENTITY.scale)?,
+            };
+            let mesh = Mesh::from_native_json(entry.get("mesh").ok_or_else(|| invalid("mesh"))?)?;
+            objects.push(SceneObject::new(name, mesh, transform));
+        }
+        if objects.is_empty() {
+            return Err(invalid("objects"));
+        }
+        let active = document
+            .get("active")
+            .and_then(Json::as_usize)
+            .filter(|&active| active < objects.len())
+            .ok_or_else(|| invalid("active"))?;
+        Ok(Scene { objects, active })
+    }
+
+    pub fn load_native(path: &Path) -> Result<Scene, NativeError> {
+        let text = fs::read_to_string(path).map_err(|error| NativeError::Io(error.to_string()))?;
+        Scene::from_native(&text)
+    }
+
+    pub fn save_native(&self, path: &Path) -> Result<(), NativeError> {
+        fs::write(path, self.to_native()).map_err(|error| NativeError::Io(error.to_string()))
+    }
+}
+
+// the shortest decimals that read back as the same f32s (as the mesh format writes them)
+fn vec3_json(value: Vec3) -> Json {
+    Json::Array(
+        value
+            .to_array()
+            .map(|c| Json::float(c.to_string().parse().unwrap_or(c as f64)))
+            .to_vec(),
+    )
+}
+
+fn parse_vec3(value: &Json) -> Option<Vec3> {
+    match value.as_array()?.as_slice() {
+        [x, y, z] => Some(Vec3::new(
+            x.as_float()? as f32,
+            y.as_float()? as f32,
+            z.as_float()? as f32,
+        )),
+        _ => None,
+    }
+}
+
+fn invalid(field: &str) -> NativeError {
+    NativeError::Invalid(field.to_string())
+}
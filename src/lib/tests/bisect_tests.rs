@@ -0,0 +1,163 @@
+// Unit tests for cutting a mesh by a plane.
+//
+// Test coverage includes:
+// - Splitting polys and lines that cross the plane (shared cut verts)
+// - Planes running through existing verts and edges
+// - Dropping one side, filling the cut, separating both halves
+// - Concave polys crossing the plane more than once
+
+use crate::mesh::{BisectKeep, LinePolicy, Mesh, Plane};
+use crate::tests::helpers::all_polys;
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    fn x_plane() -> Plane {
+        Plane::new(Vec3::ZERO, coord(1.0, 0.0, 0.0)).unwrap()
+    }
+
+    fn poly_area(mesh: &Mesh, poly_index: usize) -> f32 {
+        mesh.poly_newell_normal(&mesh.polys()[poly_index]).length() / 2.0
+    }
+
+    // ==================== plane tests ====================
+
+    #[test]
+    fn test_plane_containing_line() {
+        let plane = Plane::containing_line(Vec3::ZERO, coord(1.0, 0.0, 0.0), Vec3::Z).unwrap();
+        assert_eq!(plane.normal, coord(0.0, -1.0, 0.0));
+        assert_eq!(plane.signed_distance(coord(5.0, 2.0, 3.0)), -2.0);
+        assert_eq!(Plane::containing_line(Vec3::ZERO, Vec3::Z, Vec3::Z), None);
+    }
+
+    #[test]
+    fn test_plane_needs_a_normal() {
+        assert_eq!(Plane::new(Vec3::ONE, Vec3::ZERO), None);
+        assert_eq!(
+            Plane::new(Vec3::ONE, coord(0.0, 2.0, 0.0)).unwrap().normal,
+            Vec3::Y
+        );
+    }
+
+    // ==================== split tests ====================
+
+    #[test]
+    fn test_bisect_cube_keeps_both_sides() {
+        let mut mesh = Mesh::new_cube();
+        let cut = mesh.bisect(x_plane(), BisectKeep::Both, false);
+
+        assert_eq!(mesh.verts().len(), 12);
+        assert_eq!(mesh.polys().len(), 10);
+        assert_eq!(mesh.lines().len(), 12 + 4 + 4);
+        assert_eq!(cut.len(), 4);
+        assert_eq!(mesh.validate(), vec![]);
+        assert!(mesh.is_closed_component(&all_polys(&mesh)));
+        assert!((mesh.signed_volume(&all_polys(&mesh)) - 8.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_bisect_through_existing_edges() {
+        let mut mesh = Mesh::new_cube();
+        let diagonal = Plane::new(Vec3::ZERO, coord(1.0, 0.0, -1.0)).unwrap();
+        let cut = mesh.bisect(diagonal, BisectKeep::Both, false);
+
+        // only the top and bottom faces are crossed, split along their diagonals
+        assert_eq!(mesh.verts().len(), 8);
+        assert_eq!(mesh.polys().len(), 8);
+        assert_eq!(cut.len(), 4);
+        assert_eq!(mesh.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_bisect_splits_wire_lines() {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(-1.0, 0.0, 0.0));
+        mesh.add_vert(coord(3.0, 0.0, 0.0));
+        mesh.add_line((0, 1));
+
+        mesh.bisect(x_plane(), BisectKeep::Both, false);
+        assert_eq!(mesh.verts()[2], Vec3::ZERO);
+        assert_eq!(mesh.lines(), &vec![(0, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn test_bisect_concave_poly_crossing_twice() {
+        // U shape in the xy plane, cut across both arms
+        let mut mesh = Mesh::new();
+        for (x, y) in [
+            (0.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 2.0),
+            (2.0, 2.0),
+            (2.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 2.0),
+            (0.0, 2.0),
+        ] {
+            mesh.add_vert(coord(x, y, 0.0));
+        }
+        mesh.add_poly((0..8).collect());
+        let plane = Plane::new(coord(0.0, 1.5, 0.0), Vec3::Y).unwrap();
+
+        mesh.bisect(plane, BisectKeep::Both, false);
+        let area: f32 = all_polys(&mesh).iter().map(|&p| poly_area(&mesh, p)).sum();
+        assert!((area - 5.0).abs() < 1e-4);
+        for poly in mesh.polys() {
+            let above = poly.iter().any(|&v| mesh.verts()[v].y > 1.5 + 1e-4);
+            let below = poly.iter().any(|&v| mesh.verts()[v].y < 1.5 - 1e-4);
+            assert!(!(above && below));
+        }
+    }
+
+    // ==================== keep / fill tests ====================
+
+    #[test]
+    fn test_bisect_drops_back_side() {
+        let mut mesh = Mesh::new_cube();
+        mesh.bisect(x_plane(), BisectKeep::Front, false);
+
+        assert_eq!(mesh.verts().len(), 8);
+        assert_eq!(mesh.polys().len(), 5);
+        assert!(mesh.verts().iter().all(|v| v.x >= 0.0));
+        assert_eq!(mesh.boundary_loops().len(), 1);
+        assert_eq!(mesh.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_bisect_drops_front_side_and_fills() {
+        let mut mesh = Mesh::new_cube();
+        mesh.set_line_policy(LinePolicy::Manual);
+        mesh.bisect(x_plane(), BisectKeep::Back, true);
+
+        assert!(mesh.verts().iter().all(|v| v.x <= 0.0));
+        assert_eq!(mesh.polys().len(), 6);
+        assert_eq!(mesh.lines().len(), 12);
+        assert_eq!(mesh.validate(), vec![]);
+        assert!(mesh.is_closed_component(&all_polys(&mesh)));
+        assert!((mesh.signed_volume(&all_polys(&mesh)) - 4.0).abs() < 1e-4);
+        assert_eq!(mesh.orient_outward(), 0);
+    }
+
+    #[test]
+    fn test_bisect_fill_separates_both_halves() {
+        let mut mesh = Mesh::new_uv_sphere(1.0, 8, 4);
+        let cut = mesh.bisect(x_plane(), BisectKeep::Both, true);
+
+        let components = mesh.connected_components();
+        assert_eq!(components.len(), 2);
+        for component in components.iter() {
+            assert!(mesh.is_closed_component(component));
+            assert!(mesh.signed_volume(component) > 0.0);
+        }
+        assert_eq!(mesh.validate(), vec![]);
+        assert_eq!(mesh.orient_outward(), 0);
+        // the cut outline is there once per half
+        assert_eq!(cut.len() % 2, 0);
+    }
+}
@@ -0,0 +1,248 @@
+// Unit tests for vert and poly colors.
+//
+// Test coverage includes:
+// - Setting / painting / clearing colors, out of range indicies
+// - Corner colors (poly color over vert color)
+// - Colors following their verts / polys through delete_vert, remove_poly, merge,
+//   loop cut, bisect, subdivide, decimate, booleans and append
+
+use crate::mesh::{BisectKeep, BooleanOp, DecimateTarget, Mesh, Plane, SubdivisionMode};
+use crate::tests::helpers::all_polys;
+use macroquad::prelude::{BLUE, Color, GREEN, RED, Vec3, YELLOW};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_color_eq(actual: Option<Color>, expected: Color) {
+        let actual = actual.expect("no color");
+        assert!(
+            actual.to_vec().distance(expected.to_vec()) < 1e-5,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_set_paint_and_clear() {
+        let mut mesh = Mesh::new_cube();
+        assert!(!mesh.has_vert_colors() && !mesh.has_poly_colors());
+        assert_eq!(mesh.vert_color(0), None);
+
+        assert_eq!(mesh.set_vert_color(99, Some(RED)), None);
+        assert_eq!(mesh.set_poly_color(99, Some(RED)), None);
+        mesh.set_vert_color(0, Some(RED)).unwrap();
+        assert_eq!(mesh.vert_color(0), Some(RED));
+
+        // duplicates count once, missing indicies are skipped
+        assert_eq!(mesh.paint_verts(&[1, 2, 2, 99], Some(BLUE)), 2);
+        assert_eq!(mesh.paint_polys(&[0, 5], Some(GREEN)), 2);
+        assert_eq!(mesh.poly_color(5), Some(GREEN));
+        assert_eq!(mesh.poly_color(1), None);
+
+        mesh.paint_verts(&[0], None);
+        assert_eq!(mesh.vert_color(0), None);
+
+        mesh.clear_colors();
+        assert!(!mesh.has_vert_colors() && !mesh.has_poly_colors());
+    }
+
+    #[test]
+    fn test_corner_color() {
+        let mut mesh = Mesh::new_cube();
+        // front poly is [0, 3, 2, 1]
+        mesh.set_vert_color(3, Some(RED)).unwrap();
+        assert_eq!(mesh.corner_color(0, 0), None);
+        assert_eq!(mesh.corner_color(0, 1), Some(RED));
+
+        mesh.set_poly_color(0, Some(BLUE)).unwrap();
+        assert_eq!(mesh.corner_color(0, 1), Some(BLUE));
+        assert_eq!(mesh.corner_color(0, 9), Some(BLUE));
+        assert_eq!(mesh.corner_color(9, 0), None);
+    }
+
+    #[test]
+    fn test_colors_follow_delete_vert() {
+        let mut mesh = Mesh::new_cube();
+        mesh.set_vert_color(7, Some(RED)).unwrap();
+        mesh.set_vert_color(1, Some(BLUE)).unwrap();
+        // back poly [5, 6, 7, 4] loses a corner and stays
+        mesh.set_poly_color(1, Some(GREEN)).unwrap();
+
+        // the last vert (7) moves into slot 0
+        mesh.delete_vert(0).unwrap();
+        assert_eq!(mesh.verts().len(), 8 - 1);
+        assert_eq!(mesh.vert_color(0), Some(RED));
+        assert_eq!(mesh.vert_color(1), Some(BLUE));
+        assert_eq!(mesh.vert_color(7), None);
+
+        let back = mesh
+            .polys()
+            .iter()
+            .position(|poly| poly.len() == 4 && poly.contains(&5) && poly.contains(&6))
+            .unwrap();
+        assert_eq!(mesh.poly_color(back), Some(GREEN));
+        let green_count = all_polys(&mesh)
+            .into_iter()
+            .filter(|&poly_index| mesh.poly_color(poly_index).is_some())
+            .count();
+        assert_eq!(green_count, 1);
+    }
+
+    #[test]
+    fn test_colors_follow_remove_poly() {
+        let mut mesh = Mesh::new_cube();
+        mesh.set_poly_color(5, Some(RED)).unwrap();
+        // the last poly moves into slot 0
+        mesh.remove_poly(0).unwrap();
+        assert_eq!(mesh.poly_color(0), Some(RED));
+        assert_eq!(mesh.poly_color(5), None);
+    }
+
+    #[test]
+    fn test_merge_keeps_first_color() {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(Vec3::ZERO);
+        mesh.add_vert(Vec3::new(0.0001, 0.0, 0.0));
+        mesh.add_vert(Vec3::X);
+        mesh.set_vert_color(0, Some(RED)).unwrap();
+        mesh.set_vert_color(1, Some(BLUE)).unwrap();
+        mesh.set_vert_color(2, Some(GREEN)).unwrap();
+
+        assert_eq!(mesh.merge_by_distance(0.001), 1);
+        assert_eq!(mesh.vert_color(0), Some(RED));
+        assert_eq!(mesh.vert_color(1), Some(GREEN));
+    }
+
+    #[test]
+    fn test_loop_cut_colors() {
+        let mut mesh = Mesh::new_cube();
+        let polys = all_polys(&mesh);
+        mesh.paint_polys(&polys, Some(YELLOW));
+        mesh.set_vert_color(0, Some(Color::new(0.0, 0.0, 0.0, 1.0)))
+            .unwrap();
+        mesh.set_vert_color(1, Some(Color::new(1.0, 0.0, 0.0, 1.0)))
+            .unwrap();
+
+        mesh.loop_cut((0, 1), 0.25);
+        assert_eq!(mesh.polys().len(), 10);
+        assert!(
+            all_polys(&mesh)
+                .into_iter()
+                .all(|poly_index| mesh.poly_color(poly_index) == Some(YELLOW))
+        );
+
+        // the cut vert on 0 -> 1 is colored in between, the others have uncolored ends
+        let cut = (8..mesh.verts().len())
+            .find(|&v| {
+                (mesh.verts()[v].y + 1.0).abs() < 1e-5 && (mesh.verts()[v].z + 1.0).abs() < 1e-5
+            })
+            .unwrap();
+        let red = (mesh.verts()[cut].x + 1.0) / 2.0;
+        assert_color_eq(mesh.vert_color(cut), Color::new(red, 0.0, 0.0, 1.0));
+        let colored_count = (8..mesh.verts().len())
+            .filter(|&v| mesh.vert_color(v).is_some())
+            .count();
+        assert_eq!(colored_count, 1);
+    }
+
+    #[test]
+    fn test_bisect_pieces_keep_colors() {
+        let mut mesh = Mesh::new_cube();
+        for poly_index in all_polys(&mesh) {
+            let color = Color::new(poly_index as f32 / 5.0, 0.0, 0.0, 1.0);
+            mesh.set_poly_color(poly_index, Some(color)).unwrap();
+        }
+        let verts: Vec<usize> = (0..8).collect();
+        mesh.paint_verts(&verts, Some(BLUE));
+        let colors_before: Vec<Option<Color>> = all_polys(&mesh)
+            .iter()
+            .map(|&p| mesh.poly_color(p))
+            .collect();
+
+        mesh.bisect(
+            Plane::new(Vec3::ZERO, Vec3::X).unwrap(),
+            BisectKeep::Both,
+            false,
+        );
+        // the 4 side polys split in two, each half colored like the poly it came from
+        assert_eq!(mesh.polys().len(), 10);
+        for poly_index in all_polys(&mesh) {
+            assert!(colors_before.contains(&mesh.poly_color(poly_index)));
+        }
+        // cut verts between two blue verts are blue
+        assert!((0..mesh.verts().len()).all(|v| mesh.vert_color(v) == Some(BLUE)));
+    }
+
+    #[test]
+    fn test_subdivide_colors() {
+        let mut mesh = Mesh::new_cube();
+        mesh.set_poly_color(0, Some(RED)).unwrap();
+        let verts: Vec<usize> = (0..8).collect();
+        mesh.paint_verts(&verts, Some(BLUE));
+
+        mesh.subdivide(SubdivisionMode::Simple, &[]);
+        assert_eq!(mesh.polys().len(), 24);
+        let red_count = all_polys(&mesh)
+            .into_iter()
+            .filter(|&poly_index| mesh.poly_color(poly_index) == Some(RED))
+            .count();
+        assert_eq!(red_count, 4);
+        // edge and face points between blue verts are blue too
+        assert!((0..mesh.verts().len()).all(|v| mesh.vert_color(v) == Some(BLUE)));
+    }
+
+    #[test]
+    fn test_decimate_keeps_poly_colors() {
+        let mut mesh = Mesh::new_cube();
+        mesh.subdivide(SubdivisionMode::Simple, &[]);
+        let polys = all_polys(&mesh);
+        mesh.paint_polys(&polys, Some(GREEN));
+
+        mesh.decimate(DecimateTarget::FaceCount(12), false);
+        assert!(!mesh.polys().is_empty());
+        assert!(
+            all_polys(&mesh)
+                .into_iter()
+                .all(|poly_index| mesh.poly_color(poly_index) == Some(GREEN))
+        );
+        assert_eq!(
+            mesh.verts().len(),
+            mesh.polys().iter().flatten().max().unwrap() + 1
+        );
+    }
+
+    #[test]
+    fn test_boolean_keeps_poly_colors() {
+        let mut first = Mesh::new_cube();
+        let polys = all_polys(&first);
+        first.paint_polys(&polys, Some(RED));
+        let mut second = Mesh::new();
+        second.append(&Mesh::new_cube(), Vec3::splat(1.0));
+        let polys = all_polys(&second);
+        second.paint_polys(&polys, Some(BLUE));
+
+        let result = first.boolean(&second, BooleanOp::Union).unwrap();
+        let colors: Vec<Option<Color>> = all_polys(&result)
+            .iter()
+            .map(|&poly_index| result.poly_color(poly_index))
+            .collect();
+        assert!(colors.contains(&Some(RED)) && colors.contains(&Some(BLUE)));
+        assert!(!colors.contains(&None));
+    }
+
+    #[test]
+    fn test_append_colors() {
+        let mut mesh = Mesh::new_cube();
+        let mut other = Mesh::new_cube();
+        other.set_vert_color(0, Some(RED)).unwrap();
+        other.set_poly_color(0, Some(BLUE)).unwrap();
+
+        mesh.append(&other, Vec3::X * 4.0);
+        assert_eq!(mesh.vert_color(0), None);
+        assert_eq!(mesh.vert_color(8), Some(RED));
+        assert_eq!(mesh.poly_color(0), None);
+        assert_eq!(mesh.poly_color(6), Some(BLUE));
+    }
+}
@@ -0,0 +1,163 @@
+// Unit tests for boolean operations between closed meshes.
+//
+// Test coverage includes:
+// - Union, difference and intersection of overlapping cubes (volume, closed, winding)
+// - Coplanar and touching faces
+// - Curved operands (box minus cylinder)
+// - Open operands are rejected
+// - Booleans between two groups of polys of the same mesh
+
+use crate::mesh::{BooleanOp, CsgError, Mesh};
+use crate::tests::helpers::{all_polys, offset_cube};
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    fn assert_solid(mesh: &Mesh, volume: f32) {
+        let polys = all_polys(mesh);
+        assert!(mesh.is_closed_component(&polys));
+        assert_eq!(mesh.validate(), vec![]);
+        assert!((mesh.signed_volume(&polys) - volume).abs() < 1e-3);
+        assert_eq!(mesh.clone().orient_outward(), 0);
+    }
+
+    // ==================== overlapping cube tests ====================
+
+    #[test]
+    fn test_union_of_overlapping_cubes() {
+        let other = offset_cube(coord(1.0, 0.5, 0.25));
+        let result = Mesh::new_cube().boolean(&other, BooleanOp::Union).unwrap();
+        assert_solid(&result, 13.375);
+    }
+
+    #[test]
+    fn test_difference_of_overlapping_cubes() {
+        let other = offset_cube(coord(1.0, 0.5, 0.25));
+        let result = Mesh::new_cube()
+            .boolean(&other, BooleanOp::Difference)
+            .unwrap();
+        assert_solid(&result, 5.375);
+    }
+
+    #[test]
+    fn test_intersection_of_overlapping_cubes() {
+        let other = offset_cube(coord(1.0, 0.5, 0.25));
+        let result = Mesh::new_cube()
+            .boolean(&other, BooleanOp::Intersection)
+            .unwrap();
+        assert_solid(&result, 2.625);
+    }
+
+    #[test]
+    fn test_boolean_fixes_inside_out_operand() {
+        let mut other = offset_cube(coord(1.0, 0.5, 0.25));
+        let polys = all_polys(&other);
+        other.flip_polys(&polys);
+        let result = Mesh::new_cube().boolean(&other, BooleanOp::Union).unwrap();
+        assert_solid(&result, 13.375);
+    }
+
+    // ==================== coplanar tests ====================
+
+    #[test]
+    fn test_union_with_coplanar_faces() {
+        let other = offset_cube(coord(1.0, 0.0, 0.0));
+        let result = Mesh::new_cube().boolean(&other, BooleanOp::Union).unwrap();
+        assert_solid(&result, 12.0);
+    }
+
+    #[test]
+    fn test_union_of_touching_cubes() {
+        let other = offset_cube(coord(2.0, 0.0, 0.0));
+        let result = Mesh::new_cube().boolean(&other, BooleanOp::Union).unwrap();
+        assert_solid(&result, 16.0);
+    }
+
+    #[test]
+    fn test_difference_with_identical_cube_is_empty() {
+        let result = Mesh::new_cube()
+            .boolean(&Mesh::new_cube(), BooleanOp::Difference)
+            .unwrap();
+        assert!(result.polys().is_empty());
+    }
+
+    // ==================== curved tests ====================
+
+    #[test]
+    fn test_box_minus_cylinder() {
+        let segments = 16;
+        let cylinder = Mesh::new_cylinder(0.5, 4.0, segments);
+        let result = Mesh::new_cube()
+            .boolean(&cylinder, BooleanOp::Difference)
+            .unwrap();
+
+        let angle = std::f32::consts::TAU / segments as f32;
+        let hole_area = 0.5 * segments as f32 * 0.25 * angle.sin();
+        assert_solid(&result, 8.0 - 2.0 * hole_area);
+    }
+
+    // ==================== error tests ====================
+
+    #[test]
+    fn test_open_operands_are_rejected() {
+        let mut open = Mesh::new_cube();
+        open.remove_poly(0);
+
+        assert_eq!(
+            open.boolean(&Mesh::new_cube(), BooleanOp::Union).err(),
+            Some(CsgError::FirstNotClosed)
+        );
+        assert_eq!(
+            Mesh::new_cube().boolean(&open, BooleanOp::Union).err(),
+            Some(CsgError::SecondNotClosed)
+        );
+        assert_eq!(
+            Mesh::new_cube()
+                .boolean(&Mesh::new(), BooleanOp::Union)
+                .err(),
+            Some(CsgError::SecondNotClosed)
+        );
+    }
+
+    // ==================== component tests ====================
+
+    #[test]
+    fn test_boolean_between_components() {
+        let mut mesh = Mesh::new_cube();
+        mesh.append(&Mesh::new_cube(), coord(1.0, 0.5, 0.25));
+        let first: Vec<usize> = (0..6).collect();
+        let second: Vec<usize> = (6..12).collect();
+
+        let new_polys = mesh
+            .boolean_polys(&first, &second, BooleanOp::Union)
+            .unwrap();
+
+        assert_eq!(new_polys, all_polys(&mesh));
+        assert_solid(&mesh, 13.375);
+    }
+
+    #[test]
+    fn test_boolean_between_components_keeps_other_polys() {
+        let mut mesh = Mesh::new_cube();
+        mesh.append(&Mesh::new_cube(), coord(1.0, 0.5, 0.25));
+        mesh.append(&Mesh::new_cube(), coord(5.0, 0.0, 0.0));
+        let first: Vec<usize> = (0..6).collect();
+        let second: Vec<usize> = (6..12).collect();
+
+        let new_polys = mesh
+            .boolean_polys(&first, &second, BooleanOp::Intersection)
+            .unwrap();
+
+        let untouched: Vec<usize> = (0..6).collect();
+        assert!(new_polys.iter().all(|p| !untouched.contains(p)));
+        assert!((mesh.signed_volume(&untouched) - 8.0).abs() < 1e-4);
+        assert!((mesh.signed_volume(&new_polys) - 2.625).abs() < 1e-3);
+        assert_eq!(mesh.verts().len(), 8 + 8);
+    }
+}
@@ -0,0 +1,160 @@
+// Unit tests for quadric error metric decimation.
+//
+// Test coverage includes:
+// - Face count and error threshold targets, resulting stats
+// - Closed meshes stay closed and valid, flat areas collapse for free
+// - Boundaries keep every vert, n-gons are triangulated (and counted as triangles)
+// - Manual lines are kept as they are
+// - UVs kept on the remaining triangles
+// - Bilateral symmetry is kept when respected
+
+use crate::mesh::{DecimateTarget, LinePolicy, Mesh, MirrorMode, ProjectionPlane};
+use crate::tests::helpers::all_polys;
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total_area(mesh: &Mesh) -> f32 {
+        all_polys(mesh)
+            .iter()
+            .map(|&p| mesh.poly_newell_normal(&mesh.polys()[p]).length() / 2.0)
+            .sum()
+    }
+
+    #[test]
+    fn test_decimate_to_face_count() {
+        let mut mesh = Mesh::new_icosphere(1.0, 3);
+        let report = mesh.decimate(DecimateTarget::FaceCount(200), false);
+
+        assert_eq!(report.polys_before, 1280);
+        assert_eq!(report.verts_before, 642);
+        assert!(report.polys_after <= 200 && report.polys_after > 100);
+        assert_eq!(report.polys_after, mesh.polys().len());
+        assert_eq!(report.verts_after, mesh.verts().len());
+        // every collapse on a closed mesh removes two triangles
+        assert_eq!(
+            report.polys_before - report.polys_after,
+            report.collapsed_edges * 2
+        );
+        assert!(report.max_error > 0.0);
+
+        assert_eq!(mesh.validate(), vec![]);
+        assert!(mesh.is_closed_component(&all_polys(&mesh)));
+        // verts settle near the sphere (slightly outside, where the tangent planes meet)
+        assert!(mesh.verts().iter().all(|v| (v.length() - 1.0).abs() < 0.05));
+        assert_eq!(mesh.clone().orient_outward(), 0);
+    }
+
+    #[test]
+    fn test_flat_grid_collapses_for_free_and_keeps_boundary() {
+        let mut mesh = Mesh::new_grid(2.0, 8);
+        let report = mesh.decimate(DecimateTarget::MaxError(1e-6), false);
+
+        assert!(report.max_error < 1e-6);
+        // only the 32 boundary verts remain
+        assert_eq!(mesh.verts().len(), 32);
+        assert_eq!(mesh.boundary_loops()[0].len(), 32);
+        assert!(mesh.verts().iter().all(|v| v.y.abs() < 1e-5));
+        assert!((total_area(&mesh) - 4.0).abs() < 1e-4);
+        assert!(mesh.polys().iter().all(|poly| poly.len() == 3));
+        assert_eq!(mesh.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_uvs_kept_on_remaining_triangles() {
+        let mut mesh = Mesh::new_grid(2.0, 8);
+        mesh.project_uvs_planar(&all_polys(&mesh), ProjectionPlane::XZ, false);
+        let uv_of = |mesh: &Mesh, v: usize| {
+            let poly_index = (0..mesh.polys().len())
+                .find(|&p| mesh.polys()[p].contains(&v))
+                .unwrap();
+            let corner = mesh.polys()[poly_index].iter().position(|&c| c == v);
+            mesh.poly_uvs(poly_index).unwrap()[corner.unwrap()]
+        };
+        let uvs_before: Vec<_> = (0..mesh.verts().len())
+            .map(|v| (mesh.verts()[v], uv_of(&mesh, v)))
+            .collect();
+
+        mesh.decimate(DecimateTarget::MaxError(1e-6), false);
+        assert_eq!(mesh.verts().len(), 32);
+        for (poly_index, poly) in mesh.polys().iter().enumerate() {
+            let uvs = mesh.poly_uvs(poly_index).unwrap();
+            for (&v, uv) in poly.iter().zip(uvs) {
+                let (_, expected) = uvs_before
+                    .iter()
+                    .find(|(coord, _)| *coord == mesh.verts()[v])
+                    .unwrap();
+                assert!(uv.distance(*expected) < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_error_threshold_keeps_curved_surface() {
+        let mut mesh = Mesh::new_uv_sphere(1.0, 12, 6);
+        let vert_count = mesh.verts().len();
+        mesh.decimate(DecimateTarget::MaxError(0.0), false);
+        assert_eq!(mesh.verts().len(), vert_count);
+    }
+
+    #[test]
+    fn test_face_count_already_met_leaves_mesh_alone() {
+        let mut mesh = Mesh::new_cylinder(1.0, 2.0, 8);
+        // 8 quads and 2 octagons triangulate to 28 triangles
+        let report = mesh.decimate(DecimateTarget::FaceCount(28), false);
+        assert_eq!(report.polys_after, 10);
+        assert_eq!(report.collapsed_edges, 0);
+        assert_eq!(mesh.polys()[8].len(), 8);
+    }
+
+    #[test]
+    fn test_face_count_counts_triangles() {
+        let mut mesh = Mesh::new_cylinder(1.0, 2.0, 8);
+        let report = mesh.decimate(DecimateTarget::FaceCount(20), false);
+        assert!(report.polys_after <= 20);
+        assert!(report.collapsed_edges > 0);
+        assert_eq!(mesh.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_manual_lines_are_not_derived() {
+        let mut mesh = Mesh::new_cube();
+        mesh.set_line_policy(LinePolicy::Manual);
+        mesh.decimate(DecimateTarget::MaxError(0.0), false);
+        assert_eq!(mesh.polys().len(), 12);
+        // the triangle diagonals get no lines
+        assert_eq!(mesh.lines().len(), 12);
+    }
+
+    #[test]
+    fn test_ngons_are_triangulated() {
+        let mut mesh = Mesh::new_cylinder(1.0, 2.0, 8);
+        mesh.decimate(DecimateTarget::FaceCount(9), false);
+        assert!(mesh.polys().iter().all(|poly| poly.len() == 3));
+        assert!(mesh.is_closed_component(&all_polys(&mesh)));
+        assert_eq!(mesh.wire_lines(), Vec::<usize>::new());
+        assert_eq!(mesh.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_respects_bilateral_symmetry() {
+        let mut mesh = Mesh::new_icosphere(1.0, 2);
+        mesh.set_mirror_mode(MirrorMode::Bilateral);
+        let report = mesh.decimate(DecimateTarget::FaceCount(120), true);
+        assert!(report.polys_after < 320);
+
+        for v in mesh.verts() {
+            let mirrored = Vec3::new(-v.x, v.y, v.z);
+            assert!(
+                mesh.verts()
+                    .iter()
+                    .any(|other| other.distance(mirrored) < 1e-3),
+                "no mirror partner for {}",
+                v
+            );
+        }
+        assert_eq!(mesh.validate(), vec![]);
+    }
+}
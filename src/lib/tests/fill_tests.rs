@@ -0,0 +1,229 @@
+// Unit tests for hole filling and loop bridging.
+//
+// Test coverage includes:
+// - Filling boundary loops with an n-gon or triangles (winding matches the neighbors)
+// - Ear clipping concave loops drawn with lines
+// - Bridging loops with equal and different vert counts, best twist
+// - Lines for new edges, invalid loops and out-of-range verts
+
+use crate::mesh::{Axis, FillError, FillMode, Mesh};
+use crate::tests::helpers::{all_polys, assert_closed_and_outward};
+use itertools::Itertools;
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    // open tube around the y axis between the two heights
+    fn tube(segments: usize, bottom: f32, top: f32) -> Mesh {
+        let profile = [coord(1.0, top, 0.0), coord(1.0, bottom, 0.0)];
+        Mesh::new_lathe(&profile, false, Axis::Y, segments, false).unwrap()
+    }
+
+    // square loop drawn with lines at height z, listed starting from corner `start`
+    fn add_wire_square(mesh: &mut Mesh, z: f32, start: usize) -> Vec<usize> {
+        let corners = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+        let verts: Vec<usize> = (0..4)
+            .map(|i| {
+                let (x, y) = corners[(i + start) % 4];
+                mesh.add_vert(coord(x, y, z))
+            })
+            .collect();
+        for i in 0..4 {
+            mesh.add_line((verts[i], verts[(i + 1) % 4]));
+        }
+        verts
+    }
+
+    // ==================== fill tests ====================
+
+    #[test]
+    fn test_fill_cube_hole_with_ngon() {
+        let mut mesh = Mesh::new_cube();
+        mesh.remove_poly(0);
+        let hole = mesh.boundary_loops_touching(&[0]);
+        assert_eq!(hole.len(), 1);
+
+        let new_polys = mesh.fill_loop(&hole[0], FillMode::NGon).unwrap();
+        assert_eq!(new_polys, vec![5]);
+        assert_eq!(mesh.lines().len(), 12);
+        assert_closed_and_outward(&mesh);
+    }
+
+    #[test]
+    fn test_fill_tube_ends_with_triangles() {
+        let mut mesh = tube(8, -1.0, 1.0);
+        for hole in mesh.boundary_loops() {
+            let new_polys = mesh.fill_loop(&hole, FillMode::Triangles).unwrap();
+            assert_eq!(new_polys.len(), 6);
+        }
+        assert!(mesh.polys().iter().skip(8).all(|poly| poly.len() == 3));
+        assert_closed_and_outward(&mesh);
+    }
+
+    #[test]
+    fn test_fill_concave_wire_loop() {
+        // L shape, counter-clockwise seen from +z
+        let mut mesh = Mesh::new();
+        for (x, y) in [
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 2.0),
+            (0.0, 2.0),
+        ] {
+            mesh.add_vert(coord(x, y, 0.0));
+        }
+        let loop_verts: Vec<usize> = (0..6).collect();
+
+        let new_polys = mesh.fill_loop(&loop_verts, FillMode::Triangles).unwrap();
+        assert_eq!(new_polys.len(), 4);
+
+        // every triangle faces +z and together they cover exactly the L (area 3)
+        let area: f32 = new_polys
+            .iter()
+            .map(|&poly_index| {
+                let normal = mesh.poly_newell_normal(&mesh.polys()[poly_index]);
+                assert!(normal.z > 0.0);
+                normal.length() / 2.0
+            })
+            .sum();
+        assert!((area - 3.0).abs() < 1e-5);
+        // the boundary plus the 3 diagonals got lines
+        assert_eq!(mesh.lines().len(), 9);
+    }
+
+    #[test]
+    fn test_fill_invalid_loop() {
+        let mut mesh = Mesh::new_cube();
+        assert_eq!(
+            mesh.fill_loop(&[0, 1], FillMode::NGon),
+            Err(FillError::InvalidLoop)
+        );
+        assert_eq!(
+            mesh.fill_loop(&[0, 1, 2, 1], FillMode::NGon),
+            Err(FillError::InvalidLoop)
+        );
+        assert_eq!(
+            mesh.fill_loop(&[0, 1, 99], FillMode::Triangles),
+            Err(FillError::InvalidLoop)
+        );
+    }
+
+    #[test]
+    fn test_fill_empty_mesh() {
+        let mut mesh = Mesh::new();
+        assert_eq!(
+            mesh.fill_loop(&[0, 1, 2], FillMode::NGon),
+            Err(FillError::InvalidLoop)
+        );
+        assert_eq!(mesh.validate(), vec![]);
+    }
+
+    // ==================== bridge tests ====================
+
+    #[test]
+    fn test_bridge_facing_tube_ends() {
+        let mut mesh = tube(8, 1.0, 2.0);
+        mesh.append(&tube(8, -2.0, -1.0), Vec3::ZERO);
+        let inner_loops: Vec<Vec<usize>> = mesh
+            .boundary_loops()
+            .into_iter()
+            .filter(|boundary_loop| mesh.verts()[boundary_loop[0]].y.abs() < 1.5)
+            .collect();
+        assert_eq!(inner_loops.len(), 2);
+
+        let new_polys = mesh.bridge_loops(&inner_loops[0], &inner_loops[1]).unwrap();
+        assert_eq!(new_polys.len(), 8);
+        assert_eq!(mesh.connected_components().len(), 1);
+        // rungs run straight up and down (no twist)
+        for &poly_index in new_polys.iter() {
+            let poly = &mesh.polys()[poly_index];
+            let spot = |v: usize| {
+                let p = mesh.verts()[v] * 1000.0;
+                (p.x.round() as i32, p.z.round() as i32)
+            };
+            assert_eq!(poly.iter().map(|&v| spot(v)).unique().count(), 2);
+        }
+
+        for hole in mesh.boundary_loops() {
+            mesh.fill_loop(&hole, FillMode::NGon).unwrap();
+        }
+        assert_closed_and_outward(&mesh);
+    }
+
+    #[test]
+    fn test_bridge_different_vert_counts() {
+        let mut mesh = tube(8, 1.0, 2.0);
+        mesh.append(&tube(6, -2.0, -1.0), Vec3::ZERO);
+        let loops = mesh.boundary_loops();
+        let top_ring = |boundary_loop: &Vec<usize>| mesh.verts()[boundary_loop[0]].y.abs() < 1.5;
+        let inner: Vec<&Vec<usize>> = loops.iter().filter(|l| top_ring(l)).collect();
+
+        let new_polys = mesh.bridge_loops(inner[0], inner[1]).unwrap();
+        assert_eq!(new_polys.len(), 8);
+        let triangles = new_polys
+            .iter()
+            .filter(|&&p| mesh.polys()[p].len() == 3)
+            .count();
+        assert_eq!(triangles, 2);
+
+        for hole in mesh.boundary_loops() {
+            mesh.fill_loop(&hole, FillMode::Triangles).unwrap();
+        }
+        assert!(mesh.is_closed_component(&all_polys(&mesh)));
+        assert_eq!(mesh.clone().orient_outward(), 0);
+    }
+
+    #[test]
+    fn test_bridge_wire_loops_picks_best_twist() {
+        let mut mesh = Mesh::new();
+        let bottom = add_wire_square(&mut mesh, 0.0, 0);
+        let top = add_wire_square(&mut mesh, 1.0, 2);
+
+        let new_polys = mesh.bridge_loops(&bottom, &top).unwrap();
+        assert_eq!(new_polys.len(), 4);
+        for &poly_index in new_polys.iter() {
+            let poly = &mesh.polys()[poly_index];
+            // vertical walls: each quad spans exactly 2 distinct (x, y) spots
+            let spots = poly
+                .iter()
+                .map(|&v| {
+                    let p = mesh.verts()[v];
+                    ((p.x * 10.0) as i32, (p.y * 10.0) as i32)
+                })
+                .unique()
+                .count();
+            assert_eq!(spots, 2);
+            // and faces away from the middle of the box
+            let normal = mesh.poly_normal(poly_index);
+            let center = mesh.poly_centroid(poly) - coord(0.5, 0.5, 0.5);
+            assert!(normal.dot(center) > 0.0);
+        }
+        // the 8 loop lines plus 4 rungs
+        assert_eq!(mesh.lines().len(), 12);
+    }
+
+    #[test]
+    fn test_bridge_errors() {
+        let mut mesh = Mesh::new_cube();
+        assert_eq!(
+            mesh.bridge_loops(&[0, 1, 2], &[2, 3, 4]),
+            Err(FillError::LoopsShareVerts)
+        );
+        assert_eq!(
+            mesh.bridge_loops(&[0, 1], &[2, 3, 4]),
+            Err(FillError::InvalidLoop)
+        );
+        assert_eq!(
+            mesh.bridge_loops(&[0, 1, 2], &[3, 4, 99]),
+            Err(FillError::InvalidLoop)
+        );
+    }
+}
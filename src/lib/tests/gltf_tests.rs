@@ -0,0 +1,474 @@
+// Unit tests for glTF export.
+//
+// Test coverage includes:
+// - Structural checks of the document (buffer views inside the buffer, aligned offsets,
+//   accessor sizes, min / max matching the data, indicies in range)
+// - Smooth and flat normals, triangulation of quads
+// - Optional lines primitive
+// - UVs as TEXCOORD_0 (v flipped), smooth verts split at UV seams
+// - A primitive per material slot in use, slots as glTF materials
+// - Several meshes as nodes with their placement, materials merged by name
+// - .glb header and chunk layout, .gltf buffer uri
+// - JSON writing / parsing used by the exporter
+
+use crate::json::Json;
+use crate::mesh::{GltfExportOptions, GltfNode, Mesh, ProjectionPlane, nodes_to_gltf};
+use macroquad::prelude::{Color, Quat, Vec3};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMOOTH: GltfExportOptions = GltfExportOptions {
+        flat_normals: false,
+        include_lines: false,
+    };
+    const FLAT_WITH_LINES: GltfExportOptions = GltfExportOptions {
+        flat_normals: true,
+        include_lines: true,
+    };
+
+    fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn number(json: &Json, key: &str) -> usize {
+        json.get(key).and_then(Json::as_usize).unwrap()
+    }
+
+    // values of an accessor as f64, one Vec per element
+    fn accessor_values(document: &Json, buffer: &[u8], accessor: usize) -> Vec<Vec<f64>> {
+        let accessor = document.get("accessors").unwrap().index(accessor).unwrap();
+        let view = document
+            .get("bufferViews")
+            .unwrap()
+            .index(number(accessor, "bufferView"))
+            .unwrap();
+        let components = match accessor.get("type").unwrap().as_str().unwrap() {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            other => panic!("unexpected accessor type {}", other),
+        };
+        let offset = number(view, "byteOffset");
+        let count = number(accessor, "count");
+        assert_eq!(number(view, "byteLength"), count * components * 4);
+        assert_eq!(offset % 4, 0);
+        assert!(offset + number(view, "byteLength") <= buffer.len());
+
+        let is_float = number(accessor, "componentType") == 5126;
+        (0..count)
+            .map(|i| {
+                (0..components)
+                    .map(|c| {
+                        let at = offset + (i * components + c) * 4;
+                        let bytes = buffer[at..at + 4].try_into().unwrap();
+                        match is_float {
+                            true => f32::from_le_bytes(bytes) as f64,
+                            false => u32::from_le_bytes(bytes) as f64,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    //
+    // everything a loader would check before trusting the file, returns the primitives
+    //
+    fn check_structure(document: &Json, buffer: &[u8]) -> Vec<Json> {
+        assert_eq!(
+            document.get("asset").unwrap().get("version"),
+            Some(&Json::string("2.0"))
+        );
+        let buffers = document.get("buffers").unwrap().as_array().unwrap();
+        assert_eq!(number(&buffers[0], "byteLength"), buffer.len());
+
+        let accessors = document.get("accessors").unwrap().as_array().unwrap();
+        for (index, accessor) in accessors.iter().enumerate() {
+            let values = accessor_values(document, buffer, index);
+            let components = values[0].len();
+            for c in 0..components {
+                let min = values.iter().map(|v| v[c]).fold(f64::MAX, f64::min);
+                let max = values.iter().map(|v| v[c]).fold(f64::MIN, f64::max);
+                assert_eq!(
+                    accessor.get("min").unwrap().index(c).unwrap().as_f64(),
+                    Some(min)
+                );
+                assert_eq!(
+                    accessor.get("max").unwrap().index(c).unwrap().as_f64(),
+                    Some(max)
+                );
+            }
+        }
+
+        let mesh = document.get("meshes").unwrap().index(0).unwrap();
+        let primitives = mesh.get("primitives").unwrap().as_array().unwrap().clone();
+        for primitive in primitives.iter() {
+            let attributes = primitive.get("attributes").unwrap();
+            let position_count = number(&accessors[number(attributes, "POSITION")], "count");
+            if let Some(normal) = attributes.get("NORMAL") {
+                let normals = accessor_values(document, buffer, normal.as_usize().unwrap());
+                assert_eq!(normals.len(), position_count);
+                for n in normals {
+                    let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+                    assert!((length - 1.0).abs() < 1e-5);
+                }
+            }
+            let indicies = accessor_values(document, buffer, number(primitive, "indices"));
+            assert!(indicies.iter().all(|i| (i[0] as usize) < position_count));
+        }
+        primitives
+    }
+
+    fn parse_glb(bytes: &[u8]) -> (Json, Vec<u8>) {
+        assert_eq!(&bytes[0..4], b"glTF");
+        assert_eq!(read_u32(bytes, 4), 2);
+        assert_eq!(read_u32(bytes, 8) as usize, bytes.len());
+
+        let json_length = read_u32(bytes, 12) as usize;
+        assert_eq!(&bytes[16..20], b"JSON");
+        assert_eq!(json_length % 4, 0);
+        let json_text = std::str::from_utf8(&bytes[20..20 + json_length]).unwrap();
+        let document = Json::parse(json_text).unwrap();
+
+        let bin_start = 20 + json_length;
+        let bin_length = read_u32(bytes, bin_start) as usize;
+        assert_eq!(&bytes[bin_start + 4..bin_start + 8], b"BIN\0");
+        assert_eq!(bin_start + 8 + bin_length, bytes.len());
+        (document, bytes[bin_start + 8..].to_vec())
+    }
+
+    // ==================== gltf tests ====================
+
+    #[test]
+    fn test_gltf_of_cube_with_smooth_normals() {
+        let (text, buffer) = Mesh::new_cube().to_gltf(SMOOTH, "cube.bin");
+        let document = Json::parse(&text).unwrap();
+        let primitives = check_structure(&document, &buffer);
+
+        assert_eq!(primitives.len(), 1);
+        assert_eq!(number(&primitives[0], "mode"), 4);
+        let indicies = accessor_values(&document, &buffer, number(&primitives[0], "indices"));
+        assert_eq!(indicies.len(), 6 * 2 * 3);
+
+        let position = number(primitives[0].get("attributes").unwrap(), "POSITION");
+        let accessor = document.get("accessors").unwrap().index(position).unwrap();
+        assert_eq!(number(accessor, "count"), 8);
+        assert_eq!(
+            accessor.get("min"),
+            Some(&Json::numbers([-1.0, -1.0, -1.0]))
+        );
+        assert_eq!(accessor.get("max"), Some(&Json::numbers([1.0, 1.0, 1.0])));
+
+        let buffers = document.get("buffers").unwrap();
+        assert_eq!(
+            buffers.index(0).unwrap().get("uri"),
+            Some(&Json::string("cube.bin"))
+        );
+        let scene = document.get("scenes").unwrap().index(0).unwrap();
+        assert_eq!(scene.get("nodes"), Some(&Json::numbers([0.0])));
+        assert_eq!(
+            number(document.get("nodes").unwrap().index(0).unwrap(), "mesh"),
+            0
+        );
+    }
+
+    #[test]
+    fn test_gltf_flat_normals_split_verts() {
+        let (text, buffer) = Mesh::new_cube().to_gltf(FLAT_WITH_LINES, "cube.bin");
+        let document = Json::parse(&text).unwrap();
+        let primitives = check_structure(&document, &buffer);
+
+        let attributes = primitives[0].get("attributes").unwrap();
+        let normals = accessor_values(&document, &buffer, number(attributes, "NORMAL"));
+        assert_eq!(normals.len(), 24);
+        assert!(normals.contains(&vec![0.0, 1.0, 0.0]));
+        assert!(normals.contains(&vec![0.0, 0.0, -1.0]));
+    }
+
+    #[test]
+    fn test_gltf_smooth_normals_point_away_from_center() {
+        let sphere = Mesh::new_uv_sphere(1.0, 12, 8);
+        let (text, buffer) = sphere.to_gltf(SMOOTH, "sphere.bin");
+        let document = Json::parse(&text).unwrap();
+        let primitives = check_structure(&document, &buffer);
+
+        let attributes = primitives[0].get("attributes").unwrap();
+        let positions = accessor_values(&document, &buffer, number(attributes, "POSITION"));
+        let normals = accessor_values(&document, &buffer, number(attributes, "NORMAL"));
+        for (p, n) in positions.iter().zip(normals) {
+            assert!(p[0] * n[0] + p[1] * n[1] + p[2] * n[2] > 0.9);
+        }
+    }
+
+    #[test]
+    fn test_gltf_lines_primitive() {
+        let mut mesh = Mesh::new_cube();
+        let tip = mesh.add_vert(Vec3::new(0.0, 3.0, 0.0));
+        mesh.add_line((3, tip));
+
+        let (text, buffer) = mesh.to_gltf(FLAT_WITH_LINES, "lines.bin");
+        let document = Json::parse(&text).unwrap();
+        let primitives = check_structure(&document, &buffer);
+
+        assert_eq!(primitives.len(), 2);
+        assert_eq!(number(&primitives[1], "mode"), 1);
+        assert_eq!(primitives[1].get("attributes").unwrap().get("NORMAL"), None);
+        let indicies = accessor_values(&document, &buffer, number(&primitives[1], "indices"));
+        assert_eq!(indicies.len(), 13 * 2);
+    }
+
+    #[test]
+    fn test_gltf_of_wire_only_mesh() {
+        let mut mesh = Mesh::new();
+        let a = mesh.add_vert(Vec3::ZERO);
+        let b = mesh.add_vert(Vec3::X);
+        mesh.add_line((a, b));
+
+        let (text, buffer) = mesh.to_gltf(FLAT_WITH_LINES, "wire.bin");
+        let document = Json::parse(&text).unwrap();
+        let primitives = check_structure(&document, &buffer);
+        assert_eq!(primitives.len(), 1);
+        assert_eq!(number(&primitives[0], "mode"), 1);
+
+        let (text, _) = Mesh::new().to_gltf(SMOOTH, "empty.bin");
+        let document = Json::parse(&text).unwrap();
+        assert_eq!(document.get("meshes"), None);
+        assert_eq!(document.get("nodes").unwrap().as_array().unwrap().len(), 1);
+    }
+
+    // ==================== glb tests ====================
+
+    #[test]
+    fn test_glb_layout() {
+        let mesh = Mesh::new_torus(1.0, 0.25, 12, 8);
+        let bytes = mesh.to_glb(FLAT_WITH_LINES);
+        assert_eq!(bytes.len() % 4, 0);
+
+        let (document, buffer) = parse_glb(&bytes);
+        let primitives = check_structure(&document, &buffer);
+        assert_eq!(primitives.len(), 2);
+        let buffers = document.get("buffers").unwrap();
+        assert_eq!(buffers.index(0).unwrap().get("uri"), None);
+    }
+
+    #[test]
+    fn test_glb_matches_gltf() {
+        let mesh = Mesh::new_cone(1.0, 2.0, 10);
+        let (text, gltf_buffer) = mesh.to_gltf(SMOOTH, "cone.bin");
+        let (mut glb_document, glb_buffer) = parse_glb(&mesh.to_glb(SMOOTH));
+
+        let mut buffers = glb_document.get("buffers").unwrap().clone();
+        if let Json::Array(buffers) = &mut buffers {
+            buffers[0].set("uri", Json::string("cone.bin"));
+        }
+        glb_document.set("buffers", buffers);
+        assert_eq!(glb_document, Json::parse(&text).unwrap());
+        assert_eq!(glb_buffer, gltf_buffer);
+    }
+
+    // ==================== json tests ====================
+
+    #[test]
+    fn test_json_round_trip() {
+        let value = Json::object([
+            ("name", Json::string("quote \" slash \\ line\n")),
+            ("values", Json::numbers([0.0, -1.5, 1e-7, 16777216.0])),
+            ("flags", Json::Array(vec![Json::Bool(true), Json::Null])),
+            ("empty", Json::Object(vec![])),
+        ]);
+        assert_eq!(Json::parse(&value.to_string()), Ok(value));
+        assert_eq!(
+            Json::parse(" { \"a\" : [ 1 , \"\\u0041\" ] } "),
+            Ok(Json::object([(
+                "a",
+                Json::Array(vec![Json::Number(1.0), Json::string("A")])
+            )]))
+        );
+        assert!(Json::parse("{\"a\": }").is_err());
+        assert!(Json::parse("[1, 2").is_err());
+        assert!(Json::parse("1 2").is_err());
+    }
+
+    #[test]
+    fn test_uvs_as_texcoords() {
+        let (document, buffer) = Mesh::new_cube().to_gltf(SMOOTH, "mesh.bin");
+        let document = Json::parse(&document).unwrap();
+        let primitives = check_structure(&document, &buffer);
+        assert!(
+            primitives[0]
+                .get("attributes")
+                .unwrap()
+                .get("TEXCOORD_0")
+                .is_none()
+        );
+
+        let mut mesh = Mesh::new_cube();
+        let polys: Vec<usize> = (0..mesh.polys().len()).collect();
+        mesh.project_uvs_planar(&polys, ProjectionPlane::XY, false);
+        for options in [SMOOTH, FLAT_WITH_LINES] {
+            let (document, buffer) = mesh.to_gltf(options, "mesh.bin");
+            let document = Json::parse(&document).unwrap();
+            let primitives = check_structure(&document, &buffer);
+            let attributes = primitives[0].get("attributes").unwrap();
+            let positions = accessor_values(&document, &buffer, number(attributes, "POSITION"));
+            let uvs = accessor_values(&document, &buffer, number(attributes, "TEXCOORD_0"));
+            assert_eq!(uvs.len(), positions.len());
+            // planar XY: u follows x, v (pointing down) follows -y
+            for (position, uv) in positions.iter().zip(uvs.iter()) {
+                assert!((uv[0] - (position[0] + 1.0) / 2.0).abs() < 1e-6);
+                assert!((uv[1] - (1.0 - position[1]) / 2.0).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_primitive_per_material() {
+        let mut mesh = Mesh::new_cube();
+        mesh.add_material("unused");
+        let metal = mesh.add_material("metal");
+        mesh.set_material_color(metal, Color::new(0.5, 0.25, 1.0, 1.0))
+            .unwrap();
+        mesh.assign_material(&[0, 3], Some(metal));
+
+        for options in [SMOOTH, FLAT_WITH_LINES] {
+            let (document, buffer) = mesh.to_gltf(options, "mesh.bin");
+            let document = Json::parse(&document).unwrap();
+            let primitives = check_structure(&document, &buffer);
+            let triangle_primitives: Vec<&Json> = primitives
+                .iter()
+                .filter(|primitive| number(primitive, "mode") == 4)
+                .collect();
+
+            // polys without a material first, empty slots left out
+            assert_eq!(triangle_primitives.len(), 2);
+            assert!(triangle_primitives[0].get("material").is_none());
+            assert_eq!(number(triangle_primitives[1], "material"), metal);
+            let counts: Vec<usize> = triangle_primitives
+                .iter()
+                .map(|primitive| {
+                    accessor_values(&document, &buffer, number(primitive, "indices")).len()
+                })
+                .collect();
+            assert_eq!(counts, vec![4 * 2 * 3, 2 * 2 * 3]);
+            // the vert attributes are shared
+            assert_eq!(
+                triangle_primitives[0].get("attributes"),
+                triangle_primitives[1].get("attributes")
+            );
+
+            let materials = document.get("materials").unwrap().as_array().unwrap();
+            assert_eq!(materials.len(), 2);
+            let material = &materials[metal];
+            assert_eq!(material.get("name"), Some(&Json::string("metal")));
+            assert_eq!(
+                material
+                    .get("pbrMetallicRoughness")
+                    .unwrap()
+                    .get("baseColorFactor"),
+                Some(&Json::numbers([0.5, 0.25, 1.0, 1.0]))
+            );
+        }
+
+        let (document, _) = Mesh::new_cube().to_gltf(SMOOTH, "mesh.bin");
+        assert!(!document.contains("material"));
+    }
+
+    #[test]
+    fn test_nodes_with_placement() {
+        let mut first = Mesh::new_cube();
+        let metal = first.add_material("metal");
+        first.assign_material(&[0], Some(metal));
+        let mut second = Mesh::new_cube();
+        second.add_material("wood");
+        let second_metal = second.add_material("metal");
+        second.assign_material(&[1], Some(second_metal));
+        let empty = Mesh::new();
+
+        let placed = GltfNode {
+            translation: Vec3::new(1.0, 2.0, 3.0),
+            rotation: Quat::from_rotation_y(0.5),
+            scale: Vec3::splat(2.0),
+            ..GltfNode::new("second", &second)
+        };
+        let nodes = [
+            GltfNode::new("first", &first),
+            placed,
+            GltfNode::new("empty", &empty),
+        ];
+        let (document, buffer) = nodes_to_gltf(&nodes, SMOOTH, "scene.bin");
+        let document = Json::parse(&document).unwrap();
+        check_structure(&document, &buffer);
+
+        let scene_nodes = document.get("scenes").unwrap().index(0).unwrap();
+        assert_eq!(
+            scene_nodes.get("nodes"),
+            Some(&Json::numbers([0.0, 1.0, 2.0]))
+        );
+        let node_entries = document.get("nodes").unwrap().as_array().unwrap();
+        // the identity placement is left out, as is the mesh of a node without geometry
+        assert!(node_entries[0].get("translation").is_none());
+        assert!(node_entries[0].get("scale").is_none());
+        assert_eq!(number(&node_entries[1], "mesh"), 1);
+        assert_eq!(
+            node_entries[1].get("translation"),
+            Some(&Json::numbers([1.0, 2.0, 3.0]))
+        );
+        let rotation = Quat::from_rotation_y(0.5).to_array().map(|c| c as f64);
+        assert_eq!(
+            node_entries[1].get("rotation"),
+            Some(&Json::numbers(rotation))
+        );
+        assert_eq!(
+            node_entries[1].get("scale"),
+            Some(&Json::numbers([2.0, 2.0, 2.0]))
+        );
+        assert!(node_entries[2].get("mesh").is_none());
+        assert_eq!(document.get("meshes").unwrap().as_array().unwrap().len(), 2);
+
+        // metal is one material for both meshes
+        let names: Vec<&str> = document
+            .get("materials")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|material| material.get("name").unwrap().as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["metal", "wood"]);
+        let second_primitives = document
+            .get("meshes")
+            .unwrap()
+            .index(1)
+            .unwrap()
+            .get("primitives")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .clone();
+        assert_eq!(number(&second_primitives[1], "material"), 0);
+    }
+
+    #[test]
+    fn test_smooth_verts_split_at_uv_seams() {
+        let mut mesh = Mesh::new_cube();
+        let polys: Vec<usize> = (0..mesh.polys().len()).collect();
+        mesh.project_uvs_box(&polys);
+        let (document, buffer) = mesh.to_gltf(SMOOTH, "mesh.bin");
+        let document = Json::parse(&document).unwrap();
+        let primitives = check_structure(&document, &buffer);
+        let attributes = primitives[0].get("attributes").unwrap();
+        let positions = accessor_values(&document, &buffer, number(attributes, "POSITION"));
+        // more verts than the cube's 8, fewer than one per corner when corners agree
+        assert!(positions.len() > 8 && positions.len() <= 24);
+        let normals = accessor_values(&document, &buffer, number(attributes, "NORMAL"));
+        // split verts keep the smooth normal of the original vert
+        for (position, normal) in positions.iter().zip(normals.iter()) {
+            let expected = 1.0 / 3.0f64.sqrt();
+            for c in 0..3 {
+                assert!((normal[c] - position[c].signum() * expected).abs() < 1e-5);
+            }
+        }
+    }
+}
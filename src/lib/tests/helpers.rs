@@ -0,0 +1,23 @@
+// Helpers shared by the unit tests.
+
+use crate::mesh::{Mesh, PolyIndex};
+use macroquad::prelude::Vec3;
+
+pub fn all_polys(mesh: &Mesh) -> Vec<PolyIndex> {
+    (0..mesh.polys().len()).collect()
+}
+
+// valid, closed, enclosing a positive volume, and no poly needs flipping to face outward
+pub fn assert_closed_and_outward(mesh: &Mesh) {
+    assert_eq!(mesh.validate(), vec![]);
+    assert!(mesh.is_closed_component(&all_polys(mesh)));
+    assert!(mesh.signed_volume(&all_polys(mesh)) > 0.0);
+    assert_eq!(mesh.clone().orient_outward(), 0);
+}
+
+// the unit cube moved by the offset
+pub fn offset_cube(offset: Vec3) -> Mesh {
+    let mut mesh = Mesh::new();
+    mesh.append(&Mesh::new_cube(), offset);
+    mesh
+}
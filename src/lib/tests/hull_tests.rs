@@ -0,0 +1,169 @@
+// Unit tests for convex hull generation.
+//
+// Test coverage includes:
+// - Cube corners plus interior points (coplanar triangles merged into quads)
+// - Closed, outward wound hulls of random-ish point clouds
+// - Hulls of a selected subset of verts, indicies out of range skipped
+// - Flat point sets (a single valid n-gon), collinear / coincident points
+
+use crate::mesh::{HullError, Mesh, MeshIssue};
+use crate::tests::helpers::all_polys;
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    fn cube_corners() -> Vec<Vec3> {
+        Mesh::new_cube().verts().clone()
+    }
+
+    // deterministic scatter of points inside the unit sphere
+    fn scattered_points(count: usize) -> Vec<Vec3> {
+        (0..count)
+            .map(|i| {
+                let t = i as f32;
+                coord((t * 12.9898).sin(), (t * 78.233).sin(), (t * 37.719).sin())
+                    .clamp_length_max(1.0)
+            })
+            .collect()
+    }
+
+    fn assert_convex(hull: &Mesh, points: &[Vec3]) {
+        let polys = all_polys(hull);
+        assert!(hull.is_closed_component(&polys));
+        assert_eq!(hull.validate(), vec![]);
+        assert_eq!(hull.clone().orient_outward(), 0);
+        for poly_index in polys {
+            let normal = hull.poly_normal(poly_index);
+            let corner = hull.verts()[hull.polys()[poly_index][0]];
+            for &p in points {
+                assert!((p - corner).dot(normal) < 1e-4);
+            }
+        }
+    }
+
+    // ==================== solid hull tests ====================
+
+    #[test]
+    fn test_hull_of_cube_corners() {
+        let hull = Mesh::new_convex_hull(&cube_corners()).unwrap();
+
+        assert_eq!(hull.verts().len(), 8);
+        assert_eq!(hull.polys().len(), 6);
+        assert!(hull.polys().iter().all(|poly| poly.len() == 4));
+        assert_eq!(hull.lines().len(), 12);
+        assert!((hull.signed_volume(&all_polys(&hull)) - 8.0).abs() < 1e-4);
+        assert_convex(&hull, &cube_corners());
+    }
+
+    #[test]
+    fn test_hull_ignores_interior_and_face_points() {
+        let mut points = cube_corners();
+        points.extend([
+            Vec3::ZERO,
+            coord(0.5, -0.25, 0.1),
+            coord(1.0, 0.0, 0.0),
+            coord(0.0, 1.0, 0.5),
+        ]);
+        let hull = Mesh::new_convex_hull(&points).unwrap();
+
+        assert_eq!(hull.verts().len(), 8);
+        assert_eq!(hull.polys().len(), 6);
+        assert_convex(&hull, &points);
+    }
+
+    #[test]
+    fn test_hull_of_scattered_points() {
+        let points = scattered_points(200);
+        let hull = Mesh::new_convex_hull(&points).unwrap();
+
+        assert!(hull.verts().len() < points.len());
+        assert!(hull.signed_volume(&all_polys(&hull)) > 0.0);
+        assert_convex(&hull, &points);
+    }
+
+    #[test]
+    fn test_hull_of_sphere_verts() {
+        let sphere = Mesh::new_uv_sphere(1.0, 12, 8);
+        let hull = Mesh::new_convex_hull(sphere.verts()).unwrap();
+
+        assert_eq!(hull.verts().len(), sphere.verts().len());
+        assert_convex(&hull, sphere.verts());
+    }
+
+    #[test]
+    fn test_hull_of_selected_verts() {
+        let mut mesh = Mesh::new_cube();
+        mesh.append(&Mesh::new_cube(), coord(4.0, 0.0, 0.0));
+        let verts: Vec<usize> = (0..4).chain(12..16).collect();
+
+        let hull = mesh.convex_hull(&verts).unwrap();
+
+        let points: Vec<Vec3> = verts.iter().map(|&v| mesh.verts()[v]).collect();
+        assert_eq!(hull.verts().len(), 8);
+        assert_convex(&hull, &points);
+    }
+
+    #[test]
+    fn test_hull_skips_verts_out_of_range() {
+        let mesh = Mesh::new_cube();
+        let hull = mesh.convex_hull(&[0, 1, 2, 3, 4, 5, 6, 7, 99]).unwrap();
+        assert_eq!(hull.verts().len(), 8);
+        assert_eq!(
+            mesh.convex_hull(&[0, 1, 99]).err(),
+            Some(HullError::NotEnoughPoints)
+        );
+    }
+
+    // ==================== degenerate tests ====================
+
+    #[test]
+    fn test_hull_of_flat_points_is_one_ngon() {
+        let points = vec![
+            coord(0.0, 0.0, 0.0),
+            coord(2.0, 0.0, 0.0),
+            coord(2.0, 0.0, 2.0),
+            coord(0.0, 0.0, 2.0),
+            coord(1.0, 0.0, 1.0),
+            coord(1.0, 0.0, 0.0),
+        ];
+        let hull = Mesh::new_convex_hull(&points).unwrap();
+
+        assert_eq!(hull.verts().len(), 4);
+        assert_eq!(hull.polys().len(), 1);
+        assert_eq!(hull.polys()[0].len(), 4);
+        assert_eq!(hull.poly_normal(0).abs(), coord(0.0, 1.0, 0.0));
+        assert_eq!(hull.lines().len(), 4);
+        assert_eq!(hull.validate(), Vec::<MeshIssue>::new());
+        let mut repaired = hull.clone();
+        repaired.repair();
+        assert_eq!(repaired.polys(), hull.polys());
+    }
+
+    #[test]
+    fn test_hull_of_collinear_points() {
+        let points: Vec<Vec3> = (0..5).map(|i| coord(i as f32, i as f32, 0.0)).collect();
+        assert_eq!(
+            Mesh::new_convex_hull(&points).err(),
+            Some(HullError::Collinear)
+        );
+    }
+
+    #[test]
+    fn test_hull_of_too_few_points() {
+        let same = vec![coord(1.0, 1.0, 1.0); 5];
+        assert_eq!(
+            Mesh::new_convex_hull(&same).err(),
+            Some(HullError::NotEnoughPoints)
+        );
+        assert_eq!(
+            Mesh::new_convex_hull(&same[..2]).err(),
+            Some(HullError::NotEnoughPoints)
+        );
+    }
+}
@@ -0,0 +1,156 @@
+// Unit tests for the lathe (revolve) operation.
+//
+// Test coverage includes:
+// - Ordering selected verts into open / closed chains, rejecting branches and gaps
+// - Revolving profiles with poles on the axis (welded) and capped / uncapped ends
+// - Closed results are valid and face outward whichever way the profile runs
+// - Closed profiles (torus) and profiles lying on the axis
+
+use crate::mesh::{Axis, LatheError, Mesh, VertChain};
+use crate::tests::helpers::assert_closed_and_outward;
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    // polyline drawn in the xy plane: 0 - 1 - 2 - 3
+    fn profile_mesh() -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(1.0, 1.0, 0.0)); // 0
+        mesh.add_vert(coord(0.0, 2.0, 0.0)); // 1 (on the y axis)
+        mesh.add_vert(coord(1.0, -1.0, 0.0)); // 2
+        mesh.add_vert(coord(0.0, -2.0, 0.0)); // 3 (on the y axis)
+        mesh.add_line((1, 0));
+        mesh.add_line((0, 2));
+        mesh.add_line((3, 2));
+        mesh
+    }
+
+    // ==================== chain tests ====================
+
+    #[test]
+    fn test_vert_chain_orders_open_chain_from_an_end() {
+        let mesh = profile_mesh();
+        let chain = mesh.vert_chain(&[2, 0, 3, 1]).unwrap();
+        assert_eq!(
+            chain,
+            VertChain {
+                verts: vec![1, 0, 2, 3],
+                is_closed: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_vert_chain_detects_closed_chain() {
+        let mut mesh = profile_mesh();
+        mesh.add_line((1, 3));
+        let chain = mesh.vert_chain(&[0, 1, 2, 3]).unwrap();
+        assert!(chain.is_closed);
+        assert_eq!(chain.verts, vec![0, 1, 3, 2]);
+    }
+
+    #[test]
+    fn test_vert_chain_rejects_branches_and_gaps() {
+        let mut mesh = profile_mesh();
+        assert_eq!(mesh.vert_chain(&[0, 1, 3]), Err(LatheError::NotAChain));
+        assert_eq!(mesh.vert_chain(&[0]), Err(LatheError::NotAChain));
+
+        let branch = mesh.add_vert(coord(2.0, 1.0, 0.0));
+        mesh.add_line((0, branch));
+        assert_eq!(
+            mesh.vert_chain(&[0, 1, 2, 3, branch]),
+            Err(LatheError::NotAChain)
+        );
+    }
+
+    // ==================== revolve tests ====================
+
+    #[test]
+    fn test_lathe_welds_poles() {
+        let mesh = profile_mesh();
+        let chain = mesh.vert_chain(&[0, 1, 2, 3]).unwrap();
+        let lathed = mesh.lathe(&chain, Axis::Y, 8, false).unwrap();
+
+        // two poles + two rings of 8
+        assert_eq!(lathed.verts().len(), 2 + 2 * 8);
+        // triangle fans at the poles, one quad strip between the rings
+        assert_eq!(lathed.polys().len(), 8 * 3);
+        assert_eq!(lathed.polys().iter().filter(|p| p.len() == 3).count(), 16);
+        assert_closed_and_outward(&lathed);
+    }
+
+    #[test]
+    fn test_lathe_faces_outward_for_either_profile_direction() {
+        let profile = [
+            coord(0.0, 1.0, 0.0),
+            coord(1.0, 0.0, 0.0),
+            coord(0.0, -1.0, 0.0),
+        ];
+        let reversed: Vec<Vec3> = profile.iter().rev().copied().collect();
+
+        for profile in [profile.to_vec(), reversed] {
+            let lathed = Mesh::new_lathe(&profile, false, Axis::Y, 6, false).unwrap();
+            assert_closed_and_outward(&lathed);
+        }
+    }
+
+    #[test]
+    fn test_lathe_open_ends_with_and_without_caps() {
+        let profile = [coord(1.0, 0.0, 1.0), coord(1.0, 0.0, -1.0)];
+
+        let tube = Mesh::new_lathe(&profile, false, Axis::Z, 6, false).unwrap();
+        assert_eq!(tube.polys().len(), 6);
+        assert_eq!(tube.boundary_loops().len(), 2);
+
+        let capped = Mesh::new_lathe(&profile, false, Axis::Z, 6, true).unwrap();
+        assert_eq!(capped.polys().len(), 8);
+        assert_closed_and_outward(&capped);
+    }
+
+    #[test]
+    fn test_lathe_closed_profile_makes_torus() {
+        let profile = [
+            coord(2.0, 0.5, 0.0),
+            coord(1.5, 0.0, 0.0),
+            coord(2.0, -0.5, 0.0),
+            coord(2.5, 0.0, 0.0),
+        ];
+        let lathed = Mesh::new_lathe(&profile, true, Axis::Y, 12, true).unwrap();
+        assert_eq!(lathed.verts().len(), 4 * 12);
+        assert_eq!(lathed.polys().len(), 4 * 12);
+        assert_closed_and_outward(&lathed);
+    }
+
+    #[test]
+    fn test_lathe_rotates_around_chosen_axis() {
+        let profile = [coord(1.0, 1.0, 0.0), coord(1.0, 1.0, 1.0)];
+        let lathed = Mesh::new_lathe(&profile, false, Axis::X, 4, false).unwrap();
+        // every vert keeps its x and its distance from the x axis
+        for v in lathed.verts() {
+            assert!((v.x - 1.0).abs() < 1e-5);
+        }
+        assert!((lathed.verts()[1] - coord(1.0, 0.0, 1.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_lathe_profile_on_axis_fails() {
+        let profile = [coord(0.0, 1.0, 0.0), coord(0.0, -1.0, 0.0)];
+        assert_eq!(
+            Mesh::new_lathe(&profile, false, Axis::Y, 8, true).err(),
+            Some(LatheError::ProfileOnAxis)
+        );
+    }
+
+    #[test]
+    fn test_lathe_clamps_steps() {
+        let profile = [coord(1.0, 1.0, 0.0), coord(1.0, -1.0, 0.0)];
+        let lathed = Mesh::new_lathe(&profile, false, Axis::Y, 1, false).unwrap();
+        assert_eq!(lathed.verts().len(), 2 * 3);
+    }
+}
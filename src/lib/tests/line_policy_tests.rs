@@ -0,0 +1,131 @@
+// Unit tests for deriving edge lines from polys.
+//
+// Test coverage includes:
+// - Manual policy keeps the existing explicit behavior
+// - DeriveFromPolys: lines added with polys, no duplicates, existing polys synced on switch
+// - Cleanup of unused edge lines on remove_poly / delete_vert, wire lines and lines drawn
+//   by hand on a poly edge kept
+
+use crate::mesh::{LinePolicy, Mesh};
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    fn derived_mesh() -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.set_line_policy(LinePolicy::DeriveFromPolys);
+        mesh.add_vert(coord(0.0, 0.0, 0.0)); // 0
+        mesh.add_vert(coord(1.0, 0.0, 0.0)); // 1
+        mesh.add_vert(coord(1.0, 1.0, 0.0)); // 2
+        mesh.add_vert(coord(0.0, 1.0, 0.0)); // 3
+        mesh.add_vert(coord(2.0, 0.5, 0.0)); // 4
+        mesh
+    }
+
+    #[test]
+    fn test_manual_policy_is_default() {
+        let mut mesh = Mesh::new();
+        assert_eq!(mesh.line_policy(), LinePolicy::Manual);
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 0.0, 0.0));
+        mesh.add_vert(coord(0.0, 1.0, 0.0));
+        mesh.add_poly(vec![0, 1, 2]);
+        assert!(mesh.lines().is_empty());
+    }
+
+    #[test]
+    fn test_cube_lines_are_derived() {
+        let mesh = Mesh::new_cube();
+        assert_eq!(mesh.line_policy(), LinePolicy::DeriveFromPolys);
+        assert_eq!(mesh.lines().len(), 12);
+        assert!(mesh.wire_lines().is_empty());
+    }
+
+    #[test]
+    fn test_add_poly_adds_missing_lines_only() {
+        let mut mesh = derived_mesh();
+        mesh.add_poly(vec![0, 1, 2, 3]);
+        assert_eq!(mesh.lines().len(), 4);
+        mesh.add_poly(vec![1, 4, 2]);
+        assert_eq!(mesh.lines().len(), 6);
+    }
+
+    #[test]
+    fn test_switching_policy_syncs_existing_polys() {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 0.0, 0.0));
+        mesh.add_vert(coord(0.0, 1.0, 0.0));
+        mesh.add_line((1, 0));
+        mesh.add_poly(vec![0, 1, 2]);
+
+        mesh.set_line_policy(LinePolicy::DeriveFromPolys);
+        assert_eq!(mesh.lines().len(), 3);
+        assert!(mesh.find_line((0, 1)).is_some());
+    }
+
+    #[test]
+    fn test_remove_poly_cleans_up_unused_lines() {
+        let mut mesh = derived_mesh();
+        mesh.add_poly(vec![0, 1, 2, 3]);
+        mesh.add_poly(vec![1, 4, 2]);
+
+        mesh.remove_poly(1);
+        // the shared edge 1 - 2 stays with the quad
+        assert_eq!(mesh.lines().len(), 4);
+        assert!(mesh.find_line((1, 2)).is_some());
+        assert!(mesh.find_line((1, 4)).is_none());
+    }
+
+    #[test]
+    fn test_remove_poly_keeps_wire_lines() {
+        let mut mesh = derived_mesh();
+        mesh.add_line((3, 4));
+        mesh.add_poly(vec![0, 1, 2, 3]);
+
+        mesh.remove_poly(0);
+        assert_eq!(mesh.lines(), &vec![(3, 4)]);
+        assert_eq!(mesh.wire_lines(), vec![0]);
+    }
+
+    #[test]
+    fn test_remove_poly_keeps_drawn_lines_on_its_edges() {
+        let mut mesh = derived_mesh();
+        mesh.add_line((1, 0));
+        mesh.add_poly(vec![0, 1, 2, 3]);
+        assert_eq!(mesh.lines().len(), 4);
+
+        // the line drawn before the poly is not one of its derived edges
+        mesh.remove_poly(0);
+        assert_eq!(mesh.lines(), &vec![(1, 0)]);
+        assert_eq!(mesh.wire_lines(), vec![0]);
+    }
+
+    #[test]
+    fn test_delete_vert_rederives_lines() {
+        let mut mesh = derived_mesh();
+        mesh.add_poly(vec![0, 1, 2, 3]);
+
+        // quad -> triangle 0, 1, 2 (vert 4 swaps into slot 3)
+        mesh.delete_vert(3);
+        assert_eq!(mesh.polys(), &vec![vec![0, 1, 2]]);
+        assert_eq!(mesh.lines().len(), 3);
+        assert!(mesh.find_line((2, 0)).is_some());
+    }
+
+    #[test]
+    fn test_delete_vert_removes_lines_of_collapsed_poly() {
+        let mut mesh = derived_mesh();
+        mesh.add_poly(vec![0, 1, 4]);
+
+        mesh.delete_vert(0);
+        assert!(mesh.polys().is_empty());
+        assert!(mesh.lines().is_empty());
+    }
+}
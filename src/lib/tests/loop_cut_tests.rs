@@ -0,0 +1,157 @@
+// Unit tests for edge ring / edge loop traversal and loop cuts.
+//
+// Test coverage includes:
+// - Edge rings around closed quad strips (cube) and open strips (grid)
+// - Edge loops through regular verts, stopping at irregular ones
+// - Loop cut: new verts, split polys, rebuilt lines, bordering n-gons
+
+use crate::mesh::{Line, Mesh, edge_key};
+use macroquad::prelude::Vec3;
+use std::collections::HashSet;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    fn edge_set(edges: &[Line]) -> HashSet<Line> {
+        edges.iter().map(|e| edge_key(e.0, e.1)).collect()
+    }
+
+    // 3 x 3 grid of quads in the xy plane, verts indexed row major (4 per row)
+    fn grid_mesh() -> Mesh {
+        let mut mesh = Mesh::new();
+        for y in 0..4 {
+            for x in 0..4 {
+                mesh.add_vert(coord(x as f32, y as f32, 0.0));
+            }
+        }
+        for y in 0..3 {
+            for x in 0..3 {
+                let i = y * 4 + x;
+                mesh.add_poly(vec![i, i + 1, i + 5, i + 4]);
+                mesh.add_line((i, i + 1));
+                mesh.add_line((i, i + 4));
+            }
+            mesh.add_line((y * 4 + 3, y * 4 + 7));
+        }
+        for x in 0..3 {
+            mesh.add_line((12 + x, 13 + x));
+        }
+        mesh
+    }
+
+    // ==================== edge ring tests ====================
+
+    #[test]
+    fn test_cube_edge_ring_is_closed() {
+        let mesh = Mesh::new_cube();
+        let ring = mesh.edge_ring((0, 1));
+        // front bottom -> bottom back -> back top -> front top
+        assert_eq!(edge_set(&ring), edge_set(&[(0, 1), (4, 5), (6, 7), (2, 3)]));
+    }
+
+    #[test]
+    fn test_grid_edge_ring_is_open() {
+        let mesh = grid_mesh();
+        // vertical edge in the middle row crosses all three quads of that row
+        let ring = mesh.edge_ring((5, 9));
+        assert_eq!(
+            edge_set(&ring),
+            edge_set(&[(4, 8), (5, 9), (6, 10), (7, 11)])
+        );
+    }
+
+    #[test]
+    fn test_ring_edges_keep_orientation() {
+        let mesh = grid_mesh();
+        let ring = mesh.edge_ring((5, 9));
+        for (a, b) in ring {
+            assert_eq!(b, a + 4);
+        }
+    }
+
+    #[test]
+    fn test_select_edge_ring_returns_line_indicies() {
+        let mesh = grid_mesh();
+        let lines = mesh.select_edge_ring((5, 9));
+        assert_eq!(lines.len(), 4);
+    }
+
+    // ==================== edge loop tests ====================
+
+    #[test]
+    fn test_grid_edge_loop_stops_at_boundary() {
+        let mesh = grid_mesh();
+        let edge_loop = mesh.edge_loop((5, 6));
+        // 5 and 6 are interior, 4 and 7 are boundary verts
+        assert_eq!(edge_set(&edge_loop), edge_set(&[(4, 5), (5, 6), (6, 7)]));
+    }
+
+    #[test]
+    fn test_cube_edge_loop_stops_at_valence_three() {
+        let mesh = Mesh::new_cube();
+        assert_eq!(mesh.edge_loop((0, 1)), vec![(0, 1)]);
+    }
+
+    // ==================== loop cut tests ====================
+
+    #[test]
+    fn test_loop_cut_cube() {
+        let mut mesh = Mesh::new_cube();
+        let new_lines = mesh.loop_cut((0, 1), 0.5);
+
+        assert_eq!(new_lines.len(), 4);
+        assert_eq!(mesh.verts().len(), 12);
+        assert_eq!(mesh.polys().len(), 10);
+        // 12 original edges + 4 from splitting + 4 new loop lines
+        assert_eq!(mesh.lines().len(), 20);
+
+        for &line_index in new_lines.iter() {
+            let (a, b) = mesh.lines()[line_index];
+            assert_eq!(mesh.verts()[a].x, 0.0);
+            assert_eq!(mesh.verts()[b].x, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_loop_cut_uses_parameter() {
+        let mut mesh = grid_mesh();
+        mesh.loop_cut((5, 9), 0.25);
+        for cut in mesh.verts().iter().skip(16) {
+            assert_eq!(cut.y, 1.25);
+        }
+    }
+
+    #[test]
+    fn test_loop_cut_inserts_vert_into_neighbor_ngon() {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 1.0, 0.0));
+        mesh.add_vert(coord(0.0, 1.0, 0.0));
+        mesh.add_vert(coord(2.0, 0.5, 0.0));
+        mesh.add_poly(vec![0, 1, 2, 3]);
+        mesh.add_poly(vec![1, 4, 2]);
+
+        mesh.loop_cut((1, 2), 0.5);
+
+        assert_eq!(mesh.polys().len(), 3);
+        let triangle = &mesh.polys()[1];
+        assert_eq!(triangle.len(), 4);
+        assert!(triangle.contains(&5)); // cut vert of edge 1 -> 2
+    }
+
+    #[test]
+    fn test_loop_cut_on_wire_line_does_nothing() {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 0.0, 0.0));
+        mesh.add_line((0, 1));
+        assert!(mesh.loop_cut((0, 1), 0.5).is_empty());
+        assert_eq!(mesh.verts().len(), 2);
+    }
+}
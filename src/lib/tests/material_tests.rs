@@ -0,0 +1,184 @@
+// Unit tests for material slots.
+//
+// Test coverage includes:
+// - Adding (unique names, distinct colors), renaming and removing slots
+// - Assigning polys and selecting them by material
+// - Slots following their polys through delete_vert, remove_poly, loop cut, bisect,
+//   subdivide, decimate, booleans and append (merged by name)
+// - Material colors shown where the poly has no color of its own
+
+use crate::mesh::{
+    BisectKeep, BooleanOp, DecimateTarget, Mesh, Plane, PolyIndex, SubdivisionMode, material_color,
+};
+use crate::tests::helpers::{all_polys, offset_cube};
+use macroquad::prelude::{RED, Vec3};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_rename_remove() {
+        let mut mesh = Mesh::new_cube();
+        assert!(mesh.materials().is_empty());
+
+        let wood = mesh.add_material("wood");
+        let metal = mesh.add_material("metal");
+        assert_eq!((wood, metal), (0, 1));
+        assert_eq!(mesh.add_material("wood"), wood);
+        assert_eq!(mesh.material_index("metal"), Some(metal));
+        assert_ne!(mesh.materials()[0].color, mesh.materials()[1].color);
+        assert_eq!(mesh.materials()[1].color, material_color(1));
+
+        assert_eq!(mesh.rename_material(metal, "wood"), None);
+        assert_eq!(mesh.rename_material(5, "glass"), None);
+        mesh.rename_material(metal, "steel").unwrap();
+        assert_eq!(mesh.material_index("steel"), Some(metal));
+        mesh.set_material_color(metal, RED).unwrap();
+        assert_eq!(mesh.materials()[metal].color, RED);
+
+        mesh.assign_material(&[0, 1], Some(wood));
+        mesh.assign_material(&[2], Some(metal));
+        let removed = mesh.remove_material(wood).unwrap();
+        assert_eq!(removed.name, "wood");
+        assert_eq!(mesh.poly_material(0), None);
+        // steel moved down into slot 0
+        assert_eq!(mesh.poly_material(2), Some(0));
+        assert_eq!(mesh.remove_material(1), None);
+    }
+
+    #[test]
+    fn test_assign_and_select() {
+        let mut mesh = Mesh::new_cube();
+        let wood = mesh.add_material("wood");
+
+        // duplicates count once, missing polys are skipped, missing slots assign nothing
+        assert_eq!(mesh.assign_material(&[1, 3, 3, 99], Some(wood)), 2);
+        assert_eq!(mesh.assign_material(&[0], Some(7)), 0);
+        assert_eq!(mesh.polys_with_material(Some(wood)), vec![1, 3]);
+        assert_eq!(mesh.polys_with_material(None), vec![0, 2, 4, 5]);
+
+        assert_eq!(mesh.assign_material(&[3], None), 1);
+        assert_eq!(mesh.polys_with_material(Some(wood)), vec![1]);
+    }
+
+    #[test]
+    fn test_corner_color_falls_back_to_material() {
+        let mut mesh = Mesh::new_cube();
+        let wood = mesh.add_material("wood");
+        mesh.assign_material(&[0, 1], Some(wood));
+        let wood_color = mesh.materials()[wood].color;
+
+        assert_eq!(mesh.corner_color(0, 0), Some(wood_color));
+        assert_eq!(mesh.corner_color(2, 0), None);
+        mesh.set_vert_color(mesh.polys()[0][0], Some(RED)).unwrap();
+        assert_eq!(mesh.corner_color(0, 0), Some(RED));
+        assert_eq!(mesh.corner_color(0, 1), Some(wood_color));
+    }
+
+    #[test]
+    fn test_materials_follow_delete_vert_and_remove_poly() {
+        let mut mesh = Mesh::new_cube();
+        let wood = mesh.add_material("wood");
+        // back poly [5, 6, 7, 4] loses a corner and stays
+        mesh.assign_material(&[1], Some(wood));
+
+        mesh.delete_vert(0).unwrap();
+        let back = mesh
+            .polys()
+            .iter()
+            .position(|poly| poly.len() == 4 && poly.contains(&5) && poly.contains(&6))
+            .unwrap();
+        assert_eq!(mesh.polys_with_material(Some(wood)), vec![back]);
+
+        let last = mesh.polys().len() - 1;
+        mesh.assign_material(&[last], Some(wood));
+        mesh.assign_material(&[0], None);
+        // the last poly moves into slot 0
+        mesh.remove_poly(0).unwrap();
+        assert_eq!(mesh.poly_material(0), Some(wood));
+    }
+
+    #[test]
+    fn test_loop_cut_bisect_subdivide_decimate() {
+        let mut mesh = Mesh::new_cube();
+        let wood = mesh.add_material("wood");
+        let polys = all_polys(&mesh);
+        mesh.assign_material(&polys, Some(wood));
+
+        mesh.loop_cut((0, 1), 0.5);
+        assert_eq!(mesh.polys_with_material(Some(wood)).len(), 10);
+
+        mesh.bisect(
+            Plane::new(Vec3::ZERO, Vec3::Y).unwrap(),
+            BisectKeep::Both,
+            false,
+        );
+        assert!(mesh.polys_with_material(None).is_empty());
+
+        mesh.subdivide(SubdivisionMode::Simple, &[]);
+        assert!(mesh.polys_with_material(None).is_empty());
+        assert_eq!(mesh.materials().len(), 1);
+
+        mesh.decimate(DecimateTarget::FaceCount(12), false);
+        assert!(!mesh.polys().is_empty());
+        assert!(mesh.polys_with_material(None).is_empty());
+    }
+
+    #[test]
+    fn test_append_merges_by_name() {
+        let mut mesh = Mesh::new_cube();
+        let metal = mesh.add_material("metal");
+        mesh.assign_material(&[0], Some(metal));
+
+        let mut other = Mesh::new_cube();
+        let wood = other.add_material("wood");
+        let other_metal = other.add_material("metal");
+        other.assign_material(&[0], Some(wood));
+        other.assign_material(&[1], Some(other_metal));
+
+        mesh.append(&other, Vec3::X * 4.0);
+        let names: Vec<&str> = mesh.materials().iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["metal", "wood"]);
+        assert_eq!(mesh.polys_with_material(Some(0)), vec![0, 7]);
+        assert_eq!(mesh.polys_with_material(Some(1)), vec![6]);
+    }
+
+    #[test]
+    fn test_boolean_keeps_materials() {
+        let mut first = Mesh::new_cube();
+        let wood = first.add_material("wood");
+        let polys = all_polys(&first);
+        first.assign_material(&polys, Some(wood));
+        let mut second = offset_cube(Vec3::splat(1.0));
+        let metal = second.add_material("metal");
+        let polys = all_polys(&second);
+        second.assign_material(&polys, Some(metal));
+
+        let result = first.boolean(&second, BooleanOp::Difference).unwrap();
+        let names: Vec<&str> = result.materials().iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["wood", "metal"]);
+        // the cavity is lined with the faces of the second cube
+        assert!(!result.polys_with_material(Some(0)).is_empty());
+        assert!(!result.polys_with_material(Some(1)).is_empty());
+        assert!(result.polys_with_material(None).is_empty());
+    }
+
+    #[test]
+    fn test_boolean_polys_keeps_materials() {
+        let mut mesh = Mesh::new_cube();
+        mesh.append(&Mesh::new_cube(), Vec3::splat(1.0));
+        let wood = mesh.add_material("wood");
+        mesh.assign_material(&[0, 1, 2, 3, 4, 5], Some(wood));
+
+        let first: Vec<PolyIndex> = (0..6).collect();
+        let second: Vec<PolyIndex> = (6..12).collect();
+        let new_polys = mesh
+            .boolean_polys(&first, &second, BooleanOp::Union)
+            .unwrap();
+        assert_eq!(mesh.materials().len(), 1);
+        let wood_polys = mesh.polys_with_material(Some(wood));
+        assert!(!wood_polys.is_empty());
+        assert!(wood_polys.len() < new_polys.len());
+    }
+}
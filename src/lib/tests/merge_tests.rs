@@ -0,0 +1,116 @@
+// Unit tests for merge by distance (vertex welding).
+//
+// Test coverage includes:
+// - Vert counts and returned removal count
+// - Remapping of line and poly indicies
+// - Collapsing degenerate / duplicate lines and polys
+// - Threshold edges: zero threshold, verts across spatial hash cells
+
+use crate::mesh::Mesh;
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    // two triangles sharing an edge, but with the shared verts duplicated
+    fn split_quad_mesh() -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0)); // 0
+        mesh.add_vert(coord(1.0, 0.0, 0.0)); // 1
+        mesh.add_vert(coord(1.0, 1.0, 0.0)); // 2
+        mesh.add_vert(coord(0.0, 0.0, 0.0005)); // 3, dup of 0
+        mesh.add_vert(coord(1.0, 1.0, 0.0)); // 4, dup of 2
+        mesh.add_vert(coord(0.0, 1.0, 0.0)); // 5
+        mesh.add_poly(vec![0, 1, 2]);
+        mesh.add_poly(vec![3, 4, 5]);
+        mesh.add_line((0, 2));
+        mesh.add_line((3, 4));
+        mesh
+    }
+
+    #[test]
+    fn test_merge_removes_duplicate_verts() {
+        let mut mesh = split_quad_mesh();
+        let removed = mesh.merge_by_distance(0.001);
+        assert_eq!(removed, 2);
+        assert_eq!(mesh.verts().len(), 4);
+    }
+
+    #[test]
+    fn test_merge_remaps_polys() {
+        let mut mesh = split_quad_mesh();
+        mesh.merge_by_distance(0.001);
+        assert_eq!(mesh.polys()[0], vec![0, 1, 2]);
+        assert_eq!(mesh.polys()[1], vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn test_merge_removes_duplicate_lines() {
+        let mut mesh = split_quad_mesh();
+        mesh.merge_by_distance(0.001);
+        assert_eq!(mesh.lines(), &vec![(0, 2)]);
+    }
+
+    #[test]
+    fn test_merge_keeps_first_vert_position() {
+        let mut mesh = split_quad_mesh();
+        mesh.merge_by_distance(0.001);
+        assert_eq!(mesh.verts()[0], coord(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_merge_nothing_within_threshold() {
+        let mut mesh = Mesh::new_cube();
+        assert_eq!(mesh.merge_by_distance(0.1), 0);
+        assert_eq!(mesh.verts().len(), 8);
+        assert_eq!(mesh.lines().len(), 12);
+        assert_eq!(mesh.polys().len(), 6);
+    }
+
+    #[test]
+    fn test_merge_collapses_degenerate_line_and_poly() {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(0.01, 0.0, 0.0));
+        mesh.add_vert(coord(0.0, 1.0, 0.0));
+        mesh.add_line((0, 1));
+        mesh.add_poly(vec![0, 1, 2]);
+
+        mesh.merge_by_distance(0.05);
+        assert_eq!(mesh.lines().len(), 0);
+        assert_eq!(mesh.polys().len(), 0);
+    }
+
+    #[test]
+    fn test_merge_quad_edge_collapse_leaves_triangle() {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 1.0, 0.0));
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_poly(vec![0, 1, 2, 3]);
+
+        mesh.merge_by_distance(0.0);
+        assert_eq!(mesh.polys()[0], vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_merge_across_cell_boundary() {
+        let mut mesh = Mesh::new();
+        // on either side of a cell boundary at x = 0.1
+        mesh.add_vert(coord(0.0999, 0.0, 0.0));
+        mesh.add_vert(coord(0.1001, 0.0, 0.0));
+        assert_eq!(mesh.merge_by_distance(0.1), 1);
+    }
+
+    #[test]
+    fn test_merge_empty_mesh() {
+        let mut mesh = Mesh::new();
+        assert_eq!(mesh.merge_by_distance(1.0), 0);
+    }
+}
@@ -22,6 +22,7 @@
 //     lines: Vec<Line>,
 // }
 
+#[allow(unused_imports)]
 use crate::mesh::{Line, Mesh, Poly};
 use macroquad::prelude::Vec3;
 
@@ -194,6 +195,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::get_first, unused_variables)]
     fn test_delete_vertex_reindexes_remaining_lines() {
         let mut mesh = empty_mesh();
         // Create vertices: v0=0, v1=1, v2=2, v3=3
@@ -217,6 +219,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::get_first)]
     fn test_delete_last_vertex_removes_its_lines() {
         let mut mesh = empty_mesh();
         let v0 = mesh.add_vert(coord(0.0, 0.0, 0.0));
@@ -235,6 +238,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::get_first)]
     fn test_delete_first_vertex_reindexes_all_lines() {
         let mut mesh = empty_mesh();
         let _v0 = mesh.add_vert(coord(0.0, 0.0, 0.0));
@@ -318,6 +322,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::get_first)]
     fn test_add_poly_returns_indices() {
         let mut mesh = triangle_mesh();
         mesh.add_poly(vec![0, 1, 2]);
@@ -401,6 +406,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::get_first)]
     fn test_remove_poly_swap_remove_behavior() {
         let mut mesh = empty_mesh();
         // Create 4 vertices for two separate triangles
@@ -421,6 +427,7 @@ mod tests {
     // ==================== delete_vertex affecting polys tests ====================
 
     #[test]
+    #[allow(clippy::get_first)]
     fn test_delete_vertex_removes_reference_from_poly() {
         let mut mesh = empty_mesh();
         // Create a quad (4 vertices)
@@ -455,6 +462,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::get_first)]
     fn test_delete_vertex_remaps_swapped_vertex_indices_in_poly() {
         let mut mesh = empty_mesh();
         // Create 4 vertices
@@ -496,6 +504,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::get_first)]
     fn test_delete_vertex_keeps_valid_polys() {
         let mut mesh = empty_mesh();
         // Create 5 vertices (pentagon)
@@ -1 +1,28 @@
+mod bisect_tests;
+mod color_tests;
+mod csg_tests;
+mod decimate_tests;
+mod fill_tests;
+mod gltf_tests;
+mod helpers;
+mod hull_tests;
+mod lathe_tests;
+mod line_policy_tests;
+mod loop_cut_tests;
+mod material_tests;
+mod merge_tests;
 mod mesh_tests;
+mod native_tests;
+mod obj_tests;
+mod ply_tests;
+mod primitive_tests;
+mod scene_tests;
+mod selection_set_tests;
+mod smooth_tests;
+mod stl_tests;
+mod subdivide_tests;
+mod svg_tests;
+mod topology_tests;
+mod uv_tests;
+mod validate_tests;
+mod winding_tests;
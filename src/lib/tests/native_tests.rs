@@ -0,0 +1,288 @@
+// Unit tests for the native (JSON) mesh format.
+//
+// Test coverage includes:
+// - Round trips of verts, lines (and which were derived from polys), polys, mirror mode,
+//   line policy, colors, UVs, materials and selection sets
+// - Optional lists left out when the mesh has no such data
+// - NaN / infinite floats written as strings and read back
+// - Documents nested deeper than the parser's limit rejected
+// - Wrong format / newer versions, malformed fields, out of range indicies
+
+use crate::mesh::{LinePolicy, Mesh, MirrorMode, NativeError, SetKind};
+use macroquad::prelude::{BLUE, Color, RED, Vec2, Vec3};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_same_mesh(actual: &Mesh, expected: &Mesh) {
+        assert_eq!(actual.verts(), expected.verts());
+        assert_eq!(actual.lines(), expected.lines());
+        assert_eq!(actual.polys(), expected.polys());
+        assert_eq!(actual.line_policy(), expected.line_policy());
+        assert_eq!(
+            actual.mirror_mode().to_string(),
+            expected.mirror_mode().to_string()
+        );
+        for v in 0..expected.verts().len() {
+            assert_eq!(actual.vert_color(v), expected.vert_color(v));
+        }
+        for poly_index in 0..expected.polys().len() {
+            assert_eq!(
+                actual.poly_color(poly_index),
+                expected.poly_color(poly_index)
+            );
+            assert_eq!(actual.poly_uvs(poly_index), expected.poly_uvs(poly_index));
+            assert_eq!(
+                actual.poly_material(poly_index),
+                expected.poly_material(poly_index)
+            );
+        }
+        assert_eq!(actual.materials(), expected.materials());
+        assert_eq!(actual.selection_sets(), expected.selection_sets());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut mesh = Mesh::new_tapered_box();
+        mesh.set_mirror_mode(MirrorMode::Radial(4));
+        let wire_end = mesh.add_vert(Vec3::new(0.1, 0.2, 0.3));
+        mesh.add_line((0, wire_end)).unwrap();
+        mesh.set_vert_color(2, Some(Color::new(0.1, 0.2, 0.3, 0.5)))
+            .unwrap();
+        mesh.set_poly_color(4, Some(RED)).unwrap();
+        let polys: Vec<usize> = (0..mesh.polys().len()).collect();
+        mesh.project_uvs_box(&polys[..3]);
+        mesh.add_material("unused");
+        let metal = mesh.add_material("metal");
+        mesh.assign_material(&[1, 2], Some(metal));
+        mesh.save_selection_set("rim", SetKind::Verts, &[0, 3]);
+        mesh.save_selection_set("wire", SetKind::Lines, &[0]);
+        mesh.save_selection_set("empty", SetKind::Polys, &[]);
+
+        let loaded = Mesh::from_native(&mesh.to_native()).unwrap();
+        assert_same_mesh(&loaded, &mesh);
+        assert_eq!(loaded.wire_lines().len(), 1);
+        // and again, nothing drifts
+        assert_eq!(loaded.to_native(), mesh.to_native());
+    }
+
+    #[test]
+    fn test_manual_lines_kept_as_they_are() {
+        let mut mesh = Mesh::new();
+        for x in 0..3 {
+            mesh.add_vert(Vec3::new(x as f32, 0.0, 0.0));
+        }
+        mesh.add_line((0, 1)).unwrap();
+        mesh.add_line((2, 1)).unwrap();
+        mesh.set_mirror_mode(MirrorMode::Bilateral);
+
+        let loaded = Mesh::from_native(&mesh.to_native()).unwrap();
+        assert_same_mesh(&loaded, &mesh);
+        assert_eq!(loaded.line_policy(), LinePolicy::Manual);
+    }
+
+    #[test]
+    fn test_drawn_lines_stay_drawn() {
+        let mut mesh = Mesh::new();
+        mesh.set_line_policy(LinePolicy::DeriveFromPolys);
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)] {
+            mesh.add_vert(Vec3::new(x, y, 0.0));
+        }
+        mesh.add_line((1, 0)).unwrap();
+        mesh.add_poly(vec![0, 1, 2]).unwrap();
+
+        let native = mesh.to_native();
+        assert!(native.contains(r#""derived_lines":[1,2]"#));
+        let mut loaded = Mesh::from_native(&native).unwrap();
+        loaded.remove_poly(0);
+        assert_eq!(loaded.lines(), &vec![(1, 0)]);
+    }
+
+    #[test]
+    fn test_optional_lists_left_out() {
+        let mut mesh = Mesh::new_cube();
+        let native = mesh.to_native();
+        assert!(native.starts_with(r#"{"format":"mesh_editor","version":1,"mirror_mode":"None""#));
+        for field in [
+            "vert_colors",
+            "poly_colors",
+            "poly_uvs",
+            "materials",
+            "poly_materials",
+            "selection_sets",
+        ] {
+            assert!(!native.contains(field));
+        }
+
+        mesh.set_vert_color(0, Some(BLUE)).unwrap();
+        let native = mesh.to_native();
+        assert!(native.contains(r#""vert_colors":[[0,0.47,0.95,1],null,"#));
+        assert!(!native.contains("poly_colors"));
+    }
+
+    #[test]
+    fn test_non_finite_floats_round_trip() {
+        let mut mesh = Mesh::new_cube();
+        mesh.update_vert(0, Vec3::new(f32::NAN, f32::INFINITY, f32::NEG_INFINITY))
+            .unwrap();
+        let native = mesh.to_native();
+        assert!(native.contains(r#""verts":[["NaN","Infinity","-Infinity"],"#));
+
+        let loaded = Mesh::from_native(&native).unwrap();
+        let vert = loaded.verts()[0];
+        assert!(vert.x.is_nan());
+        assert_eq!((vert.y, vert.z), (f32::INFINITY, f32::NEG_INFINITY));
+        assert_eq!(&loaded.verts()[1..], &mesh.verts()[1..]);
+    }
+
+    #[test]
+    fn test_deep_nesting_rejected() {
+        let nested = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(matches!(
+            Mesh::from_native(&nested).err(),
+            Some(NativeError::Parse(message)) if message.starts_with("nested too deep")
+        ));
+        // a mesh document is only a few levels deep
+        let shallow = format!("{}{}", "[".repeat(100), "]".repeat(100));
+        assert!(matches!(
+            Mesh::from_native(&shallow).err(),
+            Some(NativeError::Format(_))
+        ));
+    }
+
+    #[test]
+    fn test_uvs_with_gaps() {
+        let mut mesh = Mesh::new_cube();
+        let uvs = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+        ];
+        mesh.set_poly_uvs(3, uvs.clone()).unwrap();
+
+        let loaded = Mesh::from_native(&mesh.to_native()).unwrap();
+        assert_eq!(loaded.poly_uvs(3), Some(uvs.as_slice()));
+        assert_eq!(loaded.poly_uvs(0), None);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |text: &str| Mesh::from_native(text).err();
+        let document = |fields: &str| {
+            format!(
+                r#"{{"format":"mesh_editor","version":1,"mirror_mode":"None","line_policy":"manual",{}}}"#,
+                fields
+            )
+        };
+
+        assert!(matches!(error("{"), Some(NativeError::Parse(_))));
+        assert!(matches!(
+            error(r#"{"format":"other"}"#),
+            Some(NativeError::Format(_))
+        ));
+        assert!(matches!(
+            error(r#"{"format":"mesh_editor","version":2}"#),
+            Some(NativeError::Format(_))
+        ));
+        assert_eq!(
+            error(&document(r#""lines":[],"polys":[]"#)),
+            Some(NativeError::Invalid("verts".to_string()))
+        );
+        assert_eq!(
+            error(&document(r#""verts":[[0,0]],"lines":[],"polys":[]"#)),
+            Some(NativeError::Invalid("verts".to_string()))
+        );
+
+        let verts = r#""verts":[[0,0,0],[1,0,0],[0,1,0]]"#;
+        assert_eq!(
+            error(&document(&format!(
+                r#"{},"lines":[[0,1],[1,3]],"polys":[]"#,
+                verts
+            ))),
+            Some(NativeError::IndexOutOfRange {
+                field: "lines".to_string(),
+                index: 1
+            })
+        );
+        assert_eq!(
+            error(&document(&format!(
+                r#"{},"lines":[],"polys":[[0,1,5]]"#,
+                verts
+            ))),
+            Some(NativeError::IndexOutOfRange {
+                field: "polys".to_string(),
+                index: 0
+            })
+        );
+        assert_eq!(
+            error(&document(&format!(
+                r#"{},"lines":[],"polys":[[0,1,1]]"#,
+                verts
+            ))),
+            Some(NativeError::Invalid("polys".to_string()))
+        );
+        // one color per vert
+        assert_eq!(
+            error(&document(&format!(
+                r#"{},"lines":[],"polys":[],"vert_colors":[null]"#,
+                verts
+            ))),
+            Some(NativeError::Invalid("vert_colors".to_string()))
+        );
+        // one UV per corner
+        assert_eq!(
+            error(&document(&format!(
+                r#"{},"lines":[],"polys":[[0,1,2]],"poly_uvs":[[[0,0],[1,0]]]"#,
+                verts
+            ))),
+            Some(NativeError::Invalid("poly_uvs".to_string()))
+        );
+
+        // slots must exist, names must be unique
+        let material = r#"{"name":"a","color":[1,0,0,1]}"#;
+        assert_eq!(
+            error(&document(&format!(
+                r#"{},"lines":[],"polys":[[0,1,2]],"materials":[{}],"poly_materials":[1]"#,
+                verts, material
+            ))),
+            Some(NativeError::Invalid("poly_materials".to_string()))
+        );
+        assert_eq!(
+            error(&document(&format!(
+                r#"{},"lines":[],"polys":[],"materials":[{},{}]"#,
+                verts, material, material
+            ))),
+            Some(NativeError::Invalid("materials".to_string()))
+        );
+
+        // sets must point at elements that exist
+        assert_eq!(
+            error(&document(&format!(
+                r#"{},"lines":[],"polys":[],"selection_sets":[{{"name":"a","kind":"verts","indicies":[3]}}]"#,
+                verts
+            ))),
+            Some(NativeError::Invalid("selection_sets".to_string()))
+        );
+
+        let valid = document(&format!(r#"{},"lines":[],"polys":[[0,1,2]]"#, verts));
+        assert!(Mesh::from_native(&valid).is_ok());
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            NativeError::Invalid("verts".to_string()).to_string(),
+            "'verts' is missing or invalid"
+        );
+        assert_eq!(
+            NativeError::IndexOutOfRange {
+                field: "polys".to_string(),
+                index: 3
+            }
+            .to_string(),
+            "polys 3 points at a vert that doesn't exist"
+        );
+    }
+}
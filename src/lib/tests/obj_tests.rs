@@ -0,0 +1,235 @@
+// Unit tests for OBJ import and export.
+//
+// Test coverage includes:
+// - Written statements (v, vt shared between corners, f v/vt, l)
+// - Round trips of verts, polys, UVs and wire lines
+// - Vert colors as the `v x y z r g b` extension
+// - Material slots as usemtl groups, colors through MTL
+// - Negative indicies, v//vn corners, comments and skipped statements
+// - Bad numbers, out of range indicies, degenerate faces
+
+use crate::mesh::{LinePolicy, Mesh, ObjError, ProjectionPlane};
+use macroquad::prelude::{Color, Vec2, Vec3};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uv(u: f32, v: f32) -> Vec2 {
+        Vec2::new(u, v)
+    }
+
+    #[test]
+    fn test_export_statements() {
+        let mut mesh = Mesh::new();
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)] {
+            mesh.add_vert(Vec3::new(x, y, 0.5));
+        }
+        let tip = mesh.add_vert(Vec3::new(2.0, 0.0, 0.0));
+        mesh.add_poly(vec![0, 1, 2]).unwrap();
+        mesh.add_poly(vec![0, 2, 3]).unwrap();
+        mesh.set_poly_uvs(0, vec![uv(0.0, 0.0), uv(1.0, 0.0), uv(1.0, 1.0)])
+            .unwrap();
+        mesh.add_line((1, tip)).unwrap();
+        mesh.set_line_policy(LinePolicy::DeriveFromPolys);
+
+        assert_eq!(
+            mesh.to_obj(),
+            "# mesh_editor
+v 0 0 0.5
+v 1 0 0.5
+v 1 1 0.5
+v 0 1 0.5
+v 2 0 0
+vt 0 0
+vt 1 0
+vt 1 1
+f 1/1 2/2 3/3
+f 1 3 4
+l 2 5
+"
+        );
+    }
+
+    #[test]
+    fn test_shared_uvs_written_once() {
+        let mut mesh = Mesh::new_cube();
+        let polys: Vec<usize> = (0..mesh.polys().len()).collect();
+        mesh.project_uvs_box(&polys);
+        let obj = mesh.to_obj();
+        // box projection of a cube only uses the 4 corners of the square
+        assert_eq!(obj.lines().filter(|l| l.starts_with("vt ")).count(), 4);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut mesh = Mesh::new_tapered_box();
+        let polys: Vec<usize> = (0..mesh.polys().len()).collect();
+        mesh.project_uvs_planar(&polys[..3], ProjectionPlane::XY, false);
+        mesh.project_uvs_box(&polys[3..]);
+        let wire_end = mesh.add_vert(Vec3::new(0.1, 5.0, -0.3));
+        mesh.add_line((0, wire_end)).unwrap();
+
+        let imported = Mesh::from_obj(&mesh.to_obj()).unwrap();
+        assert_eq!(imported.verts(), mesh.verts());
+        assert_eq!(imported.polys(), mesh.polys());
+        for poly_index in polys {
+            assert_eq!(imported.poly_uvs(poly_index), mesh.poly_uvs(poly_index));
+        }
+        assert_eq!(imported.line_policy(), LinePolicy::DeriveFromPolys);
+        assert_eq!(imported.lines().len(), mesh.lines().len());
+        assert_eq!(imported.wire_lines().len(), 1);
+    }
+
+    #[test]
+    fn test_import_variants() {
+        let text = "# comment
+mtllib scene.mtl
+o thing
+v 0 0 0
+v 1 0 0 1.0
+v 1 1 0
+v 0 1 0 # trailing comment
+vn 0 0 1
+vt 0.5
+vt 0.25 0.75 0
+s off
+f -4//1 -3//1 -2//1
+f 1/1/1 3/2/1 4/2/1
+f 1/1 2 3
+l 2 4 1
+";
+        let mesh = Mesh::from_obj(text).unwrap();
+        assert_eq!(mesh.verts().len(), 4);
+        assert_eq!(
+            mesh.polys(),
+            &vec![vec![0, 1, 2], vec![0, 2, 3], vec![0, 1, 2]]
+        );
+        assert_eq!(mesh.poly_uvs(0), None);
+        assert_eq!(
+            mesh.poly_uvs(1).unwrap(),
+            &[uv(0.5, 0.0), uv(0.25, 0.75), uv(0.25, 0.75)]
+        );
+        // not every corner has a UV
+        assert_eq!(mesh.poly_uvs(2), None);
+        // the polyline adds 2-4 as a wire line, 4-1 is a poly edge already
+        assert_eq!(mesh.wire_lines().len(), 1);
+    }
+
+    #[test]
+    fn test_vert_colors() {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(Vec3::ZERO);
+        mesh.add_vert(Vec3::X);
+        mesh.set_vert_color(0, Some(Color::new(1.0, 0.5, 0.25, 1.0)))
+            .unwrap();
+
+        let obj = mesh.to_obj();
+        assert_eq!(obj, "# mesh_editor\nv 0 0 0 1 0.5 0.25\nv 1 0 0 1 1 1\n");
+        let round_trip = Mesh::from_obj(&obj).unwrap();
+        assert_eq!(
+            round_trip.vert_color(0),
+            Some(Color::new(1.0, 0.5, 0.25, 1.0))
+        );
+        assert_eq!(
+            round_trip.vert_color(1),
+            Some(Color::new(1.0, 1.0, 1.0, 1.0))
+        );
+
+        // x y z w is not a color, verts without colors stay uncolored
+        let mixed = Mesh::from_obj("v 0 0 0 1\nv 1 0 0 0 1 0\n").unwrap();
+        assert_eq!(mixed.vert_color(0), None);
+        assert_eq!(mixed.vert_color(1), Some(Color::new(0.0, 1.0, 0.0, 1.0)));
+        assert!(!Mesh::new_cube().to_obj().contains("v -1 -1 -1 "));
+    }
+
+    #[test]
+    fn test_materials() {
+        let mut mesh = Mesh::new_cube();
+        let wood = mesh.add_material("dark wood");
+        let metal = mesh.add_material("metal");
+        mesh.assign_material(&[1, 4], Some(metal));
+        mesh.assign_material(&[2], Some(wood));
+        mesh.set_material_color(metal, Color::new(0.5, 0.25, 1.0, 1.0))
+            .unwrap();
+
+        // polys without a material first, then slot by slot
+        let obj = mesh.to_obj();
+        let statements: Vec<&str> = obj
+            .lines()
+            .filter(|line| line.starts_with('f') || line.starts_with("usemtl"))
+            .map(|line| line.split(' ').next().unwrap())
+            .collect();
+        assert_eq!(
+            statements,
+            vec!["f", "f", "f", "usemtl", "f", "usemtl", "f", "f"]
+        );
+        assert!(obj.contains("usemtl dark wood\nf 1 2 6 5\n"));
+        assert_eq!(
+            mesh.to_mtl().lines().skip(3).collect::<Vec<&str>>(),
+            vec!["newmtl metal", "Kd 0.5 0.25 1"]
+        );
+
+        let mut round_trip = Mesh::from_obj(&obj).unwrap();
+        let names: Vec<&str> = round_trip
+            .materials()
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["dark wood", "metal"]);
+        assert_eq!(round_trip.polys_with_material(None), vec![0, 1, 2]);
+        assert_eq!(round_trip.polys_with_material(Some(1)), vec![4, 5]);
+
+        // colors for the slots with a name in the library, others are left alone
+        let colored = round_trip.apply_mtl("newmtl metal\nKd 0 1 0\nnewmtl glass\nKd 1 1 1\n");
+        assert_eq!(colored, 1);
+        assert_eq!(
+            round_trip.materials()[1].color,
+            Color::new(0.0, 1.0, 0.0, 1.0)
+        );
+
+        assert!(matches!(
+            Mesh::from_obj("usemtl\n"),
+            Err(ObjError::Parse { line: 1, .. })
+        ));
+        assert!(!Mesh::new_cube().to_obj().contains("usemtl"));
+    }
+
+    #[test]
+    fn test_import_errors() {
+        assert_eq!(
+            Mesh::from_obj("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4\n").err(),
+            Some(ObjError::IndexOutOfRange { line: 4, index: 4 })
+        );
+        assert_eq!(
+            Mesh::from_obj("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 -4\n").err(),
+            Some(ObjError::IndexOutOfRange { line: 4, index: -4 })
+        );
+        assert_eq!(
+            Mesh::from_obj("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 0 1 2\n").err(),
+            Some(ObjError::IndexOutOfRange { line: 4, index: 0 })
+        );
+        assert!(matches!(
+            Mesh::from_obj("v 0 zero 0\n"),
+            Err(ObjError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            Mesh::from_obj("v 0 0\n"),
+            Err(ObjError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            Mesh::from_obj("v 0 0 0\nv 1 0 0\n\nf 1 2 1\n"),
+            Err(ObjError::Parse { line: 4, .. })
+        ));
+        assert!(matches!(
+            Mesh::from_obj("v 0 0 0\nl 1\n"),
+            Err(ObjError::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_error_display() {
+        let error = ObjError::IndexOutOfRange { line: 3, index: -7 };
+        assert_eq!(error.to_string(), "line 3: index -7 is out of range");
+    }
+}
@@ -0,0 +1,379 @@
+// Unit tests for PLY import and export.
+//
+// Test coverage includes:
+// - Round trips in ascii, binary little endian and binary big endian
+// - Vertex colors and arbitrary extra properties / elements kept through a round trip
+// - Vert / poly colors of the mesh read from and written as color channels
+// - Faces to polys, edges to lines (and derived lines when there are no edges)
+// - Malformed headers, truncated data, bad numbers, out of range indicies, bad faces
+// - Empty color lists and non-finite coords rejected on import
+// - Faces with more corners than a uchar count holds
+
+use crate::mesh::{LinePolicy, Mesh, PlyError, PlyExtras, PlyFormat, PlyScalar};
+use macroquad::prelude::{BLUE, Color, GREEN, Vec3, WHITE};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMATS: [PlyFormat; 3] = [
+        PlyFormat::Ascii,
+        PlyFormat::BinaryLittleEndian,
+        PlyFormat::BinaryBigEndian,
+    ];
+
+    const ASCII_SQUARE: &str = "ply
+format ascii 1.0
+comment made by hand
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+property float confidence
+element face 1
+property list uchar int vertex_indices
+property int material
+element camera 1
+property double focal_length
+end_header
+0 0 0 255 0 0 0.5
+1 0 0 0 255 0 0.25
+1 0 1 0 0 255 1
+0 0 1 255 255 255 0.125
+4 0 1 2 3 7
+35.5
+";
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    // ==================== import tests ====================
+
+    #[test]
+    fn test_import_ascii_square() {
+        let (mesh, extras) = Mesh::from_ply(ASCII_SQUARE.as_bytes()).unwrap();
+
+        assert_eq!(mesh.verts()[2], coord(1.0, 0.0, 1.0));
+        assert_eq!(mesh.polys(), &vec![vec![0, 1, 2, 3]]);
+        assert_eq!(mesh.lines().len(), 4);
+        assert_eq!(mesh.line_policy(), LinePolicy::DeriveFromPolys);
+        assert_eq!(extras.comments, vec![String::from("made by hand")]);
+
+        let colors = extras.vertex_colors().unwrap();
+        assert_eq!(colors[0], Color::new(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(colors[3], Color::new(1.0, 1.0, 1.0, 1.0));
+
+        let face = extras.element("face").unwrap();
+        assert_eq!(face.properties[0].name, "material");
+        assert_eq!(face.properties[0].values, vec![vec![7.0]]);
+        let camera = extras.element("camera").unwrap();
+        assert_eq!(camera.properties[0].values, vec![vec![35.5]]);
+    }
+
+    #[test]
+    fn test_import_edges_as_lines() {
+        let text = "ply
+format ascii 1.0
+element vertex 3
+property float x
+property float y
+property float z
+element edge 2
+property int vertex1
+property int vertex2
+end_header
+0 0 0
+1 0 0
+1 1 0
+0 1
+1 2
+";
+        let (mesh, extras) = Mesh::from_ply(text.as_bytes()).unwrap();
+
+        assert_eq!(mesh.lines(), &vec![(0, 1), (1, 2)]);
+        assert!(mesh.polys().is_empty());
+        assert_eq!(mesh.line_policy(), LinePolicy::Manual);
+        assert_eq!(extras, PlyExtras::default());
+    }
+
+    // ==================== round trip tests ====================
+
+    #[test]
+    fn test_round_trip_keeps_extras_in_every_format() {
+        let (mesh, extras) = Mesh::from_ply(ASCII_SQUARE.as_bytes()).unwrap();
+
+        for format in FORMATS {
+            let bytes = mesh.to_ply(format, &extras);
+            let (round_trip, round_trip_extras) = Mesh::from_ply(&bytes).unwrap();
+
+            assert_eq!(round_trip.verts(), mesh.verts());
+            assert_eq!(round_trip.polys(), mesh.polys());
+            assert_eq!(round_trip.lines().len(), mesh.lines().len());
+            assert_eq!(round_trip_extras, extras);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_of_cube() {
+        let cube = Mesh::new_cube();
+        for format in FORMATS {
+            let bytes = cube.to_ply(format, &PlyExtras::default());
+            let (mesh, extras) = Mesh::from_ply(&bytes).unwrap();
+
+            assert_eq!(mesh.verts(), cube.verts());
+            assert_eq!(mesh.polys(), cube.polys());
+            assert_eq!(mesh.lines().len(), 12);
+            assert_eq!(extras, PlyExtras::default());
+        }
+    }
+
+    #[test]
+    fn test_round_trip_of_polys_with_many_corners() {
+        let cylinder = Mesh::new_cylinder(1.0, 2.0, 300);
+        for format in FORMATS {
+            let bytes = cylinder.to_ply(format, &PlyExtras::default());
+            let (mesh, _) = Mesh::from_ply(&bytes).unwrap();
+            assert_eq!(mesh.polys(), cylinder.polys());
+        }
+    }
+
+    #[test]
+    fn test_round_trip_of_manual_and_wire_lines() {
+        let mut mesh = Mesh::new();
+        let a = mesh.add_vert(coord(0.0, 0.0, 0.0));
+        let b = mesh.add_vert(coord(1.0, 0.5, -2.25));
+        mesh.add_line((a, b));
+
+        let bytes = mesh.to_ply(PlyFormat::BinaryBigEndian, &PlyExtras::default());
+        let (round_trip, _) = Mesh::from_ply(&bytes).unwrap();
+        assert_eq!(round_trip.lines(), &vec![(0, 1)]);
+
+        let mut cube = Mesh::new_cube();
+        let tip = cube.add_vert(coord(0.0, 3.0, 0.0));
+        cube.add_line((3, tip));
+        let bytes = cube.to_ply(PlyFormat::Ascii, &PlyExtras::default());
+        assert!(String::from_utf8_lossy(&bytes).contains("element edge 1\n"));
+    }
+
+    #[test]
+    fn test_set_vertex_colors() {
+        let cube = Mesh::new_cube();
+        let colors: Vec<Color> = (0..8)
+            .map(|i| Color::new(i as f32 / 7.0, 0.0, 1.0, 1.0))
+            .collect();
+        let mut extras = PlyExtras::default();
+        extras.set_vertex_colors(&colors);
+
+        let bytes = cube.to_ply(PlyFormat::BinaryLittleEndian, &extras);
+        let (_, round_trip_extras) = Mesh::from_ply(&bytes).unwrap();
+        let round_trip = round_trip_extras.vertex_colors().unwrap();
+
+        for (color, expected) in round_trip.iter().zip(colors) {
+            assert!((color.r - expected.r).abs() < 1.0 / 255.0);
+            assert_eq!(color.b, 1.0);
+        }
+        let vertex = round_trip_extras.element("vertex").unwrap();
+        assert!(
+            vertex
+                .properties
+                .iter()
+                .all(|p| p.scalar == PlyScalar::UChar)
+        );
+    }
+
+    fn assert_color_near(actual: Option<Color>, expected: Color) {
+        let actual = actual.expect("no color");
+        assert!(
+            actual.to_vec().distance(expected.to_vec()) <= 1.0 / 255.0,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_mesh_colors() {
+        let (mesh, _) = Mesh::from_ply(ASCII_SQUARE.as_bytes()).unwrap();
+        assert_eq!(mesh.vert_color(0), Some(Color::new(1.0, 0.0, 0.0, 1.0)));
+        assert_eq!(mesh.vert_color(2), Some(Color::new(0.0, 0.0, 1.0, 1.0)));
+        assert_eq!(mesh.poly_color(0), None);
+
+        let mut cube = Mesh::new_cube();
+        cube.set_vert_color(0, Some(BLUE)).unwrap();
+        cube.set_poly_color(2, Some(GREEN)).unwrap();
+        for format in FORMATS {
+            let bytes = cube.to_ply(format, &PlyExtras::default());
+            let (round_trip, _) = Mesh::from_ply(&bytes).unwrap();
+            assert_color_near(round_trip.vert_color(0), BLUE);
+            assert_color_near(round_trip.poly_color(2), GREEN);
+            // unset colors are written white
+            assert_eq!(round_trip.vert_color(1), Some(WHITE));
+            assert_eq!(round_trip.poly_color(0), Some(WHITE));
+        }
+    }
+
+    #[test]
+    fn test_mesh_colors_replace_extras() {
+        let (mut mesh, extras) = Mesh::from_ply(ASCII_SQUARE.as_bytes()).unwrap();
+        mesh.set_vert_color(1, Some(BLUE)).unwrap();
+
+        let bytes = mesh.to_ply(PlyFormat::Ascii, &extras);
+        let (round_trip, round_trip_extras) = Mesh::from_ply(&bytes).unwrap();
+        assert_color_near(round_trip.vert_color(1), BLUE);
+        assert_eq!(
+            round_trip.vert_color(0),
+            Some(Color::new(1.0, 0.0, 0.0, 1.0))
+        );
+
+        // one set of channels, the other extras still there
+        let vertex = round_trip_extras.element("vertex").unwrap();
+        let names: Vec<&str> = vertex.properties.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["red", "green", "blue", "confidence"]);
+    }
+
+    #[test]
+    fn test_extras_for_changed_geometry_are_dropped() {
+        let (mut mesh, extras) = Mesh::from_ply(ASCII_SQUARE.as_bytes()).unwrap();
+        mesh.add_vert(coord(5.0, 5.0, 5.0));
+
+        let bytes = mesh.to_ply(PlyFormat::Ascii, &extras);
+        let (round_trip, round_trip_extras) = Mesh::from_ply(&bytes).unwrap();
+        let vertex = round_trip_extras.element("vertex").unwrap();
+        assert!(vertex.properties.iter().all(|p| p.name != "confidence"));
+        assert!(round_trip_extras.element("camera").is_some());
+        // the colors belong to the mesh and stay
+        assert_eq!(
+            round_trip.vert_color(0),
+            Some(Color::new(1.0, 0.0, 0.0, 1.0))
+        );
+    }
+
+    // ==================== error tests ====================
+
+    #[test]
+    fn test_malformed_header() {
+        assert!(matches!(
+            Mesh::from_ply(b"solid cube\n"),
+            Err(PlyError::Header { line: 1, .. })
+        ));
+
+        let unknown_type = ASCII_SQUARE.replace("property float confidence", "property real c");
+        assert!(matches!(
+            Mesh::from_ply(unknown_type.as_bytes()),
+            Err(PlyError::Header { line: 11, .. })
+        ));
+
+        let no_end = &ASCII_SQUARE[..ASCII_SQUARE.find("end_header").unwrap()];
+        assert!(matches!(
+            Mesh::from_ply(no_end.as_bytes()),
+            Err(PlyError::Header { .. })
+        ));
+
+        let no_z = ASCII_SQUARE.replace("property float z", "property float w");
+        assert!(matches!(
+            Mesh::from_ply(no_z.as_bytes()),
+            Err(PlyError::MissingProperty { .. })
+        ));
+    }
+
+    #[test]
+    fn test_truncated_data() {
+        let cube = Mesh::new_cube();
+        let bytes = cube.to_ply(PlyFormat::BinaryLittleEndian, &PlyExtras::default());
+
+        assert_eq!(
+            Mesh::from_ply(&bytes[..bytes.len() - 3]).err(),
+            Some(PlyError::Truncated {
+                element: String::from("face"),
+                index: 5
+            })
+        );
+
+        let text = &ASCII_SQUARE[..ASCII_SQUARE.find("4 0 1 2 3").unwrap()];
+        assert_eq!(
+            Mesh::from_ply(text.as_bytes()).err(),
+            Some(PlyError::Truncated {
+                element: String::from("face"),
+                index: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_bad_ascii_values() {
+        let bad_number = ASCII_SQUARE.replace("1 0 1 0 0 255 1", "1 0 one 0 0 255 1");
+        assert!(matches!(
+            Mesh::from_ply(bad_number.as_bytes()),
+            Err(PlyError::Parse { line: 20, .. })
+        ));
+
+        let out_of_range = ASCII_SQUARE.replace("4 0 1 2 3 7", "4 0 1 2 9 7");
+        assert_eq!(
+            Mesh::from_ply(out_of_range.as_bytes()).err(),
+            Some(PlyError::IndexOutOfRange {
+                element: String::from("face"),
+                index: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_bad_faces_and_empty_lists() {
+        for corners in ["4 0 1 2 2 7", "2 0 1 7"] {
+            let bad_face = ASCII_SQUARE.replace("4 0 1 2 3 7", corners);
+            assert!(matches!(
+                Mesh::from_ply(bad_face.as_bytes()),
+                Err(PlyError::Invalid { index: 0, .. })
+            ));
+        }
+
+        let list_coords = "ply
+format ascii 1.0
+element vertex 2
+property list uchar float x
+property float y
+property float z
+end_header
+1 5 0 0
+0 0 0
+";
+        assert!(matches!(
+            Mesh::from_ply(list_coords.as_bytes()),
+            Err(PlyError::Invalid { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_empty_color_lists_and_non_finite_coords() {
+        let list_colors = "ply
+format ascii 1.0
+element vertex 2
+property float x
+property float y
+property float z
+property list uchar uchar red
+property uchar green
+property uchar blue
+end_header
+0 0 0 1 255 0 0
+1 0 0 0 0 255
+";
+        assert!(matches!(
+            Mesh::from_ply(list_colors.as_bytes()),
+            Err(PlyError::Invalid { index: 1, .. })
+        ));
+
+        for value in ["nan", "inf", "-inf"] {
+            let non_finite =
+                ASCII_SQUARE.replace("1 0 1 0 0 255 1", &format!("1 {} 1 0 0 255 1", value));
+            assert!(matches!(
+                Mesh::from_ply(non_finite.as_bytes()),
+                Err(PlyError::Invalid { index: 2, .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,178 @@
+// Unit tests for the primitive generators.
+//
+// Test coverage includes:
+// - Vert / poly counts for every shape
+// - Closed shapes are valid, consistently wound and face outward
+// - Open shapes (grid, disc) face +y
+// - Parameter clamping, resized / refined and append
+
+use crate::mesh::{LinePolicy, Mesh, Primitive};
+use crate::tests::helpers::{all_polys, assert_closed_and_outward};
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // closed and outward, and every poly edge has a line
+    fn assert_closed_solid(mesh: &Mesh) {
+        assert_closed_and_outward(mesh);
+        assert_eq!(mesh.wire_lines(), Vec::<usize>::new());
+    }
+
+    fn assert_faces_up(mesh: &Mesh) {
+        assert_eq!(mesh.validate(), vec![]);
+        for poly_index in all_polys(mesh) {
+            assert!((mesh.poly_normal(poly_index) - Vec3::Y).length() < 1e-4);
+        }
+    }
+
+    // ==================== shape tests ====================
+
+    #[test]
+    fn test_uv_sphere() {
+        let mesh = Mesh::new_uv_sphere(1.0, 8, 4);
+        assert_eq!(mesh.verts().len(), 8 * 3 + 2);
+        assert_eq!(mesh.polys().len(), 8 * 4);
+        assert_eq!(mesh.lines().len(), 8 * 3 + 8 * 4);
+        assert!(mesh.verts().iter().all(|v| (v.length() - 1.0).abs() < 1e-5));
+        assert_closed_solid(&mesh);
+    }
+
+    #[test]
+    fn test_icosphere() {
+        let base = Mesh::new_icosphere(2.0, 0);
+        assert_eq!((base.verts().len(), base.polys().len()), (12, 20));
+        assert_closed_solid(&base);
+
+        let mesh = Mesh::new_icosphere(2.0, 2);
+        assert_eq!((mesh.verts().len(), mesh.polys().len()), (162, 320));
+        assert!(mesh.verts().iter().all(|v| (v.length() - 2.0).abs() < 1e-5));
+        assert_closed_solid(&mesh);
+    }
+
+    #[test]
+    fn test_cylinder() {
+        let mesh = Mesh::new_cylinder(1.0, 2.0, 6);
+        assert_eq!((mesh.verts().len(), mesh.polys().len()), (12, 8));
+        assert_closed_solid(&mesh);
+        assert!((mesh.poly_normal(6) - Vec3::Y).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_cone() {
+        let mesh = Mesh::new_cone(1.0, 2.0, 6);
+        assert_eq!((mesh.verts().len(), mesh.polys().len()), (7, 7));
+        assert_closed_solid(&mesh);
+    }
+
+    #[test]
+    fn test_torus() {
+        let mesh = Mesh::new_torus(1.0, 0.25, 12, 6);
+        assert_eq!((mesh.verts().len(), mesh.polys().len()), (72, 72));
+        assert_closed_solid(&mesh);
+        assert!(mesh.boundary_loops().is_empty());
+    }
+
+    #[test]
+    fn test_capsule() {
+        let mesh = Mesh::new_capsule(0.5, 2.0, 8, 3);
+        assert_eq!(mesh.verts().len(), 8 * 6 + 2);
+        assert_eq!(mesh.polys().len(), 8 * 2 + 8 * 5);
+        let top = mesh.verts().iter().map(|v| v.y).fold(f32::MIN, f32::max);
+        assert!((top - 1.5).abs() < 1e-5);
+        assert_closed_solid(&mesh);
+    }
+
+    #[test]
+    fn test_grid() {
+        let mesh = Mesh::new_grid(2.0, 3);
+        assert_eq!((mesh.verts().len(), mesh.polys().len()), (16, 9));
+        assert_eq!(mesh.boundary_loops().len(), 1);
+        assert_faces_up(&mesh);
+    }
+
+    #[test]
+    fn test_disc() {
+        let mesh = Mesh::new_disc(1.0, 8);
+        assert_eq!((mesh.verts().len(), mesh.polys().len()), (9, 8));
+        assert_eq!(mesh.boundary_loops()[0].len(), 8);
+        assert_faces_up(&mesh);
+    }
+
+    #[test]
+    fn test_every_default_primitive_builds_valid_mesh() {
+        for primitive in Primitive::defaults() {
+            let mesh = primitive.build();
+            assert_eq!(mesh.line_policy(), LinePolicy::DeriveFromPolys);
+            assert_eq!(mesh.validate(), vec![], "{}", primitive.name());
+        }
+    }
+
+    // ==================== parameter tests ====================
+
+    #[test]
+    fn test_too_few_segments_are_clamped() {
+        let mesh = Mesh::new_cylinder(1.0, 1.0, 1);
+        assert_eq!(mesh.verts().len(), 6);
+        assert_closed_solid(&mesh);
+    }
+
+    #[test]
+    fn test_resized_scales_lengths() {
+        let primitive = Primitive::Cylinder {
+            radius: 1.0,
+            depth: 2.0,
+            segments: 8,
+        };
+        assert_eq!(
+            primitive.resized(0.5),
+            Primitive::Cylinder {
+                radius: 0.5,
+                depth: 1.0,
+                segments: 8,
+            }
+        );
+    }
+
+    #[test]
+    fn test_refined_clamps_counts() {
+        let primitive = Primitive::UvSphere {
+            radius: 1.0,
+            segments: 4,
+            rings: 3,
+        };
+        assert_eq!(
+            primitive.refined(-5),
+            Primitive::UvSphere {
+                radius: 1.0,
+                segments: 3,
+                rings: 2,
+            }
+        );
+        assert_eq!(
+            primitive.refined(1),
+            Primitive::UvSphere {
+                radius: 1.0,
+                segments: 6,
+                rings: 4,
+            }
+        );
+    }
+
+    // ==================== append tests ====================
+
+    #[test]
+    fn test_append_offsets_and_reindexes() {
+        let mut mesh = Mesh::new_cube();
+        let cone = Mesh::new_cone(1.0, 2.0, 4);
+        let new_verts = mesh.append(&cone, Vec3::new(5.0, 0.0, 0.0));
+
+        assert_eq!(new_verts, (8..13).collect::<Vec<_>>());
+        assert_eq!(mesh.verts()[12], Vec3::new(5.0, 1.0, 0.0));
+        assert_eq!(mesh.polys().len(), 6 + 5);
+        assert_eq!(mesh.lines().len(), 12 + 8);
+        assert_eq!(mesh.connected_components().len(), 2);
+        assert_eq!(mesh.validate(), vec![]);
+    }
+}
@@ -0,0 +1,279 @@
+// Unit tests for scenes of objects.
+//
+// Test coverage includes:
+// - Adding (unique names), renaming, removing (never the last) and cycling objects
+// - Removing polys along with the verts and derived lines only they used
+// - Separating polys into a new object and joining objects back at their locations
+// - Transform matrices (scale, then rotate, then move) and applying them to the verts,
+//   mirroring transforms keeping the normals pointing outward
+// - Scene space coords mapped into object space, e.g. a bisect line drawn over a placed object
+// - Baked scene meshes and glTF nodes carrying the transforms
+// - Native scene round trip, plain mesh documents and bad documents
+
+use crate::mesh::{BisectKeep, Mesh, NativeError, Plane, PolyIndex};
+use crate::scene::{Scene, Transform};
+use macroquad::prelude::{Mat4, Quat, Vec3};
+use std::f32::consts::FRAC_PI_2;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_cubes() -> Mesh {
+        let mut mesh = Mesh::new_cube();
+        mesh.append(&Mesh::new_cube(), Vec3::X * 4.0);
+        mesh
+    }
+
+    fn second_cube() -> Vec<PolyIndex> {
+        (6..12).collect()
+    }
+
+    #[test]
+    fn test_add_rename_remove_cycle() {
+        let mut scene = Scene::with_object("Cube", Mesh::new_cube());
+        assert_eq!(scene.objects().len(), 1);
+        assert_eq!(scene.remove_object(0).map(|object| object.name), None);
+
+        let second = scene.add_object("Cube", Mesh::new_cube(), Transform::IDENTITY);
+        let third = scene.add_object("Cube", Mesh::new(), Transform::IDENTITY);
+        let names: Vec<&str> = scene.objects().iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, vec!["Cube", "Cube.001", "Cube.002"]);
+        assert_eq!(scene.active_index(), 0);
+
+        assert_eq!(scene.rename_object(second, "Cube"), None);
+        scene.rename_object(second, "Other").unwrap();
+        assert_eq!(scene.object_index("Other"), Some(second));
+
+        assert_eq!(scene.cycle_active(true), third);
+        assert_eq!(scene.cycle_active(false), 0);
+        scene.set_active(third).unwrap();
+        assert_eq!(scene.set_active(5), None);
+
+        // the active object moves down with its index
+        assert_eq!(scene.remove_object(0).unwrap().name, "Cube");
+        assert_eq!(scene.active_object().name, "Cube.002");
+        scene.remove_object(1).unwrap();
+        assert_eq!(scene.active_object().name, "Other");
+    }
+
+    #[test]
+    fn test_remove_polys() {
+        let mut mesh = two_cubes();
+        assert_eq!(mesh.remove_polys(&second_cube()), 6);
+        assert_eq!(mesh.verts().len(), 8);
+        assert_eq!(mesh.polys().len(), 6);
+        assert_eq!(mesh.lines().len(), 12);
+        assert!(mesh.verts().iter().all(|v| v.x <= 1.0));
+        assert_eq!(mesh.remove_polys(&[99]), 0);
+
+        // verts shared with remaining polys stay
+        let mut mesh = Mesh::new_cube();
+        mesh.remove_polys(&[0]);
+        assert_eq!(mesh.verts().len(), 8);
+        assert_eq!(mesh.polys().len(), 5);
+    }
+
+    #[test]
+    fn test_separate_and_join() {
+        let mut scene = Scene::with_object("Cubes", two_cubes());
+        let separated = scene.separate_polys(&second_cube()).unwrap();
+        assert_eq!(scene.objects()[separated].name, "Cubes.001");
+        assert_eq!(scene.active_mesh().polys().len(), 6);
+        let other = &scene.objects()[separated].mesh;
+        assert_eq!(other.polys().len(), 6);
+        assert!(other.verts().iter().all(|v| v.x >= 3.0));
+        assert_eq!(scene.separate_polys(&[99]), None);
+
+        // joined where it sits in the scene, relative to the active object
+        scene.active_object_mut().transform = Transform::from_location(Vec3::Y);
+        scene.add_object(
+            "Moved",
+            Mesh::new_cube(),
+            Transform::from_location(Vec3::Y * 5.0),
+        );
+        assert_eq!(scene.join_objects(&[0, 1, 2, 2]), 2);
+        assert_eq!(scene.objects().len(), 1);
+        let mesh = scene.active_mesh();
+        assert_eq!(mesh.polys().len(), 18);
+        assert!(mesh.verts().contains(&Vec3::new(1.0, 5.0, 1.0)));
+        assert!(mesh.verts().contains(&Vec3::new(5.0, 0.0, 1.0)));
+    }
+
+    fn close(a: Vec3, b: Vec3) -> bool {
+        a.distance(b) < 1e-5
+    }
+
+    fn has_vert(mesh: &Mesh, coord: Vec3) -> bool {
+        mesh.verts().iter().any(|&v| close(v, coord))
+    }
+
+    fn quarter_turn_y() -> Transform {
+        Transform {
+            location: Vec3::X * 10.0,
+            rotation: Vec3::new(0.0, FRAC_PI_2, 0.0),
+            scale: Vec3::new(2.0, 1.0, 1.0),
+        }
+    }
+
+    #[test]
+    fn test_transform_matrix() {
+        assert!(Transform::IDENTITY.is_identity());
+        assert!(!quarter_turn_y().is_identity());
+        // (1, 0, 0) -> scaled (2, 0, 0) -> turned (0, 0, -2) -> moved (10, 0, -2)
+        let matrix = quarter_turn_y().matrix();
+        assert!(close(
+            matrix.transform_point3(Vec3::X),
+            Vec3::new(10.0, 0.0, -2.0)
+        ));
+        let quat = quarter_turn_y().quat();
+        assert!(quat.abs_diff_eq(Quat::from_rotation_y(FRAC_PI_2), 1e-6));
+    }
+
+    #[test]
+    fn test_bisect_line_in_scene_space() {
+        let mut scene = Scene::with_object("Cube", Mesh::new_cube());
+        scene.active_object_mut().transform = quarter_turn_y();
+        let to_object = quarter_turn_y().inverse_matrix();
+        assert!(close(
+            to_object.transform_point3(Vec3::new(10.0, 0.0, -2.0)),
+            Vec3::X
+        ));
+
+        // line across the drawn cube (x 9..11, z -2..2) in the xz pane, looking along y
+        let (start, end) = (Vec3::new(10.5, 0.0, -5.0), Vec3::new(10.5, 0.0, 5.0));
+        let plane = Plane::containing_line(
+            to_object.transform_point3(start),
+            to_object.transform_point3(end),
+            to_object.transform_vector3(Vec3::Y).normalize(),
+        )
+        .unwrap();
+        let vert_count = scene.active_mesh().verts().len();
+        let cut_lines = scene
+            .active_mesh_mut()
+            .bisect(plane, BisectKeep::Both, false);
+        assert_eq!(cut_lines.len(), 4);
+
+        let baked = scene.active_object().baked_mesh();
+        assert_eq!(baked.verts().len(), vert_count + 4);
+        for &cut_vert in &baked.verts()[vert_count..] {
+            assert!((cut_vert.x - 10.5).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_apply_transform() {
+        let mut scene = Scene::with_object("Cube", Mesh::new_cube());
+        scene.active_object_mut().transform = quarter_turn_y();
+        let polys = scene.active_mesh().polys().clone();
+
+        let object = scene.active_object_mut();
+        object.apply_transform();
+        assert!(object.transform.is_identity());
+        assert_eq!(object.mesh.polys(), &polys);
+        assert!(has_vert(&object.mesh, Vec3::new(11.0, 1.0, -2.0)));
+        assert!(has_vert(&object.mesh, Vec3::new(9.0, -1.0, 2.0)));
+        let all_polys: Vec<PolyIndex> = (0..polys.len()).collect();
+        assert!(object.mesh.signed_volume(&all_polys) > 0.0);
+    }
+
+    #[test]
+    fn test_mirror_keeps_normals_outward() {
+        let mut mesh = Mesh::new_cube();
+        let all_polys: Vec<PolyIndex> = (0..mesh.polys().len()).collect();
+        let volume = mesh.signed_volume(&all_polys);
+        let first_poly = mesh.polys()[0].clone();
+        mesh.apply_matrix(Mat4::from_scale(Vec3::new(-1.0, 1.0, 1.0)));
+        assert!((mesh.signed_volume(&all_polys) - volume).abs() < 1e-5);
+        let reversed: Vec<usize> = first_poly.iter().rev().copied().collect();
+        assert_eq!(mesh.polys()[0], reversed);
+    }
+
+    #[test]
+    fn test_join_keeps_placement() {
+        let mut scene = Scene::with_object("Base", Mesh::new_cube());
+        scene.active_object_mut().transform = Transform {
+            scale: Vec3::splat(2.0),
+            ..Transform::from_location(Vec3::Y)
+        };
+        scene.add_object("Turned", Mesh::new_cube(), quarter_turn_y());
+        let expected = scene.objects()[1].baked_mesh();
+
+        scene.join_objects(&[1]);
+        // back in scene space the joined verts are where the object was
+        let baked = scene.active_object().baked_mesh();
+        assert_eq!(baked.verts().len(), 16);
+        for &v in expected.verts() {
+            assert!(has_vert(&baked, v));
+        }
+    }
+
+    #[test]
+    fn test_baked_mesh_and_gltf_nodes() {
+        let mut scene = Scene::with_object("Base", Mesh::new_cube());
+        scene.add_object("Turned", Mesh::new_cube(), quarter_turn_y());
+        let baked = scene.baked_mesh();
+        assert_eq!(baked.polys().len(), 12);
+        assert!(has_vert(&baked, Vec3::new(1.0, 1.0, 1.0)));
+        assert!(has_vert(&baked, Vec3::new(11.0, 1.0, -2.0)));
+
+        let nodes = scene.gltf_nodes();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[1].name, "Turned");
+        assert_eq!(nodes[1].translation, Vec3::X * 10.0);
+        assert_eq!(nodes[1].scale, Vec3::new(2.0, 1.0, 1.0));
+        assert_eq!(nodes[0].rotation, Quat::IDENTITY);
+        // the meshes stay in object space
+        assert_eq!(nodes[1].mesh.verts(), Mesh::new_cube().verts());
+    }
+
+    #[test]
+    fn test_native_round_trip() {
+        let mut scene = Scene::with_object("Cubes", two_cubes());
+        scene.separate_polys(&second_cube()).unwrap();
+        scene.active_object_mut().transform = Transform::from_location(Vec3::new(1.0, 2.5, -3.0));
+        scene.set_active(1).unwrap();
+        scene.active_object_mut().transform = Transform {
+            rotation: Vec3::new(0.1, 0.2, 0.3),
+            scale: Vec3::new(1.5, 0.5, 1.0),
+            ..Transform::IDENTITY
+        };
+
+        let loaded = Scene::from_native(&scene.to_native()).unwrap();
+        assert_eq!(loaded.active_index(), 1);
+        assert_eq!(loaded.objects().len(), 2);
+        for (object, original) in loaded.objects().iter().zip(scene.objects()) {
+            assert_eq!(object.name, original.name);
+            assert_eq!(object.transform, original.transform);
+            assert_eq!(object.mesh.verts(), original.mesh.verts());
+            assert_eq!(object.mesh.polys(), original.mesh.polys());
+        }
+    }
+
+    #[test]
+    fn test_native_plain_mesh_and_errors() {
+        let scene = Scene::from_native(&Mesh::new_cube().to_native()).unwrap();
+        assert_eq!(scene.objects().len(), 1);
+        assert_eq!(scene.active_mesh().polys().len(), 6);
+
+        let empty = r#"{"format": "mesh_editor_scene", "version": 1, "active": 0, "objects": []}"#;
+        assert_eq!(
+            Scene::from_native(empty).err(),
+            Some(NativeError::Invalid(String::from("objects")))
+        );
+        let mesh = Mesh::new_cube().to_native();
+        let bad_active = format!(
+            r#"{{"format": "mesh_editor_scene", "version": 1, "active": 3, "objects": [{{"name": "a", "location": [0, 0, 0], "mesh": {}}}]}}"#,
+            mesh
+        );
+        assert_eq!(
+            Scene::from_native(&bad_active).err(),
+            Some(NativeError::Invalid(String::from("active")))
+        );
+        let newer = r#"{"format": "mesh_editor_scene", "version": 9, "active": 0, "objects": []}"#;
+        assert!(matches!(
+            Scene::from_native(newer),
+            Err(NativeError::Format(_))
+        ));
+    }
+}
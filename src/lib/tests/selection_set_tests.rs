@@ -0,0 +1,226 @@
+// Unit tests for named selection sets.
+//
+// Test coverage includes:
+// - Saving (sorted, unique, out of range left out), replacing and removing sets
+// - Indicies following their elements through delete_vert swap removes, remove_line,
+//   remove_poly, merge, bisect, subdivide and decimate
+// - Append joining sets by name
+
+use crate::mesh::{
+    BisectKeep, DecimateTarget, LinePolicy, Mesh, Plane, SelectionSet, SetKind, SubdivisionMode,
+};
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_indicies(mesh: &Mesh, name: &str) -> Vec<usize> {
+        mesh.selection_set(name).unwrap().indicies.clone()
+    }
+
+    #[test]
+    fn test_save_replace_remove() {
+        let mut mesh = Mesh::new_cube();
+        assert!(mesh.selection_sets().is_empty());
+
+        assert_eq!(
+            mesh.save_selection_set("top", SetKind::Verts, &[7, 2, 2, 99]),
+            2
+        );
+        assert_eq!(
+            mesh.selection_set("top"),
+            Some(&SelectionSet::new("top", SetKind::Verts, &[2, 7]))
+        );
+
+        mesh.save_selection_set("sides", SetKind::Polys, &[4, 5]);
+        mesh.save_selection_set("top", SetKind::Polys, &[3]);
+        assert_eq!(mesh.selection_sets().len(), 2);
+        assert_eq!(mesh.selection_set("top").unwrap().kind, SetKind::Polys);
+
+        assert_eq!(mesh.remove_selection_set("top").unwrap().indicies, vec![3]);
+        assert_eq!(mesh.remove_selection_set("top"), None);
+        assert_eq!(mesh.selection_sets()[0].name, "sides");
+    }
+
+    #[test]
+    fn test_delete_vert_remaps_sets() {
+        let mut mesh = Mesh::new_cube();
+        mesh.save_selection_set("verts", SetKind::Verts, &[0, 3, 7]);
+        // back [5, 6, 7, 4] and top [3, 7, 6, 2] don't touch vert 0
+        mesh.save_selection_set("polys", SetKind::Polys, &[1, 3]);
+        let back_top_line = mesh.find_line((7, 6)).unwrap();
+        mesh.save_selection_set("lines", SetKind::Lines, &[back_top_line]);
+
+        // the last vert (7) moves into slot 0, polys and lines around 0 shrink or go
+        mesh.delete_vert(0).unwrap();
+        assert_eq!(set_indicies(&mesh, "verts"), vec![0, 3]);
+        assert_eq!(mesh.verts()[0], Vec3::new(-1.0, 1.0, 1.0));
+
+        let polys: Vec<Vec<Vec3>> = set_indicies(&mesh, "polys")
+            .iter()
+            .map(|&p| mesh.polys()[p].iter().map(|&v| mesh.verts()[v]).collect())
+            .collect();
+        assert_eq!(polys.len(), 2);
+        for poly in polys {
+            assert_eq!(poly.len(), 4);
+            assert!(poly.contains(&Vec3::new(-1.0, 1.0, 1.0)));
+            assert!(poly.contains(&Vec3::new(1.0, 1.0, 1.0)));
+        }
+
+        let lines = set_indicies(&mesh, "lines");
+        assert_eq!(lines.len(), 1);
+        let (a, b) = mesh.lines()[lines[0]];
+        let mut ends = [mesh.verts()[a], mesh.verts()[b]];
+        ends.sort_by(|p, q| p.x.total_cmp(&q.x));
+        assert_eq!(ends, [Vec3::new(-1.0, 1.0, 1.0), Vec3::new(1.0, 1.0, 1.0)]);
+    }
+
+    #[test]
+    fn test_remove_line_and_poly_remap_sets() {
+        let mut mesh = Mesh::new();
+        for x in 0..4 {
+            mesh.add_vert(Vec3::new(x as f32, 0.0, 0.0));
+        }
+        for a in 0..3 {
+            mesh.add_line((a, a + 1)).unwrap();
+        }
+        mesh.save_selection_set("lines", SetKind::Lines, &[0, 2]);
+        // the last line moves into slot 0
+        mesh.remove_line(0).unwrap();
+        assert_eq!(set_indicies(&mesh, "lines"), vec![0]);
+        assert_eq!(mesh.lines()[0], (2, 3));
+
+        let mut mesh = Mesh::new_cube();
+        mesh.save_selection_set("polys", SetKind::Polys, &[1, 5]);
+        mesh.remove_poly(1).unwrap();
+        assert_eq!(set_indicies(&mesh, "polys"), vec![1]);
+        assert_eq!(mesh.polys()[1], vec![1, 2, 6, 5]);
+    }
+
+    #[test]
+    fn test_merge_remaps_sets() {
+        let mut mesh = Mesh::new();
+        mesh.set_line_policy(LinePolicy::Manual);
+        mesh.add_vert(Vec3::ZERO);
+        mesh.add_vert(Vec3::X);
+        mesh.add_vert(Vec3::new(0.0001, 0.0, 0.0));
+        mesh.add_vert(Vec3::Y);
+        mesh.add_line((0, 1)).unwrap();
+        mesh.add_line((2, 1)).unwrap();
+        mesh.add_line((1, 3)).unwrap();
+        mesh.save_selection_set("verts", SetKind::Verts, &[2, 3]);
+        mesh.save_selection_set("lines", SetKind::Lines, &[1, 2]);
+
+        assert_eq!(mesh.merge_by_distance(0.001), 1);
+        assert_eq!(set_indicies(&mesh, "verts"), vec![0, 2]);
+        // the doubled line is gone, (1, 3) moved down
+        assert_eq!(mesh.lines(), &vec![(0, 1), (1, 2)]);
+        assert_eq!(set_indicies(&mesh, "lines"), vec![1]);
+    }
+
+    #[test]
+    fn test_bisect_drops_removed_verts() {
+        let mut mesh = Mesh::new_cube();
+        mesh.save_selection_set("verts", SetKind::Verts, &[0, 6]);
+        mesh.bisect(
+            Plane::new(Vec3::ZERO, Vec3::X).unwrap(),
+            BisectKeep::Front,
+            false,
+        );
+        // vert 6 (x = 1) stays, vert 0 (x = -1) is cut away
+        let verts = set_indicies(&mesh, "verts");
+        assert_eq!(verts.len(), 1);
+        assert_eq!(mesh.verts()[verts[0]], Vec3::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_bisect_splits_polys_and_lines() {
+        let mut mesh = Mesh::new_cube();
+        // front [0, 3, 2, 1] is cut in two, top [3, 7, 6, 2] too
+        mesh.save_selection_set("front", SetKind::Polys, &[0]);
+        let bottom_front = mesh.find_line((0, 1)).unwrap();
+        mesh.save_selection_set("edge", SetKind::Lines, &[bottom_front]);
+
+        mesh.bisect(
+            Plane::new(Vec3::ZERO, Vec3::X).unwrap(),
+            BisectKeep::Both,
+            false,
+        );
+        let front = set_indicies(&mesh, "front");
+        assert_eq!(front.len(), 2);
+        for poly_index in front {
+            assert!(
+                mesh.polys()[poly_index]
+                    .iter()
+                    .all(|&v| mesh.verts()[v].z == -1.0)
+            );
+        }
+        let edge = set_indicies(&mesh, "edge");
+        assert_eq!(edge.len(), 2);
+        for line_index in edge {
+            let (a, b) = mesh.lines()[line_index];
+            assert!([a, b].iter().all(|&v| mesh.verts()[v].y == -1.0));
+            assert!([a, b].iter().all(|&v| mesh.verts()[v].z == -1.0));
+        }
+    }
+
+    #[test]
+    fn test_subdivide_splits_sets() {
+        let mut mesh = Mesh::new_cube();
+        mesh.save_selection_set("top", SetKind::Polys, &[3]);
+        mesh.save_selection_set("verts", SetKind::Verts, &[0, 7]);
+        let line_count = mesh.lines().len();
+        let all_lines: Vec<usize> = (0..line_count).collect();
+        mesh.save_selection_set("lines", SetKind::Lines, &all_lines);
+
+        mesh.subdivide(SubdivisionMode::Simple, &[]);
+        // vertex points keep their index
+        assert_eq!(set_indicies(&mesh, "verts"), vec![0, 7]);
+        let top = set_indicies(&mesh, "top");
+        assert_eq!(top.len(), 4);
+        for poly_index in top {
+            assert!(
+                mesh.polys()[poly_index]
+                    .iter()
+                    .all(|&v| mesh.verts()[v].y == 1.0)
+            );
+        }
+        // each edge in two halves, the lines across the faces are new
+        assert_eq!(set_indicies(&mesh, "lines").len(), line_count * 2);
+    }
+
+    #[test]
+    fn test_decimate_keeps_sets_in_range() {
+        let mut mesh = Mesh::new_cube();
+        mesh.subdivide(SubdivisionMode::Simple, &[]);
+        let polys: Vec<usize> = (0..mesh.polys().len()).collect();
+        mesh.save_selection_set("all", SetKind::Polys, &polys);
+        let verts: Vec<usize> = (0..mesh.verts().len()).collect();
+        mesh.save_selection_set("verts", SetKind::Verts, &verts);
+
+        mesh.decimate(DecimateTarget::FaceCount(12), false);
+        // every triangle came from a poly in the set, welded verts join their survivor
+        assert_eq!(set_indicies(&mesh, "all").len(), mesh.polys().len());
+        assert_eq!(set_indicies(&mesh, "verts").len(), mesh.verts().len());
+    }
+
+    #[test]
+    fn test_append_joins_sets() {
+        let mut mesh = Mesh::new_cube();
+        mesh.save_selection_set("corner", SetKind::Verts, &[0]);
+        mesh.save_selection_set("front", SetKind::Polys, &[0]);
+
+        let mut other = Mesh::new_cube();
+        other.save_selection_set("corner", SetKind::Verts, &[1]);
+        other.save_selection_set("front", SetKind::Lines, &[0]);
+        other.save_selection_set("back", SetKind::Polys, &[1]);
+
+        mesh.append(&other, Vec3::X * 4.0);
+        assert_eq!(set_indicies(&mesh, "corner"), vec![0, 9]);
+        // a name used for another kind keeps the set already there
+        assert_eq!(mesh.selection_set("front").unwrap().kind, SetKind::Polys);
+        assert_eq!(set_indicies(&mesh, "front"), vec![0]);
+        assert_eq!(set_indicies(&mesh, "back"), vec![7]);
+    }
+}
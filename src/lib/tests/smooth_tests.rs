@@ -0,0 +1,129 @@
+// Unit tests for laplacian and taubin smoothing.
+//
+// Test coverage includes:
+// - Noise on a flat grid is smoothed out, only selected verts move
+// - Boundary, pinned and wire chain end verts stay fixed
+// - Taubin shrinks a closed shape far less than plain laplacian
+// - Strengths of 0 or less do nothing, larger than 1 are clamped
+
+use crate::mesh::{Mesh, SmoothingMode};
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    // 6 x 6 grid with the interior verts pushed up and down in a checker pattern
+    fn noisy_grid() -> Mesh {
+        let mut mesh = Mesh::new_grid(5.0, 5);
+        for v in 0..mesh.verts().len() {
+            if !mesh.is_boundary_vert(v) {
+                let offset = if v % 2 == 0 { 0.2 } else { -0.2 };
+                let p = mesh.verts()[v];
+                mesh.update_vert(v, p + coord(0.0, offset, 0.0));
+            }
+        }
+        mesh
+    }
+
+    fn max_height(mesh: &Mesh) -> f32 {
+        mesh.verts().iter().map(|v| v.y.abs()).fold(0.0, f32::max)
+    }
+
+    fn mean_radius(mesh: &Mesh) -> f32 {
+        mesh.verts().iter().map(|v| v.length()).sum::<f32>() / mesh.verts().len() as f32
+    }
+
+    #[test]
+    fn test_laplacian_removes_noise() {
+        let mut mesh = noisy_grid();
+        let all: Vec<usize> = (0..mesh.verts().len()).collect();
+        let moved = mesh.smooth_verts(&all, &[], SmoothingMode::Laplacian, 0.5, 10);
+
+        assert_eq!(moved, 16);
+        assert!(max_height(&mesh) < 0.05);
+    }
+
+    #[test]
+    fn test_boundary_and_pinned_verts_stay() {
+        let original = noisy_grid();
+        let mut mesh = original.clone();
+        let all: Vec<usize> = (0..mesh.verts().len()).collect();
+        let pinned = 7; // interior
+        mesh.smooth_verts(&all, &[pinned], SmoothingMode::Taubin, 0.5, 5);
+
+        assert_eq!(mesh.verts()[pinned], original.verts()[pinned]);
+        for v in 0..mesh.verts().len() {
+            if mesh.is_boundary_vert(v) {
+                assert_eq!(mesh.verts()[v], original.verts()[v]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_only_selected_verts_move() {
+        let original = noisy_grid();
+        let mut mesh = original.clone();
+        mesh.smooth_verts(&[7, 8], &[], SmoothingMode::Laplacian, 0.5, 3);
+
+        for v in 0..mesh.verts().len() {
+            let changed = mesh.verts()[v] != original.verts()[v];
+            assert_eq!(changed, v == 7 || v == 8, "vert {}", v);
+        }
+    }
+
+    #[test]
+    fn test_wire_chain_ends_stay() {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 1.0, 0.0));
+        mesh.add_vert(coord(2.0, 0.0, 0.0));
+        mesh.add_line((0, 1));
+        mesh.add_line((1, 2));
+
+        let moved = mesh.smooth_verts(&[0, 1, 2], &[], SmoothingMode::Laplacian, 1.0, 1);
+        assert_eq!(moved, 1);
+        assert_eq!(mesh.verts()[0], coord(0.0, 0.0, 0.0));
+        assert_eq!(mesh.verts()[1], coord(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_taubin_keeps_volume_better_than_laplacian() {
+        let sphere = Mesh::new_uv_sphere(1.0, 16, 8);
+        let all: Vec<usize> = (0..sphere.verts().len()).collect();
+
+        let mut laplacian = sphere.clone();
+        laplacian.smooth_verts(&all, &[], SmoothingMode::Laplacian, 0.5, 10);
+        let mut taubin = sphere.clone();
+        taubin.smooth_verts(&all, &[], SmoothingMode::Taubin, 0.5, 10);
+
+        let laplacian_shrink = 1.0 - mean_radius(&laplacian);
+        let taubin_shrink = (1.0 - mean_radius(&taubin)).abs();
+        assert!(laplacian_shrink > 0.05);
+        assert!(taubin_shrink < laplacian_shrink / 4.0);
+    }
+
+    #[test]
+    fn test_strength_out_of_range() {
+        let all: Vec<usize> = (0..noisy_grid().verts().len()).collect();
+
+        let mut mesh = noisy_grid();
+        assert_eq!(
+            mesh.smooth_verts(&all, &[], SmoothingMode::Taubin, 0.0, 5),
+            0
+        );
+        assert_eq!(mesh.verts(), noisy_grid().verts());
+
+        // clamped to 1
+        let mut mesh = noisy_grid();
+        assert_eq!(
+            mesh.smooth_verts(&all, &[], SmoothingMode::Taubin, 10.0, 5),
+            16
+        );
+        assert!(mesh.verts().iter().all(|v| v.is_finite()));
+    }
+}
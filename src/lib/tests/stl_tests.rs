@@ -0,0 +1,254 @@
+// Unit tests for STL import and export.
+//
+// Test coverage includes:
+// - ASCII and binary round trips (welded verts, closed result, volume)
+// - Face normals and triangulation of quads / concave n-gons on export
+// - Welding within a tolerance, rebuilding quads from coplanar triangle pairs (line sets
+//   follow the dropped diagonals)
+// - Format detection (binary headers starting with "solid")
+// - Truncated and malformed files
+
+use crate::mesh::{Mesh, SetKind, StlError, StlFormat};
+use crate::tests::helpers::all_polys;
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    fn read_f32(bytes: &[u8], offset: usize) -> f32 {
+        f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    const ASCII_TRIANGLE: &str = "solid test
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 0 1 0
+    endloop
+  endfacet
+endsolid test
+";
+
+    // ==================== round trip tests ====================
+
+    #[test]
+    fn test_ascii_round_trip_of_cube() {
+        let bytes = Mesh::new_cube().to_stl(StlFormat::Ascii);
+        let mesh = Mesh::from_stl(&bytes, 0.0, false).unwrap();
+
+        assert_eq!(mesh.verts().len(), 8);
+        assert_eq!(mesh.polys().len(), 12);
+        assert_eq!(mesh.lines().len(), 18);
+        assert!(mesh.is_closed_component(&all_polys(&mesh)));
+        assert!((mesh.signed_volume(&all_polys(&mesh)) - 8.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_binary_round_trip_of_cube() {
+        let bytes = Mesh::new_cube().to_stl(StlFormat::Binary);
+        assert_eq!(bytes.len(), 84 + 12 * 50);
+
+        let mesh = Mesh::from_stl(&bytes, 0.0, false).unwrap();
+        assert_eq!(mesh.verts().len(), 8);
+        assert_eq!(mesh.polys().len(), 12);
+        assert!((mesh.signed_volume(&all_polys(&mesh)) - 8.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_round_trip_rebuilds_quads() {
+        let bytes = Mesh::new_cube().to_stl(StlFormat::Binary);
+        let mesh = Mesh::from_stl(&bytes, 0.0, true).unwrap();
+
+        assert_eq!(mesh.polys().len(), 6);
+        assert!(mesh.polys().iter().all(|poly| poly.len() == 4));
+        assert_eq!(mesh.lines().len(), 12);
+        assert_eq!(mesh.validate(), vec![]);
+        assert!((mesh.signed_volume(&all_polys(&mesh)) - 8.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_rebuild_quads_keeps_non_coplanar_triangles() {
+        let sphere = Mesh::new_icosphere(1.0, 1);
+        let bytes = sphere.to_stl(StlFormat::Ascii);
+        let mesh = Mesh::from_stl(&bytes, 0.0, true).unwrap();
+
+        assert_eq!(mesh.polys().len(), sphere.polys().len());
+    }
+
+    #[test]
+    fn test_rebuild_quads_remaps_line_sets() {
+        let bytes = Mesh::new_cube().to_stl(StlFormat::Binary);
+        let mut mesh = Mesh::from_stl(&bytes, 0.0, false).unwrap();
+        let key = |(a, b): (usize, usize)| (a.min(b), a.max(b));
+        let mut joined = mesh.clone();
+        joined.join_coplanar_triangles();
+        let kept: Vec<(usize, usize)> = joined.lines().iter().map(|&line| key(line)).collect();
+
+        // the cube edges stored after the first diagonals move down when those are dropped
+        let late_edges: Vec<usize> = (12..mesh.lines().len())
+            .filter(|&i| kept.contains(&key(mesh.lines()[i])))
+            .collect();
+        assert!(!late_edges.is_empty());
+        let edges: Vec<(usize, usize)> = late_edges.iter().map(|&i| mesh.lines()[i]).collect();
+        mesh.save_selection_set("edges", SetKind::Lines, &late_edges);
+
+        mesh.join_coplanar_triangles();
+        let set_lines: Vec<(usize, usize)> = mesh
+            .selection_set("edges")
+            .unwrap()
+            .indicies
+            .iter()
+            .map(|&i| mesh.lines()[i])
+            .collect();
+        assert_eq!(set_lines, edges);
+    }
+
+    // ==================== export tests ====================
+
+    #[test]
+    fn test_binary_export_writes_face_normals() {
+        let mut mesh = Mesh::new();
+        let a = mesh.add_vert(coord(0.0, 0.0, 0.0));
+        let b = mesh.add_vert(coord(2.0, 0.0, 0.0));
+        let c = mesh.add_vert(coord(0.0, 0.0, -2.0));
+        mesh.add_poly(vec![a, b, c]);
+
+        let bytes = mesh.to_stl(StlFormat::Binary);
+        assert!(!bytes.starts_with(b"solid"));
+        assert_eq!(u32::from_le_bytes(bytes[80..84].try_into().unwrap()), 1);
+        let normal = coord(
+            read_f32(&bytes, 84),
+            read_f32(&bytes, 88),
+            read_f32(&bytes, 92),
+        );
+        assert_eq!(normal, coord(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn test_export_triangulates_concave_polys() {
+        let mut mesh = Mesh::new();
+        let l_shape = [
+            coord(0.0, 0.0, 0.0),
+            coord(2.0, 0.0, 0.0),
+            coord(2.0, 1.0, 0.0),
+            coord(1.0, 1.0, 0.0),
+            coord(1.0, 2.0, 0.0),
+            coord(0.0, 2.0, 0.0),
+        ];
+        let verts: Vec<usize> = l_shape.iter().map(|&v| mesh.add_vert(v)).collect();
+        mesh.add_poly(verts);
+
+        let text = String::from_utf8(mesh.to_stl(StlFormat::Ascii)).unwrap();
+        assert_eq!(text.matches("facet normal 0 0 1").count(), 4);
+
+        let imported = Mesh::from_stl(text.as_bytes(), 0.0, false).unwrap();
+        let area: f32 = all_polys(&imported)
+            .iter()
+            .map(|&p| imported.poly_newell_normal(&imported.polys()[p]).length() / 2.0)
+            .sum();
+        assert!((area - 3.0).abs() < 1e-5);
+    }
+
+    // ==================== import tests ====================
+
+    #[test]
+    fn test_import_ascii_triangle() {
+        let mesh = Mesh::from_stl(ASCII_TRIANGLE.as_bytes(), 0.0, false).unwrap();
+        assert_eq!(mesh.verts().len(), 3);
+        assert_eq!(mesh.polys(), &vec![vec![0, 1, 2]]);
+        assert_eq!(mesh.lines().len(), 3);
+    }
+
+    #[test]
+    fn test_import_welds_within_tolerance() {
+        let text = ASCII_TRIANGLE.replace("endsolid test", "")
+            + "  facet normal 0 0 1
+    outer loop
+      vertex 1.00001 0 0
+      vertex 1 1 0
+      vertex 0 1.00001 0
+    endloop
+  endfacet
+endsolid test
+";
+        let exact = Mesh::from_stl(text.as_bytes(), 0.0, false).unwrap();
+        assert_eq!(exact.verts().len(), 6);
+
+        let welded = Mesh::from_stl(text.as_bytes(), 0.001, false).unwrap();
+        assert_eq!(welded.verts().len(), 4);
+        assert_eq!(welded.lines().len(), 5);
+    }
+
+    #[test]
+    fn test_binary_with_solid_header_is_detected() {
+        let mut bytes = Mesh::new_cube().to_stl(StlFormat::Binary);
+        bytes[..5].copy_from_slice(b"solid");
+
+        let mesh = Mesh::from_stl(&bytes, 0.0, false).unwrap();
+        assert_eq!(mesh.polys().len(), 12);
+    }
+
+    // ==================== error tests ====================
+
+    #[test]
+    fn test_truncated_binary() {
+        let bytes = Mesh::new_cube().to_stl(StlFormat::Binary);
+
+        assert_eq!(
+            Mesh::from_stl(&bytes[..bytes.len() - 10], 0.0, false).err(),
+            Some(StlError::Truncated {
+                expected_bytes: 684,
+                actual_bytes: 674
+            })
+        );
+        assert_eq!(
+            Mesh::from_stl(&bytes[..40], 0.0, false).err(),
+            Some(StlError::Truncated {
+                expected_bytes: 84,
+                actual_bytes: 40
+            })
+        );
+    }
+
+    #[test]
+    fn test_truncated_ascii() {
+        let text = &ASCII_TRIANGLE[..ASCII_TRIANGLE.find("endloop").unwrap()];
+        assert_eq!(
+            Mesh::from_stl(text.as_bytes(), 0.0, false).err(),
+            Some(StlError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn test_malformed_ascii() {
+        let bad_number = ASCII_TRIANGLE.replace("vertex 1 0 0", "vertex 1 zero 0");
+        let Some(StlError::Parse { line, message }) =
+            Mesh::from_stl(bad_number.as_bytes(), 0.0, false).err()
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(line, 5);
+        assert!(message.contains("zero"));
+
+        let missing_corner = ASCII_TRIANGLE.replace("      vertex 0 1 0\n", "");
+        assert!(matches!(
+            Mesh::from_stl(missing_corner.as_bytes(), 0.0, false),
+            Err(StlError::Parse { line: 6, .. })
+        ));
+    }
+
+    #[test]
+    fn test_non_finite_coords() {
+        let text = ASCII_TRIANGLE.replace("vertex 0 1 0", "vertex 0 NaN 0");
+        assert_eq!(
+            Mesh::from_stl(text.as_bytes(), 0.0, false).err(),
+            Some(StlError::NonFiniteCoord { triangle: 0 })
+        );
+    }
+}
@@ -0,0 +1,165 @@
+// Unit tests for simple and Catmull-Clark subdivision.
+//
+// Test coverage includes:
+// - Topology: vert / poly / line counts after one and multiple levels
+// - Simple mode: original verts untouched, edge + face points are linear
+// - Catmull-Clark: smoothing of closed meshes, boundary and crease handling
+// - Wire lines that are not part of any poly
+// - UVs interpolated onto the new corners
+
+use crate::mesh::{Mesh, SubdivisionMode};
+use macroquad::prelude::{Vec2, Vec3};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    fn unit_quad() -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 1.0, 0.0));
+        mesh.add_vert(coord(0.0, 1.0, 0.0));
+        mesh.add_line((0, 1));
+        mesh.add_line((1, 2));
+        mesh.add_line((2, 3));
+        mesh.add_line((3, 0));
+        mesh.add_poly(vec![0, 1, 2, 3]);
+        mesh
+    }
+
+    // ==================== topology tests ====================
+
+    #[test]
+    fn test_cube_subdivision_counts() {
+        let mesh = Mesh::new_cube().subdivided(SubdivisionMode::CatmullClark, 1, &[]);
+        // 8 verts + 12 edge points + 6 face points
+        assert_eq!(mesh.verts().len(), 26);
+        assert_eq!(mesh.polys().len(), 24);
+        assert_eq!(mesh.lines().len(), 48);
+    }
+
+    #[test]
+    fn test_two_levels_of_subdivision() {
+        let mesh = Mesh::new_cube().subdivided(SubdivisionMode::Simple, 2, &[]);
+        assert_eq!(mesh.polys().len(), 96);
+        assert_eq!(mesh.verts().len(), 98);
+    }
+
+    #[test]
+    fn test_zero_levels_is_a_copy() {
+        let cube = Mesh::new_cube();
+        let mesh = cube.subdivided(SubdivisionMode::CatmullClark, 0, &[]);
+        assert_eq!(mesh.verts(), cube.verts());
+        assert_eq!(mesh.polys(), cube.polys());
+    }
+
+    #[test]
+    fn test_triangle_becomes_three_quads() {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 0.0, 0.0));
+        mesh.add_vert(coord(0.0, 1.0, 0.0));
+        mesh.add_poly(vec![0, 1, 2]);
+
+        mesh.subdivide(SubdivisionMode::Simple, &[]);
+        assert_eq!(mesh.polys().len(), 3);
+        assert!(mesh.polys().iter().all(|poly| poly.len() == 4));
+    }
+
+    // ==================== simple mode tests ====================
+
+    #[test]
+    fn test_simple_keeps_original_verts() {
+        let mut mesh = unit_quad();
+        mesh.subdivide(SubdivisionMode::Simple, &[]);
+        assert_eq!(mesh.verts()[0], coord(0.0, 0.0, 0.0));
+        assert_eq!(mesh.verts()[2], coord(1.0, 1.0, 0.0));
+        // face point is last
+        assert_eq!(*mesh.verts().last().unwrap(), coord(0.5, 0.5, 0.0));
+    }
+
+    #[test]
+    fn test_subdivided_quad_keeps_winding() {
+        let mut mesh = unit_quad();
+        mesh.subdivide(SubdivisionMode::Simple, &[]);
+        for poly in mesh.polys() {
+            let a = mesh.verts()[poly[0]];
+            let b = mesh.verts()[poly[1]];
+            let c = mesh.verts()[poly[2]];
+            assert!((b - a).cross(c - a).z > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_uvs_are_interpolated() {
+        let mut mesh = unit_quad();
+        let uvs = mesh.verts().iter().map(|v| Vec2::new(v.x, v.y)).collect();
+        mesh.set_poly_uvs(0, uvs).unwrap();
+        mesh.subdivide(SubdivisionMode::Simple, &[]);
+
+        // the quad's UVs follow x / y, so every new corner's UV matches its vert
+        for (poly_index, poly) in mesh.polys().iter().enumerate() {
+            let uvs = mesh.poly_uvs(poly_index).unwrap();
+            for (&v, uv) in poly.iter().zip(uvs) {
+                assert_eq!(*uv, Vec2::new(mesh.verts()[v].x, mesh.verts()[v].y));
+            }
+        }
+    }
+
+    // ==================== catmull-clark tests ====================
+
+    #[test]
+    fn test_catmull_clark_shrinks_cube_corners() {
+        let mesh = Mesh::new_cube().subdivided(SubdivisionMode::CatmullClark, 1, &[]);
+        let corner = mesh.verts()[6];
+        // classic result for a cube corner: 5/9 of the original position
+        assert!((corner - coord(5.0 / 9.0, 5.0 / 9.0, 5.0 / 9.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_catmull_clark_open_quad_keeps_corners() {
+        // every corner of a single quad has two boundary edges
+        let mut mesh = unit_quad();
+        mesh.subdivide(SubdivisionMode::CatmullClark, &[]);
+        let corner = mesh.verts()[0];
+        assert!((corner - coord(0.125, 0.125, 0.0)).length() < 1e-5);
+        // boundary edge points are plain midpoints
+        assert!(mesh.verts().contains(&coord(0.5, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_fully_creased_cube_keeps_shape() {
+        let cube = Mesh::new_cube();
+        let creases: Vec<usize> = (0..cube.lines().len()).collect();
+        let mesh = cube.subdivided(SubdivisionMode::CatmullClark, 1, &creases);
+        for v in mesh.verts().iter().take(8) {
+            assert!((v.abs() - Vec3::ONE).length() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_subdivide_returns_split_creases() {
+        let mut mesh = Mesh::new_cube();
+        let creases = mesh.subdivide(SubdivisionMode::CatmullClark, &[0, 1]);
+        assert_eq!(creases.len(), 4);
+    }
+
+    // ==================== wire line tests ====================
+
+    #[test]
+    fn test_wire_line_is_split() {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(2.0, 0.0, 0.0));
+        mesh.add_line((0, 1));
+
+        mesh.subdivide(SubdivisionMode::CatmullClark, &[]);
+        assert_eq!(mesh.lines().len(), 2);
+        assert_eq!(mesh.verts()[2], coord(1.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,199 @@
+// Unit tests for SVG blueprint export.
+//
+// Test coverage includes:
+// - Projection axes of each plane, flipped views
+// - Lines and poly edges drawn once, edges seen end-on skipped
+// - Hidden-line removal: fully hidden, partly hidden and penetrating lines
+// - Filled polys sorted far to near, front / back facing fills
+// - Dimensioned bounding box frame
+
+use crate::mesh::{Mesh, ProjectionPlane, SvgExportOptions};
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(plane: ProjectionPlane) -> SvgExportOptions {
+        SvgExportOptions {
+            plane,
+            is_flipped: false,
+            fill_polys: false,
+            hidden_line_removal: false,
+            dimension_frame: false,
+            scale: 1.0,
+        }
+    }
+
+    fn attribute(element: &str, name: &str) -> f32 {
+        let start = element.find(&format!(" {}=\"", name)).unwrap() + name.len() + 3;
+        let end = start + element[start..].find('"').unwrap();
+        element[start..end].parse().unwrap()
+    }
+
+    // x1, y1, x2, y2 of every <line> (frame off, so these are the mesh's lines)
+    fn lines(svg: &str) -> Vec<[f32; 4]> {
+        svg.lines()
+            .filter(|line| line.starts_with("<line "))
+            .map(|line| ["x1", "y1", "x2", "y2"].map(|name| attribute(line, name)))
+            .collect()
+    }
+
+    // wire line behind (z = -1) or through a quad at z = 0 covering -1..1 in x and y
+    fn quad_with_line(start: Vec3, end: Vec3) -> Mesh {
+        let mut mesh = Mesh::new();
+        let quad = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .map(|(x, y)| mesh.add_vert(Vec3::new(x, y, 0.0)));
+        mesh.add_poly(quad.to_vec()).unwrap();
+        let a = mesh.add_vert(start);
+        let b = mesh.add_vert(end);
+        mesh.add_line((a, b)).unwrap();
+        mesh
+    }
+
+    fn assert_segments(actual: &[[f32; 4]], expected: &[[f32; 4]]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            for (a, e) in a.iter().zip(e) {
+                assert!((a - e).abs() < 1e-2, "{:?} != {:?}", actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_projection_axes() {
+        assert_eq!(ProjectionPlane::XY.axes(false), (Vec3::X, Vec3::Y, Vec3::Z));
+        assert_eq!(
+            ProjectionPlane::XZ.axes(false),
+            (Vec3::NEG_X, Vec3::Z, Vec3::Y)
+        );
+        assert_eq!(
+            ProjectionPlane::YZ.axes(false),
+            (Vec3::NEG_Z, Vec3::Y, Vec3::X)
+        );
+        assert_eq!(
+            ProjectionPlane::XY.axes(true),
+            (Vec3::NEG_X, Vec3::Y, Vec3::NEG_Z)
+        );
+    }
+
+    #[test]
+    fn test_document_structure() {
+        let svg = Mesh::new_cube().to_svg(&options(ProjectionPlane::XY));
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        // 2 units + 10mm margin on each side
+        assert!(svg.contains("width=\"22mm\" height=\"22mm\" viewBox=\"-11 -11 22 22\""));
+
+        let empty = Mesh::new().to_svg(&options(ProjectionPlane::XY));
+        assert!(empty.ends_with("</svg>\n"));
+        assert!(lines(&empty).is_empty());
+    }
+
+    #[test]
+    fn test_cube_edges_without_hidden_line_removal() {
+        // 12 edges, the 4 along z are seen end-on; front and back edges overlap
+        let svg = Mesh::new_cube().to_svg(&options(ProjectionPlane::XY));
+        assert_eq!(lines(&svg).len(), 8);
+    }
+
+    #[test]
+    fn test_cube_hidden_line_removal() {
+        let mut options = options(ProjectionPlane::XZ);
+        options.hidden_line_removal = true;
+        let svg = Mesh::new_cube().to_svg(&options);
+        let lines = lines(&svg);
+        // only the outline of the top face is left
+        assert_eq!(lines.len(), 4);
+        assert_eq!(Mesh::new_cube().to_svg(&options), svg);
+    }
+
+    #[test]
+    fn test_line_partly_behind_poly() {
+        let mesh = quad_with_line(Vec3::new(-2.0, 0.0, -1.0), Vec3::new(2.0, 0.0, -1.0));
+        let mut options = options(ProjectionPlane::XY);
+        options.hidden_line_removal = true;
+        let drawn = lines(&mesh.to_svg(&options));
+        // quad outline + the two ends of the line sticking out
+        let wire: Vec<[f32; 4]> = drawn.into_iter().filter(|l| l[1] == 0.0).collect();
+        assert_segments(&wire, &[[-2.0, 0.0, -1.0, 0.0], [1.0, 0.0, 2.0, 0.0]]);
+
+        // seen from behind the line is in front of the quad
+        options.is_flipped = true;
+        let drawn = lines(&mesh.to_svg(&options));
+        let wire: Vec<[f32; 4]> = drawn.into_iter().filter(|l| l[1] == 0.0).collect();
+        assert_segments(&wire, &[[2.0, 0.0, -2.0, 0.0]]);
+    }
+
+    #[test]
+    fn test_line_through_poly() {
+        let mesh = quad_with_line(Vec3::new(-2.0, 0.0, -1.0), Vec3::new(2.0, 0.0, 1.0));
+        let mut options = options(ProjectionPlane::XY);
+        options.hidden_line_removal = true;
+        let drawn = lines(&mesh.to_svg(&options));
+        let wire: Vec<[f32; 4]> = drawn.into_iter().filter(|l| l[1] == 0.0).collect();
+        // behind the quad until it pierces it at x = 0
+        assert_segments(&wire, &[[-2.0, 0.0, -1.0, 0.0], [0.0, 0.0, 2.0, 0.0]]);
+    }
+
+    #[test]
+    fn test_poly_edges_drawn_without_lines() {
+        let mut mesh = quad_with_line(Vec3::new(3.0, 0.0, 0.0), Vec3::new(4.0, 0.0, 0.0));
+        let svg = mesh.to_svg(&options(ProjectionPlane::XY));
+        assert_eq!(lines(&svg).len(), 5);
+
+        // an edge seen end-on is dropped
+        mesh.add_vert(Vec3::new(3.0, 0.0, 5.0));
+        mesh.add_line((4, 6)).unwrap();
+        let svg = mesh.to_svg(&options(ProjectionPlane::XY));
+        assert_eq!(lines(&svg).len(), 5);
+    }
+
+    #[test]
+    fn test_filled_polys_far_to_near() {
+        let mut mesh = Mesh::new();
+        for z in [1.0, -1.0] {
+            let quad = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                .map(|(x, y)| mesh.add_vert(Vec3::new(x + z, y, z)));
+            mesh.add_poly(quad.to_vec()).unwrap();
+        }
+        let mut options = options(ProjectionPlane::XY);
+        options.fill_polys = true;
+        let svg = mesh.to_svg(&options);
+        let polygons: Vec<&str> = svg
+            .lines()
+            .filter(|line| line.starts_with("<polygon "))
+            .collect();
+        assert_eq!(polygons.len(), 2);
+        // the far quad (shifted to -x) first, both facing the viewer
+        assert!(polygons[0].starts_with("<polygon points=\"-2,1 0,1 0,-1 -2,-1\""));
+        assert!(polygons.iter().all(|p| p.contains("fill=\"#e0e0e0\"")));
+
+        options.is_flipped = true;
+        let svg = mesh.to_svg(&options);
+        let polygons: Vec<&str> = svg
+            .lines()
+            .filter(|line| line.starts_with("<polygon "))
+            .collect();
+        assert!(polygons[0].starts_with("<polygon points=\"0,1 -2,1 -2,-1 0,-1\""));
+        assert!(polygons.iter().all(|p| p.contains("fill=\"#b0b0b0\"")));
+    }
+
+    #[test]
+    fn test_dimension_frame() {
+        let mut options = options(ProjectionPlane::XZ);
+        options.dimension_frame = true;
+        options.scale = 10.0;
+        let svg = Mesh::new_tapered_box().to_svg(&options);
+        // 2 wide in x, 4 deep in z, in world units
+        assert!(svg.contains(">X 2</text>"));
+        assert!(svg.contains(">Z 4</text>"));
+        assert!(svg.contains("<rect x=\"-10\" y=\"-20\" width=\"20\" height=\"40\""));
+        // arrow heads on both ends of both dimension lines
+        let arrows = svg
+            .lines()
+            .filter(|line| line.starts_with("<polygon ") && line.contains("fill=\"black\""))
+            .count();
+        assert_eq!(arrows, 4);
+    }
+}
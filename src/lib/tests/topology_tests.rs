@@ -0,0 +1,212 @@
+// Unit tests for the adjacency index kept on Mesh.
+//
+// Test coverage includes:
+// - vert -> polys / lines, edge -> polys, poly neighbors and one-ring queries
+// - Boundary edges and boundary loops on open meshes, unclosed chains left out
+// - Incremental updates after add / remove of lines and polys (swap_remove remapping) and
+//   vert deletion (matching the adjacency worked out from scratch)
+// - Rebuild after bulk operations
+
+use crate::mesh::{Mesh, SubdivisionMode};
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    fn sorted(mut indicies: Vec<usize>) -> Vec<usize> {
+        indicies.sort();
+        indicies
+    }
+
+    // two quads sharing the edge 1 - 4
+    fn two_quads() -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0)); // 0
+        mesh.add_vert(coord(1.0, 0.0, 0.0)); // 1
+        mesh.add_vert(coord(2.0, 0.0, 0.0)); // 2
+        mesh.add_vert(coord(0.0, 1.0, 0.0)); // 3
+        mesh.add_vert(coord(1.0, 1.0, 0.0)); // 4
+        mesh.add_vert(coord(2.0, 1.0, 0.0)); // 5
+        mesh.add_poly(vec![0, 1, 4, 3]);
+        mesh.add_poly(vec![1, 2, 5, 4]);
+        mesh
+    }
+
+    // every query agrees with the adjacency worked out from the lines and polys
+    fn assert_topology_consistent(mesh: &Mesh) {
+        for v in 0..mesh.verts().len() {
+            let polys: Vec<usize> = (0..mesh.polys().len())
+                .filter(|&p| mesh.polys()[p].contains(&v))
+                .collect();
+            let lines: Vec<usize> = (0..mesh.lines().len())
+                .filter(|&l| mesh.lines()[l].0 == v || mesh.lines()[l].1 == v)
+                .collect();
+            assert_eq!(sorted(mesh.vert_polys(v).to_vec()), polys);
+            assert_eq!(sorted(mesh.vert_lines(v).to_vec()), lines);
+        }
+        for (p, poly) in mesh.polys().iter().enumerate() {
+            for i in 0..poly.len() {
+                let edge = (poly[i], poly[(i + 1) % poly.len()]);
+                assert!(mesh.edge_polys(edge).contains(&p));
+            }
+        }
+        let edge_count: usize = mesh
+            .topology()
+            .edge_polys_map()
+            .values()
+            .map(Vec::len)
+            .sum();
+        let poly_edge_count: usize = mesh.polys().iter().map(Vec::len).sum();
+        assert_eq!(edge_count, poly_edge_count);
+    }
+
+    // ==================== query tests ====================
+
+    #[test]
+    fn test_cube_vert_polys() {
+        let mesh = Mesh::new_cube();
+        // front, bottom, left
+        assert_eq!(sorted(mesh.vert_polys(0).to_vec()), vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_cube_vert_lines() {
+        let mesh = Mesh::new_cube();
+        assert_eq!(sorted(mesh.vert_lines(0).to_vec()), vec![0, 3, 9]);
+    }
+
+    #[test]
+    fn test_edge_polys_ignore_direction() {
+        let mesh = two_quads();
+        assert_eq!(sorted(mesh.edge_polys((1, 4)).to_vec()), vec![0, 1]);
+        assert_eq!(sorted(mesh.edge_polys((4, 1)).to_vec()), vec![0, 1]);
+        assert!(mesh.edge_polys((0, 5)).is_empty());
+    }
+
+    #[test]
+    fn test_poly_neighbors() {
+        let mesh = Mesh::new_cube();
+        // front touches bottom, top, left, right but not back
+        assert_eq!(sorted(mesh.poly_neighbors(0)), vec![2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_one_ring_includes_wire_lines() {
+        let mut mesh = two_quads();
+        let wire = mesh.add_vert(coord(1.0, -1.0, 0.0));
+        mesh.add_line((1, wire));
+        assert_eq!(sorted(mesh.one_ring(1)), vec![0, 2, 4, wire]);
+    }
+
+    #[test]
+    fn test_out_of_range_queries_are_empty() {
+        let mesh = two_quads();
+        assert!(mesh.vert_polys(99).is_empty());
+        assert!(mesh.vert_lines(99).is_empty());
+        assert!(mesh.poly_neighbors(99).is_empty());
+    }
+
+    // ==================== boundary tests ====================
+
+    #[test]
+    fn test_closed_mesh_has_no_boundary() {
+        let mesh = Mesh::new_cube();
+        assert!(mesh.boundary_edges().is_empty());
+        assert!(mesh.boundary_loops().is_empty());
+        assert!(!mesh.is_boundary_vert(0));
+    }
+
+    #[test]
+    fn test_boundary_loop_of_open_strip() {
+        let mesh = two_quads();
+        assert_eq!(mesh.boundary_edges().len(), 6);
+        assert!(!mesh.is_boundary_edge((1, 4)));
+
+        let loops = mesh.boundary_loops();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0], vec![0, 1, 2, 5, 4, 3]);
+    }
+
+    #[test]
+    fn test_cube_with_two_holes_has_two_loops() {
+        let mut mesh = Mesh::new_cube();
+        mesh.remove_poly(1); // back
+        mesh.remove_poly(0); // front
+        assert_eq!(mesh.boundary_loops().len(), 2);
+    }
+
+    #[test]
+    fn test_unclosed_chain_is_not_a_loop() {
+        // three triangles on the edge 0 - 1: vert 0 has two boundary edges coming in and
+        // one going out, so the walk 1 -> 2 -> 0 runs into a dead end
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 0.0, 0.0));
+        mesh.add_vert(coord(0.5, 1.0, 0.0));
+        mesh.add_vert(coord(0.5, -1.0, 0.0));
+        mesh.add_vert(coord(0.5, 0.0, 1.0));
+        mesh.add_poly(vec![0, 1, 2]);
+        mesh.add_poly(vec![1, 0, 3]);
+        mesh.add_poly(vec![0, 1, 4]);
+        assert_eq!(mesh.boundary_loops(), vec![vec![0, 3, 1, 4]]);
+    }
+
+    // ==================== incremental update tests ====================
+
+    #[test]
+    fn test_remove_poly_remaps_swapped_poly() {
+        let mut mesh = Mesh::new_cube();
+        mesh.remove_poly(0);
+        // right face (5) moved into slot 0
+        assert_eq!(sorted(mesh.vert_polys(1).to_vec()), vec![0, 2]);
+        assert_eq!(sorted(mesh.edge_polys((1, 2)).to_vec()), vec![0]);
+    }
+
+    #[test]
+    fn test_remove_line_remaps_swapped_line() {
+        let mut mesh = Mesh::new_cube();
+        mesh.remove_line(0);
+        // (6, 2) moved into slot 0
+        assert_eq!(sorted(mesh.vert_lines(0).to_vec()), vec![3, 9]);
+        assert_eq!(sorted(mesh.vert_lines(2).to_vec()), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_delete_vert_updates_topology() {
+        let mut mesh = two_quads();
+        mesh.delete_vert(0);
+        // vert 5 swapped into slot 0, poly 0 lost a corner and is now a triangle
+        assert_eq!(sorted(mesh.vert_polys(0).to_vec()), vec![1]);
+        assert_eq!(sorted(mesh.one_ring(4)), vec![0, 1, 3]);
+        assert_topology_consistent(&mesh);
+    }
+
+    #[test]
+    fn test_delete_vert_removes_degenerate_polys() {
+        let mut mesh = Mesh::new_cube();
+        mesh.add_vert(coord(0.0, 3.0, 0.0));
+        mesh.add_poly(vec![3, 8, 2]);
+        mesh.add_poly(vec![0, 8, 3]);
+        // both triangles shrink to two corners, the cube faces around vert 3 to triangles
+        mesh.delete_vert(3);
+        assert_eq!(mesh.polys().len(), 6);
+        assert_topology_consistent(&mesh);
+
+        // the last vert (the apex) was swapped into the deleted slot
+        mesh.delete_vert(0);
+        assert_topology_consistent(&mesh);
+    }
+
+    #[test]
+    fn test_bulk_operation_rebuilds_topology() {
+        let mut mesh = Mesh::new_cube();
+        mesh.subdivide(SubdivisionMode::Simple, &[]);
+        assert_eq!(mesh.vert_polys(0).len(), 3);
+        assert_eq!(mesh.one_ring(0).len(), 3);
+    }
+}
@@ -0,0 +1,277 @@
+// Unit tests for per-corner UVs and the UV projections.
+//
+// Test coverage includes:
+// - Setting / clearing UVs, corner count checks
+// - Planar, box and cylindrical projections (range, mirroring, seams, indicies out of range)
+// - UVs following their polys through remove_poly, delete_vert, flip, merge, loop cut,
+//   append and bisect (cut pieces get interpolated UVs)
+
+use crate::mesh::{Axis, BisectKeep, Mesh, Plane, ProjectionPlane};
+use crate::tests::helpers::all_polys;
+use macroquad::prelude::{Vec2, Vec3};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uv(u: f32, v: f32) -> Vec2 {
+        Vec2::new(u, v)
+    }
+
+    fn assert_uv_eq(actual: Vec2, expected: Vec2) {
+        assert!(
+            actual.distance(expected) < 1e-5,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    // signed area of the poly's UVs, positive when counter-clockwise
+    fn uv_area(uvs: &[Vec2]) -> f32 {
+        (0..uvs.len())
+            .map(|i| uvs[i].perp_dot(uvs[(i + 1) % uvs.len()]))
+            .sum::<f32>()
+            / 2.0
+    }
+
+    // quad in the xy plane, corners (0,0) (1,0) (1,1) (0,1) with matching UVs
+    fn uv_quad() -> Mesh {
+        let mut mesh = Mesh::new();
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)] {
+            mesh.add_vert(Vec3::new(x, y, 0.0));
+        }
+        mesh.add_poly(vec![0, 1, 2, 3]).unwrap();
+        mesh.set_poly_uvs(
+            0,
+            vec![uv(0.0, 0.0), uv(1.0, 0.0), uv(1.0, 1.0), uv(0.0, 1.0)],
+        )
+        .unwrap();
+        mesh
+    }
+
+    #[test]
+    fn test_set_and_clear_uvs() {
+        let mut mesh = Mesh::new_cube();
+        assert!(!mesh.has_uvs());
+        assert_eq!(mesh.poly_uvs(0), None);
+
+        // one per corner or nothing
+        assert_eq!(mesh.set_poly_uvs(0, vec![uv(0.0, 0.0)]), None);
+        assert_eq!(mesh.set_poly_uvs(99, Vec::new()), None);
+        let uvs = vec![uv(0.0, 0.0), uv(1.0, 0.0), uv(1.0, 1.0), uv(0.0, 1.0)];
+        mesh.set_poly_uvs(0, uvs.clone()).unwrap();
+        assert!(mesh.has_uvs());
+        assert_eq!(mesh.poly_uvs(0), Some(uvs.as_slice()));
+
+        mesh.clear_uvs();
+        assert!(!mesh.has_uvs());
+    }
+
+    #[test]
+    fn test_planar_projection() {
+        let mut mesh = Mesh::new_cube();
+        let polys = all_polys(&mesh);
+        mesh.project_uvs_planar(&polys, ProjectionPlane::XY, false);
+
+        // x and y of the cube (-1..1) map straight to 0..1
+        for (poly_index, poly) in mesh.polys().iter().enumerate() {
+            let uvs = mesh.poly_uvs(poly_index).unwrap();
+            for (&v, &corner_uv) in poly.iter().zip(uvs) {
+                let coord = mesh.verts()[v];
+                assert_uv_eq(corner_uv, uv((coord.x + 1.0) / 2.0, (coord.y + 1.0) / 2.0));
+            }
+        }
+
+        // seen from behind u is mirrored
+        mesh.project_uvs_planar(&polys, ProjectionPlane::XY, true);
+        let uvs = mesh.poly_uvs(0).unwrap();
+        let coord = mesh.verts()[mesh.polys()[0][0]];
+        assert_uv_eq(uvs[0], uv((1.0 - coord.x) / 2.0, (coord.y + 1.0) / 2.0));
+    }
+
+    #[test]
+    fn test_planar_projection_keeps_aspect() {
+        // tapered box: 2 wide in x, 4 deep in z
+        let mut mesh = Mesh::new_tapered_box();
+        let polys = all_polys(&mesh);
+        mesh.project_uvs_planar(&polys, ProjectionPlane::XZ, false);
+        let uvs: Vec<Vec2> = (0..polys.len())
+            .flat_map(|p| mesh.poly_uvs(p).unwrap().to_vec())
+            .collect();
+        let (min, max) = uvs.iter().fold((Vec2::MAX, Vec2::MIN), |(min, max), &p| {
+            (min.min(p), max.max(p))
+        });
+        assert_uv_eq(min, uv(0.25, 0.0));
+        assert_uv_eq(max, uv(0.75, 1.0));
+    }
+
+    #[test]
+    fn test_only_given_polys_projected() {
+        let mut mesh = Mesh::new_cube();
+        mesh.project_uvs_planar(&[2], ProjectionPlane::XY, false);
+        assert!(mesh.poly_uvs(2).is_some());
+        assert!(mesh.poly_uvs(0).is_none());
+    }
+
+    #[test]
+    fn test_box_projection() {
+        let mut mesh = Mesh::new_cube();
+        mesh.orient_outward();
+        let polys = all_polys(&mesh);
+        mesh.project_uvs_box(&polys);
+
+        for poly_index in polys {
+            let uvs = mesh.poly_uvs(poly_index).unwrap();
+            // every face covers the whole square, none of them mirrored
+            assert!((uv_area(uvs) - 1.0).abs() < 1e-5, "{:?}", uvs);
+            for corner_uv in uvs {
+                assert!(corner_uv.x.abs() < 1e-5 || (corner_uv.x - 1.0).abs() < 1e-5);
+                assert!(corner_uv.y.abs() < 1e-5 || (corner_uv.y - 1.0).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cylindrical_projection() {
+        let segments = 8;
+        let mut mesh = Mesh::new_cylinder(1.0, 2.0, segments);
+        let sides: Vec<usize> = (0..segments).collect();
+        mesh.project_uvs_cylindrical(&sides, Axis::Y);
+
+        let mut seam_count = 0;
+        for &poly_index in sides.iter() {
+            let uvs = mesh.poly_uvs(poly_index).unwrap();
+            let (min_u, max_u) = uvs.iter().fold((f32::MAX, f32::MIN), |(min, max), p| {
+                (min.min(p.x), max.max(p.x))
+            });
+            // each side spans one segment, also the one across the seam
+            assert!(
+                (max_u - min_u - 1.0 / segments as f32).abs() < 1e-4,
+                "{:?}",
+                uvs
+            );
+            if max_u > 1.0 {
+                seam_count += 1;
+            }
+            for corner_uv in uvs {
+                assert!(corner_uv.y.abs() < 1e-5 || (corner_uv.y - 1.0).abs() < 1e-5);
+            }
+        }
+        assert!(seam_count <= 1);
+    }
+
+    #[test]
+    fn test_cylindrical_projection_axis_vert() {
+        // the corner on the axis (through the middle of the poly) takes the others' u
+        let mut mesh = Mesh::new();
+        mesh.add_vert(Vec3::new(0.0, 0.0, 0.0));
+        mesh.add_vert(Vec3::new(1.0, 0.0, 1.0));
+        mesh.add_vert(Vec3::new(-1.0, 0.0, -1.0));
+        mesh.add_poly(vec![0, 1, 2]).unwrap();
+        mesh.project_uvs_cylindrical(&[0], Axis::Y);
+        let uvs = mesh.poly_uvs(0).unwrap();
+        assert!(uvs.iter().all(|corner_uv| corner_uv.is_finite()));
+        assert_uv_eq(uvs[0], uv((uvs[1].x + uvs[2].x) / 2.0, uvs[0].y));
+    }
+
+    // ==================== keeping UVs with their polys ====================
+
+    #[test]
+    fn test_remove_poly_keeps_uvs_in_place() {
+        let mut mesh = Mesh::new_cube();
+        let polys = all_polys(&mesh);
+        mesh.project_uvs_box(&polys);
+        let last_uvs = mesh.poly_uvs(5).unwrap().to_vec();
+        mesh.remove_poly(1).unwrap();
+        // the last poly was swapped into the hole, its UVs with it
+        assert_eq!(mesh.poly_uvs(1).unwrap(), last_uvs.as_slice());
+    }
+
+    #[test]
+    fn test_delete_vert_removes_corner_uv() {
+        let mut mesh = uv_quad();
+        mesh.add_vert(Vec3::new(2.0, 0.0, 0.0));
+        mesh.delete_vert(1).unwrap();
+        // the new vert was swapped into index 1
+        assert_eq!(mesh.polys()[0], vec![0, 2, 3]);
+        assert_eq!(
+            mesh.poly_uvs(0).unwrap(),
+            &[uv(0.0, 0.0), uv(1.0, 1.0), uv(0.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_flip_reverses_uvs() {
+        let mut mesh = uv_quad();
+        mesh.flip_polys(&[0]);
+        assert_eq!(mesh.polys()[0], vec![3, 2, 1, 0]);
+        assert_eq!(mesh.poly_uvs(0).unwrap()[0], uv(0.0, 1.0));
+    }
+
+    #[test]
+    fn test_merge_keeps_uvs_of_remaining_corners() {
+        let mut mesh = uv_quad();
+        // corner 1 welded onto corner 2
+        mesh.update_vert(1, Vec3::new(1.0, 1.0, 0.0)).unwrap();
+        mesh.merge_by_distance(0.0);
+        assert_eq!(mesh.polys()[0].len(), 3);
+        assert_eq!(
+            mesh.poly_uvs(0).unwrap(),
+            &[uv(0.0, 0.0), uv(1.0, 0.0), uv(0.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_loop_cut_interpolates_uvs() {
+        let mut mesh = uv_quad();
+        mesh.loop_cut((0, 1), 0.25);
+        assert_eq!(mesh.polys().len(), 2);
+        for poly_index in 0..2 {
+            let uvs = mesh.poly_uvs(poly_index).unwrap();
+            for (&v, &corner_uv) in mesh.polys()[poly_index].iter().zip(uvs) {
+                // UVs equal the xy position on this quad
+                assert_uv_eq(corner_uv, mesh.verts()[v].truncate());
+            }
+        }
+    }
+
+    #[test]
+    fn test_append_carries_uvs() {
+        let mut mesh = Mesh::new_cube();
+        mesh.append(&uv_quad(), Vec3::ZERO);
+        assert!(mesh.poly_uvs(5).is_none());
+        assert_eq!(mesh.poly_uvs(6).unwrap()[2], uv(1.0, 1.0));
+    }
+
+    #[test]
+    fn test_bisect_interpolates_uvs() {
+        let mut mesh = uv_quad();
+        mesh.append(&uv_quad(), Vec3::new(5.0, 0.0, 0.0));
+        let plane =
+            Plane::containing_line(Vec3::new(0.5, 0.0, 0.0), Vec3::new(0.5, 1.0, 0.0), Vec3::Z)
+                .unwrap();
+        mesh.bisect(plane, BisectKeep::Both, false);
+        assert_eq!(mesh.polys().len(), 3);
+
+        // the cut pieces get the UVs of their corners (the quad's UVs match its xy)
+        for poly_index in 0..2 {
+            let uvs = mesh.poly_uvs(poly_index).unwrap();
+            for (&v, &actual) in mesh.polys()[poly_index].iter().zip(uvs) {
+                assert_uv_eq(actual, mesh.verts()[v].truncate());
+            }
+        }
+        // the quad away from the plane keeps its UVs as they were
+        assert_eq!(mesh.poly_uvs(2), uv_quad().poly_uvs(0));
+    }
+
+    #[test]
+    fn test_projections_skip_polys_out_of_range() {
+        let mut mesh = Mesh::new_cube();
+        mesh.project_uvs_planar(&[0, 99], ProjectionPlane::XY, false);
+        mesh.project_uvs_box(&[1, 99]);
+        mesh.project_uvs_cylindrical(&[2, 99], Axis::Y);
+        assert!((0..3).all(|poly_index| mesh.poly_uvs(poly_index).is_some()));
+        assert!(mesh.poly_uvs(3).is_none());
+    }
+}
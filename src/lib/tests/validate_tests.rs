@@ -0,0 +1,160 @@
+// Unit tests for mesh validation and repair.
+//
+// Test coverage includes:
+// - A clean mesh (cube) reports no issues
+// - Each diagnostic: degenerate / duplicate lines, duplicate polys, non-manifold edges,
+//   inconsistent winding, non-planar polys, isolated and non-finite verts
+// - repair(): removes what it safely can and reports the rest
+
+use crate::mesh::{Mesh, MeshIssue};
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    fn quad_verts() -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 1.0, 0.0));
+        mesh.add_vert(coord(0.0, 1.0, 0.0));
+        mesh
+    }
+
+    // ==================== validate tests ====================
+
+    #[test]
+    fn test_cube_is_valid() {
+        assert!(Mesh::new_cube().validate().is_empty());
+        assert!(Mesh::new_tapered_box().validate().is_empty());
+    }
+
+    #[test]
+    fn test_degenerate_and_duplicate_lines() {
+        let mut mesh = quad_verts();
+        mesh.add_line((0, 1));
+        mesh.add_line((1, 0));
+        mesh.add_line((2, 2));
+        mesh.add_line((2, 3));
+
+        let issues = mesh.validate();
+        assert!(issues.contains(&MeshIssue::DuplicateLine {
+            line: 1,
+            duplicate_of: 0
+        }));
+        assert!(issues.contains(&MeshIssue::DegenerateLine(2)));
+    }
+
+    #[test]
+    fn test_duplicate_poly() {
+        let mut mesh = quad_verts();
+        mesh.add_poly(vec![0, 1, 2, 3]);
+        mesh.add_poly(vec![2, 3, 0, 1]);
+
+        let issues = mesh.validate();
+        assert!(issues.contains(&MeshIssue::DuplicatePoly {
+            poly: 1,
+            duplicate_of: 0
+        }));
+    }
+
+    #[test]
+    fn test_non_manifold_edge() {
+        let mut mesh = quad_verts();
+        mesh.add_vert(coord(0.5, 0.5, 1.0));
+        mesh.add_poly(vec![0, 1, 2]);
+        mesh.add_poly(vec![1, 0, 3]);
+        mesh.add_poly(vec![1, 0, 4]);
+
+        let issues = mesh.validate();
+        assert!(issues.iter().any(|issue| matches!(
+            issue,
+            MeshIssue::NonManifoldEdge { edge: (0, 1), polys } if polys.len() == 3
+        )));
+    }
+
+    #[test]
+    fn test_inconsistent_winding() {
+        let mut mesh = quad_verts();
+        mesh.add_poly(vec![0, 1, 2]);
+        mesh.add_poly(vec![0, 2, 3]);
+        assert!(mesh.validate().is_empty());
+
+        let mut flipped = quad_verts();
+        flipped.add_poly(vec![0, 1, 2]);
+        flipped.add_poly(vec![2, 0, 3]);
+        assert_eq!(
+            flipped.validate(),
+            vec![MeshIssue::InconsistentWinding {
+                edge: (0, 2),
+                polys: (0, 1)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_non_planar_poly() {
+        let mut mesh = quad_verts();
+        mesh.update_vert(2, coord(1.0, 1.0, 0.5));
+        mesh.add_poly(vec![0, 1, 2, 3]);
+
+        let issues = mesh.validate();
+        assert!(
+            matches!(issues[0], MeshIssue::NonPlanarPoly { poly: 0, deviation } if deviation > 0.1)
+        );
+    }
+
+    #[test]
+    fn test_isolated_and_non_finite_verts() {
+        let mut mesh = quad_verts();
+        mesh.add_poly(vec![0, 1, 2]);
+        mesh.update_vert(0, coord(f32::NAN, 0.0, 0.0));
+
+        let issues = mesh.validate();
+        assert!(issues.contains(&MeshIssue::NonFiniteVert(0)));
+        assert!(issues.contains(&MeshIssue::IsolatedVert(3)));
+    }
+
+    // ==================== repair tests ====================
+
+    #[test]
+    fn test_repair_removes_safe_issues() {
+        let mut mesh = quad_verts();
+        mesh.add_vert(coord(5.0, 5.0, 5.0)); // isolated
+        mesh.add_line((0, 1));
+        mesh.add_line((0, 1));
+        mesh.add_line((3, 3));
+        mesh.add_poly(vec![0, 1, 2, 3]);
+        mesh.add_poly(vec![0, 1, 2, 3]);
+
+        let report = mesh.repair();
+        assert_eq!(report.removed_lines, 2);
+        assert_eq!(report.removed_polys, 1);
+        assert_eq!(report.removed_verts, 1);
+        assert!(report.remaining.is_empty());
+        assert!(mesh.validate().is_empty());
+    }
+
+    #[test]
+    fn test_repair_reports_remaining_issues() {
+        let mut mesh = quad_verts();
+        mesh.add_poly(vec![0, 1, 2]);
+        mesh.add_poly(vec![2, 0, 3]);
+
+        let report = mesh.repair();
+        assert_eq!(report.remaining.len(), 1);
+        assert_eq!(mesh.polys().len(), 2);
+    }
+
+    #[test]
+    fn test_repair_clean_mesh_is_noop() {
+        let mut mesh = Mesh::new_cube();
+        assert_eq!(mesh.repair(), Default::default());
+        assert_eq!(mesh.verts().len(), 8);
+    }
+}
@@ -0,0 +1,138 @@
+// Unit tests for poly winding operations.
+//
+// Test coverage includes:
+// - Built-in cube is wound outward (positive volume, outward normals)
+// - Built-in cube and tapered box: every face points away from the center
+// - flip_polys, poly_normal, connected_components
+// - make_winding_consistent on mixed windings, per component
+// - orient_outward for inside-out shells and open meshes
+
+use crate::mesh::{Mesh, MeshIssue};
+use crate::tests::helpers::assert_closed_and_outward;
+use macroquad::prelude::Vec3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    fn has_winding_issues(mesh: &Mesh) -> bool {
+        mesh.validate()
+            .iter()
+            .any(|issue| matches!(issue, MeshIssue::InconsistentWinding { .. }))
+    }
+
+    #[test]
+    fn test_cube_is_wound_outward() {
+        let mesh = Mesh::new_cube();
+        let all: Vec<usize> = (0..6).collect();
+        assert!((mesh.signed_volume(&all) - 8.0).abs() < 1e-4);
+        // front face sits at z = -1
+        assert_eq!(mesh.poly_normal(0), coord(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn test_stock_boxes_face_outward() {
+        for mesh in [Mesh::new_cube(), Mesh::new_tapered_box()] {
+            assert_closed_and_outward(&mesh);
+            // both are centered at the origin
+            for (poly_index, poly) in mesh.polys().iter().enumerate() {
+                let center = mesh.poly_centroid(poly);
+                assert!(mesh.poly_normal(poly_index).dot(center) > 0.0);
+            }
+        }
+        let normals: Vec<Vec3> = (0..6).map(|p| Mesh::new_cube().poly_normal(p)).collect();
+        assert_eq!(
+            normals,
+            vec![
+                coord(0.0, 0.0, -1.0),
+                coord(0.0, 0.0, 1.0),
+                coord(0.0, -1.0, 0.0),
+                coord(0.0, 1.0, 0.0),
+                coord(-1.0, 0.0, 0.0),
+                coord(1.0, 0.0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flip_polys() {
+        let mut mesh = Mesh::new_cube();
+        mesh.flip_polys(&[0, 0, 99]);
+        assert_eq!(mesh.polys()[0], vec![1, 2, 3, 0]);
+        assert_eq!(mesh.poly_normal(0), coord(0.0, 0.0, 1.0));
+        assert!(has_winding_issues(&mesh));
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let mut mesh = Mesh::new_cube();
+        let offset = mesh.verts().len();
+        mesh.add_vert(coord(5.0, 0.0, 0.0));
+        mesh.add_vert(coord(6.0, 0.0, 0.0));
+        mesh.add_vert(coord(5.0, 1.0, 0.0));
+        mesh.add_poly(vec![offset, offset + 1, offset + 2]);
+
+        let components = mesh.connected_components();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].len(), 6);
+        assert_eq!(components[1], vec![6]);
+    }
+
+    #[test]
+    fn test_make_winding_consistent() {
+        let mut mesh = Mesh::new_cube();
+        mesh.flip_polys(&[1, 3]);
+
+        let flipped = mesh.make_winding_consistent();
+        assert_eq!(flipped, 2);
+        assert!(!has_winding_issues(&mesh));
+        assert_eq!(mesh.polys()[1], vec![5, 6, 7, 4]);
+    }
+
+    #[test]
+    fn test_make_winding_consistent_keeps_seed() {
+        let mut mesh = Mesh::new_cube();
+        mesh.flip_polys(&[0]);
+        // poly 0 is the seed, so everything else follows it inward
+        assert_eq!(mesh.make_winding_consistent(), 5);
+        assert!(!has_winding_issues(&mesh));
+        let all: Vec<usize> = (0..6).collect();
+        assert!(mesh.signed_volume(&all) < 0.0);
+    }
+
+    #[test]
+    fn test_orient_outward_fixes_inside_out_cube() {
+        let mut mesh = Mesh::new_cube();
+        let all: Vec<usize> = (0..6).collect();
+        mesh.flip_polys(&all);
+        mesh.flip_polys(&[2]);
+
+        assert_eq!(mesh.orient_outward(), 5);
+        assert_eq!(mesh.polys(), Mesh::new_cube().polys());
+    }
+
+    #[test]
+    fn test_orient_outward_noop_on_outward_cube() {
+        let mut mesh = Mesh::new_cube();
+        assert_eq!(mesh.orient_outward(), 0);
+    }
+
+    #[test]
+    fn test_orient_outward_open_mesh_only_made_consistent() {
+        let mut mesh = Mesh::new();
+        mesh.add_vert(coord(0.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 0.0, 0.0));
+        mesh.add_vert(coord(1.0, 1.0, 0.0));
+        mesh.add_vert(coord(0.0, 1.0, 0.0));
+        mesh.add_poly(vec![0, 1, 2]);
+        mesh.add_poly(vec![2, 0, 3]);
+
+        assert!(!mesh.is_closed_component(&[0, 1]));
+        assert_eq!(mesh.orient_outward(), 1);
+        assert!(!has_winding_issues(&mesh));
+    }
+}